@@ -1022,6 +1022,7 @@ pub struct WorkspaceCommandHelper {
     op_summary_template_text: String,
     may_update_working_copy: bool,
     working_copy_shared_with_git: bool,
+    working_copy_is_linked_git_worktree: bool,
 }
 
 enum SnapshotWorkingCopyError {
@@ -1061,6 +1062,8 @@ impl WorkspaceCommandHelper {
             loaded_at_head && !env.command.global_args().ignore_working_copy;
         let working_copy_shared_with_git =
             crate::git_util::is_colocated_git_workspace(&workspace, &repo);
+        let working_copy_is_linked_git_worktree = working_copy_shared_with_git
+            && crate::git_util::is_linked_git_worktree(&workspace, &repo);
 
         let helper = Self {
             workspace,
@@ -1070,6 +1073,7 @@ impl WorkspaceCommandHelper {
             op_summary_template_text,
             may_update_working_copy,
             working_copy_shared_with_git,
+            working_copy_is_linked_git_worktree,
         };
         // Parse commit_summary template early to report error before starting
         // mutable operation.
@@ -1148,6 +1152,9 @@ impl WorkspaceCommandHelper {
     #[instrument(skip_all)]
     fn import_git_head(&mut self, ui: &Ui) -> Result<(), CommandError> {
         assert!(self.may_update_working_copy);
+        if let Some(worktree) = self.open_linked_git_worktree() {
+            return self.import_git_worktree_head(ui, &worktree);
+        }
         let mut tx = self.start_transaction();
         jj_lib::git::import_head(tx.repo_mut())?;
         if !tx.repo().has_changes() {
@@ -1164,22 +1171,12 @@ impl WorkspaceCommandHelper {
         // - new HEAD was exported by jj, but the new working-copy commit isn't checked
         //   out yet.
 
-        let mut tx = tx.into_inner();
+        let tx = tx.into_inner();
         let old_git_head = self.repo().view().git_head().clone();
         let new_git_head = tx.repo().view().git_head().clone();
         if let Some(new_git_head_id) = new_git_head.as_normal() {
-            let workspace_name = self.workspace_name().to_owned();
             let new_git_head_commit = tx.repo().store().get_commit(new_git_head_id)?;
-            tx.repo_mut()
-                .check_out(workspace_name, &new_git_head_commit)?;
-            let mut locked_ws = self.workspace.start_working_copy_mutation()?;
-            // The working copy was presumably updated by the git command that updated
-            // HEAD, so we just need to reset our working copy
-            // state to it without updating working copy files.
-            locked_ws.locked_wc().reset(&new_git_head_commit)?;
-            tx.repo_mut().rebase_descendants()?;
-            self.user_repo = ReadonlyUserRepo::new(tx.commit("import git head")?);
-            locked_ws.finish(self.user_repo.repo.op_id().clone())?;
+            self.check_out_git_head(tx, &new_git_head_commit)?;
             if old_git_head.is_present() {
                 writeln!(
                     ui.status(),
@@ -1195,6 +1192,89 @@ impl WorkspaceCommandHelper {
         Ok(())
     }
 
+    /// Imports new HEAD from the linked Git worktree colocated with this
+    /// workspace.
+    ///
+    /// The HEAD of a linked worktree is compared against the parent of the
+    /// working-copy commit. If it has changed, this function checks out the new
+    /// HEAD in the same way as `import_git_head()`.
+    #[cfg(feature = "git")]
+    fn import_git_worktree_head(
+        &mut self,
+        ui: &Ui,
+        worktree: &gix::Repository,
+    ) -> Result<(), CommandError> {
+        let Some(wc_commit_id) = self.get_wc_commit_id() else {
+            return Ok(());
+        };
+        let wc_commit = self.repo().store().get_commit(wc_commit_id)?;
+        let mut tx = self.start_transaction();
+        let Some(new_git_head_commit) =
+            jj_lib::git::import_worktree_head(tx.repo_mut(), worktree, &wc_commit)?
+        else {
+            return Ok(());
+        };
+        let tx = tx.into_inner();
+        self.check_out_git_head(tx, &new_git_head_commit)?;
+        writeln!(
+            ui.status(),
+            "Reset the working copy parent to the new Git HEAD."
+        )?;
+        Ok(())
+    }
+
+    #[cfg(feature = "git")]
+    fn check_out_git_head(
+        &mut self,
+        mut tx: Transaction,
+        new_git_head_commit: &Commit,
+    ) -> Result<(), CommandError> {
+        let workspace_name = self.workspace_name().to_owned();
+        tx.repo_mut()
+            .check_out(workspace_name, new_git_head_commit)?;
+        let mut locked_ws = self.workspace.start_working_copy_mutation()?;
+        // The working copy was presumably updated by the git command that updated
+        // HEAD, so we just need to reset our working copy
+        // state to it without updating working copy files.
+        locked_ws.locked_wc().reset(new_git_head_commit)?;
+        tx.repo_mut().rebase_descendants()?;
+        self.user_repo = ReadonlyUserRepo::new(tx.commit("import git head")?);
+        locked_ws.finish(self.user_repo.repo.op_id().clone())?;
+        Ok(())
+    }
+
+    /// Opens the linked Git worktree colocated with this workspace, if any.
+    #[cfg(feature = "git")]
+    fn open_linked_git_worktree(&self) -> Option<gix::Repository> {
+        if !self.working_copy_is_linked_git_worktree {
+            return None;
+        }
+        jj_lib::git::open_linked_worktree(self.repo().store(), self.workspace_root())
+    }
+
+    /// Returns the hooks of the underlying Git repo if running them is enabled
+    /// by the `git.run-hooks` config.
+    #[cfg(feature = "git")]
+    pub fn git_hooks(&self) -> Result<Option<crate::git_hooks::GitHooks>, CommandError> {
+        if !self.settings().get_bool("git.run-hooks")? {
+            return Ok(None);
+        }
+        let Ok(git_repo) = jj_lib::git::get_git_repo(self.repo().store()) else {
+            return Ok(None);
+        };
+        let git_repo = self.open_linked_git_worktree().unwrap_or(git_repo);
+        Ok(Some(crate::git_hooks::GitHooks::new(
+            git_repo,
+            self.workspace_root(),
+            self.working_copy_shared_with_git,
+        )))
+    }
+
+    #[cfg(not(feature = "git"))]
+    pub fn git_hooks(&self) -> Result<Option<crate::git_hooks::GitHooks>, CommandError> {
+        Ok(None)
+    }
+
     /// Imports branches and tags from the underlying Git repo, abandons old
     /// bookmarks.
     ///
@@ -1367,7 +1447,14 @@ to the current parents may contain changes from multiple commits.
         let mut diagnostics = FilesetDiagnostics::new();
         let expressions: Vec<_> = file_args
             .iter()
-            .map(|arg| fileset::parse_maybe_bare(&mut diagnostics, arg, self.path_converter()))
+            .map(|arg| {
+                fileset::parse_maybe_bare(
+                    &mut diagnostics,
+                    arg,
+                    self.path_converter(),
+                    self.env.command.revset_extensions().fileset_extensions(),
+                )
+            })
             .try_collect()?;
         print_parse_diagnostics(ui, "In fileset expression", &diagnostics)?;
         Ok(FilesetExpression::union_all(expressions))
@@ -1383,9 +1470,10 @@ to the current parents may contain changes from multiple commits.
                 cwd: "".into(),
                 base: "".into(),
             },
+            self.env.command.revset_extensions().fileset_extensions(),
         )?;
         print_parse_diagnostics(ui, "In `snapshot.auto-track`", &diagnostics)?;
-        Ok(expression.to_matcher())
+        to_path_matcher(&expression)
     }
 
     pub fn snapshot_options_with_start_tracking_matcher<'a>(
@@ -1843,6 +1931,69 @@ to the current parents may contain changes from multiple commits.
         Err(error)
     }
 
+    /// Returns the commits in `candidates` that `signing.policy.require-signed`
+    /// requires to be signed, but that don't have a good signature from one of
+    /// the `signing.policy.allowed-keys`.
+    pub fn find_untrusted_commits(
+        &self,
+        ui: &Ui,
+        candidates: &Rc<UserRevsetExpression>,
+    ) -> Result<Vec<Commit>, CommandError> {
+        let settings = self.settings();
+        let required_revset_str =
+            RevisionArg::from(settings.get_string("signing.policy.require-signed")?);
+        let allowed_keys: Vec<String> = settings.get("signing.policy.allowed-keys")?;
+        let trusted = if allowed_keys.is_empty() {
+            RevsetExpression::filter(RevsetFilterPredicate::SignedBy(StringPattern::everything()))
+        } else {
+            let expressions = allowed_keys
+                .into_iter()
+                .map(|key| {
+                    RevsetExpression::filter(RevsetFilterPredicate::SignedBy(StringPattern::exact(
+                        key,
+                    )))
+                })
+                .collect_vec();
+            RevsetExpression::union_all(&expressions)
+        };
+        let mut untrusted = self.parse_revset(ui, &required_revset_str)?;
+        untrusted.intersect_with(&candidates.minus(&trusted));
+        let commits = untrusted.evaluate_to_commits()?.try_collect()?;
+        Ok(commits)
+    }
+
+    /// Warns if a command is about to build on top of commits that violate the
+    /// signature policy.
+    pub fn warn_untrusted_commits(
+        &self,
+        ui: &Ui,
+        commit_ids: &[CommitId],
+    ) -> Result<(), CommandError> {
+        let untrusted =
+            self.find_untrusted_commits(ui, &RevsetExpression::commits(commit_ids.to_vec()))?;
+        if untrusted.is_empty() {
+            return Ok(());
+        }
+        writeln!(
+            ui.warning_default(),
+            "Building on commits without a trusted signature:"
+        )?;
+        if let Some(mut formatter) = ui.status_formatter() {
+            for commit in &untrusted {
+                write!(formatter, "  ")?;
+                self.write_commit_summary(formatter.as_mut(), commit)?;
+                writeln!(formatter)?;
+            }
+        }
+        writeln!(
+            ui.hint_default(),
+            "Configured signing.policy.require-signed: '{}'",
+            self.settings()
+                .get_string("signing.policy.require-signed")?
+        )?;
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     fn snapshot_working_copy(
         &mut self,
@@ -1869,6 +2020,9 @@ to the current parents may contain changes from multiple commits.
             .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)
             .map_err(snapshot_command_error)?;
 
+        #[cfg(feature = "git")]
+        let linked_git_worktree = self.open_linked_git_worktree();
+
         // Compare working-copy tree and operation with repo's, and reload as needed.
         let mut locked_ws = self
             .workspace
@@ -1966,11 +2120,15 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             if self.working_copy_shared_with_git {
                 let old_tree = wc_commit.tree().map_err(snapshot_command_error)?;
                 let new_tree = commit.tree().map_err(snapshot_command_error)?;
-                jj_lib::git::update_intent_to_add(
-                    self.user_repo.repo.as_ref(),
-                    &old_tree,
-                    &new_tree,
-                )
+                if let Some(worktree) = &linked_git_worktree {
+                    jj_lib::git::update_worktree_intent_to_add(worktree, &old_tree, &new_tree)
+                } else {
+                    jj_lib::git::update_intent_to_add(
+                        self.user_repo.repo.as_ref(),
+                        &old_tree,
+                        &new_tree,
+                    )
+                }
                 .map_err(snapshot_command_error)?;
 
                 let stats = jj_lib::git::export_refs(mut_repo).map_err(snapshot_command_error)?;
@@ -2107,7 +2265,12 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             if let Some(wc_commit) = &maybe_new_wc_commit {
                 // This can fail if HEAD was updated concurrently. In that case,
                 // the actual state will be imported on the next snapshot.
-                match jj_lib::git::reset_head(tx.repo_mut(), wc_commit) {
+                let result = if let Some(worktree) = self.open_linked_git_worktree() {
+                    jj_lib::git::reset_worktree_head(tx.repo(), &worktree, wc_commit)
+                } else {
+                    jj_lib::git::reset_head(tx.repo_mut(), wc_commit)
+                };
+                match result {
                     Ok(()) => {}
                     Err(err @ jj_lib::git::GitResetHeadError::UpdateHeadRef(_)) => {
                         writeln!(ui.warning_default(), "{err}")?;
@@ -2120,6 +2283,61 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             crate::git_util::print_git_export_stats(ui, &stats)?;
         }
 
+        // Notes live outside of the operation log, so failing to copy them or
+        // to run the post-rewrite hook shouldn't prevent the rewrite from being
+        // recorded.
+        #[cfg(feature = "git")]
+        let post_rewrite = if jj_lib::git::get_git_backend(tx.repo().store()).is_ok() {
+            use std::error::Error as _;
+            let namespaces = &self.settings().git_settings()?.notes_namespaces;
+            let has_notes = match jj_lib::git::has_notes(tx.repo().store(), namespaces) {
+                Ok(has_notes) => has_notes,
+                Err(err) => {
+                    writeln!(ui.warning_default(), "Failed to copy Git notes: {err}")?;
+                    crate::command_error::print_error_sources(ui, err.source())?;
+                    false
+                }
+            };
+            let git_hooks = self
+                .git_hooks()?
+                .filter(|git_hooks| git_hooks.has_post_rewrite());
+            // Finding the rewritten commits loads every commit added by the
+            // transaction, so avoid it unless something needs them.
+            let rewrites = if has_notes || git_hooks.is_some() {
+                match tx.repo().rewritten_commits_since_base() {
+                    Ok(rewrites) => rewrites,
+                    Err(err) => {
+                        writeln!(
+                            ui.warning_default(),
+                            "Failed to find rewritten commits: {err}"
+                        )?;
+                        crate::command_error::print_error_sources(ui, err.source())?;
+                        vec![]
+                    }
+                }
+            } else {
+                vec![]
+            };
+            if has_notes && !rewrites.is_empty() {
+                let signature = self.settings().signature();
+                let result = jj_lib::git::rewrite_notes(
+                    tx.repo().store(),
+                    &rewrites,
+                    namespaces,
+                    &signature,
+                );
+                if let Err(err) = result {
+                    writeln!(ui.warning_default(), "Failed to copy Git notes: {err}")?;
+                    crate::command_error::print_error_sources(ui, err.source())?;
+                }
+            }
+            git_hooks
+                .filter(|_| !rewrites.is_empty())
+                .map(|git_hooks| (git_hooks, rewrites))
+        } else {
+            None
+        };
+
         self.user_repo = ReadonlyUserRepo::new(tx.commit(description)?);
 
         // Update working copy before reporting repo changes, so that
@@ -2136,6 +2354,25 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
 
         self.report_repo_changes(ui, &old_repo)?;
 
+        #[cfg(feature = "git")]
+        if let Some((git_hooks, rewrites)) = post_rewrite {
+            // Like `git rebase`, report a rebase if any commit was moved.
+            // Otherwise, the commits were amended in place.
+            let store = self.repo().store();
+            let mut kind = "amend";
+            let mut pairs = vec![];
+            for (new_id, old_ids) in rewrites {
+                let new_commit = store.get_commit(&new_id)?;
+                for old_id in old_ids {
+                    if store.get_commit(&old_id)?.parent_ids() != new_commit.parent_ids() {
+                        kind = "rebase";
+                    }
+                    pairs.push((old_id, new_id.clone()));
+                }
+            }
+            git_hooks.run_post_rewrite(ui, kind, &pairs)?;
+        }
+
         let settings = self.settings();
         let missing_user_name = settings.user_name().is_empty();
         let missing_user_mail = settings.user_email().is_empty();
@@ -2512,14 +2749,26 @@ fn map_workspace_load_error(err: WorkspaceLoadError, user_wc_path: Option<&str>)
                      running this:
 jj git init --colocate",
                 )
+            } else if git_dir.is_file() {
+                user_error_with_hint(
+                    message,
+                    "It looks like this is a linked Git worktree. If the main worktree is a jj \
+                     repo, you can create a workspace here by running this from the main \
+                     worktree:
+jj workspace add --colocate <path-to-this-worktree>",
+                )
             } else {
                 user_error(message)
             }
         }
-        WorkspaceLoadError::RepoDoesNotExist(repo_dir) => user_error(format!(
-            "The repository directory at {} is missing. Was it moved?",
-            repo_dir.display(),
-        )),
+        WorkspaceLoadError::RepoDoesNotExist(repo_dir) => user_error_with_hint(
+            format!(
+                "The repository directory at {} is missing. Was it moved?",
+                repo_dir.display(),
+            ),
+            "Run `jj workspace relink <path-to-repo>` to point this workspace to the new \
+             location of the repo.",
+        ),
         WorkspaceLoadError::StoreLoadError(err @ StoreLoadError::UnsupportedType { .. }) => {
             internal_error_with_message(
                 "This version of the jj binary doesn't support this type of repo",
@@ -2809,6 +3058,20 @@ Discard the conflicting changes with `jj restore --from {}`.",
     Ok(())
 }
 
+/// Builds a matcher from the fileset `expression` which is evaluated by paths
+/// only.
+///
+/// File predicates such as `binary()` can't be evaluated without tree entries,
+/// so an error is returned if the `expression` contains any of them.
+pub fn to_path_matcher(expression: &FilesetExpression) -> Result<Box<dyn Matcher>, CommandError> {
+    if expression.has_file_predicates() {
+        return Err(user_error(
+            "File predicates such as `binary()` or `ignored()` are not supported here",
+        ));
+    }
+    Ok(expression.to_matcher())
+}
+
 /// Prints warning about explicit paths that don't match any of the tree
 /// entries.
 pub fn print_unmatched_explicit_paths<'a>(
@@ -3933,6 +4196,13 @@ impl<'a> CliRunner<'a> {
         }
 
         let settings = UserSettings::from_config(config)?;
+        let mut revset_extensions = self.revset_extensions;
+        revset_util::register_external_revset_functions(ui, &mut revset_extensions, &settings)?;
+        let external_fileset_functions = revset_util::register_external_fileset_functions(
+            ui,
+            revset_extensions.fileset_extensions_mut(),
+            &settings,
+        )?;
         let command_helper_data = CommandHelperData {
             app: self.app,
             cwd,
@@ -3943,7 +4213,7 @@ impl<'a> CliRunner<'a> {
             config_migrations: self.config_migrations,
             raw_config,
             settings,
-            revset_extensions: self.revset_extensions.into(),
+            revset_extensions: revset_extensions.into(),
             commit_template_extensions: self.commit_template_extensions,
             operation_template_extensions: self.operation_template_extensions,
             maybe_workspace_loader,
@@ -3962,7 +4232,9 @@ impl<'a> CliRunner<'a> {
                 })
             },
         );
-        (dispatch_fn)(ui, &command_helper)
+        let result = (dispatch_fn)(ui, &command_helper);
+        external_fileset_functions.report_errors(ui)?;
+        result
     }
 
     #[must_use]
@@ -509,6 +509,7 @@ mod git {
     use jj_lib::git::GitExportError;
     use jj_lib::git::GitFetchError;
     use jj_lib::git::GitImportError;
+    use jj_lib::git::GitNotesError;
     use jj_lib::git::GitPushError;
     use jj_lib::git::GitRemoteManagementError;
     use jj_lib::git::GitResetHeadError;
@@ -562,6 +563,7 @@ jj currently does not support partial clones. To use jj with this repository, tr
                 ),
                 GitFetchError::InvalidBranchPattern(_) => user_error(err),
                 GitFetchError::Subprocess(_) => user_error(err),
+                GitFetchError::Notes(_) => user_error(err),
             }
         }
     }
@@ -575,11 +577,25 @@ jj currently does not support partial clones. To use jj with this repository, tr
                     "Run `jj git remote rename` to give a different name.",
                 ),
                 GitPushError::Subprocess(_) => user_error(err),
+                GitPushError::Notes(err) => err.into(),
                 GitPushError::UnexpectedBackend(_) => user_error(err),
             }
         }
     }
 
+    impl From<GitNotesError> for CommandError {
+        fn from(err: GitNotesError) -> Self {
+            match err {
+                GitNotesError::InvalidNamespace(_) | GitNotesError::UnexpectedBackend(_) => {
+                    user_error(err)
+                }
+                GitNotesError::InternalGitError(_) | GitNotesError::Backend(_) => {
+                    internal_error(err)
+                }
+            }
+        }
+    }
+
     impl From<GitRemoteManagementError> for CommandError {
         fn from(err: GitRemoteManagementError) -> Self {
             user_error(err)
@@ -870,8 +886,9 @@ fn revset_resolution_error_hint(err: &RevsetResolutionError) -> Option<String> {
 fn string_pattern_parse_error_hint(err: &StringPatternParseError) -> Option<String> {
     match err {
         StringPatternParseError::InvalidKind(_) => Some(
-            "Try prefixing with one of `exact:`, `glob:`, `regex:`, `substring:`, or one of these \
-             with `-i` suffix added (e.g. `glob-i:`) for case-insensitive matching"
+            "Try prefixing with one of `exact:`, `glob:`, `regex:`, `substring:`, `exact-line:`, \
+             `line-glob:`, or one of the first four with `-i` suffix added (e.g. `glob-i:`) for \
+             case-insensitive matching"
                 .into(),
         ),
         StringPatternParseError::GlobPattern(_) | StringPatternParseError::Regex(_) => None,
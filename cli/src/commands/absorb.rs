@@ -16,7 +16,7 @@ use clap_complete::ArgValueCompleter;
 use jj_lib::absorb::absorb_hunks;
 use jj_lib::absorb::split_hunks_to_trees;
 use jj_lib::absorb::AbsorbSource;
-use jj_lib::matchers::EverythingMatcher;
+use jj_lib::fileset::FilesetExpression;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
@@ -77,11 +77,13 @@ pub(crate) fn cmd_absorb(
         .parse_union_revsets(ui, &args.into)?
         .resolve()?;
 
+    let repo = workspace_command.repo().as_ref();
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
-        .to_matcher();
-
-    let repo = workspace_command.repo().as_ref();
+        .to_tree_matcher(vec![
+            source_commit.parent_tree(repo)?,
+            source_commit.tree()?,
+        ]);
     let source = AbsorbSource::from_commit(repo, source_commit)?;
     let selected_trees = split_hunks_to_trees(repo, &source, &destinations, &matcher).block_on()?;
 
@@ -132,9 +134,9 @@ pub(crate) fn cmd_absorb(
             if !commit.is_empty(repo)? {
                 writeln!(formatter, "Remaining changes:")?;
                 let diff_renderer = workspace_command.diff_renderer(vec![DiffFormat::Summary]);
-                let matcher = &EverythingMatcher; // also print excluded paths
+                let files = &FilesetExpression::all(); // also print excluded paths
                 let width = ui.term_width();
-                diff_renderer.show_patch(ui, formatter.as_mut(), commit, matcher, width)?;
+                diff_renderer.show_patch(ui, formatter.as_mut(), commit, files, width)?;
             }
         }
     }
@@ -68,6 +68,11 @@ pub(crate) struct CommitArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Don't run the Git pre-commit and commit-msg hooks
+    ///
+    /// The hooks are only run if enabled by the `git.run-hooks` config.
+    #[arg(long)]
+    no_verify: bool,
 }
 
 #[instrument(skip_all)]
@@ -84,11 +89,20 @@ pub(crate) fn cmd_commit(
     let commit = workspace_command.repo().store().get_commit(commit_id)?;
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
-        .to_matcher();
+        .to_tree_matcher(vec![
+            commit.parent_tree(workspace_command.repo().as_ref())?,
+            commit.tree()?,
+        ]);
     let advanceable_bookmarks = workspace_command.get_advanceable_bookmarks(commit.parent_ids())?;
     let diff_selector =
         workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
     let text_editor = workspace_command.text_editor()?;
+    let git_hooks = if args.no_verify {
+        None
+    } else {
+        workspace_command.git_hooks()?
+    };
+    let uses_editor = args.message_paragraphs.is_empty();
     let mut tx = workspace_command.start_transaction();
     let base_tree = commit.parent_tree(tx.repo())?;
     let format_instructions = || {
@@ -116,6 +130,10 @@ new working-copy commit.
             args.paths.join(" ")
         )?;
     }
+    if let Some(git_hooks) = &git_hooks {
+        let tree = tx.repo().store().get_root_tree(&tree_id)?;
+        git_hooks.run_pre_commit(ui, &tree, uses_editor)?;
+    }
 
     let mut commit_builder = tx.repo_mut().rewrite_commit(&commit).detach();
     commit_builder.set_tree_id(tree_id);
@@ -149,6 +167,12 @@ new working-copy commit.
         let description = description_template(ui, &tx, "", &temp_commit)?;
         edit_description(&text_editor, &description)?
     };
+    let description = match &git_hooks {
+        Some(git_hooks) if !description.is_empty() => {
+            git_hooks.run_commit_msg(ui, &description, uses_editor)?
+        }
+        _ => description,
+    };
     commit_builder.set_description(description);
     let new_commit = commit_builder.write(tx.repo_mut())?;
 
@@ -13,14 +13,21 @@
 // limitations under the License.
 
 use std::fmt::Debug;
-use std::io::Write as _;
+use std::io;
+use std::io::Write;
 
+use jj_lib::default_index::revset_engine;
+use jj_lib::default_index::revset_engine::RevsetProfile;
+use jj_lib::default_index::DefaultReadonlyIndex;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
 use jj_lib::revset;
+use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetDiagnostics;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::print_parse_diagnostics;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::revset_util;
 use crate::ui::Ui;
@@ -29,6 +36,13 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 pub struct DebugRevsetArgs {
     revision: String,
+    /// Print how each node of the expression is evaluated
+    #[arg(long)]
+    explain: bool,
+    /// Print the number of commits visited by and the time spent in each node
+    /// of the expression
+    #[arg(long)]
+    profile: bool,
 }
 
 pub fn cmd_debug_revset(
@@ -67,14 +81,69 @@ pub fn cmd_debug_revset(
     writeln!(ui.stdout(), "{backend_expression:#?}")?;
     writeln!(ui.stdout())?;
 
-    let revset = expression.evaluate_unoptimized(repo)?;
+    let (revset, profile): (Box<dyn Revset>, _) = if args.explain || args.profile {
+        let index = repo.readonly_index();
+        let Some(default_index) = index.as_any().downcast_ref::<DefaultReadonlyIndex>() else {
+            return Err(user_error(format!(
+                "Cannot explain revset evaluation with indexes of type '{}'",
+                repo.index_store().name()
+            )));
+        };
+        let (revset, profile) =
+            revset_engine::evaluate_with_profile(&backend_expression, repo.store(), default_index)?;
+        (Box::new(revset), Some(profile))
+    } else {
+        (expression.evaluate_unoptimized(repo)?, None)
+    };
     writeln!(ui.stdout(), "-- Evaluated:")?;
     writeln!(ui.stdout(), "{revset:#?}")?;
     writeln!(ui.stdout())?;
 
+    if let Some(profile) = &profile {
+        if !args.profile {
+            writeln!(ui.stdout(), "-- Plan:")?;
+            write_profile(&mut ui.stdout(), profile, 0, false)?;
+            writeln!(ui.stdout())?;
+        }
+    }
+
     writeln!(ui.stdout(), "-- Commit IDs:")?;
     for commit_id in revset.iter() {
         writeln!(ui.stdout(), "{}", commit_id?.hex())?;
     }
+
+    if let Some(profile) = &profile {
+        if args.profile {
+            writeln!(ui.stdout())?;
+            writeln!(ui.stdout(), "-- Profile:")?;
+            write_profile(&mut ui.stdout(), profile, 0, true)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_profile(
+    out: &mut dyn Write,
+    profile: &RevsetProfile,
+    depth: usize,
+    with_stats: bool,
+) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+    write!(out, "{indent}{} ({})", profile.label, profile.strategy)?;
+    if with_stats {
+        let stats = profile.stats();
+        write!(
+            out,
+            ": yielded {}, tested {}, evaluation {:?}, iteration {:?}",
+            stats.num_yielded(),
+            stats.num_tested(),
+            stats.evaluation_time(),
+            stats.iteration_time()
+        )?;
+    }
+    writeln!(out)?;
+    for child in &profile.children {
+        write_profile(out, child, depth + 1, with_stats)?;
+    }
     Ok(())
 }
@@ -107,6 +107,11 @@ pub(crate) struct DescribeArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Don't run the Git commit-msg hook
+    ///
+    /// The hook is only run if enabled by the `git.run-hooks` config.
+    #[arg(long)]
+    no_verify: bool,
 }
 
 #[instrument(skip_all)]
@@ -130,6 +135,11 @@ pub(crate) fn cmd_describe(
     }
     workspace_command.check_rewritable(commits.iter().ids())?;
     let text_editor = workspace_command.text_editor()?;
+    let git_hooks = if args.no_verify {
+        None
+    } else {
+        workspace_command.git_hooks()?
+    };
 
     let mut tx = workspace_command.start_transaction();
     let tx_description = if commits.len() == 1 {
@@ -246,7 +256,7 @@ pub(crate) fn cmd_describe(
 
     // Filter out unchanged commits to avoid rebasing descendants in
     // `transform_descendants` below unnecessarily.
-    let commit_builders: HashMap<_, _> = iter::zip(&commits, commit_builders)
+    let mut commit_builders: HashMap<_, _> = iter::zip(&commits, commit_builders)
         .filter(|(old_commit, commit_builder)| {
             old_commit.description() != commit_builder.description()
                 || args.reset_author
@@ -258,6 +268,23 @@ pub(crate) fn cmd_describe(
         .map(|(old_commit, commit_builder)| (old_commit.id(), commit_builder))
         .collect();
 
+    if let Some(git_hooks) = &git_hooks {
+        // Run the hook in topological order, like the descriptions are edited
+        for commit in commits.iter().rev() {
+            let Some(commit_builder) = commit_builders.get_mut(commit.id()) else {
+                continue;
+            };
+            if commit_builder.description().is_empty()
+                || commit_builder.description() == commit.description()
+            {
+                continue;
+            }
+            let description =
+                git_hooks.run_commit_msg(ui, commit_builder.description(), use_editor)?;
+            commit_builder.set_description(description);
+        }
+    }
+
     let mut num_described = 0;
     let mut num_reparented = 0;
     // Even though `MutRepo::rewrite_commit` and `MutRepo::rebase_descendants` can
@@ -169,6 +169,7 @@ pub(crate) fn cmd_diff(
         }
     }
 
+    let tree_matcher = fileset_expression.to_tree_matcher(vec![from_tree.clone(), to_tree.clone()]);
     let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
     diff_renderer.show_diff(
@@ -176,7 +177,7 @@ pub(crate) fn cmd_diff(
         ui.stdout_formatter().as_mut(),
         &from_tree,
         &to_tree,
-        &matcher,
+        tree_matcher.as_ref(),
         &copy_records,
         ui.term_width(),
     )?;
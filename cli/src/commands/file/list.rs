@@ -12,14 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
+use std::sync::Arc;
+
 use clap_complete::ArgValueCompleter;
+use jj_lib::file_util;
+use jj_lib::file_util::PathError;
+use jj_lib::gitignore::GitIgnoreFile;
+use jj_lib::gitignore::GitIgnoreRule;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::repo_path::RepoPathComponent;
 use tracing::instrument;
 
+use crate::cli_util::to_path_matcher;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
 use crate::commit_templater::TreeEntry;
 use crate::complete;
+use crate::formatter::Formatter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -49,6 +64,14 @@ pub(crate) struct FileListArgs {
     #[arg(long, short = 'T')]
     template: Option<String>,
 
+    /// List untracked files in the working copy which are ignored
+    ///
+    /// Each path is printed along with the ignore file, line number, and
+    /// pattern which ignored it, similar to `git check-ignore -v`. Ignored
+    /// directories are listed with a trailing `/` and are not descended into.
+    #[arg(long, conflicts_with_all = ["revision", "template"])]
+    ignored: bool,
+
     /// Only list files matching these prefixes (instead of all files)
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
@@ -63,9 +86,14 @@ pub(crate) fn cmd_file_list(
     let workspace_command = command.workspace_helper(ui)?;
     let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
     let tree = commit.tree()?;
+    if args.ignored {
+        let matcher = to_path_matcher(&workspace_command.parse_file_patterns(ui, &args.paths)?)?;
+        ui.request_pager();
+        return list_ignored_paths(ui, command, &workspace_command, &tree, matcher.as_ref());
+    }
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
-        .to_matcher();
+        .to_tree_matcher(vec![tree.clone()]);
     let template: TemplateRenderer<TreeEntry> = {
         let language = workspace_command.commit_template_language();
         let text = match &args.template {
@@ -88,3 +116,114 @@ pub(crate) fn cmd_file_list(
     }
     Ok(())
 }
+
+fn list_ignored_paths(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    tree: &MergedTree,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    let mut formatter = ui.stdout_formatter();
+    let lister = IgnoredPathLister {
+        command,
+        workspace_command,
+        tree,
+        matcher,
+    };
+    lister.visit_dir(
+        formatter.as_mut(),
+        RepoPathBuf::root(),
+        workspace_command.workspace_root(),
+        &workspace_command.base_ignores()?,
+    )
+}
+
+struct IgnoredPathLister<'a> {
+    command: &'a CommandHelper,
+    workspace_command: &'a WorkspaceCommandHelper,
+    tree: &'a MergedTree,
+    matcher: &'a dyn Matcher,
+}
+
+impl IgnoredPathLister<'_> {
+    fn visit_dir(
+        &self,
+        formatter: &mut dyn Formatter,
+        dir: RepoPathBuf,
+        disk_dir: &Path,
+        parent_ignores: &Arc<GitIgnoreFile>,
+    ) -> Result<(), CommandError> {
+        if self.matcher.visit(&dir).is_nothing() {
+            return Ok(());
+        }
+        let git_ignore = parent_ignores.chain_with_dir(&dir.to_internal_dir_string(), disk_dir)?;
+        let mut entries = disk_dir
+            .read_dir()
+            .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+            .map_err(|err| PathError {
+                path: disk_dir.to_owned(),
+                error: err,
+            })?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str() else {
+                continue;
+            };
+            if dir.is_root() && (name == ".jj" || name == ".git") {
+                continue;
+            }
+            let Ok(name) = RepoPathComponent::new(name) else {
+                continue;
+            };
+            let path = dir.join(name);
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            if is_dir {
+                match git_ignore.matched_rule(&path.to_internal_dir_string()) {
+                    Some(rule) if !rule.is_negative => {
+                        if !self.matcher.visit(&path).is_nothing() {
+                            self.write_path(formatter, &path, true, &rule)?;
+                        }
+                    }
+                    _ => self.visit_dir(formatter, path, &entry.path(), &git_ignore)?,
+                }
+            } else if let Some(rule) = git_ignore.matched_rule(path.as_internal_file_string()) {
+                if !rule.is_negative
+                    && self.matcher.matches(&path)
+                    && self.tree.path_value(&path)?.is_absent()
+                {
+                    self.write_path(formatter, &path, false, &rule)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_path(
+        &self,
+        formatter: &mut dyn Formatter,
+        path: &RepoPath,
+        is_dir: bool,
+        rule: &GitIgnoreRule,
+    ) -> Result<(), CommandError> {
+        let source = file_util::relative_path(self.command.cwd(), &rule.source);
+        write!(
+            formatter.labeled("source"),
+            "{}:{}:{}",
+            source.display(),
+            rule.line_num,
+            rule.pattern
+        )?;
+        write!(
+            formatter,
+            "\t{}",
+            self.workspace_command.format_file_path(path)
+        )?;
+        if is_dir {
+            write!(formatter, "{}", std::path::MAIN_SEPARATOR)?;
+        }
+        writeln!(formatter)?;
+        Ok(())
+    }
+}
@@ -88,7 +88,7 @@ pub(crate) fn cmd_file_show(
         }
     }
 
-    let matcher = fileset_expression.to_matcher();
+    let matcher = fileset_expression.to_tree_matcher(vec![tree.clone()]);
     ui.request_pager();
     write_tree_entries(
         ui,
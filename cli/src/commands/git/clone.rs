@@ -65,6 +65,13 @@ pub struct GitCloneArgs {
     /// Create a shallow clone of the given depth
     #[arg(long)]
     depth: Option<NonZeroU32>,
+    /// Create a partial clone that omits the objects matching the filter spec
+    ///
+    /// For example, `--filter=blob:none` omits all file contents, which are
+    /// then fetched from the remote when needed. The filter is remembered for
+    /// subsequent fetches. The remote must support filtering.
+    #[arg(long, value_name = "FILTER_SPEC")]
+    filter: Option<String>,
 }
 
 fn clone_destination_for_source(source: &str) -> Option<&str> {
@@ -119,7 +126,7 @@ pub fn cmd_git_clone(
     let clone_result = (|| -> Result<_, CommandError> {
         let workspace_command = init_workspace(ui, command, &canonical_wc_path, args.colocate)?;
         let mut workspace_command =
-            configure_remote(ui, command, workspace_command, remote_name, &source)?;
+            configure_remote(ui, command, workspace_command, remote_name, &source, args)?;
         let default_branch = fetch_new_remote(ui, &mut workspace_command, remote_name, args.depth)?;
         Ok((workspace_command, default_branch))
     })();
@@ -190,8 +197,22 @@ fn configure_remote(
     workspace_command: WorkspaceCommandHelper,
     remote_name: &RemoteName,
     source: &str,
+    args: &GitCloneArgs,
 ) -> Result<WorkspaceCommandHelper, CommandError> {
     git::add_remote(workspace_command.repo().store(), remote_name, source)?;
+    let mut workspace_command = reload_workspace(ui, command, workspace_command)?;
+    if let Some(filter) = &args.filter {
+        git::set_up_partial_clone(workspace_command.repo().store(), remote_name, filter)?;
+        workspace_command = reload_workspace(ui, command, workspace_command)?;
+    }
+    Ok(workspace_command)
+}
+
+fn reload_workspace(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: WorkspaceCommandHelper,
+) -> Result<WorkspaceCommandHelper, CommandError> {
     // Reload workspace to apply new remote configuration to
     // gix::ThreadSafeRepository behind the store.
     let workspace = command.load_workspace_at(
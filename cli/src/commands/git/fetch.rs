@@ -13,10 +13,13 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::num::NonZeroU32;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::git;
 use jj_lib::git::GitFetch;
 use jj_lib::ref_name::RemoteName;
@@ -27,6 +30,7 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::config_error;
+use crate::command_error::internal_error;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
@@ -44,8 +48,12 @@ pub struct GitFetchArgs {
     /// Fetch only some of the branches
     ///
     /// By default, the specified name matches exactly. Use `glob:` prefix to
-    /// expand `*` as a glob, e.g. `--branch 'glob:push-*'`. Other wildcard
-    /// characters such as `?` are *not* supported.
+    /// select branches by [wildcard pattern], e.g. `--branch 'glob:push-*'`.
+    /// Patterns other than `*` globs, such as `regex:`, are matched against
+    /// the list of branches on the remote.
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
     #[arg(
         long, short,
         alias = "bookmark",
@@ -76,6 +84,12 @@ pub struct GitFetchArgs {
     /// Fetch from all remotes
     #[arg(long, conflicts_with = "remotes")]
     all_remotes: bool,
+    /// Limit fetching to the given number of commits from the tip of each
+    /// remote branch
+    ///
+    /// This can also be used to deepen the history of a shallow clone.
+    #[arg(long)]
+    depth: Option<NonZeroU32>,
 }
 
 #[tracing::instrument(skip_all)]
@@ -119,7 +133,7 @@ pub fn cmd_git_fetch(
         .collect_vec();
 
     let mut tx = workspace_command.start_transaction();
-    do_git_fetch(ui, &mut tx, &remotes, &args.branch)?;
+    let history_deepened = do_git_fetch(ui, &mut tx, &remotes, &args.branch, args.depth)?;
     tx.finish(
         ui,
         format!(
@@ -127,6 +141,9 @@ pub fn cmd_git_fetch(
             remotes.iter().map(|n| n.as_symbol()).join(",")
         ),
     )?;
+    if history_deepened {
+        reindex_deepened_history(ui, &workspace_command)?;
+    }
     Ok(())
 }
 
@@ -169,18 +186,62 @@ fn do_git_fetch(
     tx: &mut WorkspaceCommandTransaction,
     remotes: &[&RemoteName],
     branch_names: &[StringPattern],
-) -> Result<(), CommandError> {
+    depth: Option<NonZeroU32>,
+) -> Result<bool, CommandError> {
     let git_settings = tx.settings().git_settings()?;
+    let old_shallow_ids = git::shallow_commit_ids(tx.repo().store())?;
     let mut git_fetch = GitFetch::new(tx.repo_mut(), &git_settings)?;
 
     for remote_name in remotes {
         with_remote_git_callbacks(ui, |callbacks| {
-            git_fetch.fetch(remote_name, branch_names, callbacks, None)
+            git_fetch.fetch(remote_name, branch_names, callbacks, depth)
         })?;
     }
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
-    warn_if_branches_not_found(ui, tx, branch_names, remotes)
+    warn_if_branches_not_found(ui, tx, branch_names, remotes)?;
+    is_history_deepened(tx, &old_shallow_ids)
+}
+
+/// Returns true if the parents of previously shallow commits were fetched.
+fn is_history_deepened(
+    tx: &WorkspaceCommandTransaction,
+    old_shallow_ids: &[CommitId],
+) -> Result<bool, CommandError> {
+    if old_shallow_ids.is_empty() {
+        return Ok(false);
+    }
+    let new_shallow_ids: HashSet<_> = git::shallow_commit_ids(tx.repo().store())?
+        .into_iter()
+        .collect();
+    Ok(old_shallow_ids
+        .iter()
+        .any(|id| !new_shallow_ids.contains(id)))
+}
+
+/// Shallow commits are indexed as children of the root commit. If their real
+/// parents got fetched, the index has to be rebuilt to pick them up.
+fn reindex_deepened_history(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let index_store = repo.loader().index_store();
+    let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() else {
+        writeln!(
+            ui.warning_default(),
+            "The history of shallow commits was fetched, but indexes of type '{}' can't be \
+             rebuilt to include it.",
+            index_store.name()
+        )?;
+        return Ok(());
+    };
+    writeln!(ui.status(), "Reindexing to include the fetched history")?;
+    default_index_store.reinit().map_err(internal_error)?;
+    default_index_store
+        .build_index_at_operation(repo.operation(), repo.store())
+        .map_err(internal_error)?;
+    Ok(())
 }
 
 fn warn_if_branches_not_found(
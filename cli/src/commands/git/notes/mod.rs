@@ -0,0 +1,58 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod remove;
+mod set;
+mod show;
+
+use clap::Subcommand;
+
+use self::remove::cmd_git_notes_remove;
+use self::remove::GitNotesRemoveArgs;
+use self::set::cmd_git_notes_set;
+use self::set::GitNotesSetArgs;
+use self::show::cmd_git_notes_show;
+use self::show::GitNotesShowArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Manage Git notes attached to commits
+///
+/// Notes are stored in the `refs/notes/<namespace>` refs of the underlying
+/// Git repo, in the same format as `git notes` uses. Unlike descriptions,
+/// notes can be changed without rewriting the commit.
+///
+/// When a commit is rewritten, notes in the namespaces listed in the
+/// `git.notes-namespaces` config are copied to the new commit, so they follow
+/// the change ID. Notes are also available in templates as
+/// `commit.notes(namespace)`.
+#[derive(Subcommand, Clone, Debug)]
+pub enum GitNotesCommand {
+    Remove(GitNotesRemoveArgs),
+    Set(GitNotesSetArgs),
+    Show(GitNotesShowArgs),
+}
+
+pub fn cmd_git_notes(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &GitNotesCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        GitNotesCommand::Remove(args) => cmd_git_notes_remove(ui, command, args),
+        GitNotesCommand::Set(args) => cmd_git_notes_set(ui, command, args),
+        GitNotesCommand::Show(args) => cmd_git_notes_show(ui, command, args),
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::git;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Remove the note attached to a revision
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitNotesRemoveArgs {
+    /// The revision whose note to remove
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCompleter::new(complete::revset_expression_all)
+    )]
+    revision: RevisionArg,
+    /// The notes namespace (`refs/notes/<NAMESPACE>`)
+    #[arg(long, default_value = "commits")]
+    namespace: String,
+}
+
+pub fn cmd_git_notes_remove(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitNotesRemoveArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    git::write_notes(
+        workspace_command.repo().store(),
+        &args.namespace,
+        [(commit.id().clone(), None)],
+        &command.settings().signature(),
+        "Notes removed by 'jj git notes remove'",
+    )?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Removed note from ")?;
+        workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::git;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::description_util::edit_description;
+use crate::description_util::join_message_paragraphs;
+use crate::text_util::complete_newline;
+use crate::ui::Ui;
+
+/// Add or replace the note attached to a revision
+///
+/// Without `--message`, an editor is opened with the current note. Setting an
+/// empty note removes it.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitNotesSetArgs {
+    /// The revision to attach the note to
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCompleter::new(complete::revset_expression_all)
+    )]
+    revision: RevisionArg,
+    /// The notes namespace (`refs/notes/<NAMESPACE>`)
+    #[arg(long, default_value = "commits")]
+    namespace: String,
+    /// The note to use (don't open editor)
+    #[arg(long = "message", short, value_name = "MESSAGE")]
+    message_paragraphs: Vec<String>,
+}
+
+pub fn cmd_git_notes_set(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitNotesSetArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let store = workspace_command.repo().store();
+    let note = if args.message_paragraphs.is_empty() {
+        let mut notes = git::read_notes(store, &args.namespace)?;
+        let old_note = notes.remove(commit.id()).unwrap_or_default();
+        let text_editor = workspace_command.text_editor()?;
+        edit_description(&text_editor, &old_note)?
+    } else {
+        join_message_paragraphs(&args.message_paragraphs)
+    };
+    let note = complete_newline(note.trim_end());
+    let (note, message) = if note.is_empty() {
+        (None, "Notes removed by 'jj git notes set'")
+    } else {
+        (Some(note), "Notes added by 'jj git notes set'")
+    };
+    let removed = note.is_none();
+    git::write_notes(
+        store,
+        &args.namespace,
+        [(commit.id().clone(), note)],
+        &command.settings().signature(),
+        message,
+    )?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        if removed {
+            write!(formatter, "Removed note from ")?;
+        } else {
+            write!(formatter, "Set note on ")?;
+        }
+        workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::git;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Show the note attached to a revision
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitNotesShowArgs {
+    /// The revision whose note to show
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCompleter::new(complete::revset_expression_all)
+    )]
+    revision: RevisionArg,
+    /// The notes namespace (`refs/notes/<NAMESPACE>`)
+    #[arg(long, default_value = "commits")]
+    namespace: String,
+}
+
+pub fn cmd_git_notes_show(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitNotesShowArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let mut notes = git::read_notes(workspace_command.repo().store(), &args.namespace)?;
+    let Some(note) = notes.remove(commit.id()) else {
+        return Err(user_error(format!(
+            "No note found for commit {} in namespace '{}'",
+            short_commit_hash(commit.id()),
+            args.namespace
+        )));
+    };
+    ui.request_pager();
+    ui.stdout().write_all(note.as_bytes())?;
+    Ok(())
+}
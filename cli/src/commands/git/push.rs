@@ -17,20 +17,28 @@ use std::collections::HashSet;
 use std::fmt;
 use std::io;
 use std::io::Write as _;
+use std::process::Stdio;
+use std::rc::Rc;
 
 use clap::ArgGroup;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use indexmap::IndexSet;
 use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
+use jj_lib::git::GitPushOptions;
 use jj_lib::git::GitPushStats;
+use jj_lib::git::GitRefUpdate;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRef;
+use jj_lib::ref_name::GitRefNameBuf;
 use jj_lib::ref_name::RefName;
 use jj_lib::ref_name::RefNameBuf;
 use jj_lib::ref_name::RemoteName;
@@ -42,9 +50,12 @@ use jj_lib::refs::BookmarkPushUpdate;
 use jj_lib::refs::LocalAndRemoteRef;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::UserRevsetExpression;
 use jj_lib::settings::UserSettings;
+use jj_lib::signing::SigStatus;
 use jj_lib::signing::SignBehavior;
 use jj_lib::str_util::StringPattern;
+use jj_lib::trailer::parse_description_trailers;
 use jj_lib::view::View;
 
 use crate::cli_util::has_tracked_remote_bookmarks;
@@ -58,10 +69,13 @@ use crate::command_error::cli_error;
 use crate::command_error::cli_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
+use crate::config::CommandNameAndArgs;
 use crate::formatter::Formatter;
+use crate::git_hooks::PrePushUpdate;
 use crate::git_util::with_remote_git_callbacks;
 use crate::revset_util::parse_bookmark_name;
 use crate::ui::Ui;
@@ -191,6 +205,45 @@ pub struct GitPushArgs {
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
+    /// Print the push plan as JSON to stdout
+    ///
+    /// The plan lists every ref to update with its old and new target, whether
+    /// the update is a fast-forward or a forced update, and the commits that
+    /// will be uploaded.
+    #[arg(long, requires = "dry_run")]
+    json: bool,
+    /// Don't run the pre-push hooks
+    ///
+    /// This skips the command configured by `git.pre-push-hook`, and the Git
+    /// `pre-push` hook if `git.run-hooks` is enabled.
+    #[arg(long)]
+    no_verify: bool,
+    /// Push a single revision to this remote ref instead of a bookmark
+    ///
+    /// The revision is selected by `--revisions` and defaults to `@`. No
+    /// bookmark is created. Gerrit-style push options can be appended to the
+    /// ref, e.g. `--to 'refs/for/main%topic=my-topic'`.
+    ///
+    /// When pushing to `refs/for/*`, a `Change-Id` trailer derived from the
+    /// change ID is added to the pushed commits which don't have one yet, so
+    /// that pushing the same change again updates the same review.
+    #[arg(
+        long,
+        value_name = "REF",
+        conflicts_with_all = ["bookmark", "change", "named", "all", "tracked", "deleted"],
+    )]
+    to: Option<String>,
+    /// Update either all refs on the remote or none of them
+    ///
+    /// Requires support from the remote.
+    #[arg(long)]
+    atomic: bool,
+    /// Transmit this option to the server (can be repeated)
+    ///
+    /// The options are passed to the server's hooks, like Git's
+    /// `--push-option`.
+    #[arg(long = "option", short = 'o', value_name = "KEY=VALUE")]
+    options: Vec<String>,
 }
 
 fn make_bookmark_term(bookmark_names: &[impl fmt::Display]) -> String {
@@ -209,6 +262,37 @@ enum BookmarkMoveDirection {
     Sideways,
 }
 
+/// Machine-readable description of what `jj git push` is about to do.
+///
+/// This is printed by `--dry-run --json`, and fed to the pre-push hook.
+#[derive(Debug, serde::Serialize)]
+struct PushPlan {
+    remote: String,
+    updates: Vec<PushPlanUpdate>,
+    /// Commits that the remote doesn't have yet, as full hex ids.
+    commits: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct PushPlanUpdate {
+    /// Name of the bookmark if the ref is a branch.
+    bookmark: Option<String>,
+    #[serde(rename = "ref")]
+    git_ref: String,
+    old_target: Option<String>,
+    new_target: Option<String>,
+    kind: PushPlanUpdateKind,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum PushPlanUpdateKind {
+    Create,
+    Delete,
+    FastForward,
+    Force,
+}
+
 pub fn cmd_git_push(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -223,6 +307,9 @@ pub fn cmd_git_push(
         default_remote = get_default_push_remote(ui, &workspace_command)?;
         &default_remote
     };
+    if let Some(destination) = &args.to {
+        return push_revision_to_ref(ui, workspace_command, remote, destination, args);
+    }
 
     let mut tx = workspace_command.start_transaction();
     let view = tx.repo().view();
@@ -392,6 +479,9 @@ pub fn cmd_git_push(
     }
     if bookmark_updates.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
+        if !args.dry_run {
+            push_git_notes(ui, &tx, remote, args)?;
+        }
         return Ok(());
     }
 
@@ -400,8 +490,12 @@ pub fn cmd_git_push(
     } else {
         None
     };
-    let commits_to_sign =
-        validate_commits_ready_to_push(ui, &bookmark_updates, remote, &tx, args, sign_behavior)?;
+    let new_heads = bookmark_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .collect_vec();
+    let (commits_to_sign, untrusted_commit_ids) =
+        validate_commits_ready_to_push(ui, new_heads, remote, &tx, args, sign_behavior)?;
     if !args.dry_run && !commits_to_sign.is_empty() {
         if let Some(sign_behavior) = sign_behavior {
             let num_updated_signatures = commits_to_sign.len();
@@ -409,6 +503,7 @@ pub fn cmd_git_push(
             (num_rebased_descendants, bookmark_updates) = sign_commits_before_push(
                 &mut tx,
                 commits_to_sign,
+                &untrusted_commit_ids,
                 sign_behavior,
                 bookmark_updates,
             )?;
@@ -436,6 +531,15 @@ pub fn cmd_git_push(
         print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &bookmark_updates)?;
     }
 
+    let ref_updates = bookmark_updates
+        .iter()
+        .map(|(name, update)| GitRefUpdate {
+            qualified_name: format!("refs/heads/{name}", name = name.as_str()).into(),
+            expected_current_target: update.old_target.clone(),
+            new_target: update.new_target.clone(),
+        })
+        .collect_vec();
+    check_push_plan(ui, &tx, remote, &ref_updates, args)?;
     if args.dry_run {
         writeln!(ui.status(), "Dry-run requested, not pushing.")?;
         return Ok(());
@@ -446,13 +550,290 @@ pub fn cmd_git_push(
     };
     let git_settings = tx.settings().git_settings()?;
     let push_stats = with_remote_git_callbacks(ui, |cb| {
-        git::push_branches(tx.repo_mut(), &git_settings, remote, &targets, cb)
+        git::push_branches(
+            tx.repo_mut(),
+            &git_settings,
+            remote,
+            &targets,
+            cb,
+            &push_options(args),
+        )
     })?;
     process_push_stats(&push_stats)?;
+    push_git_notes(ui, &tx, remote, args)?;
     tx.finish(ui, tx_description)?;
     Ok(())
 }
 
+/// Pushes a single revision to an arbitrary remote ref given by `--to`.
+fn push_revision_to_ref(
+    ui: &mut Ui,
+    mut workspace_command: WorkspaceCommandHelper,
+    remote: &RemoteName,
+    destination: &str,
+    args: &GitPushArgs,
+) -> Result<(), CommandError> {
+    // Gerrit accepts push options after the ref name, e.g. "refs/for/main%topic=foo"
+    let (ref_name, _) = destination.split_once('%').unwrap_or((destination, ""));
+    if !ref_name.starts_with("refs/") {
+        return Err(user_error_with_hint(
+            format!("Invalid destination ref: {destination}"),
+            "The destination must be a full ref name, e.g. refs/for/main",
+        ));
+    }
+    let revision = match args.revisions.as_slice() {
+        [] => RevisionArg::AT,
+        [revision] => revision.clone(),
+        _ => return Err(cli_error("--to accepts a single revision")),
+    };
+    let commit = workspace_command.resolve_single_rev(ui, &revision)?;
+    // jj only knows the remote state of bookmarks. Other refs are expected not
+    // to exist on the remote, which is always the case for Gerrit's magic
+    // refs/for/* refs.
+    let expected_current_target = ref_name.strip_prefix("refs/heads/").and_then(|name| {
+        let remote_symbol = RefName::new(name).to_remote_symbol(remote);
+        let remote_ref = workspace_command
+            .repo()
+            .view()
+            .get_remote_bookmark(remote_symbol);
+        remote_ref.target.as_normal().cloned()
+    });
+
+    let mut tx = workspace_command.start_transaction();
+    let sign_behavior = if tx.settings().get_bool("git.sign-on-push")? {
+        Some(SignBehavior::Own)
+    } else {
+        None
+    };
+    let (commits_to_sign, untrusted_commit_ids) = validate_commits_ready_to_push(
+        ui,
+        vec![commit.id().clone()],
+        remote,
+        &tx,
+        args,
+        sign_behavior,
+    )?;
+    let commits_without_change_id = if ref_name.starts_with("refs/for/") {
+        let workspace_helper = tx.base_workspace_helper();
+        let commits_to_push =
+            commits_to_push_expression(workspace_helper, remote, vec![commit.id().clone()]);
+        workspace_helper
+            .attach_revset_evaluator(commits_to_push)
+            .evaluate_to_commits()?
+            .filter_ok(|commit| {
+                !parse_description_trailers(commit.description())
+                    .iter()
+                    .any(|trailer| trailer.key == "Change-Id")
+            })
+            .try_collect()?
+    } else {
+        vec![]
+    };
+
+    let mut new_target = commit.id().clone();
+    let needs_rewrite = !commits_without_change_id.is_empty() || !commits_to_sign.is_empty();
+    if !args.dry_run && needs_rewrite {
+        let num_added_change_ids = commits_without_change_id.len();
+        let num_updated_signatures = commits_to_sign.len();
+        let num_rebased_descendants;
+        (num_rebased_descendants, new_target) = rewrite_commits_before_push_to_ref(
+            &mut tx,
+            commits_without_change_id,
+            commits_to_sign,
+            &untrusted_commit_ids,
+            sign_behavior,
+            new_target,
+        )?;
+        if let Some(mut formatter) = ui.status_formatter() {
+            if num_added_change_ids > 0 {
+                writeln!(
+                    formatter,
+                    "Added Change-Id trailers to {num_added_change_ids} commits"
+                )?;
+            }
+            if num_updated_signatures > 0 {
+                writeln!(
+                    formatter,
+                    "Updated signatures of {num_updated_signatures} commits"
+                )?;
+            }
+            if num_rebased_descendants > 0 {
+                writeln!(
+                    formatter,
+                    "Rebased {num_rebased_descendants} descendant commits"
+                )?;
+            }
+        }
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Changes to push to {remote}:",
+            remote = remote.as_symbol()
+        )?;
+        writeln!(
+            formatter,
+            "  Push commit {commit} to {destination}",
+            commit = short_commit_hash(&new_target)
+        )?;
+    }
+
+    let ref_updates = [GitRefUpdate {
+        qualified_name: destination.into(),
+        expected_current_target,
+        new_target: Some(new_target),
+    }];
+    check_push_plan(ui, &tx, remote, &ref_updates, args)?;
+    if args.dry_run {
+        writeln!(ui.status(), "Dry-run requested, not pushing.")?;
+        return Ok(());
+    }
+
+    let git_settings = tx.settings().git_settings()?;
+    let push_stats = with_remote_git_callbacks(ui, |cb| {
+        git::push_updates(
+            tx.repo(),
+            &git_settings,
+            remote,
+            &ref_updates,
+            cb,
+            &push_options(args),
+        )
+    })?;
+    process_push_stats(&push_stats)?;
+    // Record the new position of the bookmark, so the next push to it doesn't
+    // fail the lease. The tracking state of the bookmark is kept.
+    if let Some(name) = ref_name.strip_prefix("refs/heads/") {
+        let remote_symbol = RefName::new(name).to_remote_symbol(remote);
+        let state = tx.repo().view().get_remote_bookmark(remote_symbol).state;
+        let target = RefTarget::resolved(ref_updates[0].new_target.clone());
+        let git_ref_name: GitRefNameBuf =
+            format!("refs/remotes/{remote}/{name}", remote = remote.as_str()).into();
+        tx.repo_mut()
+            .set_git_ref_target(&git_ref_name, target.clone());
+        tx.repo_mut()
+            .set_remote_bookmark(remote_symbol, RemoteRef { target, state });
+    }
+    if tx.repo().has_changes() {
+        tx.finish(
+            ui,
+            format!(
+                "push {commit} to {destination} on git remote {remote}",
+                commit = commit.id().hex(),
+                remote = remote.as_symbol()
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+/// Prints the push plan if requested, and runs the pre-push hooks on it.
+///
+/// The `git.pre-push-hook` command runs first, then the Git `pre-push` hook
+/// if `git.run-hooks` is enabled. The push is aborted as soon as one of them
+/// fails, so the Git hook doesn't run if the command rejected the push.
+fn check_push_plan(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    remote: &RemoteName,
+    ref_updates: &[GitRefUpdate],
+    args: &GitPushArgs,
+) -> Result<(), CommandError> {
+    let plan = build_push_plan(tx, remote, ref_updates)?;
+    if args.json {
+        let mut stdout = ui.stdout();
+        serde_json::to_writer_pretty(&mut stdout, &plan).map_err(io::Error::from)?;
+        writeln!(stdout)?;
+    }
+
+    if !args.no_verify {
+        let hook = tx
+            .settings()
+            .get::<CommandNameAndArgs>("git.pre-push-hook")
+            .optional()?;
+        if let Some(hook) = hook {
+            run_pre_push_hook(ui, tx, &hook, &plan)?;
+        }
+        if let Some(git_hooks) = tx.base_workspace_helper().git_hooks()? {
+            let updates = ref_updates
+                .iter()
+                .map(|update| PrePushUpdate {
+                    local_ref: update.qualified_name.as_str().to_owned(),
+                    local_id: update.new_target.clone(),
+                    remote_ref: update.qualified_name.as_str().to_owned(),
+                    remote_id: update.expected_current_target.clone(),
+                })
+                .collect_vec();
+            git_hooks.run_pre_push(ui, remote, &updates)?;
+        }
+    }
+    Ok(())
+}
+
+fn push_options(args: &GitPushArgs) -> GitPushOptions {
+    GitPushOptions {
+        atomic: args.atomic,
+        remote_push_options: args.options.clone(),
+    }
+}
+
+/// Pushes the notes refs listed in `git.notes-namespaces` if `git.push-notes`
+/// is enabled.
+///
+/// The bookmarks have already been pushed at this point, so failing to push
+/// the notes is reported as a warning.
+fn push_git_notes(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    remote: &RemoteName,
+    args: &GitPushArgs,
+) -> Result<(), CommandError> {
+    let git_settings = tx.settings().git_settings()?;
+    if !git_settings.push_notes || git_settings.notes_namespaces.is_empty() {
+        return Ok(());
+    }
+    let result = with_remote_git_callbacks(ui, |cb| {
+        git::push_notes(tx.repo(), &git_settings, remote, cb, &push_options(args))
+    });
+    let push_stats = match result {
+        Ok(push_stats) => push_stats,
+        Err(err) => {
+            writeln!(ui.warning_default(), "Failed to push Git notes: {err}")?;
+            return Ok(());
+        }
+    };
+    if let Some(mut formatter) = ui.status_formatter() {
+        for reference in &push_stats.pushed {
+            write!(formatter, "Pushed notes ")?;
+            write!(formatter.labeled("git_ref"), "{}", reference.as_symbol())?;
+            writeln!(formatter)?;
+        }
+    }
+    let rejected = itertools::chain(
+        push_stats.rejected.iter().map(|(reference, _)| reference),
+        push_stats
+            .remote_rejected
+            .iter()
+            .map(|(reference, _)| reference),
+    )
+    .chain(&push_stats.atomic_rejected);
+    for reference in rejected {
+        writeln!(
+            ui.warning_default(),
+            "Failed to push notes {}",
+            reference.as_symbol()
+        )?;
+    }
+    if !push_stats.rejected.is_empty() {
+        writeln!(
+            ui.hint_default(),
+            "Run `jj git fetch` to merge the notes from the remote, then push again."
+        )?;
+    }
+    Ok(())
+}
+
 fn process_push_stats(push_stats: &GitPushStats) -> Result<(), CommandError> {
     if !push_stats.all_ok() {
         let mut error = user_error("Failed to push some bookmarks");
@@ -492,40 +873,62 @@ fn process_push_stats(push_stats: &GitPushStats) -> Result<(), CommandError> {
             });
             error.add_hint("Try checking if you have permission to push to all the bookmarks.");
         }
+        if !push_stats.atomic_rejected.is_empty() {
+            error.add_formatted_hint_with(|formatter| {
+                writeln!(
+                    formatter,
+                    "The following references were not updated because the push is atomic:"
+                )?;
+                for reference in &push_stats.atomic_rejected {
+                    write!(formatter, "  ")?;
+                    write!(formatter.labeled("git_ref"), "{}", reference.as_symbol())?;
+                    writeln!(formatter)?;
+                }
+                Ok(())
+            });
+        }
         Err(error)
     } else {
         Ok(())
     }
 }
 
+/// Returns the mutable commits which will be uploaded by pushing `new_heads`,
+/// excluding the ones the remote already has.
+fn commits_to_push_expression(
+    workspace_helper: &WorkspaceCommandHelper,
+    remote: &RemoteName,
+    new_heads: Vec<CommitId>,
+) -> Rc<UserRevsetExpression> {
+    let old_heads = workspace_helper
+        .repo()
+        .view()
+        .remote_bookmarks(remote)
+        .flat_map(|(_, old_head)| old_head.target.added_ids())
+        .cloned()
+        .collect_vec();
+    RevsetExpression::commits(old_heads)
+        .union(workspace_helper.env().immutable_heads_expression())
+        .range(&RevsetExpression::commits(new_heads))
+}
+
 /// Validates that the commits that will be pushed are ready (have authorship
 /// information, are not conflicted, etc.).
 ///
-/// Returns the list of commits which need to be signed.
+/// Returns the list of commits which need to be signed, and the IDs of those
+/// which must get a trusted signature to satisfy the signature policy. Since
+/// the key isn't known before signing, the latter have to be checked with
+/// [`check_new_signatures_trusted()`] once signed.
 fn validate_commits_ready_to_push(
     ui: &Ui,
-    bookmark_updates: &[(RefNameBuf, BookmarkPushUpdate)],
+    new_heads: Vec<CommitId>,
     remote: &RemoteName,
     tx: &WorkspaceCommandTransaction,
     args: &GitPushArgs,
     sign_behavior: Option<SignBehavior>,
-) -> Result<Vec<Commit>, CommandError> {
+) -> Result<(Vec<Commit>, HashSet<CommitId>), CommandError> {
     let workspace_helper = tx.base_workspace_helper();
-    let repo = workspace_helper.repo();
-
-    let new_heads = bookmark_updates
-        .iter()
-        .filter_map(|(_, update)| update.new_target.clone())
-        .collect_vec();
-    let old_heads = repo
-        .view()
-        .remote_bookmarks(remote)
-        .flat_map(|(_, old_head)| old_head.target.added_ids())
-        .cloned()
-        .collect_vec();
-    let commits_to_push = RevsetExpression::commits(old_heads)
-        .union(workspace_helper.env().immutable_heads_expression())
-        .range(&RevsetExpression::commits(new_heads));
+    let commits_to_push = commits_to_push_expression(workspace_helper, remote, new_heads);
 
     let settings = workspace_helper.settings();
     let private_revset_str = RevisionArg::from(settings.get_string("git.private-commits")?);
@@ -539,7 +942,15 @@ fn validate_commits_ready_to_push(
         sign_settings
     });
 
+    let untrusted_commit_ids: HashSet<CommitId> = workspace_helper
+        .find_untrusted_commits(ui, &commits_to_push)?
+        .iter()
+        .ids()
+        .cloned()
+        .collect();
+
     let mut commits_to_sign = vec![];
+    let mut commits_to_sign_trusted = HashSet::new();
 
     for commit in workspace_helper
         .attach_revset_evaluator(commits_to_push)
@@ -568,6 +979,13 @@ fn validate_commits_ready_to_push(
         if !args.allow_private && is_private {
             reasons.push("it is private");
         }
+        let will_be_signed = sign_settings.as_ref().is_some_and(|sign_settings| {
+            !commit.is_signed() && sign_settings.should_sign(commit.store_commit())
+        });
+        let is_untrusted = untrusted_commit_ids.contains(commit.id());
+        if !will_be_signed && is_untrusted {
+            reasons.push("it doesn't have a trusted signature");
+        }
         if !reasons.is_empty() {
             let mut error = user_error(format!(
                 "Won't push commit {} since {}",
@@ -584,15 +1002,64 @@ fn validate_commits_ready_to_push(
                     "Configured git.private-commits: '{private_revset_str}'",
                 ));
             }
+            if !will_be_signed && is_untrusted {
+                error.add_hint(format!(
+                    "Configured signing.policy.require-signed: '{}'",
+                    settings.get_string("signing.policy.require-signed")?
+                ));
+            }
             return Err(error);
         }
-        if let Some(sign_settings) = &sign_settings {
-            if !commit.is_signed() && sign_settings.should_sign(commit.store_commit()) {
-                commits_to_sign.push(commit);
+        if will_be_signed {
+            if is_untrusted {
+                commits_to_sign_trusted.insert(commit.id().clone());
             }
+            commits_to_sign.push(commit);
+        }
+    }
+    Ok((commits_to_sign, commits_to_sign_trusted))
+}
+
+/// Checks that the commits signed on push have a good signature from one of
+/// the `signing.policy.allowed-keys`, or from any key if none is configured.
+///
+/// `signed_commits` pairs the commits that had to be signed with a trusted key
+/// with the signed commits.
+fn check_new_signatures_trusted(
+    tx: &WorkspaceCommandTransaction,
+    signed_commits: &[(Commit, Commit)],
+) -> Result<(), CommandError> {
+    let settings = tx.settings();
+    let allowed_keys: Vec<String> = settings.get("signing.policy.allowed-keys")?;
+    for (old_commit, commit) in signed_commits {
+        // Signatures that can't be verified aren't good signatures.
+        let verification = commit.verification().ok().flatten();
+        let is_trusted = verification.is_some_and(|verification| {
+            verification.status == SigStatus::Good
+                && (allowed_keys.is_empty()
+                    || verification
+                        .key
+                        .is_some_and(|key| allowed_keys.contains(&key)))
+        });
+        if !is_trusted {
+            let mut error = user_error(format!(
+                "Won't push commit {} since it doesn't have a trusted signature",
+                short_commit_hash(old_commit.id())
+            ));
+            error.add_formatted_hint_with(|formatter| {
+                write!(formatter, "Rejected commit: ")?;
+                tx.base_workspace_helper()
+                    .write_commit_summary(formatter, old_commit)?;
+                Ok(())
+            });
+            error.add_hint(
+                "The signature created by git.sign-on-push isn't from one of the configured \
+                 signing.policy.allowed-keys",
+            );
+            return Err(error);
         }
     }
-    Ok(commits_to_sign)
+    Ok(())
 }
 
 /// Signs commits before pushing.
@@ -602,21 +1069,27 @@ fn validate_commits_ready_to_push(
 fn sign_commits_before_push(
     tx: &mut WorkspaceCommandTransaction,
     commits_to_sign: Vec<Commit>,
+    untrusted_commit_ids: &HashSet<CommitId>,
     sign_behavior: SignBehavior,
     bookmark_updates: Vec<(RefNameBuf, BookmarkPushUpdate)>,
 ) -> Result<(usize, Vec<(RefNameBuf, BookmarkPushUpdate)>), CommandError> {
     let commit_ids: IndexSet<CommitId> = commits_to_sign.iter().ids().cloned().collect();
     let mut old_to_new_commits_map: HashMap<CommitId, CommitId> = HashMap::new();
+    let mut signed_commits = vec![];
     let mut num_rebased_descendants = 0;
     tx.repo_mut()
         .transform_descendants(commit_ids.iter().cloned().collect_vec(), |rewriter| {
-            let old_commit_id = rewriter.old_commit().id().clone();
+            let old_commit = rewriter.old_commit().clone();
+            let old_commit_id = old_commit.id().clone();
             if commit_ids.contains(&old_commit_id) {
                 let commit = rewriter
                     .reparent()
                     .set_sign_behavior(sign_behavior)
                     .write()?;
-                old_to_new_commits_map.insert(old_commit_id, commit.id().clone());
+                old_to_new_commits_map.insert(old_commit_id.clone(), commit.id().clone());
+                if untrusted_commit_ids.contains(&old_commit_id) {
+                    signed_commits.push((old_commit, commit));
+                }
             } else {
                 num_rebased_descendants += 1;
                 let commit = rewriter.reparent().write()?;
@@ -624,6 +1097,7 @@ fn sign_commits_before_push(
             }
             Ok(())
         })?;
+    check_new_signatures_trusted(tx, &signed_commits)?;
 
     let bookmark_updates = bookmark_updates
         .into_iter()
@@ -643,22 +1117,206 @@ fn sign_commits_before_push(
     Ok((num_rebased_descendants, bookmark_updates))
 }
 
+fn bookmark_move_direction(
+    repo: &dyn Repo,
+    old_target: &CommitId,
+    new_target: &CommitId,
+) -> BookmarkMoveDirection {
+    assert_ne!(old_target, new_target);
+    if repo.index().is_ancestor(old_target, new_target) {
+        BookmarkMoveDirection::Forward
+    } else if repo.index().is_ancestor(new_target, old_target) {
+        BookmarkMoveDirection::Backward
+    } else {
+        BookmarkMoveDirection::Sideways
+    }
+}
+
+fn build_push_plan(
+    tx: &WorkspaceCommandTransaction,
+    remote: &RemoteName,
+    ref_updates: &[GitRefUpdate],
+) -> Result<PushPlan, CommandError> {
+    let repo = tx.repo();
+    let updates = ref_updates
+        .iter()
+        .map(|update| {
+            let kind = match (&update.expected_current_target, &update.new_target) {
+                (Some(old_target), Some(new_target)) => {
+                    match bookmark_move_direction(repo, old_target, new_target) {
+                        BookmarkMoveDirection::Forward => PushPlanUpdateKind::FastForward,
+                        BookmarkMoveDirection::Backward | BookmarkMoveDirection::Sideways => {
+                            PushPlanUpdateKind::Force
+                        }
+                    }
+                }
+                (Some(_), None) => PushPlanUpdateKind::Delete,
+                (None, _) => PushPlanUpdateKind::Create,
+            };
+            let git_ref = update.qualified_name.as_str();
+            PushPlanUpdate {
+                bookmark: git_ref.strip_prefix("refs/heads/").map(str::to_owned),
+                git_ref: git_ref.to_owned(),
+                old_target: update.expected_current_target.as_ref().map(|id| id.hex()),
+                new_target: update.new_target.as_ref().map(|id| id.hex()),
+                kind,
+            }
+        })
+        .collect();
+
+    let new_heads = ref_updates
+        .iter()
+        .filter_map(|update| update.new_target.clone())
+        .collect_vec();
+    let old_heads = repo
+        .view()
+        .remote_bookmarks(remote)
+        .flat_map(|(_, old_head)| old_head.target.added_ids())
+        .cloned()
+        .collect_vec();
+    let commits: Vec<_> = RevsetExpression::commits(old_heads)
+        .range(&RevsetExpression::commits(new_heads))
+        .evaluate(repo)?
+        .iter()
+        .map_ok(|id| id.hex())
+        .try_collect()?;
+
+    Ok(PushPlan {
+        remote: remote.as_str().to_owned(),
+        updates,
+        commits,
+    })
+}
+
+/// Runs the `git.pre-push-hook` command with the push plan as JSON on stdin.
+/// The push is aborted if the command exits with a non-zero status.
+fn run_pre_push_hook(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    hook: &CommandNameAndArgs,
+    plan: &PushPlan,
+) -> Result<(), CommandError> {
+    let plan_json = serde_json::to_vec_pretty(plan).unwrap();
+    let mut vars: HashMap<&str, &str> = HashMap::new();
+    vars.insert("remote", &plan.remote);
+    let mut command = hook.to_command_with_variables(&vars);
+    tracing::info!(?command, "running pre-push hook");
+    let mut child = command
+        .current_dir(tx.base_workspace_helper().workspace_root())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(ui.stderr_for_child()?)
+        .spawn()
+        .map_err(|err| {
+            user_error_with_message(format!("Failed to run pre-push hook '{hook}'"), err)
+        })?;
+    let mut stdin = child.stdin.take().unwrap();
+    let output = std::thread::scope(|s| {
+        s.spawn(move || {
+            // The hook may exit without reading the plan.
+            stdin.write_all(&plan_json).ok();
+        });
+        child.wait_with_output()
+    })?;
+    // Like Git, show the output of the hook on stderr.
+    ui.stderr().write_all(&output.stdout)?;
+    let exit_status = output.status;
+    tracing::info!(?command, ?exit_status, "pre-push hook exited");
+    if !exit_status.success() {
+        return Err(user_error_with_hint(
+            format!("Push rejected by `git.pre-push-hook` ({exit_status})"),
+            "Use --no-verify to skip the hook.",
+        ));
+    }
+    Ok(())
+}
+
+/// Adds `Change-Id` trailers and signatures to commits before pushing them to
+/// a single ref.
+///
+/// Returns the number of commits with rebased descendants and the rewritten
+/// `target`.
+fn rewrite_commits_before_push_to_ref(
+    tx: &mut WorkspaceCommandTransaction,
+    commits_without_change_id: Vec<Commit>,
+    commits_to_sign: Vec<Commit>,
+    untrusted_commit_ids: &HashSet<CommitId>,
+    sign_behavior: Option<SignBehavior>,
+    target: CommitId,
+) -> Result<(usize, CommitId), CommandError> {
+    let change_id_commit_ids: HashSet<CommitId> =
+        commits_without_change_id.iter().ids().cloned().collect();
+    let sign_commit_ids: HashSet<CommitId> = commits_to_sign.iter().ids().cloned().collect();
+    let commit_ids: IndexSet<CommitId> = commits_without_change_id
+        .iter()
+        .chain(&commits_to_sign)
+        .ids()
+        .cloned()
+        .collect();
+    let mut new_target = target.clone();
+    let mut signed_commits = vec![];
+    let mut num_rebased_descendants = 0;
+    tx.repo_mut()
+        .transform_descendants(commit_ids.iter().cloned().collect_vec(), |rewriter| {
+            let old_commit = rewriter.old_commit().clone();
+            let mut builder = rewriter.reparent();
+            if change_id_commit_ids.contains(old_commit.id()) {
+                builder = builder.set_description(add_change_id_trailer(
+                    old_commit.description(),
+                    old_commit.change_id(),
+                ));
+            }
+            if let Some(sign_behavior) = sign_behavior {
+                if sign_commit_ids.contains(old_commit.id()) {
+                    builder = builder.set_sign_behavior(sign_behavior);
+                }
+            }
+            if !commit_ids.contains(old_commit.id()) {
+                num_rebased_descendants += 1;
+            }
+            let commit = builder.write()?;
+            if *old_commit.id() == target {
+                new_target = commit.id().clone();
+            }
+            if sign_behavior.is_some() && untrusted_commit_ids.contains(old_commit.id()) {
+                signed_commits.push((old_commit, commit));
+            }
+            Ok(())
+        })?;
+    check_new_signatures_trusted(tx, &signed_commits)?;
+    Ok((num_rebased_descendants, new_target))
+}
+
+/// Returns the Gerrit `Change-Id` for the given change.
+///
+/// Gerrit expects "I" followed by 40 hex digits. Since jj change IDs only have
+/// 32, they're prefixed with the hex encoding of "jjid".
+fn gerrit_change_id(change_id: &ChangeId) -> String {
+    format!("I6a6a6964{}", change_id.hex())
+}
+
+fn add_change_id_trailer(description: &str, change_id: &ChangeId) -> String {
+    let mut description = description.to_owned();
+    if !description.is_empty() && !description.ends_with('\n') {
+        description.push('\n');
+    }
+    if parse_description_trailers(&description).is_empty() {
+        if description.is_empty() {
+            // a first empty line where the user will edit the commit summary
+            description.push('\n');
+        }
+        // create a new paragraph for the trailer
+        description.push('\n');
+    }
+    description.push_str(&format!("Change-Id: {}\n", gerrit_change_id(change_id)));
+    description
+}
+
 fn print_commits_ready_to_push(
     formatter: &mut dyn Formatter,
     repo: &dyn Repo,
     bookmark_updates: &[(RefNameBuf, BookmarkPushUpdate)],
 ) -> io::Result<()> {
-    let to_direction = |old_target: &CommitId, new_target: &CommitId| {
-        assert_ne!(old_target, new_target);
-        if repo.index().is_ancestor(old_target, new_target) {
-            BookmarkMoveDirection::Forward
-        } else if repo.index().is_ancestor(new_target, old_target) {
-            BookmarkMoveDirection::Backward
-        } else {
-            BookmarkMoveDirection::Sideways
-        }
-    };
-
     for (bookmark_name, update) in bookmark_updates {
         match (&update.old_target, &update.new_target) {
             (Some(old_target), Some(new_target)) => {
@@ -671,7 +1329,7 @@ fn print_commits_ready_to_push(
                 // among many was moved sideways (say). TODO: People on Discord
                 // suggest "Move bookmark ... forward by n commits",
                 // possibly "Move bookmark ... sideways (X forward, Y back)".
-                let msg = match to_direction(old_target, new_target) {
+                let msg = match bookmark_move_direction(repo, old_target, new_target) {
                     BookmarkMoveDirection::Forward => {
                         format!("Move forward bookmark {bookmark_name} from {old} to {new}")
                     }
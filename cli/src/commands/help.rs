@@ -13,17 +13,20 @@
 // limitations under the License.
 
 use std::fmt::Write as _;
+use std::io;
 use std::io::Write as _;
 
 use clap::builder::PossibleValue;
 use clap::builder::StyledStr;
 use crossterm::style::Stylize as _;
 use itertools::Itertools as _;
+use jj_lib::settings::UserSettings;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error;
 use crate::command_error::CommandError;
+use crate::revset_util::load_external_functions;
 use crate::ui::Ui;
 
 /// Print this message or the help of the given subcommand(s)
@@ -54,6 +57,11 @@ pub(crate) fn cmd_help(
         let keyword = find_keyword(name).expect("clap should check this with `value_parser`");
         ui.request_pager();
         write!(ui.stdout(), "{}", keyword.content)?;
+        match keyword.name {
+            "filesets" => write_external_functions(ui, command.settings(), "fileset-functions")?,
+            "revsets" => write_external_functions(ui, command.settings(), "revset-functions")?,
+            _ => {}
+        }
 
         return Ok(());
     }
@@ -78,6 +86,39 @@ pub(crate) fn cmd_help(
     Err(command_error::cli_error(help_err))
 }
 
+/// Appends functions defined in the `revset-functions` or `fileset-functions`
+/// config table to the documentation.
+fn write_external_functions(
+    ui: &Ui,
+    settings: &UserSettings,
+    table_name: &'static str,
+) -> io::Result<()> {
+    let functions = load_external_functions(settings, table_name)
+        .into_iter()
+        .filter_map(|(name, config)| Some((name, config.ok()?)))
+        .collect_vec();
+    if functions.is_empty() {
+        return Ok(());
+    }
+    let mut stdout = ui.stdout();
+    writeln!(stdout)?;
+    writeln!(stdout, "## User-defined functions")?;
+    writeln!(stdout)?;
+    writeln!(
+        stdout,
+        "These functions are defined in the `{table_name}` config table."
+    )?;
+    writeln!(stdout)?;
+    for (name, config) in functions {
+        if config.description.is_empty() {
+            writeln!(stdout, "* `{name}()`")?;
+        } else {
+            writeln!(stdout, "* `{name}()`: {}", config.description)?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 struct Keyword {
     name: &'static str,
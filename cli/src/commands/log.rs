@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
@@ -19,27 +22,38 @@ use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphEdgeType;
+use jj_lib::graph::GraphNode;
 use jj_lib::graph::TopoGroupedGraphIterator;
 use jj_lib::repo::Repo as _;
+use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::RevsetIteratorExt as _;
 use jj_lib::settings::UserSettings;
+use jj_lib::store::Store;
 use tracing::instrument;
 
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
+use crate::diff_util::DiffRenderer;
 use crate::graphlog::get_graphlog;
+use crate::graphlog::GraphLog;
 use crate::graphlog::GraphStyle;
+use crate::interactive_log::run_interactive_log;
+use crate::interactive_log::InteractiveLogNode;
+use crate::interactive_log::SharedBuffer;
+use crate::revset_util::RevsetExpressionEvaluator;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -119,6 +133,16 @@ pub(crate) struct LogArgs {
     patch: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
+    /// Browse the log in a full-screen terminal interface
+    ///
+    /// Use the arrow keys or `j`/`k` to select a revision, and `Enter` to
+    /// show or hide its diff. The selected revision can be edited (`e`),
+    /// described (`d`), abandoned (`a`), squashed into its parent (`s`), or
+    /// used as the parent of a new revision (`n`). Press `Space` to mark a
+    /// revision, then `r` to rebase it and its descendants onto the selected
+    /// revision. Each action is recorded as a separate operation.
+    #[arg(long, short, conflicts_with_all = ["no_graph", "reversed"])]
+    interactive: bool,
 }
 
 #[instrument(skip_all)]
@@ -127,58 +151,34 @@ pub(crate) fn cmd_log(
     command: &CommandHelper,
     args: &LogArgs,
 ) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if args.interactive {
+        return run_interactive_log(
+            ui,
+            &mut workspace_command,
+            |ui, workspace_command, expanded| {
+                render_interactive_log(ui, workspace_command, args, expanded)
+            },
+        );
+    }
+    let workspace_command = workspace_command;
     let settings = workspace_command.settings();
 
-    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
-    let revset_expression = {
-        // only use default revset if neither revset nor path are specified
-        let mut expression = if args.revisions.is_empty() && args.paths.is_empty() {
-            let revset_string = settings.get_string("revsets.log")?;
-            workspace_command.parse_revset(ui, &RevisionArg::from(revset_string))?
-        } else if !args.revisions.is_empty() {
-            workspace_command.parse_union_revsets(ui, &args.revisions)?
-        } else {
-            // a path was specified so we use all() and add path filter later
-            workspace_command.attach_revset_evaluator(RevsetExpression::all())
-        };
-        if !args.paths.is_empty() {
-            // Beware that args.paths = ["root:."] is not identical to []. The
-            // former will filter out empty commits.
-            let predicate = RevsetFilterPredicate::File(fileset_expression.clone());
-            expression.intersect_with(&RevsetExpression::filter(predicate));
-        }
-        expression
-    };
-    let prio_revset = settings.get_string("revsets.log-graph-prioritize")?;
-    let prio_revset = workspace_command.parse_revset(ui, &RevisionArg::from(prio_revset))?;
-
+    let (revset_expression, prio_revset, fileset_expression) =
+        parse_log_revset(ui, &workspace_command, args)?;
     let repo = workspace_command.repo();
-    let matcher = fileset_expression.to_matcher();
     let revset = revset_expression.evaluate()?;
 
     let store = repo.store();
-    let diff_renderer = workspace_command.diff_renderer_for_log(&args.diff_format, args.patch)?;
     let graph_style = GraphStyle::from_settings(settings)?;
-
-    let use_elided_nodes = settings.get_bool("ui.log-synthetic-elided-nodes")?;
-    let with_content_format = LogContentFormat::new(ui, settings)?;
-
-    let template: TemplateRenderer<Commit>;
-    let node_template: TemplateRenderer<Option<Commit>>;
-    {
-        let language = workspace_command.commit_template_language();
-        let template_string = match &args.template {
-            Some(value) => value.to_string(),
-            None => settings.get_string("templates.log")?,
-        };
-        template = workspace_command
-            .parse_template(ui, &language, &template_string)?
-            .labeled("log");
-        node_template = workspace_command
-            .parse_template(ui, &language, &get_node_template(graph_style, settings)?)?
-            .labeled("node");
-    }
+    let renderer = LogRenderer::new(
+        ui,
+        &workspace_command,
+        args,
+        graph_style,
+        &fileset_expression,
+        args.patch,
+    )?;
 
     {
         ui.request_pager();
@@ -188,109 +188,18 @@ pub(crate) fn cmd_log(
         if !args.no_graph {
             let mut raw_output = formatter.raw()?;
             let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-            let iter: Box<dyn Iterator<Item = _>> = {
-                let mut forward_iter = TopoGroupedGraphIterator::new(revset.iter_graph());
-
-                let has_commit = revset.containing_fn();
-
-                for prio in prio_revset.evaluate_to_commit_ids()? {
-                    let prio = prio?;
-                    if has_commit(&prio)? {
-                        forward_iter.prioritize_branch(prio);
-                    }
-                }
-
-                // The input to TopoGroupedGraphIterator shouldn't be truncated
-                // because the prioritized commit must exist in the input set.
-                let forward_iter = forward_iter.take(args.limit.unwrap_or(usize::MAX));
-                if args.reversed {
-                    Box::new(reverse_graph(forward_iter, |id| id)?.into_iter().map(Ok))
-                } else {
-                    Box::new(forward_iter)
-                }
-            };
+            let iter = log_graph_iter(revset.as_ref(), &prio_revset, args)?;
             for node in iter {
                 let (commit_id, edges) = node?;
-
-                // The graph is keyed by (CommitId, is_synthetic)
-                let mut graphlog_edges = vec![];
-                // TODO: Should we update revset.iter_graph() to yield a `has_missing` flag
-                // instead of all the missing edges since we don't care about
-                // where they point here anyway?
-                let mut missing_edge_id = None;
-                let mut elided_targets = vec![];
-                for edge in edges {
-                    match edge.edge_type {
-                        GraphEdgeType::Missing => {
-                            missing_edge_id = Some(edge.target);
-                        }
-                        GraphEdgeType::Direct => {
-                            graphlog_edges.push(GraphEdge::direct((edge.target, false)));
-                        }
-                        GraphEdgeType::Indirect => {
-                            if use_elided_nodes {
-                                elided_targets.push(edge.target.clone());
-                                graphlog_edges.push(GraphEdge::direct((edge.target, true)));
-                            } else {
-                                graphlog_edges.push(GraphEdge::indirect((edge.target, false)));
-                            }
-                        }
-                    }
-                }
-                if let Some(missing_edge_id) = missing_edge_id {
-                    graphlog_edges.push(GraphEdge::missing((missing_edge_id, false)));
-                }
-                let mut buffer = vec![];
-                let key = (commit_id, false);
-                let commit = store.get_commit(&key.0)?;
-                let within_graph =
-                    with_content_format.sub_width(graph.width(&key, &graphlog_edges));
-                within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                    template.format(&commit, formatter)
-                })?;
-                if !buffer.ends_with(b"\n") {
-                    buffer.push(b'\n');
-                }
-                if let Some(renderer) = &diff_renderer {
-                    let mut formatter = ui.new_formatter(&mut buffer);
-                    renderer.show_patch(
-                        ui,
-                        formatter.as_mut(),
-                        &commit,
-                        matcher.as_ref(),
-                        within_graph.width(),
-                    )?;
-                }
-
-                let node_symbol = format_template(ui, &Some(commit), &node_template);
-                graph.add_node(
-                    &key,
-                    &graphlog_edges,
-                    &node_symbol,
-                    &String::from_utf8_lossy(&buffer),
-                )?;
+                let elided_targets =
+                    renderer.add_node(ui, graph.as_mut(), commit_id, edges, true)?;
                 for elided_target in elided_targets {
-                    let elided_key = (elided_target, true);
-                    let real_key = (elided_key.0.clone(), false);
-                    let edges = [GraphEdge::direct(real_key)];
-                    let mut buffer = vec![];
-                    let within_graph =
-                        with_content_format.sub_width(graph.width(&elided_key, &edges));
-                    within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                        writeln!(formatter.labeled("elided"), "(elided revisions)")
-                    })?;
-                    let node_symbol = format_template(ui, &None, &node_template);
-                    graph.add_node(
-                        &elided_key,
-                        &edges,
-                        &node_symbol,
-                        &String::from_utf8_lossy(&buffer),
-                    )?;
+                    renderer.add_elided_node(ui, graph.as_mut(), elided_target)?;
                 }
             }
         } else {
             let iter: Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>>> = {
-                let forward_iter = revset.iter().take(args.limit.unwrap_or(usize::MAX));
+                let forward_iter = revset.iter_ordered().take(args.limit.unwrap_or(usize::MAX));
                 if args.reversed {
                     let entries: Vec<_> = forward_iter.try_collect()?;
                     Box::new(entries.into_iter().rev().map(Ok))
@@ -300,11 +209,18 @@ pub(crate) fn cmd_log(
             };
             for commit_or_error in iter.commits(store) {
                 let commit = commit_or_error?;
-                with_content_format
-                    .write(formatter, |formatter| template.format(&commit, formatter))?;
-                if let Some(renderer) = &diff_renderer {
+                renderer.content_format.write(formatter, |formatter| {
+                    renderer.template.format(&commit, formatter)
+                })?;
+                if let Some(diff_renderer) = &renderer.diff_renderer {
                     let width = ui.term_width();
-                    renderer.show_patch(ui, formatter, &commit, matcher.as_ref(), width)?;
+                    diff_renderer.show_patch(
+                        ui,
+                        formatter,
+                        &commit,
+                        &renderer.fileset_expression,
+                        width,
+                    )?;
                 }
             }
         }
@@ -337,6 +253,261 @@ pub(crate) fn cmd_log(
     Ok(())
 }
 
+/// Parses the revisions to show and the revisions to prioritize in the graph.
+fn parse_log_revset<'a>(
+    ui: &Ui,
+    workspace_command: &'a WorkspaceCommandHelper,
+    args: &LogArgs,
+) -> Result<
+    (
+        RevsetExpressionEvaluator<'a>,
+        RevsetExpressionEvaluator<'a>,
+        FilesetExpression,
+    ),
+    CommandError,
+> {
+    let settings = workspace_command.settings();
+    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    // only use default revset if neither revset nor path are specified
+    let mut expression = if args.revisions.is_empty() && args.paths.is_empty() {
+        let revset_string = settings.get_string("revsets.log")?;
+        workspace_command.parse_revset(ui, &RevisionArg::from(revset_string))?
+    } else if !args.revisions.is_empty() {
+        workspace_command.parse_union_revsets(ui, &args.revisions)?
+    } else {
+        // a path was specified so we use all() and add path filter later
+        workspace_command.attach_revset_evaluator(RevsetExpression::all())
+    };
+    if !args.paths.is_empty() {
+        // Beware that args.paths = ["root:."] is not identical to []. The
+        // former will filter out empty commits.
+        let predicate = RevsetFilterPredicate::File(fileset_expression.clone());
+        expression.intersect_with(&RevsetExpression::filter(predicate));
+    }
+    let prio_revset = settings.get_string("revsets.log-graph-prioritize")?;
+    let prio_revset = workspace_command.parse_revset(ui, &RevisionArg::from(prio_revset))?;
+    Ok((expression, prio_revset, fileset_expression))
+}
+
+type GraphNodeIter<'a> =
+    Box<dyn Iterator<Item = Result<GraphNode<CommitId>, RevsetEvaluationError>> + 'a>;
+
+/// Iterates over the graph nodes to be rendered, grouped topologically and
+/// limited and reversed as requested by `args`.
+fn log_graph_iter<'a>(
+    revset: &'a dyn Revset,
+    prio_revset: &RevsetExpressionEvaluator,
+    args: &LogArgs,
+) -> Result<GraphNodeIter<'a>, CommandError> {
+    let mut forward_iter = TopoGroupedGraphIterator::new(revset.iter_graph());
+
+    let has_commit = revset.containing_fn();
+
+    for prio in prio_revset.evaluate_to_commit_ids()? {
+        let prio = prio?;
+        if has_commit(&prio)? {
+            forward_iter.prioritize_branch(prio);
+        }
+    }
+
+    // The input to TopoGroupedGraphIterator shouldn't be truncated
+    // because the prioritized commit must exist in the input set.
+    let forward_iter = forward_iter.take(args.limit.unwrap_or(usize::MAX));
+    if args.reversed {
+        Ok(Box::new(
+            reverse_graph(forward_iter, |id| id)?.into_iter().map(Ok),
+        ))
+    } else {
+        Ok(Box::new(forward_iter))
+    }
+}
+
+/// Templates and options to render revisions as graph nodes.
+struct LogRenderer<'a> {
+    store: &'a Arc<Store>,
+    template: TemplateRenderer<'a, Commit>,
+    node_template: TemplateRenderer<'a, Option<Commit>>,
+    diff_renderer: Option<DiffRenderer<'a>>,
+    fileset_expression: FilesetExpression,
+    content_format: LogContentFormat,
+    use_elided_nodes: bool,
+}
+
+impl<'a> LogRenderer<'a> {
+    fn new(
+        ui: &Ui,
+        workspace_command: &'a WorkspaceCommandHelper,
+        args: &LogArgs,
+        graph_style: GraphStyle,
+        fileset_expression: &FilesetExpression,
+        patch: bool,
+    ) -> Result<Self, CommandError> {
+        let settings = workspace_command.settings();
+        let language = workspace_command.commit_template_language();
+        let template_string = match &args.template {
+            Some(value) => value.to_string(),
+            None => settings.get_string("templates.log")?,
+        };
+        let template = workspace_command
+            .parse_template(ui, &language, &template_string)?
+            .labeled("log");
+        let node_template = workspace_command
+            .parse_template(ui, &language, &get_node_template(graph_style, settings)?)?
+            .labeled("node");
+        Ok(LogRenderer {
+            store: workspace_command.repo().store(),
+            template,
+            node_template,
+            diff_renderer: workspace_command.diff_renderer_for_log(&args.diff_format, patch)?,
+            fileset_expression: fileset_expression.clone(),
+            content_format: LogContentFormat::new(ui, settings)?,
+            use_elided_nodes: settings.get_bool("ui.log-synthetic-elided-nodes")?,
+        })
+    }
+
+    /// Adds the commit to the graph. Returns the targets of elided edges,
+    /// which should be added next by `add_elided_node()`.
+    fn add_node(
+        &self,
+        ui: &Ui,
+        graph: &mut dyn GraphLog<(CommitId, bool)>,
+        commit_id: CommitId,
+        edges: Vec<GraphEdge<CommitId>>,
+        show_patch: bool,
+    ) -> Result<Vec<CommitId>, CommandError> {
+        // The graph is keyed by (CommitId, is_synthetic)
+        let mut graphlog_edges = vec![];
+        // TODO: Should we update revset.iter_graph() to yield a `has_missing` flag
+        // instead of all the missing edges since we don't care about
+        // where they point here anyway?
+        let mut missing_edge_id = None;
+        let mut elided_targets = vec![];
+        for edge in edges {
+            match edge.edge_type {
+                GraphEdgeType::Missing => {
+                    missing_edge_id = Some(edge.target);
+                }
+                GraphEdgeType::Direct => {
+                    graphlog_edges.push(GraphEdge::direct((edge.target, false)));
+                }
+                GraphEdgeType::Indirect => {
+                    if self.use_elided_nodes {
+                        elided_targets.push(edge.target.clone());
+                        graphlog_edges.push(GraphEdge::direct((edge.target, true)));
+                    } else {
+                        graphlog_edges.push(GraphEdge::indirect((edge.target, false)));
+                    }
+                }
+            }
+        }
+        if let Some(missing_edge_id) = missing_edge_id {
+            graphlog_edges.push(GraphEdge::missing((missing_edge_id, false)));
+        }
+        let mut buffer = vec![];
+        let key = (commit_id, false);
+        let commit = self.store.get_commit(&key.0)?;
+        let within_graph = self
+            .content_format
+            .sub_width(graph.width(&key, &graphlog_edges));
+        within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
+            self.template.format(&commit, formatter)
+        })?;
+        if !buffer.ends_with(b"\n") {
+            buffer.push(b'\n');
+        }
+        if let Some(renderer) = self.diff_renderer.as_ref().filter(|_| show_patch) {
+            let mut formatter = ui.new_formatter(&mut buffer);
+            renderer.show_patch(
+                ui,
+                formatter.as_mut(),
+                &commit,
+                &self.fileset_expression,
+                within_graph.width(),
+            )?;
+        }
+
+        let node_symbol = format_template(ui, &Some(commit), &self.node_template);
+        graph.add_node(
+            &key,
+            &graphlog_edges,
+            &node_symbol,
+            &String::from_utf8_lossy(&buffer),
+        )?;
+        Ok(elided_targets)
+    }
+
+    /// Adds a synthetic node for the revisions elided before `elided_target`.
+    fn add_elided_node(
+        &self,
+        ui: &Ui,
+        graph: &mut dyn GraphLog<(CommitId, bool)>,
+        elided_target: CommitId,
+    ) -> Result<(), CommandError> {
+        let elided_key = (elided_target, true);
+        let real_key = (elided_key.0.clone(), false);
+        let edges = [GraphEdge::direct(real_key)];
+        let mut buffer = vec![];
+        let within_graph = self
+            .content_format
+            .sub_width(graph.width(&elided_key, &edges));
+        within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
+            writeln!(formatter.labeled("elided"), "(elided revisions)")
+        })?;
+        let node_symbol = format_template(ui, &None, &self.node_template);
+        graph.add_node(
+            &elided_key,
+            &edges,
+            &node_symbol,
+            &String::from_utf8_lossy(&buffer),
+        )?;
+        Ok(())
+    }
+}
+
+/// Renders the graph for `jj log --interactive` as a list of nodes. The diff
+/// is included for the commits in `expanded`.
+fn render_interactive_log(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    args: &LogArgs,
+    expanded: &HashSet<CommitId>,
+) -> Result<Vec<InteractiveLogNode>, CommandError> {
+    let (revset_expression, prio_revset, fileset_expression) =
+        parse_log_revset(ui, workspace_command, args)?;
+    let revset = revset_expression.evaluate()?;
+    let graph_style = GraphStyle::from_settings(workspace_command.settings())?;
+    let renderer = LogRenderer::new(
+        ui,
+        workspace_command,
+        args,
+        graph_style,
+        &fileset_expression,
+        true,
+    )?;
+
+    // Diffs requested on the command line are shown for all commits.
+    let show_all_patches = workspace_command
+        .diff_renderer_for_log(&args.diff_format, args.patch)?
+        .is_some();
+
+    let mut nodes = vec![];
+    let output = SharedBuffer::default();
+    let mut writer = output.clone();
+    let mut graph = get_graphlog(graph_style, &mut writer);
+    for node in log_graph_iter(revset.as_ref(), &prio_revset, args)? {
+        let (commit_id, edges) = node?;
+        let show_patch = show_all_patches || expanded.contains(&commit_id);
+        let elided_targets =
+            renderer.add_node(ui, graph.as_mut(), commit_id.clone(), edges, show_patch)?;
+        nodes.push(InteractiveLogNode::new(Some(commit_id), &output.take()));
+        for elided_target in elided_targets {
+            renderer.add_elided_node(ui, graph.as_mut(), elided_target)?;
+            nodes.push(InteractiveLogNode::new(None, &output.take()));
+        }
+    }
+    Ok(nodes)
+}
+
 pub fn get_node_template(
     style: GraphStyle,
     settings: &UserSettings,
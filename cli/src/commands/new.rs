@@ -165,6 +165,7 @@ pub(crate) fn cmd_new(
         args.insert_before.as_deref(),
         "new commit",
     )?;
+    workspace_command.warn_untrusted_commits(ui, &parent_commit_ids)?;
     let parent_commits: Vec<_> = parent_commit_ids
         .iter()
         .map(|commit_id| workspace_command.repo().store().get_commit(commit_id))
@@ -23,6 +23,7 @@ use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::dag_walk;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::TopoGroupedGraphIterator;
 use jj_lib::matchers::EverythingMatcher;
@@ -601,7 +602,7 @@ fn show_change_diff(
         }
         ([commit], []) => {
             // TODO: Should we show a reverse diff?
-            diff_renderer.show_patch(ui, formatter, commit, &EverythingMatcher, width)?;
+            diff_renderer.show_patch(ui, formatter, commit, &FilesetExpression::all(), width)?;
         }
         ([_, _, ..], _) | (_, [_, _, ..]) => {}
         ([], []) => panic!("ModifiedChange should have at least one entry"),
@@ -12,19 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::fmt::Write as _;
 use std::io::Write as _;
+use std::iter;
+use std::slice;
 use std::sync::Arc;
 
 use clap::ArgGroup;
 use clap_complete::ArgValueCompleter;
+use indexmap::IndexMap;
+use indoc::indoc;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
 use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
 use jj_lib::rewrite::move_commits;
+use jj_lib::rewrite::squash_commits;
+use jj_lib::rewrite::CommitWithSelection;
 use jj_lib::rewrite::EmptyBehaviour;
 use jj_lib::rewrite::MoveCommitsLocation;
 use jj_lib::rewrite::MoveCommitsStats;
@@ -40,8 +51,13 @@ use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::cli_error;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::combine_messages_for_editing;
+use crate::description_util::description_template;
+use crate::description_util::edit_description;
+use crate::description_util::try_combine_messages;
 use crate::ui::Ui;
 
 /// Move revisions to different parent(s)
@@ -265,6 +281,12 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 #[command(group(ArgGroup::new("to_rebase").args(&["branch", "source", "revisions"])))]
+#[command(group(
+    ArgGroup::new("rebase_destination")
+        .args(&["destination", "insert_after", "insert_before", "interactive"])
+        .required(true)
+        .multiple(true)
+))]
 pub(crate) struct RebaseArgs {
     /// Rebase the whole branch relative to destination's ancestors (can be
     /// repeated)
@@ -324,10 +346,26 @@ pub(crate) struct RebaseArgs {
     /// parents.
     #[arg(long)]
     skip_emptied: bool,
+
+    /// Edit the list of revisions to rebase in a text editor
+    ///
+    /// The revisions given with `-r` are listed oldest first, each prefixed by
+    /// an action. Lines can be reordered, and the actions can be changed to
+    /// `pick`, `reword`, `edit`, `squash`, `fixup`, or `drop`. The edited list
+    /// is applied as a single operation.
+    ///
+    /// If no destination is given, the revisions are rebased onto the parent
+    /// of their roots.
+    #[arg(
+        long,
+        short,
+        requires = "revisions",
+        conflicts_with_all = ["branch", "source", "insert_after", "insert_before"],
+    )]
+    interactive: bool,
 }
 
 #[derive(clap::Args, Clone, Debug)]
-#[group(required = true)]
 pub struct RebaseDestinationArgs {
     /// The revision(s) to rebase onto (can be repeated to create a merge
     /// commit)
@@ -385,6 +423,15 @@ pub(crate) fn cmd_rebase(
         simplify_ancestor_merge: false,
     };
     let mut workspace_command = command.workspace_helper(ui)?;
+    if args.interactive {
+        return rebase_interactive(
+            ui,
+            &mut workspace_command,
+            &args.revisions,
+            &args.destination,
+            &rebase_options,
+        );
+    }
     let loc = if !args.revisions.is_empty() {
         plan_rebase_revisions(ui, &workspace_command, &args.revisions, &args.destination)?
     } else if !args.source.is_empty() {
@@ -392,6 +439,7 @@ pub(crate) fn cmd_rebase(
     } else {
         plan_rebase_branch(ui, &workspace_command, &args.branch, &args.destination)?
     };
+    workspace_command.warn_untrusted_commits(ui, &loc.new_parent_ids)?;
 
     let mut tx = workspace_command.start_transaction();
     let stats = move_commits(tx.repo_mut(), &loc, &rebase_options)?;
@@ -519,6 +567,419 @@ fn plan_rebase_branch(
     })
 }
 
+/// Action of a line in the todo list of `jj rebase --interactive`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TodoAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl TodoAction {
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "p" | "pick" => Some(TodoAction::Pick),
+            "r" | "reword" => Some(TodoAction::Reword),
+            "e" | "edit" => Some(TodoAction::Edit),
+            "s" | "squash" => Some(TodoAction::Squash),
+            "f" | "fixup" => Some(TodoAction::Fixup),
+            "d" | "drop" => Some(TodoAction::Drop),
+            _ => None,
+        }
+    }
+
+    fn is_squash(self) -> bool {
+        matches!(self, TodoAction::Squash | TodoAction::Fixup)
+    }
+}
+
+const TODO_INSTRUCTIONS: &str = indoc! {r#"
+    JJ: Actions:
+    JJ:   p, pick   = keep the revision
+    JJ:   r, reword = keep the revision, but edit its description
+    JJ:   e, edit   = keep the revision, and make it the working-copy commit
+    JJ:   s, squash = squash the revision into the previous one, and edit the
+    JJ:               combined description
+    JJ:   f, fixup  = like "squash", but keep the previous revision's description
+    JJ:   d, drop   = abandon the revision
+    JJ:
+    JJ: Revisions are applied from top to bottom, each on top of the previous
+    JJ: one. Every revision must be listed. If all lines are removed, the rebase
+    JJ: is aborted.
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+"#};
+
+fn rebase_interactive(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    revisions: &[RevisionArg],
+    rebase_destination: &RebaseDestinationArgs,
+    rebase_options: &RebaseOptions,
+) -> Result<(), CommandError> {
+    let target_commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?; // in reverse topological order
+    if target_commits.is_empty() {
+        writeln!(ui.status(), "No revisions to rebase.")?;
+        return Ok(());
+    }
+    workspace_command.check_rewritable(target_commits.iter().ids())?;
+    if let Some(commit) = target_commits
+        .iter()
+        .find(|commit| commit.parent_ids().len() > 1)
+    {
+        return Err(user_error(format!(
+            "Cannot rebase merge commit {} interactively",
+            short_commit_hash(commit.id())
+        )));
+    }
+
+    let target_ids = target_commits.iter().ids().cloned().collect_vec();
+    let new_parent_ids = if rebase_destination.destination.is_some() {
+        let (new_parent_ids, _) = compute_commit_location(
+            ui,
+            workspace_command,
+            rebase_destination.destination.as_deref(),
+            None,
+            None,
+            "rebased commits",
+        )?;
+        new_parent_ids
+    } else {
+        let base_ids: Vec<_> = RevsetExpression::commits(target_ids.clone())
+            .roots()
+            .parents()
+            .evaluate(workspace_command.repo().as_ref())?
+            .iter()
+            .try_collect()?;
+        if base_ids.len() != 1 {
+            return Err(user_error_with_hint(
+                "The revisions to rebase don't have a single parent",
+                "Use --destination to specify where to rebase them.",
+            ));
+        }
+        base_ids
+    };
+    for commit in &target_commits {
+        check_rebase_destinations(workspace_command.repo(), &new_parent_ids, commit)?;
+    }
+    workspace_command.warn_untrusted_commits(ui, &new_parent_ids)?;
+
+    let text_editor = workspace_command.text_editor()?;
+    let mut commits_map = IndexMap::new();
+    let mut todo = String::new();
+    writeln!(
+        todo,
+        "JJ: Rebase {} commit{} onto {}",
+        target_commits.len(),
+        if target_commits.len() == 1 { "" } else { "s" },
+        new_parent_ids.iter().map(short_commit_hash).join(", ")
+    )
+    .unwrap();
+    todo.push_str("JJ:\n");
+    for commit in target_commits.iter().rev() {
+        let commit_hash = short_commit_hash(commit.id());
+        let summary = commit.description().lines().next().unwrap_or_default();
+        writeln!(todo, "pick {commit_hash} {summary}").unwrap();
+        commits_map.insert(commit_hash, commit.id().clone());
+    }
+    todo.push('\n');
+    todo.push_str(TODO_INSTRUCTIONS);
+    let todo = text_editor
+        .edit_str(todo, Some(".jjrebase"))
+        .map_err(|err| err.with_name("rebase todo list"))?;
+    let plan = parse_rebase_todo(&todo, &commits_map)?;
+    if plan.is_empty() {
+        writeln!(
+            ui.status(),
+            "Rebase aborted because the todo list is empty."
+        )?;
+        return Ok(());
+    }
+    // Nothing to do if every revision is picked and already on top of the
+    // previous one.
+    let is_unchanged = plan.iter().all(|(action, _)| *action == TodoAction::Pick)
+        && plan
+            .iter()
+            .zip(
+                iter::once(new_parent_ids.clone())
+                    .chain(plan.iter().map(|(_, id)| vec![id.clone()])),
+            )
+            .all(|((_, id), parent_ids)| {
+                let commit = target_commits.iter().find(|commit| commit.id() == id);
+                commit.unwrap().parent_ids() == parent_ids
+            });
+    if is_unchanged {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    // Descendants of the rebased commits which aren't rebased themselves stay
+    // on top of their rewritten parents.
+    let external_children: Vec<Commit> = RevsetExpression::commits(target_ids.clone())
+        .children()
+        .minus(&RevsetExpression::commits(target_ids))
+        .evaluate(workspace_command.repo().as_ref())?
+        .iter()
+        .commits(workspace_command.repo().store())
+        .try_collect()?;
+
+    let mut tx = workspace_command.start_transaction();
+    // Revisions abandoned because they became empty (with --skip-emptied)
+    // map to their parents, so they must not be looked up as rewritten.
+    let mut abandoned_ids: HashSet<CommitId> = HashSet::new();
+    let current_commit = |repo: &MutableRepo,
+                          abandoned_ids: &HashSet<CommitId>,
+                          id: &CommitId|
+     -> Result<Commit, CommandError> {
+        if abandoned_ids.contains(id) {
+            return Err(user_error(format!(
+                "Revision {} was abandoned because it became empty",
+                short_commit_hash(id)
+            )));
+        }
+        match &*repo.new_parents(slice::from_ref(id)) {
+            [new_id] => Ok(repo.store().get_commit(new_id)?),
+            _ => Err(user_error(format!(
+                "Revision {} was rewritten into multiple revisions",
+                short_commit_hash(id)
+            ))),
+        }
+    };
+
+    // Put the kept revisions in order.
+    let mut parent_ids = new_parent_ids;
+    for (_, id) in plan
+        .iter()
+        .filter(|(action, _)| *action != TodoAction::Drop)
+    {
+        let commit = current_commit(tx.repo(), &abandoned_ids, id)?;
+        let loc = MoveCommitsLocation {
+            new_parent_ids: parent_ids.clone(),
+            new_child_ids: vec![],
+            target: MoveCommitsTarget::Commits(vec![commit.id().clone()]),
+        };
+        move_commits(tx.repo_mut(), &loc, rebase_options)?;
+        let new_ids = tx.repo().new_parents(slice::from_ref(id));
+        if new_ids == parent_ids {
+            // The revision was abandoned. The next one goes on the same parents.
+            abandoned_ids.insert(id.clone());
+        } else {
+            parent_ids = new_ids;
+        }
+    }
+    // Descendants of dropped revisions are rebased onto the original parents.
+    let mut num_dropped = 0;
+    for (_, id) in plan
+        .iter()
+        .filter(|(action, _)| *action == TodoAction::Drop)
+    {
+        let commit = current_commit(tx.repo(), &abandoned_ids, id)?;
+        let old_commit = tx.repo().store().get_commit(id)?;
+        let new_parent_ids = tx.repo().new_parents(old_commit.parent_ids());
+        tx.repo_mut()
+            .record_abandoned_commit_with_parents(commit.id().clone(), new_parent_ids);
+        num_dropped += 1;
+    }
+    for child in external_children.iter().rev() {
+        let commit = current_commit(tx.repo(), &abandoned_ids, child.id())?;
+        let new_parent_ids = tx.repo().new_parents(child.parent_ids());
+        if commit.parent_ids() != new_parent_ids {
+            let loc = MoveCommitsLocation {
+                new_parent_ids,
+                new_child_ids: vec![],
+                target: MoveCommitsTarget::Roots(vec![commit.id().clone()]),
+            };
+            move_commits(tx.repo_mut(), &loc, rebase_options)?;
+        }
+    }
+
+    // Squash each run of "squash" and "fixup" lines into the preceding revision.
+    let mut num_squashed = 0;
+    let kept_plan = plan
+        .iter()
+        .filter(|(action, _)| *action != TodoAction::Drop)
+        .collect_vec();
+    for (i, (action, destination_id)) in kept_plan.iter().enumerate() {
+        if action.is_squash() {
+            continue;
+        }
+        // Sources which were abandoned have no changes to squash.
+        let sources = kept_plan[i + 1..]
+            .iter()
+            .take_while(|(action, _)| action.is_squash())
+            .filter(|(_, id)| !abandoned_ids.contains(id))
+            .collect_vec();
+        if sources.is_empty() {
+            continue;
+        }
+        let destination = current_commit(tx.repo(), &abandoned_ids, destination_id)?;
+        let mut source_commits = vec![];
+        let mut selections = vec![];
+        for (action, id) in &sources {
+            let commit = current_commit(tx.repo(), &abandoned_ids, id)?;
+            selections.push(CommitWithSelection {
+                selected_tree: commit.tree()?,
+                parent_tree: commit.parent_tree(tx.repo())?,
+                commit: commit.clone(),
+            });
+            if *action == TodoAction::Squash {
+                source_commits.push(commit);
+            }
+        }
+        let Some(squashed) = squash_commits(tx.repo_mut(), &selections, &destination, false)?
+        else {
+            continue;
+        };
+        num_squashed += sources.len();
+        let mut commit_builder = squashed.commit_builder.detach();
+        if !source_commits.is_empty() {
+            let description = match try_combine_messages(&source_commits, &destination) {
+                Some(description) => description,
+                None => {
+                    let combined = combine_messages_for_editing(
+                        ui,
+                        &tx,
+                        &source_commits,
+                        &destination,
+                        &commit_builder,
+                    )?;
+                    commit_builder.set_description(combined);
+                    let temp_commit = commit_builder.write_hidden()?;
+                    let intro = "Enter a description for the combined commit.";
+                    let template = description_template(ui, &tx, intro, &temp_commit)?;
+                    edit_description(&text_editor, &template)?
+                }
+            };
+            commit_builder.set_description(description);
+        }
+        commit_builder.write(tx.repo_mut())?;
+    }
+
+    for (_, id) in plan
+        .iter()
+        .filter(|(action, _)| *action == TodoAction::Reword)
+    {
+        let commit = current_commit(tx.repo(), &abandoned_ids, id)?;
+        let template = description_template(ui, &tx, "", &commit)?;
+        let description = edit_description(&text_editor, &template)?;
+        tx.repo_mut()
+            .rewrite_commit(&commit)
+            .set_description(description)
+            .write()?;
+    }
+    if let Some((_, id)) = plan.iter().find(|(action, _)| *action == TodoAction::Edit) {
+        let commit = current_commit(tx.repo(), &abandoned_ids, id)?;
+        tx.edit(&commit)?;
+    }
+    tx.repo_mut().rebase_descendants()?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        let num_abandoned = num_dropped + abandoned_ids.len();
+        let num_kept = plan.len() - num_abandoned - num_squashed;
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        writeln!(formatter, "Rebased {num_kept} commit{}", plural(num_kept))?;
+        if num_squashed > 0 {
+            writeln!(
+                formatter,
+                "Squashed {num_squashed} commit{}",
+                plural(num_squashed)
+            )?;
+        }
+        if num_abandoned > 0 {
+            writeln!(
+                formatter,
+                "Abandoned {num_abandoned} commit{}",
+                plural(num_abandoned)
+            )?;
+        }
+    }
+    let first_id = &plan[0].1;
+    tx.finish(
+        ui,
+        format!(
+            "rebase commit {} and {} more interactively",
+            first_id.hex(),
+            plan.len() - 1
+        ),
+    )?;
+    Ok(())
+}
+
+/// Parses the edited todo list. Returns the actions in the order they should
+/// be applied.
+fn parse_rebase_todo(
+    todo: &str,
+    commits_map: &IndexMap<String, CommitId>,
+) -> Result<Vec<(TodoAction, CommitId)>, CommandError> {
+    let mut plan: Vec<(TodoAction, CommitId)> = vec![];
+    for line in todo.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("JJ:") {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let (Some(action_word), Some(commit_hash)) = (words.next(), words.next()) else {
+            return Err(user_error(format!(
+                r#"Missing revision in the todo list line: "{line}""#
+            )));
+        };
+        let Some(action) = TodoAction::parse(action_word) else {
+            return Err(user_error(format!(
+                r#"Unknown action "{action_word}" in the todo list line: "{line}""#
+            )));
+        };
+        let Some(commit_id) = commits_map.get(commit_hash) else {
+            return Err(user_error(format!(
+                "Revision {commit_hash} in the todo list is not being rebased"
+            )));
+        };
+        if plan.iter().any(|(_, id)| id == commit_id) {
+            return Err(user_error(format!(
+                "Revision {commit_hash} is listed more than once in the todo list"
+            )));
+        }
+        if action.is_squash() && !plan.iter().any(|(action, _)| *action != TodoAction::Drop) {
+            return Err(user_error(format!(
+                "Cannot squash revision {commit_hash} without a previous revision to squash into"
+            )));
+        }
+        plan.push((action, commit_id.clone()));
+    }
+    if plan.is_empty() {
+        return Ok(plan);
+    }
+    let missing = commits_map
+        .iter()
+        .filter(|(_, id)| !plan.iter().any(|(_, listed_id)| listed_id == *id))
+        .map(|(commit_hash, _)| commit_hash)
+        .collect_vec();
+    if !missing.is_empty() {
+        return Err(user_error_with_hint(
+            format!(
+                "The following revisions are missing from the todo list: {}",
+                missing.iter().join(", ")
+            ),
+            r#"Use "drop" to abandon a revision."#,
+        ));
+    }
+    if plan
+        .iter()
+        .filter(|(action, _)| *action == TodoAction::Edit)
+        .count()
+        > 1
+    {
+        return Err(user_error(
+            "Only one revision in the todo list can be marked for editing",
+        ));
+    }
+    Ok(plan)
+}
+
 fn check_rebase_destinations(
     repo: &Arc<ReadonlyRepo>,
     new_parents: &[CommitId],
@@ -133,12 +133,12 @@ pub(crate) fn cmd_restore(
     }
     workspace_command.check_rewritable([to_commit.id()])?;
 
+    let to_tree = to_commit.tree()?;
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
-        .to_matcher();
+        .to_tree_matcher(vec![from_tree.clone(), to_tree.clone()]);
     let diff_selector =
         workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
-    let to_tree = to_commit.tree()?;
     let format_instructions = || {
         formatdoc! {"
             You are restoring changes from: {from_commits}
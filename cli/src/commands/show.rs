@@ -14,7 +14,7 @@
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
-use jj_lib::matchers::EverythingMatcher;
+use jj_lib::fileset::FilesetExpression;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -75,7 +75,13 @@ pub(crate) fn cmd_show(
     let formatter = formatter.as_mut();
     template.format(&commit, formatter)?;
     if !args.no_patch {
-        diff_renderer.show_patch(ui, formatter, &commit, &EverythingMatcher, ui.term_width())?;
+        diff_renderer.show_patch(
+            ui,
+            formatter,
+            &commit,
+            &FilesetExpression::all(),
+            ui.term_width(),
+        )?;
     }
     Ok(())
 }
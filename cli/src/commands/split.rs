@@ -14,11 +14,18 @@
 use std::io::Write as _;
 
 use clap_complete::ArgValueCompleter;
+use futures::StreamExt as _;
+use itertools::Itertools as _;
 use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::matchers::FilesMatcher;
 use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
-use jj_lib::rewrite::CommitWithSelection;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::rewrite::restore_tree;
+use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -26,6 +33,7 @@ use crate::cli_util::DiffSelector;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -35,7 +43,7 @@ use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
 use crate::ui::Ui;
 
-/// Split a revision in two
+/// Split a revision in two or more
 ///
 /// Starts a [diff editor] on the changes in the revision. Edit the right side
 /// of the diff until it has the content you want in the first revision. Once
@@ -45,10 +53,16 @@ use crate::ui::Ui;
 /// [diff editor]:
 ///     https://jj-vcs.github.io/jj/latest/config/#editing-diffs
 ///
+/// With `--parts N`, the diff editor is started once for each of the first
+/// N-1 revisions, each time showing only the changes that haven't been
+/// selected yet. With `--by-file`, the changes to each file are put in a
+/// separate revision without starting the diff editor. Either way, all the
+/// new revisions are created in a single operation.
+///
 /// If the change you split had a description, you will be asked to enter a
 /// change description for each commit. If the change did not have a
-/// description, the second part will not get a description, and you will be
-/// asked for a description only for the first part.
+/// description, the remaining parts will not get a description, and you will
+/// be asked for a description only for the first part.
 ///
 /// Splitting an empty commit is not supported because the same effect can be
 /// achieved with `jj new`.
@@ -76,10 +90,22 @@ pub(crate) struct SplitArgs {
     /// source commit description is kept unchanged.
     #[arg(long = "message", short, value_name = "MESSAGE")]
     message_paragraphs: Vec<String>,
-    /// Split the revision into two parallel revisions instead of a parent and
-    /// child
+    /// Split the revision into parallel revisions instead of a stack of
+    /// revisions
     #[arg(long, short)]
     parallel: bool,
+    /// Split the revision into this many revisions (implies --interactive)
+    ///
+    /// The diff editor is started for each revision but the last, which gets
+    /// the remaining changes.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(2..))]
+    parts: Option<u16>,
+    /// Put the changes to each file in a separate revision
+    #[arg(
+        long,
+        conflicts_with_all = ["interactive", "tool", "parts", "paths"],
+    )]
+    by_file: bool,
     /// Files matching any of these filesets are put in the first commit
     #[arg(
         value_name = "FILESETS",
@@ -108,18 +134,27 @@ impl SplitArgs {
             ));
         }
         workspace_command.check_rewritable([target_commit.id()])?;
+        let parent_tree = target_commit.parent_tree(workspace_command.repo().as_ref())?;
         let matcher = workspace_command
             .parse_file_patterns(ui, &self.paths)?
-            .to_matcher();
-        let diff_selector = workspace_command.diff_selector(
-            ui,
-            self.tool.as_deref(),
-            self.interactive || self.paths.is_empty(),
-        )?;
+            .to_tree_matcher(vec![parent_tree, target_commit.tree()?]);
+        let parts = if self.by_file {
+            SplitParts::ByFile
+        } else {
+            let diff_selector = workspace_command.diff_selector(
+                ui,
+                self.tool.as_deref(),
+                self.interactive || self.paths.is_empty() || self.parts.is_some(),
+            )?;
+            SplitParts::Select {
+                diff_selector,
+                num_parts: self.parts.map_or(2, usize::from),
+            }
+        };
         Ok(ResolvedSplitArgs {
             target_commit,
             matcher,
-            diff_selector,
+            parts,
             parallel: self.parallel,
         })
     }
@@ -128,10 +163,22 @@ impl SplitArgs {
 struct ResolvedSplitArgs {
     target_commit: Commit,
     matcher: Box<dyn Matcher>,
-    diff_selector: DiffSelector,
+    parts: SplitParts,
     parallel: bool,
 }
 
+/// How the changes in the target commit are divided into parts.
+enum SplitParts {
+    /// The user selects the changes for each part but the last, which gets the
+    /// remaining changes.
+    Select {
+        diff_selector: DiffSelector,
+        num_parts: usize,
+    },
+    /// Each changed file is put in its own part.
+    ByFile,
+}
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_split(
     ui: &mut Ui,
@@ -142,20 +189,49 @@ pub(crate) fn cmd_split(
     let ResolvedSplitArgs {
         target_commit,
         matcher,
-        diff_selector,
+        parts,
         parallel,
     } = args.resolve(ui, &workspace_command)?;
     let text_editor = workspace_command.text_editor()?;
     let mut tx = workspace_command.start_transaction();
 
-    // Prompt the user to select the changes they want for the first commit.
-    let target = select_diff(ui, &tx, &target_commit, &matcher, &diff_selector)?;
+    // Compute the tree of each part. Each tree also contains the changes of
+    // the preceding parts, so the last tree is the tree of the target commit.
+    let trees = match &parts {
+        SplitParts::Select {
+            diff_selector,
+            num_parts,
+        } => select_diffs(ui, &tx, &target_commit, &matcher, diff_selector, *num_parts)?,
+        SplitParts::ByFile => split_trees_by_file(&tx, &target_commit)?,
+    };
+    let num_parts = trees.len();
+    let parent_tree = target_commit.parent_tree(tx.repo())?;
 
-    // Create the first commit, which includes the changes selected by the user.
-    let first_commit = {
-        let mut commit_builder = tx.repo_mut().rewrite_commit(&target.commit).detach();
-        commit_builder.set_tree_id(target.selected_tree.id());
-        let description = if !args.message_paragraphs.is_empty() {
+    let mut new_commits: Vec<Commit> = Vec::with_capacity(num_parts);
+    for (index, tree) in trees.iter().enumerate() {
+        let new_tree = if parallel && index > 0 {
+            // Merge the tree with its parent using the tree of the previous
+            // part as the base for the merge. This results in a tree with
+            // only the changes of this part.
+            tree.merge(&trees[index - 1], &parent_tree)?
+        } else {
+            tree.clone()
+        };
+        let mut commit_builder = tx.repo_mut().rewrite_commit(&target_commit).detach();
+        commit_builder.set_tree_id(new_tree.id());
+        if index > 0 {
+            let parents = if parallel {
+                target_commit.parent_ids().to_vec()
+            } else {
+                vec![new_commits[index - 1].id().clone()]
+            };
+            commit_builder
+                .set_parents(parents)
+                // Generate a new change id so that the commit being split
+                // doesn't become divergent.
+                .generate_new_change_id();
+        }
+        let description = if index == 0 && !args.message_paragraphs.is_empty() {
             let description = join_message_paragraphs(&args.message_paragraphs);
             if !description.is_empty() {
                 commit_builder.set_description(description);
@@ -163,51 +239,11 @@ pub(crate) fn cmd_split(
             } else {
                 description
             }
-        } else {
-            let new_description = add_trailers(ui, &tx, &commit_builder)?;
-            commit_builder.set_description(new_description);
-            let temp_commit = commit_builder.write_hidden()?;
-            let template = description_template(
-                ui,
-                &tx,
-                "Enter a description for the first commit.",
-                &temp_commit,
-            )?;
-            edit_description(&text_editor, &template)?
-        };
-        commit_builder.set_description(description);
-        commit_builder.write(tx.repo_mut())?
-    };
-
-    // Create the second commit, which includes everything the user didn't
-    // select.
-    let second_commit = {
-        let target_tree = target.commit.tree()?;
-        let new_tree = if args.parallel {
-            // Merge the original commit tree with its parent using the tree
-            // containing the user selected changes as the base for the merge.
-            // This results in a tree with the changes the user didn't select.
-            target_tree.merge(&target.selected_tree, &target.parent_tree)?
-        } else {
-            target_tree
-        };
-        let parents = if parallel {
-            target.commit.parent_ids().to_vec()
-        } else {
-            vec![first_commit.id().clone()]
-        };
-        let mut commit_builder = tx.repo_mut().rewrite_commit(&target.commit).detach();
-        commit_builder
-            .set_parents(parents)
-            .set_tree_id(new_tree.id())
-            // Generate a new change id so that the commit being split doesn't
-            // become divergent.
-            .generate_new_change_id();
-        let description = if target.commit.description().is_empty() {
+        } else if index > 0 && target_commit.description().is_empty() {
             // If there was no description before, don't ask for one for the
-            // second commit.
+            // remaining parts.
             "".to_string()
-        } else if !args.message_paragraphs.is_empty() {
+        } else if index > 0 && !args.message_paragraphs.is_empty() {
             // Just keep the original message unchanged
             commit_builder.description().to_owned()
         } else {
@@ -217,44 +253,45 @@ pub(crate) fn cmd_split(
             let template = description_template(
                 ui,
                 &tx,
-                "Enter a description for the second commit.",
+                &format!("Enter a description for {}.", part_label(index, num_parts)),
                 &temp_commit,
             )?;
             edit_description(&text_editor, &template)?
         };
         commit_builder.set_description(description);
-        commit_builder.write(tx.repo_mut())?
-    };
+        new_commits.push(commit_builder.write(tx.repo_mut())?);
+    }
+    let first_commit = &new_commits[0];
+    let last_commit = &new_commits[num_parts - 1];
 
     let legacy_bookmark_behavior = tx.settings().get_bool("split.legacy-bookmark-behavior")?;
     if legacy_bookmark_behavior {
-        // Mark the commit being split as rewritten to the second commit. This
-        // moves any bookmarks pointing to the target commit to the second
+        // Mark the commit being split as rewritten to the last commit. This
+        // moves any bookmarks pointing to the target commit to the last
         // commit.
         tx.repo_mut()
-            .set_rewritten_commit(target.commit.id().clone(), second_commit.id().clone());
+            .set_rewritten_commit(target_commit.id().clone(), last_commit.id().clone());
     }
     let mut num_rebased = 0;
     tx.repo_mut()
-        .transform_descendants(vec![target.commit.id().clone()], |mut rewriter| {
+        .transform_descendants(vec![target_commit.id().clone()], |mut rewriter| {
             num_rebased += 1;
             if parallel && legacy_bookmark_behavior {
-                // The old_parent is the second commit due to the rewrite above.
-                rewriter
-                    .replace_parent(second_commit.id(), [first_commit.id(), second_commit.id()]);
+                // The old_parent is the last commit due to the rewrite above.
+                rewriter.replace_parent(last_commit.id(), new_commits.iter().map(Commit::id));
             } else if parallel {
-                rewriter.replace_parent(first_commit.id(), [first_commit.id(), second_commit.id()]);
+                rewriter.replace_parent(first_commit.id(), new_commits.iter().map(Commit::id));
             } else {
-                rewriter.replace_parent(first_commit.id(), [second_commit.id()]);
+                rewriter.replace_parent(first_commit.id(), [last_commit.id()]);
             }
             rewriter.rebase()?.write()?;
             Ok(())
         })?;
-    // Move the working copy commit (@) to the second commit for any workspaces
+    // Move the working copy commit (@) to the last commit for any workspaces
     // where the target commit is the working copy commit.
     for (name, working_copy_commit) in tx.base_repo().clone().view().wc_commit_ids() {
-        if working_copy_commit == target.commit.id() {
-            tx.repo_mut().edit(name.clone(), &second_commit)?;
+        if working_copy_commit == target_commit.id() {
+            tx.repo_mut().edit(name.clone(), last_commit)?;
         }
     }
 
@@ -262,61 +299,138 @@ pub(crate) fn cmd_split(
         if num_rebased > 0 {
             writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
         }
-        write!(formatter, "First part: ")?;
-        tx.write_commit_summary(formatter.as_mut(), &first_commit)?;
-        write!(formatter, "\nSecond part: ")?;
-        tx.write_commit_summary(formatter.as_mut(), &second_commit)?;
-        writeln!(formatter)?;
+        for (index, commit) in new_commits.iter().enumerate() {
+            match (index, num_parts) {
+                (0, 2) => write!(formatter, "First part: ")?,
+                (1, 2) => write!(formatter, "Second part: ")?,
+                _ => write!(formatter, "Part {} of {num_parts}: ", index + 1)?,
+            }
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
     }
-    tx.finish(ui, format!("split commit {}", target.commit.id().hex()))?;
+    tx.finish(ui, format!("split commit {}", target_commit.id().hex()))?;
     Ok(())
 }
 
-/// Prompts the user to select the content they want in the first commit and
-/// returns the target commit and the tree corresponding to the selection.
-fn select_diff(
+/// Describes the part at `index` in messages, e.g. "the first commit".
+fn part_label(index: usize, num_parts: usize) -> String {
+    match (index, num_parts) {
+        (0, 2) => "the first commit".to_owned(),
+        (1, 2) => "the second commit".to_owned(),
+        _ => format!("commit {} of {num_parts}", index + 1),
+    }
+}
+
+/// Prompts the user to select the content they want in each part but the last
+/// one, and returns the tree of each part.
+fn select_diffs(
     ui: &Ui,
     tx: &WorkspaceCommandTransaction,
     target_commit: &Commit,
     matcher: &dyn Matcher,
     diff_selector: &DiffSelector,
-) -> Result<CommitWithSelection, CommandError> {
-    let format_instructions = || {
-        format!(
-            "\
-You are splitting a commit into two: {}
+    num_parts: usize,
+) -> Result<Vec<MergedTree>, CommandError> {
+    let format_instructions = |index: usize| {
+        let summary = tx.format_commit_summary(target_commit);
+        if num_parts == 2 {
+            format!(
+                "\
+You are splitting a commit into two: {summary}
 
 The diff initially shows the changes in the commit you're splitting.
 
 Adjust the right side until it shows the contents you want for the first commit.
 The remainder will be in the second commit.
+"
+            )
+        } else {
+            format!(
+                "\
+You are splitting a commit into {num_parts} commits: {summary}
+
+The diff initially shows the changes that are not in the previous commits.
+
+Adjust the right side until it shows the contents you want for {label}.
+The remainder will be in the later commits.
 ",
-            tx.format_commit_summary(target_commit)
-        )
+                label = part_label(index, num_parts)
+            )
+        }
     };
     let parent_tree = target_commit.parent_tree(tx.repo())?;
-    let selected_tree_id = diff_selector.select(
-        &parent_tree,
-        &target_commit.tree()?,
-        matcher,
-        format_instructions,
-    )?;
-    let selection = CommitWithSelection {
-        commit: target_commit.clone(),
-        selected_tree: tx.repo().store().get_root_tree(&selected_tree_id)?,
-        parent_tree,
-    };
-    if selection.is_full_selection() {
-        writeln!(
-            ui.warning_default(),
-            "All changes have been selected, so the second commit will be empty"
-        )?;
-    } else if selection.is_empty_selection() {
-        writeln!(
-            ui.warning_default(),
-            "No changes have been selected, so the first commit will be empty"
-        )?;
+    let target_tree = target_commit.tree()?;
+    let mut trees: Vec<MergedTree> = Vec::with_capacity(num_parts);
+    for index in 0..num_parts - 1 {
+        let base_tree = trees.last().unwrap_or(&parent_tree);
+        let selected_tree = if base_tree.id() == target_tree.id() {
+            // Nothing left to select
+            base_tree.clone()
+        } else {
+            let selected_tree_id =
+                diff_selector.select(base_tree, &target_tree, matcher, || {
+                    format_instructions(index)
+                })?;
+            tx.repo().store().get_root_tree(&selected_tree_id)?
+        };
+        trees.push(selected_tree);
+    }
+    trees.push(target_tree);
+
+    if num_parts == 2 {
+        if trees[0].id() == trees[1].id() {
+            writeln!(
+                ui.warning_default(),
+                "All changes have been selected, so the second commit will be empty"
+            )?;
+        } else if trees[0].id() == parent_tree.id() {
+            writeln!(
+                ui.warning_default(),
+                "No changes have been selected, so the first commit will be empty"
+            )?;
+        }
+    } else {
+        for (index, tree) in trees.iter().enumerate() {
+            let base_tree = index.checked_sub(1).map_or(&parent_tree, |i| &trees[i]);
+            if tree.id() == base_tree.id() {
+                writeln!(
+                    ui.warning_default(),
+                    "No changes have been selected for {}, so it will be empty",
+                    part_label(index, num_parts)
+                )?;
+            }
+        }
     }
+    Ok(trees)
+}
 
-    Ok(selection)
+/// Returns the tree of each part when each changed file is put in its own
+/// part.
+fn split_trees_by_file(
+    tx: &WorkspaceCommandTransaction,
+    target_commit: &Commit,
+) -> Result<Vec<MergedTree>, CommandError> {
+    let parent_tree = target_commit.parent_tree(tx.repo())?;
+    let target_tree = target_commit.tree()?;
+    let paths: Vec<RepoPathBuf> = parent_tree
+        .diff_stream(&target_tree, &EverythingMatcher)
+        .map(|entry| entry.path)
+        .collect()
+        .block_on();
+    if paths.len() < 2 {
+        return Err(user_error(format!(
+            "Refusing to split commit {} by file because it changes only one file.",
+            target_commit.id().hex()
+        )));
+    }
+    let mut trees: Vec<MergedTree> = (1..paths.len())
+        .map(|num_files| {
+            let matcher = FilesMatcher::new(&paths[..num_files]);
+            let tree_id = restore_tree(&target_tree, &parent_tree, &matcher)?;
+            tx.repo().store().get_root_tree(&tree_id)
+        })
+        .try_collect()?;
+    trees.push(target_tree);
+    Ok(trees)
 }
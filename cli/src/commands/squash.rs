@@ -17,7 +17,7 @@ use indoc::formatdoc;
 use itertools::Itertools as _;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt as _;
-use jj_lib::matchers::Matcher;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite;
@@ -155,9 +155,7 @@ pub(crate) fn cmd_squash(
         destination = parents.pop().unwrap();
     }
 
-    let matcher = workspace_command
-        .parse_file_patterns(ui, &args.paths)?
-        .to_matcher();
+    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
     let diff_selector =
         workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
     let text_editor = workspace_command.text_editor()?;
@@ -167,7 +165,13 @@ pub(crate) fn cmd_squash(
 
     let mut tx = workspace_command.start_transaction();
     let tx_description = format!("squash commits into {}", destination.id().hex());
-    let source_commits = select_diff(&tx, &sources, &destination, &matcher, &diff_selector)?;
+    let source_commits = select_diff(
+        &tx,
+        &sources,
+        &destination,
+        &fileset_expression,
+        &diff_selector,
+    )?;
     if let Some(squashed) = rewrite::squash_commits(
         tx.repo_mut(),
         &source_commits,
@@ -275,13 +279,15 @@ fn select_diff(
     tx: &WorkspaceCommandTransaction,
     sources: &[Commit],
     destination: &Commit,
-    matcher: &dyn Matcher,
+    fileset_expression: &FilesetExpression,
     diff_selector: &DiffSelector,
 ) -> Result<Vec<CommitWithSelection>, CommandError> {
     let mut source_commits = vec![];
     for source in sources {
         let parent_tree = source.parent_tree(tx.repo())?;
         let source_tree = source.tree()?;
+        let matcher =
+            fileset_expression.to_tree_matcher(vec![parent_tree.clone(), source_tree.clone()]);
         let format_instructions = || {
             formatdoc! {"
                 You are moving changes from: {source}
@@ -299,8 +305,12 @@ fn select_diff(
                 destination = tx.format_commit_summary(destination),
             }
         };
-        let selected_tree_id =
-            diff_selector.select(&parent_tree, &source_tree, matcher, format_instructions)?;
+        let selected_tree_id = diff_selector.select(
+            &parent_tree,
+            &source_tree,
+            matcher.as_ref(),
+            format_instructions,
+        )?;
         let selected_tree = tx.repo().store().get_root_tree(&selected_tree_id)?;
         source_commits.push(CommitWithSelection {
             commit: source.clone(),
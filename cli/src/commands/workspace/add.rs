@@ -13,12 +13,15 @@
 // limitations under the License.
 
 use std::fs;
+use std::path::Path;
 
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::file_util;
 use jj_lib::file_util::IoResultExt as _;
 use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::workspace::Workspace;
@@ -26,6 +29,7 @@ use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
@@ -73,6 +77,14 @@ pub struct WorkspaceAddArgs {
     /// How to handle sparse patterns when creating a new workspace.
     #[arg(long, value_enum, default_value_t = SparseInheritance::Copy)]
     sparse_patterns: SparseInheritance,
+    /// Colocate the new workspace with a linked Git worktree
+    ///
+    /// A new Git worktree is created at the destination, and its HEAD will
+    /// track the parent of the workspace's working-copy commit. If the
+    /// destination is already a Git worktree of the underlying Git repo, a
+    /// workspace is created on top of the worktree's HEAD.
+    #[arg(long)]
+    colocate: bool,
 }
 
 #[instrument(skip_all)]
@@ -83,10 +95,17 @@ pub fn cmd_workspace_add(
 ) -> Result<(), CommandError> {
     let old_workspace_command = command.workspace_helper(ui)?;
     let destination_path = command.cwd().join(&args.destination);
-    if destination_path.exists() {
+    let adopt_git_worktree = args.colocate
+        && !destination_path.join(".jj").exists()
+        && is_git_worktree_of(old_workspace_command.repo(), &destination_path);
+    if adopt_git_worktree {
+        if !args.revision.is_empty() {
+            return Err(user_error(
+                "Cannot specify revisions for a workspace in an existing Git worktree",
+            ));
+        }
+    } else if destination_path.exists() {
         return Err(user_error("Workspace already exists"));
-    } else {
-        fs::create_dir(&destination_path).context(&destination_path)?;
     }
     let workspace_name = if let Some(name) = &args.name {
         name.to_owned()
@@ -105,6 +124,35 @@ pub fn cmd_workspace_add(
         )));
     }
 
+    // If no parent revisions are specified, create a working-copy commit based
+    // on the parent of the current working-copy commit.
+    let parents = if args.revision.is_empty() {
+        // Check out parents of the current workspace's working-copy commit, or the
+        // root if there is no working-copy commit in the current workspace.
+        if let Some(old_wc_commit_id) = old_workspace_command.get_wc_commit_id() {
+            repo.store()
+                .get_commit(old_wc_commit_id)?
+                .parents()
+                .try_collect()?
+        } else {
+            vec![repo.store().root_commit()]
+        }
+    } else {
+        old_workspace_command
+            .resolve_some_revsets_default_single(ui, &args.revision)?
+            .iter()
+            .map(|id| repo.store().get_commit(id))
+            .try_collect()?
+    };
+
+    if adopt_git_worktree {
+        // The worktree is already populated
+    } else if args.colocate {
+        add_git_worktree(&old_workspace_command, &destination_path, parents[0].id())?;
+    } else {
+        fs::create_dir(&destination_path).context(&destination_path)?;
+    }
+
     let working_copy_factory = command.get_working_copy_factory()?;
     let repo_path = old_workspace_command.repo_path();
     // If we add per-workspace configuration, we'll need to reload settings for
@@ -133,6 +181,8 @@ pub fn cmd_workspace_add(
     }
 
     let mut new_workspace_command = command.for_workable_repo(ui, new_workspace, repo)?;
+    #[cfg(feature = "git")]
+    crate::commands::git::maybe_add_gitignore(&new_workspace_command)?;
 
     let sparsity = match args.sparse_patterns {
         SparseInheritance::Full => None,
@@ -157,34 +207,14 @@ pub fn cmd_workspace_add(
         locked_ws.finish(operation_id)?;
     }
 
-    let mut tx = new_workspace_command.start_transaction();
-
-    // If no parent revisions are specified, create a working-copy commit based
-    // on the parent of the current working-copy commit.
-    let parents = if args.revision.is_empty() {
-        // Check out parents of the current workspace's working-copy commit, or the
-        // root if there is no working-copy commit in the current workspace.
-        if let Some(old_wc_commit_id) = tx
-            .base_repo()
-            .view()
-            .get_wc_commit_id(old_workspace_command.workspace_name())
-        {
-            tx.repo()
-                .store()
-                .get_commit(old_wc_commit_id)?
-                .parents()
-                .try_collect()?
-        } else {
-            vec![tx.repo().store().root_commit()]
-        }
-    } else {
-        old_workspace_command
-            .resolve_some_revsets_default_single(ui, &args.revision)?
-            .iter()
-            .map(|id| tx.repo().store().get_commit(id))
-            .try_collect()?
-    };
+    if adopt_git_worktree {
+        // Snapshotting imports the worktree's HEAD and checks out a new
+        // working-copy commit on top of it without touching the files.
+        new_workspace_command.maybe_snapshot(ui)?;
+        return Ok(());
+    }
 
+    let mut tx = new_workspace_command.start_transaction();
     let tree = merge_commit_trees(tx.repo(), &parents)?;
     let parent_ids = parents.iter().ids().cloned().collect_vec();
     let new_wc_commit = tx.repo_mut().new_commit(parent_ids, tree.id()).write()?;
@@ -199,3 +229,42 @@ pub fn cmd_workspace_add(
     )?;
     Ok(())
 }
+
+#[cfg(feature = "git")]
+fn is_git_worktree_of(repo: &ReadonlyRepo, path: &Path) -> bool {
+    path.join(".git").is_file() && jj_lib::git::open_linked_worktree(repo.store(), path).is_some()
+}
+
+#[cfg(not(feature = "git"))]
+fn is_git_worktree_of(_repo: &ReadonlyRepo, _path: &Path) -> bool {
+    false
+}
+
+#[cfg(feature = "git")]
+fn add_git_worktree(
+    workspace_command: &WorkspaceCommandHelper,
+    path: &Path,
+    commit_id: &CommitId,
+) -> Result<(), CommandError> {
+    let git_settings = workspace_command.settings().git_settings()?;
+    jj_lib::git::add_worktree(
+        workspace_command.repo().store(),
+        &git_settings,
+        path,
+        commit_id,
+    )
+    .map_err(|err| {
+        crate::command_error::user_error_with_message("Failed to create Git worktree", err)
+    })
+}
+
+#[cfg(not(feature = "git"))]
+fn add_git_worktree(
+    _workspace_command: &WorkspaceCommandHelper,
+    _path: &Path,
+    _commit_id: &CommitId,
+) -> Result<(), CommandError> {
+    Err(user_error(
+        "Cannot create a Git worktree because jj was not compiled with the `git` feature",
+    ))
+}
@@ -15,6 +15,7 @@
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -75,5 +76,9 @@ pub fn cmd_workspace_forget(
     };
 
     tx.finish(ui, description)?;
+    let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path());
+    for ws in &wss {
+        workspace_store.forget(ws)?;
+    }
     Ok(())
 }
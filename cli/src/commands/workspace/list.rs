@@ -12,7 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
+use std::path::Path;
+
+use jj_lib::commit::Commit;
+use jj_lib::op_store::OpStoreError;
+use jj_lib::ref_name::WorkspaceName;
+use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
+use jj_lib::working_copy::WorkingCopyFreshness;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -20,6 +29,11 @@ use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// List workspaces
+///
+/// Workspaces other than the current one are checked for problems. They are
+/// reported as "missing" if the workspace directory no longer exists, as
+/// "broken link" if the workspace doesn't point to this repo, and as "stale"
+/// if its working copy hasn't been updated to the working-copy commit.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceListArgs {}
 
@@ -31,13 +45,98 @@ pub fn cmd_workspace_list(
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
+    let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path());
     let mut formatter = ui.stdout_formatter();
     let template = workspace_command.commit_summary_template();
     for (name, wc_commit_id) in repo.view().wc_commit_ids() {
         write!(formatter, "{}: ", name.as_symbol())?;
         let commit = repo.store().get_commit(wc_commit_id)?;
         template.format(&commit, formatter.as_mut())?;
+        if name != workspace_command.workspace_name() {
+            let health = check_workspace_health(
+                command,
+                &workspace_store,
+                workspace_command.repo_path(),
+                repo,
+                name,
+                &commit,
+            )?;
+            if health != WorkspaceHealth::Ok {
+                write!(formatter.labeled("warning"), " ({health})")?;
+            }
+        }
         writeln!(formatter)?;
     }
     Ok(())
 }
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum WorkspaceHealth {
+    /// The workspace looks fine, or its location is unknown.
+    Ok,
+    /// The workspace directory doesn't exist.
+    Missing,
+    /// The workspace can't be loaded or points to another repo.
+    BrokenLink,
+    /// The working copy isn't at the working-copy commit.
+    Stale,
+}
+
+impl fmt::Display for WorkspaceHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Missing => write!(f, "missing"),
+            Self::BrokenLink => write!(f, "broken link"),
+            Self::Stale => write!(f, "stale"),
+        }
+    }
+}
+
+fn check_workspace_health(
+    command: &CommandHelper,
+    workspace_store: &SimpleWorkspaceStore,
+    repo_path: &Path,
+    repo: &ReadonlyRepo,
+    name: &WorkspaceName,
+    wc_commit: &Commit,
+) -> Result<WorkspaceHealth, CommandError> {
+    let Some(workspace_root) = workspace_store.get_workspace_path(name)? else {
+        // Workspaces created by older versions don't record their location.
+        return Ok(WorkspaceHealth::Ok);
+    };
+    if !workspace_root.join(".jj").is_dir() {
+        return Ok(WorkspaceHealth::Missing);
+    }
+    let Ok(workspace) = command.load_workspace_at(&workspace_root, command.settings()) else {
+        return Ok(WorkspaceHealth::BrokenLink);
+    };
+    let same_repo = match (
+        dunce::canonicalize(workspace.repo_path()),
+        dunce::canonicalize(repo_path),
+    ) {
+        (Ok(path1), Ok(path2)) => path1 == path2,
+        _ => false,
+    };
+    if !same_repo || workspace.workspace_name() != name {
+        return Ok(WorkspaceHealth::BrokenLink);
+    }
+    // Same check as the one done before snapshotting the working copy, but
+    // without locking it.
+    let working_copy = workspace.working_copy();
+    match WorkingCopyFreshness::check_stale_at(
+        working_copy.tree_id()?,
+        working_copy.operation_id(),
+        wc_commit,
+        repo,
+    ) {
+        // The working copy may have been updated after the operation being
+        // listed.
+        Ok(WorkingCopyFreshness::Fresh | WorkingCopyFreshness::Updated(_)) => {
+            Ok(WorkspaceHealth::Ok)
+        }
+        Ok(WorkingCopyFreshness::WorkingCopyStale | WorkingCopyFreshness::SiblingOperation)
+        | Err(OpStoreError::ObjectNotFound { .. }) => Ok(WorkspaceHealth::Stale),
+        Err(err) => Err(err.into()),
+    }
+}
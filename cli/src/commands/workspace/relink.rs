@@ -0,0 +1,83 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::file_util;
+use jj_lib::workspace::relink_workspace;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use tracing::instrument;
+
+use crate::cli_util::find_workspace_dir;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Point the current workspace to the new location of a moved repo
+///
+/// Workspaces refer to the repo by path. If the repo is moved or mounted
+/// elsewhere, the workspaces attached to it can no longer be loaded. Run this
+/// command in such a workspace to repair it.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspaceRelinkArgs {
+    /// The new location of the repo
+    ///
+    /// This can be the root of any healthy workspace of the repo, or the repo
+    /// directory itself (`.jj/repo` in the workspace that contains the repo).
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    repo: String,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_relink(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceRelinkArgs,
+) -> Result<(), CommandError> {
+    // The current workspace is usually broken, so it can't be loaded until
+    // it's relinked.
+    let workspace_root = find_workspace_dir(command.cwd()).to_owned();
+    let destination = command.cwd().join(&args.repo);
+    let repo_path = if destination.join(".jj").is_dir() {
+        command
+            .load_workspace_at(&destination, command.settings())?
+            .repo_path()
+            .to_owned()
+    } else {
+        destination
+    };
+    let repo_path = relink_workspace(&workspace_root, &repo_path).map_err(user_error)?;
+
+    let workspace = command.load_workspace_at(&workspace_root, command.settings())?;
+    SimpleWorkspaceStore::load(workspace.repo_path())
+        .add(workspace.workspace_name(), workspace.workspace_root())?;
+    writeln!(
+        ui.status(),
+        "Workspace now uses the repo in \"{}\"",
+        file_util::relative_path(command.cwd(), &repo_path).display()
+    )?;
+
+    let repo = workspace.repo_loader().load_at_head()?;
+    if repo
+        .view()
+        .get_wc_commit_id(workspace.workspace_name())
+        .is_none()
+    {
+        writeln!(
+            ui.warning_default(),
+            "The workspace '{}' is not tracked in this repo.",
+            workspace.workspace_name().as_symbol()
+        )?;
+    }
+    Ok(())
+}
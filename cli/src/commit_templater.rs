@@ -38,6 +38,7 @@ use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+use jj_lib::fileset::FilesetExtensions;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::id_prefix::IdPrefixIndex;
 use jj_lib::matchers::Matcher;
@@ -908,6 +909,29 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    #[cfg(feature = "git")]
+    map.insert(
+        "notes",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            let ([], [namespace_node]) = function.expect_arguments()?;
+            let namespace = namespace_node
+                .map(|node| {
+                    template_parser::expect_string_literal_with(node, |text, _span| {
+                        Ok(text.to_owned())
+                    })
+                })
+                .transpose()?
+                .unwrap_or_else(|| "commits".to_owned());
+            let notes =
+                jj_lib::git::read_notes(language.repo.store(), &namespace).map_err(|err| {
+                    TemplateParseError::expression("Failed to read Git notes", function.name_span)
+                        .with_source(err)
+                })?;
+            let out_property = self_property
+                .map(move |commit| notes.get(commit.id()).cloned().unwrap_or_default());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "divergent",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -978,16 +1002,23 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
         |language, diagnostics, _build_ctx, self_property, function| {
             let ([], [files_node]) = function.expect_arguments()?;
             let files = if let Some(node) = files_node {
-                expect_fileset_literal(diagnostics, node, language.path_converter)?
+                expect_fileset_literal(
+                    diagnostics,
+                    node,
+                    language.path_converter,
+                    language
+                        .revset_parse_context
+                        .extensions
+                        .fileset_extensions(),
+                )?
             } else {
                 // TODO: defaults to CLI path arguments?
                 // https://github.com/jj-vcs/jj/issues/2933#issuecomment-1925870731
                 FilesetExpression::all()
             };
             let repo = language.repo;
-            let matcher: Rc<dyn Matcher> = files.to_matcher().into();
             let out_property = self_property
-                .and_then(move |commit| Ok(TreeDiff::from_commit(repo, &commit, matcher.clone())?));
+                .and_then(move |commit| Ok(TreeDiff::from_commit(repo, &commit, &files)?));
             Ok(out_property.into_dyn_wrapped())
         },
     );
@@ -1023,13 +1054,14 @@ fn expect_fileset_literal(
     diagnostics: &mut TemplateDiagnostics,
     node: &ExpressionNode,
     path_converter: &RepoPathUiConverter,
+    extensions: &FilesetExtensions,
 ) -> Result<FilesetExpression, TemplateParseError> {
     template_parser::expect_string_literal_with(node, |text, span| {
         let mut inner_diagnostics = FilesetDiagnostics::new();
-        let expression =
-            fileset::parse(&mut inner_diagnostics, text, path_converter).map_err(|err| {
-                TemplateParseError::expression("In fileset expression", span).with_source(err)
-            })?;
+        let expression = fileset::parse(&mut inner_diagnostics, text, path_converter, extensions)
+            .map_err(|err| {
+            TemplateParseError::expression("In fileset expression", span).with_source(err)
+        })?;
         diagnostics.extend_with(inner_diagnostics, |diag| {
             TemplateParseError::expression("In fileset expression", span).with_source(diag)
         });
@@ -1687,8 +1719,13 @@ impl TreeDiff {
     fn from_commit(
         repo: &dyn Repo,
         commit: &Commit,
-        matcher: Rc<dyn Matcher>,
+        files: &FilesetExpression,
     ) -> BackendResult<Self> {
+        let from_tree = commit.parent_tree(repo)?;
+        let to_tree = commit.tree()?;
+        let matcher: Rc<dyn Matcher> = files
+            .to_tree_matcher(vec![from_tree.clone(), to_tree.clone()])
+            .into();
         let mut copy_records = CopyRecords::default();
         for parent in commit.parent_ids() {
             let records =
@@ -1696,8 +1733,8 @@ impl TreeDiff {
             copy_records.add_records(records)?;
         }
         Ok(TreeDiff {
-            from_tree: commit.parent_tree(repo)?,
-            to_tree: commit.tree()?,
+            from_tree,
+            to_tree,
             matcher,
             copy_records,
         })
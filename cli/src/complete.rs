@@ -34,6 +34,7 @@ use crate::config::default_config_layers;
 use crate::config::ConfigArgKind;
 use crate::config::ConfigEnv;
 use crate::config::CONFIG_SCHEMA;
+use crate::revset_util::load_external_functions;
 use crate::revset_util::load_revset_aliases;
 use crate::ui::Ui;
 
@@ -240,6 +241,7 @@ fn revisions(match_prefix: &str, revset_filter: Option<&str>) -> Vec<CompletionC
         const REMOTE_BOOKMARK_MINE: usize = 4;
         const REMOTE_BOOKMARK: usize = 5;
         const REVSET_ALIAS: usize = 6;
+        const REVSET_FUNCTION: usize = 7;
 
         let mut candidates = Vec::new();
 
@@ -363,6 +365,23 @@ fn revisions(match_prefix: &str, revset_filter: Option<&str>) -> Vec<CompletionC
                 }),
         );
 
+        // revset functions defined by config
+
+        candidates.extend(
+            load_external_functions(settings, "revset-functions")
+                .into_iter()
+                .filter(|(name, _)| name.starts_with(match_prefix))
+                .filter_map(|(name, config)| {
+                    let config = config.ok()?;
+                    let description =
+                        Some(config.description).filter(|description| !description.is_empty());
+                    let candidate = CompletionCandidate::new(format!("{name}()"))
+                        .help(description.map(Into::into))
+                        .display_order(Some(REVSET_FUNCTION));
+                    Some(candidate)
+                }),
+        );
+
         Ok(candidates)
     })
 }
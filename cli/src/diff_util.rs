@@ -58,6 +58,7 @@ use jj_lib::files::ConflictDiffHunk;
 use jj_lib::files::DiffLineHunkSide;
 use jj_lib::files::DiffLineIterator;
 use jj_lib::files::DiffLineNumber;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
@@ -458,19 +459,24 @@ impl<'a> DiffRenderer<'a> {
     }
 
     /// Generates diff of the given `commit` compared to its parents.
+    ///
+    /// File predicates in the `files` expression are evaluated against the
+    /// trees of the `commit` and its parents.
     pub fn show_patch(
         &self,
         ui: &Ui,
         formatter: &mut dyn Formatter,
         commit: &Commit,
-        matcher: &dyn Matcher,
+        files: &FilesetExpression,
         width: usize,
     ) -> Result<(), DiffRenderError> {
         let from_tree = commit.parent_tree(self.repo)?;
         let to_tree = commit.tree()?;
+        let matcher = files.to_tree_matcher(vec![from_tree.clone(), to_tree.clone()]);
         let mut copy_records = CopyRecords::default();
         for parent_id in commit.parent_ids() {
-            let records = get_copy_records(self.repo.store(), parent_id, commit.id(), matcher)?;
+            let records =
+                get_copy_records(self.repo.store(), parent_id, commit.id(), matcher.as_ref())?;
             copy_records.add_records(records)?;
         }
         self.show_diff(
@@ -478,7 +484,7 @@ impl<'a> DiffRenderer<'a> {
             formatter,
             &from_tree,
             &to_tree,
-            matcher,
+            matcher.as_ref(),
             &copy_records,
             width,
         )
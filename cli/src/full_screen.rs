@@ -0,0 +1,204 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Terminal handling shared by the full-screen interfaces, such as the
+//! interactive log and the builtin merge editor.
+
+use std::collections::VecDeque;
+use std::io;
+use std::io::Write as _;
+
+use crossterm::cursor;
+use crossterm::event;
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use crossterm::queue;
+use crossterm::terminal;
+use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::config::ConfigValue;
+use jj_lib::settings::UserSettings;
+
+/// Page height used when the keys are scripted and there's no terminal to
+/// measure.
+const SCRIPTED_PAGE_HEIGHT: usize = 23;
+
+/// Puts the terminal in full-screen mode until dropped.
+pub(crate) struct TerminalScreen;
+
+impl TerminalScreen {
+    pub(crate) fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        let result = queue!(
+            stdout,
+            terminal::EnterAlternateScreen,
+            terminal::DisableLineWrap,
+            cursor::Hide
+        )
+        .and_then(|()| stdout.flush());
+        if let Err(err) = result {
+            Self::leave();
+            return Err(err);
+        }
+        Ok(TerminalScreen)
+    }
+
+    fn leave() {
+        let mut stdout = io::stdout();
+        let _ = queue!(
+            stdout,
+            cursor::Show,
+            terminal::EnableLineWrap,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = stdout.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Drop for TerminalScreen {
+    fn drop(&mut self) {
+        Self::leave();
+    }
+}
+
+/// Source of key presses for a full-screen interface.
+#[derive(Clone, Debug)]
+pub(crate) enum KeyInput {
+    /// Keys are read from the terminal, which the interface is drawn on.
+    Terminal,
+    /// Keys are taken from `debug.scripted-keys`, and nothing is drawn. This
+    /// is used by tests.
+    Scripted(VecDeque<KeyEvent>),
+}
+
+impl KeyInput {
+    pub(crate) fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let keys = settings
+            .get_value_with("debug.scripted-keys", parse_scripted_keys)
+            .optional()?;
+        Ok(keys.map_or(KeyInput::Terminal, KeyInput::Scripted))
+    }
+
+    pub(crate) fn is_terminal(&self) -> bool {
+        matches!(self, KeyInput::Terminal)
+    }
+
+    /// Enters full-screen mode if the keys are read from the terminal.
+    pub(crate) fn enter_screen(&self) -> io::Result<Option<TerminalScreen>> {
+        match self {
+            KeyInput::Terminal => TerminalScreen::enter().map(Some),
+            KeyInput::Scripted(_) => Ok(None),
+        }
+    }
+
+    /// Waits for the next key press.
+    pub(crate) fn read_key(&mut self) -> io::Result<KeyEvent> {
+        match self {
+            KeyInput::Terminal => loop {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        return Ok(key);
+                    }
+                }
+            },
+            KeyInput::Scripted(keys) => keys.pop_front().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "Ran out of scripted keys")
+            }),
+        }
+    }
+
+    /// Returns the number of lines available for content, excluding the
+    /// status line.
+    pub(crate) fn page_height(&self) -> io::Result<usize> {
+        match self {
+            KeyInput::Terminal => {
+                let (_, height) = terminal::size()?;
+                Ok(usize::from(height.saturating_sub(1)))
+            }
+            KeyInput::Scripted(_) => Ok(SCRIPTED_PAGE_HEIGHT),
+        }
+    }
+}
+
+fn parse_scripted_keys(value: ConfigValue) -> Result<VecDeque<KeyEvent>, String> {
+    let array = value
+        .as_array()
+        .ok_or_else(|| format!("Expected an array, but is {}", value.type_name()))?;
+    array
+        .iter()
+        .map(|item| {
+            let name = item
+                .as_str()
+                .ok_or_else(|| format!("Expected a key name, but is {}", item.type_name()))?;
+            parse_key(name).ok_or_else(|| format!("Invalid key name: {name:?}"))
+        })
+        .collect()
+}
+
+/// Parses key names such as `j`, `enter`, or `ctrl-c`.
+fn parse_key(name: &str) -> Option<KeyEvent> {
+    if let Some(name) = name.strip_prefix("ctrl-") {
+        let key = parse_key(name)?;
+        return Some(KeyEvent::new(key.code, KeyModifiers::CONTROL));
+    }
+    let code = match name {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => {
+            let mut chars = name.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            KeyCode::Char(c)
+        }
+    };
+    Some(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            parse_key("j"),
+            Some(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("space"),
+            Some(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("ctrl-c"),
+            Some(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(parse_key(""), None);
+        assert_eq!(parse_key("jk"), None);
+    }
+}
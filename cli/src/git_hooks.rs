@@ -0,0 +1,298 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs the hooks of the underlying Git repo at the analogous points of jj
+//! commands.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+
+use jj_lib::backend::CommitId;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::git;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::ref_name::RemoteName;
+
+use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Hooks of the Git repo backing a workspace.
+///
+/// Hooks are looked up in `core.hooksPath`, or in the `hooks` directory of
+/// the Git repo. Like Git, hooks are run in the workspace root, and
+/// `GIT_DIR` is set to the Git directory. If the workspace is colocated,
+/// `GIT_WORK_TREE` and `GIT_INDEX_FILE` are set as well. The `pre-commit` hook
+/// gets a temporary `GIT_INDEX_FILE` holding the tree to be committed.
+pub struct GitHooks {
+    git_repo: gix::Repository,
+    hooks_dir: PathBuf,
+    work_dir: PathBuf,
+    colocated: bool,
+}
+
+/// Ref update passed to the `pre-push` hook.
+pub struct PrePushUpdate {
+    pub local_ref: String,
+    pub local_id: Option<CommitId>,
+    pub remote_ref: String,
+    pub remote_id: Option<CommitId>,
+}
+
+impl GitHooks {
+    /// Locates the hooks of `git_repo`, which is the repo or worktree the
+    /// workspace at `workspace_root` is backed by.
+    pub fn new(git_repo: gix::Repository, workspace_root: &Path, colocated: bool) -> Self {
+        let hooks_dir = match git_repo.config_snapshot().trusted_path("core.hooksPath") {
+            // A relative path is resolved from the directory hooks run in.
+            Some(Ok(path)) => workspace_root.join(path),
+            Some(Err(_)) | None => git_repo.common_dir().join("hooks"),
+        };
+        GitHooks {
+            git_repo,
+            hooks_dir,
+            work_dir: workspace_root.to_owned(),
+            colocated,
+        }
+    }
+
+    /// Runs the `pre-commit` hook. The commit is rejected if the hook fails.
+    ///
+    /// `tree` is the tree to be committed, which the hook sees as the Git
+    /// index. `uses_editor` tells whether the commit message will be edited
+    /// interactively.
+    pub fn run_pre_commit(
+        &self,
+        ui: &Ui,
+        tree: &MergedTree,
+        uses_editor: bool,
+    ) -> Result<(), CommandError> {
+        let Some(hook_path) = self.find_hook("pre-commit") else {
+            return Ok(());
+        };
+        // The index of a colocated repo holds the parent tree, so the hook
+        // would see no staged changes. Like `git commit <paths>`, use a
+        // temporary index containing the tree to be committed instead.
+        let git_dir = self.git_repo.git_dir();
+        let index_file = tempfile::Builder::new()
+            .prefix("jj-index-")
+            .tempfile_in(git_dir)
+            .context(git_dir)?;
+        git::write_index_file(&self.git_repo, tree, index_file.path())?;
+        let envs = self.commit_envs(Some(index_file.path().to_owned()), uses_editor);
+        let status = self.run_hook(ui, &hook_path, &[], None, &envs)?;
+        if !status.success() {
+            return Err(user_error_with_hint(
+                format!("Commit rejected by pre-commit hook ({status})"),
+                "Use --no-verify to skip the hook.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Runs the `commit-msg` hook on `description`, and returns the
+    /// description as edited by the hook. The description is rejected if the
+    /// hook fails.
+    pub fn run_commit_msg(
+        &self,
+        ui: &Ui,
+        description: &str,
+        uses_editor: bool,
+    ) -> Result<String, CommandError> {
+        let Some(hook_path) = self.find_hook("commit-msg") else {
+            return Ok(description.to_owned());
+        };
+        let message_path = self.git_repo.git_dir().join("COMMIT_EDITMSG");
+        fs::write(&message_path, description).context(&message_path)?;
+        let index_path = self.colocated.then(|| self.git_repo.index_path());
+        let envs = self.commit_envs(index_path, uses_editor);
+        let status = self.run_hook(ui, &hook_path, &[message_path.as_os_str()], None, &envs)?;
+        if !status.success() {
+            return Err(user_error_with_hint(
+                format!("Description rejected by commit-msg hook ({status})"),
+                "Use --no-verify to skip the hook.",
+            ));
+        }
+        Ok(fs::read_to_string(&message_path).context(&message_path)?)
+    }
+
+    /// Runs the `pre-push` hook with the given ref updates on stdin. The push
+    /// is aborted if the hook fails.
+    pub fn run_pre_push(
+        &self,
+        ui: &Ui,
+        remote: &RemoteName,
+        updates: &[PrePushUpdate],
+    ) -> Result<(), CommandError> {
+        let Some(hook_path) = self.find_hook("pre-push") else {
+            return Ok(());
+        };
+        let url = match self.git_repo.try_find_remote(remote.as_str()) {
+            Some(Ok(git_remote)) => git_remote
+                .url(gix::remote::Direction::Push)
+                .map(|url| url.to_bstring().to_string()),
+            Some(Err(_)) | None => None,
+        };
+        let input: String = updates
+            .iter()
+            .map(|update| {
+                let local_ref = if update.local_id.is_some() {
+                    update.local_ref.as_str()
+                } else {
+                    "(delete)"
+                };
+                format!(
+                    "{local_ref} {} {} {}\n",
+                    self.format_id(update.local_id.as_ref()),
+                    update.remote_ref,
+                    self.format_id(update.remote_id.as_ref()),
+                )
+            })
+            .collect();
+        let args = [
+            OsStr::new(remote.as_str()),
+            OsStr::new(url.as_deref().unwrap_or(remote.as_str())),
+        ];
+        let status = self.run_hook(ui, &hook_path, &args, Some(input.as_bytes()), &[])?;
+        if !status.success() {
+            return Err(user_error_with_hint(
+                format!("Push rejected by pre-push hook ({status})"),
+                "Use --no-verify to skip the hook.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns true if there's a `post-rewrite` hook to run.
+    pub fn has_post_rewrite(&self) -> bool {
+        self.find_hook("post-rewrite").is_some()
+    }
+
+    /// Runs the `post-rewrite` hook with the `(old, new)` commit pairs on
+    /// stdin. `kind` is either `amend` or `rebase`. The exit status of the
+    /// hook is ignored.
+    pub fn run_post_rewrite(
+        &self,
+        ui: &Ui,
+        kind: &str,
+        rewrites: &[(CommitId, CommitId)],
+    ) -> Result<(), CommandError> {
+        let Some(hook_path) = self.find_hook("post-rewrite") else {
+            return Ok(());
+        };
+        let input: String = rewrites
+            .iter()
+            .map(|(old_id, new_id)| format!("{} {}\n", old_id.hex(), new_id.hex()))
+            .collect();
+        let args = [OsStr::new(kind)];
+        self.run_hook(ui, &hook_path, &args, Some(input.as_bytes()), &[])?;
+        Ok(())
+    }
+
+    fn find_hook(&self, name: &str) -> Option<PathBuf> {
+        let path = self.hooks_dir.join(name);
+        is_executable_file(&path).then_some(path)
+    }
+
+    fn commit_envs(
+        &self,
+        index_path: Option<PathBuf>,
+        uses_editor: bool,
+    ) -> Vec<(&'static str, PathBuf)> {
+        let mut envs = vec![];
+        if let Some(index_path) = index_path {
+            envs.push(("GIT_INDEX_FILE", index_path));
+        }
+        if !uses_editor {
+            // Tells the hook that no editor will be opened, like Git does.
+            envs.push(("GIT_EDITOR", PathBuf::from(":")));
+        }
+        envs
+    }
+
+    fn format_id(&self, id: Option<&CommitId>) -> String {
+        match id {
+            Some(id) => id.hex(),
+            None => gix::ObjectId::null(self.git_repo.object_hash()).to_string(),
+        }
+    }
+
+    fn run_hook(
+        &self,
+        ui: &Ui,
+        hook_path: &Path,
+        args: &[&OsStr],
+        input: Option<&[u8]>,
+        envs: &[(&str, PathBuf)],
+    ) -> Result<ExitStatus, CommandError> {
+        let name = hook_path.file_name().unwrap().to_string_lossy();
+        let mut command = Command::new(hook_path);
+        command
+            .args(args)
+            .current_dir(&self.work_dir)
+            .env("GIT_DIR", self.git_repo.git_dir());
+        if self.colocated {
+            command.env("GIT_WORK_TREE", &self.work_dir);
+        }
+        command.envs(envs.iter().map(|(key, value)| (key, value)));
+        tracing::info!(?command, "running Git hook");
+        let mut child = command
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(ui.stderr_for_child()?)
+            .spawn()
+            .map_err(|err| {
+                user_error_with_message(format!("Failed to run Git {name} hook"), err)
+            })?;
+        let stdin = child.stdin.take();
+        let output = std::thread::scope(|s| {
+            if let (Some(mut stdin), Some(input)) = (stdin, input) {
+                s.spawn(move || {
+                    // The hook may exit without reading its input.
+                    stdin.write_all(input).ok();
+                });
+            }
+            child.wait_with_output()
+        })?;
+        // Like Git, show the output of the hook on stderr.
+        ui.stderr().write_all(&output.stdout)?;
+        let exit_status = output.status;
+        tracing::info!(?command, ?exit_status, "Git hook exited");
+        Ok(exit_status)
+    }
+}
+
+#[cfg(unix)]
+fn is_executable_file(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt as _;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable_file(path: &Path) -> bool {
+    path.is_file()
+}
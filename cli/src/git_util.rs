@@ -53,6 +53,10 @@ use crate::ui::ProgressOutput;
 use crate::ui::Ui;
 
 pub fn is_colocated_git_workspace(workspace: &Workspace, repo: &ReadonlyRepo) -> bool {
+    is_colocated_with_main_git_worktree(workspace, repo) || is_linked_git_worktree(workspace, repo)
+}
+
+fn is_colocated_with_main_git_worktree(workspace: &Workspace, repo: &ReadonlyRepo) -> bool {
     let Ok(git_backend) = git::get_git_backend(repo.store()) else {
         return false;
     };
@@ -70,6 +74,14 @@ pub fn is_colocated_git_workspace(workspace: &Workspace, repo: &ReadonlyRepo) ->
     dunce::canonicalize(git_workdir).ok().as_deref() == dot_git_path.parent()
 }
 
+/// Whether the workspace is colocated with a linked Git worktree (created by
+/// `git worktree add`) of the underlying Git repo.
+pub fn is_linked_git_worktree(workspace: &Workspace, repo: &ReadonlyRepo) -> bool {
+    // Linked worktrees have a ".git" file pointing to the Git directory.
+    workspace.workspace_root().join(".git").is_file()
+        && git::open_linked_worktree(repo.store(), workspace.workspace_root()).is_some()
+}
+
 /// Parses user-specified remote URL or path to absolute form.
 pub fn absolute_git_url(cwd: &Path, source: &str) -> Result<String, CommandError> {
     // Git appears to turn URL-like source to absolute path if local git directory
@@ -0,0 +1,655 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Full-screen terminal interface of `jj log --interactive`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::io::IsTerminal as _;
+use std::io::Write;
+use std::mem;
+use std::rc::Rc;
+
+use crossterm::cursor;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use crossterm::queue;
+use crossterm::style::Attribute;
+use crossterm::style::SetAttribute;
+use crossterm::terminal;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::move_commits;
+use jj_lib::rewrite::squash_commits;
+use jj_lib::rewrite::CommitWithSelection;
+use jj_lib::rewrite::MoveCommitsLocation;
+use jj_lib::rewrite::MoveCommitsTarget;
+use jj_lib::rewrite::RebaseOptions;
+use jj_lib::rewrite::RewriteRefsOptions;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::description_util::add_trailers;
+use crate::description_util::combine_messages_for_editing;
+use crate::description_util::description_template;
+use crate::description_util::edit_description;
+use crate::description_util::try_combine_messages;
+use crate::full_screen::KeyInput;
+use crate::ui::Ui;
+
+const HELP_TEXT: &str = "j/k: move  enter: diff  e: edit  d: describe  n: new  a: abandon  s: \
+                         squash  space: mark  r: rebase marked  q: quit";
+
+/// Writer that can be drained while another handle to it is held by the graph
+/// renderer.
+#[derive(Clone, Debug, Default)]
+pub struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    /// Takes the data written so far.
+    pub fn take(&self) -> Vec<u8> {
+        mem::take(&mut self.0.borrow_mut())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Rendered graph node. Synthetic nodes such as elided revisions have no
+/// commit.
+#[derive(Clone, Debug)]
+pub struct InteractiveLogNode {
+    commit_id: Option<CommitId>,
+    lines: Vec<String>,
+}
+
+impl InteractiveLogNode {
+    pub fn new(commit_id: Option<CommitId>, output: &[u8]) -> Self {
+        let lines = String::from_utf8_lossy(output)
+            .lines()
+            .map(|line| line.to_owned())
+            .collect();
+        InteractiveLogNode { commit_id, lines }
+    }
+}
+
+/// Action to be run as a transaction on the selected revision.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum LogAction {
+    Describe(CommitId),
+    New(CommitId),
+    Edit(CommitId),
+    Abandon(CommitId),
+    Squash(CommitId),
+    Rebase {
+        source: CommitId,
+        destination: CommitId,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ViewCommand {
+    None,
+    Quit,
+    ToggleDiff,
+    Run(LogAction),
+}
+
+/// Navigation state of the interactive log.
+#[derive(Debug, Default)]
+struct LogView {
+    nodes: Vec<InteractiveLogNode>,
+    /// Index of the selected node, which is a commit node unless there are
+    /// none.
+    selected: usize,
+    /// Index of the first visible line.
+    scroll: usize,
+    marked: Option<CommitId>,
+    expanded: HashSet<CommitId>,
+    message: Option<String>,
+}
+
+impl LogView {
+    /// Replaces the rendered nodes, keeping the selection on the same commit
+    /// if it still exists.
+    fn set_nodes(&mut self, nodes: Vec<InteractiveLogNode>) {
+        let selected_id = self.selected_commit_id().cloned();
+        self.nodes = nodes;
+        let position_of = |id: &CommitId| {
+            self.nodes
+                .iter()
+                .position(|node| node.commit_id.as_ref() == Some(id))
+        };
+        let selected = selected_id.and_then(|id| position_of(&id));
+        if self
+            .marked
+            .as_ref()
+            .is_some_and(|id| position_of(id).is_none())
+        {
+            self.marked = None;
+        }
+        // If the selected commit disappeared, select the nearest commit at or
+        // below the previous position.
+        let index = self.selected.min(self.nodes.len().saturating_sub(1));
+        self.selected = selected
+            .or_else(|| (index..self.nodes.len()).find(|&i| self.nodes[i].commit_id.is_some()))
+            .or_else(|| (0..index).rfind(|&i| self.nodes[i].commit_id.is_some()))
+            .unwrap_or(index);
+    }
+
+    fn selected_commit_id(&self) -> Option<&CommitId> {
+        self.nodes.get(self.selected)?.commit_id.as_ref()
+    }
+
+    /// Moves the selection by `delta` commits, skipping synthetic nodes.
+    fn move_selection(&mut self, delta: isize) {
+        let mut remaining = delta.unsigned_abs();
+        let mut index = self.selected;
+        while remaining > 0 {
+            let next = if delta < 0 {
+                index.checked_sub(1)
+            } else {
+                Some(index + 1).filter(|&i| i < self.nodes.len())
+            };
+            let Some(next) = next else {
+                break;
+            };
+            index = next;
+            if self.nodes[index].commit_id.is_some() {
+                self.selected = index;
+                remaining -= 1;
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, page_height: usize) -> ViewCommand {
+        self.message = None;
+        let page = isize::try_from(page_height / 2).unwrap_or(1).max(1);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return ViewCommand::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return ViewCommand::Quit;
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(page),
+            KeyCode::PageUp => self.move_selection(-page),
+            KeyCode::Char('g') | KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::Char('G') | KeyCode::End => self.move_selection(isize::MAX),
+            _ => {}
+        }
+        let Some(commit_id) = self.selected_commit_id().cloned() else {
+            return ViewCommand::None;
+        };
+        match key.code {
+            KeyCode::Enter | KeyCode::Tab => {
+                if !self.expanded.remove(&commit_id) {
+                    self.expanded.insert(commit_id);
+                }
+                ViewCommand::ToggleDiff
+            }
+            KeyCode::Char(' ') => {
+                if self.marked.as_ref() == Some(&commit_id) {
+                    self.marked = None;
+                } else {
+                    self.marked = Some(commit_id);
+                }
+                ViewCommand::None
+            }
+            KeyCode::Char('d') => ViewCommand::Run(LogAction::Describe(commit_id)),
+            KeyCode::Char('n') => ViewCommand::Run(LogAction::New(commit_id)),
+            KeyCode::Char('e') => ViewCommand::Run(LogAction::Edit(commit_id)),
+            KeyCode::Char('a') => ViewCommand::Run(LogAction::Abandon(commit_id)),
+            KeyCode::Char('s') => ViewCommand::Run(LogAction::Squash(commit_id)),
+            KeyCode::Char('r') => {
+                if let Some(source) = self.marked.take() {
+                    ViewCommand::Run(LogAction::Rebase {
+                        source,
+                        destination: commit_id,
+                    })
+                } else {
+                    self.message = Some("Mark a revision to rebase with space first".to_owned());
+                    ViewCommand::None
+                }
+            }
+            _ => ViewCommand::None,
+        }
+    }
+
+    /// Scrolls so that the selected node is visible within `height` lines.
+    fn scroll_to_selection(&mut self, height: usize) {
+        let start: usize = self.nodes[..self.selected]
+            .iter()
+            .map(|node| node.lines.len())
+            .sum();
+        let end = start
+            + self
+                .nodes
+                .get(self.selected)
+                .map_or(0, |node| node.lines.len());
+        let total: usize = self.nodes.iter().map(|node| node.lines.len()).sum();
+        if start < self.scroll {
+            self.scroll = start;
+        } else if end > self.scroll + height {
+            self.scroll = start.min(end - height);
+        }
+        self.scroll = self.scroll.min(total.saturating_sub(height));
+    }
+
+    /// Returns the visible lines along with the node index each line belongs
+    /// to.
+    fn visible_lines(&self, height: usize) -> impl Iterator<Item = (usize, &str)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, node)| node.lines.iter().map(move |line| (i, line.as_str())))
+            .skip(self.scroll)
+            .take(height)
+    }
+
+    fn draw(&mut self, output: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let page_height = usize::from(height.saturating_sub(1));
+        queue!(
+            output,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+        let mut last_index = None;
+        self.scroll_to_selection(page_height);
+        for (row, (index, line)) in self.visible_lines(page_height).enumerate() {
+            let node = &self.nodes[index];
+            let is_first_line = last_index != Some(index);
+            last_index = Some(index);
+            let selection_mark = if index == self.selected && is_first_line {
+                '>'
+            } else {
+                ' '
+            };
+            let mark = if is_first_line && node.commit_id.is_some() && node.commit_id == self.marked
+            {
+                '*'
+            } else {
+                ' '
+            };
+            queue!(output, cursor::MoveTo(0, row.try_into().unwrap()))?;
+            write!(output, "{selection_mark}{mark}{line}")?;
+            queue!(output, SetAttribute(Attribute::Reset))?;
+        }
+        let status = self.message.as_deref().unwrap_or(HELP_TEXT);
+        let status: String = status.chars().take(usize::from(width)).collect();
+        queue!(
+            output,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            SetAttribute(Attribute::Reverse)
+        )?;
+        write!(output, "{status:width$}", width = usize::from(width))?;
+        queue!(output, SetAttribute(Attribute::Reset))?;
+        output.flush()
+    }
+}
+
+/// Runs the interactive log until the user quits.
+///
+/// The graph is rendered by `render`, which is called again after each action
+/// with the diff expanded for the given commits. Actions are run outside of
+/// the full-screen mode so that editors and command output work as usual.
+pub fn run_interactive_log(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    render: impl Fn(
+        &Ui,
+        &WorkspaceCommandHelper,
+        &HashSet<CommitId>,
+    ) -> Result<Vec<InteractiveLogNode>, CommandError>,
+) -> Result<(), CommandError> {
+    let mut input = KeyInput::from_settings(workspace_command.settings())?;
+    if input.is_terminal() && (!io::stdin().is_terminal() || !io::stdout().is_terminal()) {
+        return Err(user_error("The interactive log requires a terminal"));
+    }
+    let mut view = LogView::default();
+    view.set_nodes(render(ui, workspace_command, &view.expanded)?);
+    loop {
+        let command = {
+            let screen = input.enter_screen()?;
+            loop {
+                if screen.is_some() {
+                    view.draw(&mut io::stdout())?;
+                }
+                let key = input.read_key()?;
+                match view.handle_key(key, input.page_height()?) {
+                    ViewCommand::None => {}
+                    ViewCommand::ToggleDiff => {
+                        view.set_nodes(render(ui, workspace_command, &view.expanded)?);
+                    }
+                    command @ (ViewCommand::Quit | ViewCommand::Run(_)) => break command,
+                }
+            }
+        };
+        let ViewCommand::Run(action) = command else {
+            return Ok(());
+        };
+        let message = match run_action(ui, workspace_command, &action) {
+            Ok(message) => message,
+            Err(err) => format!("Error: {}", err.error),
+        };
+        // Without a screen to show it on, the status line goes to stderr.
+        if !input.is_terminal() {
+            writeln!(ui.status(), "{message}")?;
+        }
+        view.message = Some(message);
+        view.set_nodes(render(ui, workspace_command, &view.expanded)?);
+    }
+}
+
+/// Runs the action in a transaction. Returns a message to be displayed in the
+/// status line.
+fn run_action(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    action: &LogAction,
+) -> Result<String, CommandError> {
+    match action {
+        LogAction::Describe(commit_id) => describe(ui, workspace_command, commit_id),
+        LogAction::New(commit_id) => {
+            let commit = workspace_command.repo().store().get_commit(commit_id)?;
+            let mut tx = workspace_command.start_transaction();
+            let new_commit = tx
+                .repo_mut()
+                .new_commit(vec![commit.id().clone()], commit.tree_id().clone())
+                .write()?;
+            tx.edit(&new_commit)?;
+            tx.finish(ui, "new empty commit")?;
+            Ok(format!(
+                "Created new commit on top of {}",
+                short_commit_hash(commit_id)
+            ))
+        }
+        LogAction::Edit(commit_id) => {
+            let commit = workspace_command.repo().store().get_commit(commit_id)?;
+            workspace_command.check_rewritable([commit.id()])?;
+            if workspace_command.get_wc_commit_id() == Some(commit.id()) {
+                return Ok("Already editing that commit".to_owned());
+            }
+            let mut tx = workspace_command.start_transaction();
+            tx.edit(&commit)?;
+            tx.finish(ui, format!("edit commit {}", commit.id().hex()))?;
+            Ok(format!("Editing {}", short_commit_hash(commit_id)))
+        }
+        LogAction::Abandon(commit_id) => {
+            let commit = workspace_command.repo().store().get_commit(commit_id)?;
+            workspace_command.check_rewritable([commit.id()])?;
+            let mut tx = workspace_command.start_transaction();
+            let options = RewriteRefsOptions {
+                delete_abandoned_bookmarks: true,
+            };
+            tx.repo_mut().transform_descendants_with_options(
+                vec![commit.id().clone()],
+                &HashMap::new(),
+                &options,
+                |rewriter| {
+                    if rewriter.old_commit().id() == commit.id() {
+                        rewriter.abandon();
+                    } else {
+                        rewriter.rebase()?.write()?;
+                    }
+                    Ok(())
+                },
+            )?;
+            tx.finish(ui, format!("abandon commit {}", commit.id().hex()))?;
+            Ok(format!("Abandoned {}", short_commit_hash(commit_id)))
+        }
+        LogAction::Squash(commit_id) => squash_into_parent(ui, workspace_command, commit_id),
+        LogAction::Rebase {
+            source,
+            destination,
+        } => {
+            workspace_command.check_rewritable([source])?;
+            if workspace_command
+                .repo()
+                .index()
+                .is_ancestor(source, destination)
+            {
+                return Err(user_error(
+                    "Cannot rebase a revision onto itself or its descendant",
+                ));
+            }
+            let loc = MoveCommitsLocation {
+                new_parent_ids: vec![destination.clone()],
+                new_child_ids: vec![],
+                target: MoveCommitsTarget::Roots(vec![source.clone()]),
+            };
+            let mut tx = workspace_command.start_transaction();
+            move_commits(tx.repo_mut(), &loc, &RebaseOptions::default())?;
+            tx.finish(
+                ui,
+                format!("rebase commit {} and descendants", source.hex()),
+            )?;
+            Ok(format!(
+                "Rebased {} onto {}",
+                short_commit_hash(source),
+                short_commit_hash(destination)
+            ))
+        }
+    }
+}
+
+fn describe(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    commit_id: &CommitId,
+) -> Result<String, CommandError> {
+    let commit = workspace_command.repo().store().get_commit(commit_id)?;
+    workspace_command.check_rewritable([commit.id()])?;
+    let text_editor = workspace_command.text_editor()?;
+    let git_hooks = workspace_command.git_hooks()?;
+    let mut tx = workspace_command.start_transaction();
+    let mut commit_builder = tx.repo_mut().rewrite_commit(&commit).detach();
+    let description = add_trailers(ui, &tx, &commit_builder)?;
+    commit_builder.set_description(description);
+    let temp_commit = commit_builder.write_hidden()?;
+    let template = description_template(ui, &tx, "", &temp_commit)?;
+    let description = edit_description(&text_editor, &template)?;
+    if description == commit.description() {
+        return Ok("Nothing changed".to_owned());
+    }
+    let description = match &git_hooks {
+        Some(git_hooks) if !description.is_empty() => {
+            git_hooks.run_commit_msg(ui, &description, true)?
+        }
+        _ => description,
+    };
+    commit_builder.set_description(description);
+    commit_builder.write(tx.repo_mut())?;
+    tx.repo_mut().rebase_descendants()?;
+    tx.finish(ui, format!("describe commit {}", commit.id().hex()))?;
+    Ok(format!("Described {}", short_commit_hash(commit_id)))
+}
+
+fn squash_into_parent(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    commit_id: &CommitId,
+) -> Result<String, CommandError> {
+    let commit = workspace_command.repo().store().get_commit(commit_id)?;
+    let parents: Vec<Commit> = commit.parents().collect::<Result<_, _>>()?;
+    let [parent] = &*parents else {
+        return Err(user_error("Cannot squash a merge commit into its parents"));
+    };
+    workspace_command.check_rewritable([commit.id(), parent.id()])?;
+    let text_editor = workspace_command.text_editor()?;
+    let mut tx = workspace_command.start_transaction();
+    let source = CommitWithSelection {
+        selected_tree: commit.tree()?,
+        parent_tree: commit.parent_tree(tx.repo())?,
+        commit: commit.clone(),
+    };
+    let Some(squashed) = squash_commits(tx.repo_mut(), &[source], parent, false)? else {
+        return Ok("Nothing changed".to_owned());
+    };
+    let abandoned_commits = squashed.abandoned_commits;
+    let mut commit_builder = squashed.commit_builder.detach();
+    let new_description = match try_combine_messages(&abandoned_commits, parent) {
+        Some(description) if description.is_empty() => description,
+        Some(description) => {
+            commit_builder.set_description(description);
+            add_trailers(ui, &tx, &commit_builder)?
+        }
+        None => {
+            let intro = "Enter a description for the combined commit.";
+            let combined =
+                combine_messages_for_editing(ui, &tx, &abandoned_commits, parent, &commit_builder)?;
+            commit_builder.set_description(combined);
+            let temp_commit = commit_builder.write_hidden()?;
+            let template = description_template(ui, &tx, intro, &temp_commit)?;
+            edit_description(&text_editor, &template)?
+        }
+    };
+    commit_builder.set_description(new_description);
+    commit_builder.write(tx.repo_mut())?;
+    tx.finish(ui, format!("squash commits into {}", parent.id().hex()))?;
+    Ok(format!(
+        "Squashed {} into {}",
+        short_commit_hash(commit_id),
+        short_commit_hash(parent.id())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use super::*;
+
+    fn commit_id(hex: &'static str) -> CommitId {
+        CommitId::from_hex(hex)
+    }
+
+    fn node(commit_id: Option<CommitId>, text: &str) -> InteractiveLogNode {
+        InteractiveLogNode::new(commit_id, text.as_bytes())
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn new_view() -> LogView {
+        let mut view = LogView::default();
+        view.set_nodes(vec![
+            node(Some(commit_id("aa")), "@ aa\n│ first\n"),
+            node(None, "~ (elided revisions)\n"),
+            node(Some(commit_id("bb")), "○ bb\n"),
+            node(Some(commit_id("cc")), "◆ cc\n"),
+        ]);
+        view
+    }
+
+    #[test]
+    fn test_move_selection_skips_synthetic_nodes() {
+        let mut view = new_view();
+        assert_eq!(view.selected_commit_id(), Some(&commit_id("aa")));
+        view.handle_key(key(KeyCode::Down), 10);
+        assert_eq!(view.selected_commit_id(), Some(&commit_id("bb")));
+        view.handle_key(key(KeyCode::Char('j')), 10);
+        view.handle_key(key(KeyCode::Char('j')), 10);
+        assert_eq!(view.selected_commit_id(), Some(&commit_id("cc")));
+        view.handle_key(key(KeyCode::Home), 10);
+        assert_eq!(view.selected_commit_id(), Some(&commit_id("aa")));
+        view.handle_key(key(KeyCode::End), 10);
+        assert_eq!(view.selected_commit_id(), Some(&commit_id("cc")));
+    }
+
+    #[test]
+    fn test_set_nodes_keeps_selection() {
+        let mut view = new_view();
+        view.handle_key(key(KeyCode::Down), 10);
+        view.set_nodes(vec![
+            node(Some(commit_id("dd")), "@ dd\n"),
+            node(Some(commit_id("bb")), "○ bb\n"),
+        ]);
+        assert_eq!(view.selected_commit_id(), Some(&commit_id("bb")));
+
+        // The selection stays in place if the selected commit was rewritten.
+        view.set_nodes(vec![
+            node(Some(commit_id("dd")), "@ dd\n"),
+            node(Some(commit_id("ee")), "○ ee\n"),
+        ]);
+        assert_eq!(view.selected_commit_id(), Some(&commit_id("ee")));
+    }
+
+    #[test]
+    fn test_actions() {
+        let mut view = new_view();
+        assert_eq!(
+            view.handle_key(key(KeyCode::Char('e')), 10),
+            ViewCommand::Run(LogAction::Edit(commit_id("aa")))
+        );
+        assert_eq!(
+            view.handle_key(key(KeyCode::Enter), 10),
+            ViewCommand::ToggleDiff
+        );
+        assert!(view.expanded.contains(&commit_id("aa")));
+
+        // Rebasing requires a marked revision.
+        assert_eq!(
+            view.handle_key(key(KeyCode::Char('r')), 10),
+            ViewCommand::None
+        );
+        assert!(view.message.is_some());
+        view.handle_key(key(KeyCode::Char(' ')), 10);
+        view.handle_key(key(KeyCode::Down), 10);
+        assert_eq!(
+            view.handle_key(key(KeyCode::Char('r')), 10),
+            ViewCommand::Run(LogAction::Rebase {
+                source: commit_id("aa"),
+                destination: commit_id("bb"),
+            })
+        );
+        assert_eq!(view.marked, None);
+        assert_eq!(
+            view.handle_key(key(KeyCode::Char('q')), 10),
+            ViewCommand::Quit
+        );
+    }
+
+    #[test]
+    fn test_visible_lines_follow_selection() {
+        let mut view = new_view();
+        view.scroll_to_selection(2);
+        let lines = view.visible_lines(2).collect_vec();
+        assert_eq!(lines, vec![(0, "@ aa"), (0, "│ first")]);
+        view.handle_key(key(KeyCode::End), 2);
+        view.scroll_to_selection(2);
+        let lines = view.visible_lines(2).collect_vec();
+        assert_eq!(lines, vec![(2, "○ bb"), (3, "◆ cc")]);
+        view.handle_key(key(KeyCode::Home), 2);
+        view.scroll_to_selection(2);
+        let lines = view.visible_lines(2).collect_vec();
+        assert_eq!(lines, vec![(0, "@ aa"), (0, "│ first")]);
+    }
+}
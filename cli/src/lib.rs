@@ -24,8 +24,42 @@ pub mod config;
 pub mod description_util;
 pub mod diff_util;
 pub mod formatter;
+mod full_screen;
 pub mod generic_templater;
 #[cfg(feature = "git")]
+pub mod git_hooks;
+#[cfg(not(feature = "git"))]
+/// A stub module that provides a no-op implementation of the Git hooks.
+pub mod git_hooks {
+    use jj_lib::merged_tree::MergedTree;
+
+    use crate::command_error::CommandError;
+    use crate::ui::Ui;
+
+    /// Git hooks, which can't be instantiated without the `git` feature.
+    pub enum GitHooks {}
+
+    impl GitHooks {
+        pub fn run_pre_commit(
+            &self,
+            _ui: &Ui,
+            _tree: &MergedTree,
+            _uses_editor: bool,
+        ) -> Result<(), CommandError> {
+            match *self {}
+        }
+
+        pub fn run_commit_msg(
+            &self,
+            _ui: &Ui,
+            _description: &str,
+            _uses_editor: bool,
+        ) -> Result<String, CommandError> {
+            match *self {}
+        }
+    }
+}
+#[cfg(feature = "git")]
 pub mod git_util;
 #[cfg(not(feature = "git"))]
 /// A stub module that provides a no-op implementation of some of the functions
@@ -37,8 +71,13 @@ pub mod git_util {
     pub fn is_colocated_git_workspace(_workspace: &Workspace, _repo: &ReadonlyRepo) -> bool {
         false
     }
+
+    pub fn is_linked_git_worktree(_workspace: &Workspace, _repo: &ReadonlyRepo) -> bool {
+        false
+    }
 }
 pub mod graphlog;
+pub mod interactive_log;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
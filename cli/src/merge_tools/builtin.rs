@@ -18,8 +18,6 @@ use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopyRecords;
 use jj_lib::diff::Diff;
 use jj_lib::diff::DiffHunkKind;
-use jj_lib::files;
-use jj_lib::files::MergeResult;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
@@ -32,7 +30,7 @@ use jj_lib::store::Store;
 use pollster::FutureExt as _;
 use thiserror::Error;
 
-use super::MergeToolFile;
+use crate::description_util::TempTextEditError;
 
 #[derive(Debug, Error)]
 pub enum BuiltinToolError {
@@ -47,6 +45,12 @@ pub enum BuiltinToolError {
     Unimplemented { item: &'static str, id: String },
     #[error("Backend error")]
     BackendError(#[from] jj_lib::backend::BackendError),
+    #[error("Failed to access the terminal")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    TextEdit(#[from] TempTextEditError),
+    #[error("Merge was cancelled")]
+    Cancelled,
 }
 
 #[derive(Clone, Debug)]
@@ -490,154 +494,9 @@ pub fn edit_diff_builtin(
     Ok(tree_id)
 }
 
-fn make_merge_sections(
-    merge_result: MergeResult,
-) -> Result<Vec<scm_record::Section<'static>>, BuiltinToolError> {
-    let mut sections = Vec::new();
-    match merge_result {
-        MergeResult::Resolved(buf) => {
-            let contents = buf_to_file_contents(None, buf.into());
-            let section = match contents {
-                FileContents::Absent => None,
-                FileContents::Text {
-                    contents,
-                    hash: _,
-                    num_bytes: _,
-                } => Some(scm_record::Section::Unchanged {
-                    lines: contents
-                        .split_inclusive('\n')
-                        .map(|line| Cow::Owned(line.to_owned()))
-                        .collect(),
-                }),
-                FileContents::Binary { .. } => Some(scm_record::Section::Binary {
-                    // TODO: Perhaps, this should be an "unchanged" section?
-                    is_checked: false,
-                    old_description: None,
-                    new_description: contents.describe().map(Cow::Owned),
-                }),
-            };
-            if let Some(section) = section {
-                sections.push(section);
-            }
-        }
-        MergeResult::Conflict(hunks) => {
-            for hunk in hunks {
-                let section = match hunk.into_resolved() {
-                    Ok(contents) => {
-                        let contents = std::str::from_utf8(&contents).map_err(|err| {
-                            BuiltinToolError::DecodeUtf8 {
-                                source: err,
-                                item: "unchanged hunk",
-                            }
-                        })?;
-                        scm_record::Section::Unchanged {
-                            lines: contents
-                                .split_inclusive('\n')
-                                .map(|line| Cow::Owned(line.to_owned()))
-                                .collect(),
-                        }
-                    }
-                    Err(merge) => {
-                        let lines: Vec<scm_record::SectionChangedLine> = merge
-                            .iter()
-                            .zip(
-                                [
-                                    scm_record::ChangeType::Added,
-                                    scm_record::ChangeType::Removed,
-                                ]
-                                .into_iter()
-                                .cycle(),
-                            )
-                            .map(|(contents, change_type)| -> Result<_, BuiltinToolError> {
-                                let contents = std::str::from_utf8(contents).map_err(|err| {
-                                    BuiltinToolError::DecodeUtf8 {
-                                        source: err,
-                                        item: "conflicting hunk",
-                                    }
-                                })?;
-                                let changed_lines =
-                                    make_section_changed_lines(contents, change_type);
-                                Ok(changed_lines)
-                            })
-                            .flatten_ok()
-                            .try_collect()?;
-                        scm_record::Section::Changed { lines }
-                    }
-                };
-                sections.push(section);
-            }
-        }
-    }
-    Ok(sections)
-}
-
-fn make_merge_file(
-    merge_tool_file: &MergeToolFile,
-) -> Result<scm_record::File<'static>, BuiltinToolError> {
-    let file = &merge_tool_file.file;
-    let file_mode = if file.executable.expect("should have been resolved") {
-        mode::EXECUTABLE
-    } else {
-        mode::NORMAL
-    };
-    // TODO: Maybe we should test binary contents here, and generate per-file
-    // Binary section to select either "our" or "their" file.
-    let merge_result = files::merge_hunks(&file.contents);
-    let sections = make_merge_sections(merge_result)?;
-    Ok(scm_record::File {
-        old_path: None,
-        // Path for displaying purposes, not for file access.
-        path: Cow::Owned(
-            merge_tool_file
-                .repo_path
-                .to_fs_path_unchecked(Path::new("")),
-        ),
-        file_mode,
-        sections,
-    })
-}
-
-pub fn edit_merge_builtin(
-    tree: &MergedTree,
-    merge_tool_files: &[MergeToolFile],
-) -> Result<MergedTreeId, BuiltinToolError> {
-    let mut input = scm_record::helpers::CrosstermInput;
-    let recorder = scm_record::Recorder::new(
-        scm_record::RecordState {
-            is_read_only: false,
-            files: merge_tool_files.iter().map(make_merge_file).try_collect()?,
-            commits: Default::default(),
-        },
-        &mut input,
-    );
-    let state = recorder.run()?;
-
-    let store = tree.store();
-    let mut tree_builder = MergedTreeBuilder::new(tree.id().clone());
-    apply_changes(
-        &mut tree_builder,
-        merge_tool_files
-            .iter()
-            .map(|file| file.repo_path.clone())
-            .collect_vec(),
-        &state.files,
-        // TODO: It doesn't make sense to select new value from the source tree.
-        // Perhaps, "their" tree value should be extracted from a conflict?
-        |path| tree.path_value(path),
-        |path, contents, executable| {
-            let id = store.write_file(path, &mut &contents[..]).block_on()?;
-            Ok(Merge::normal(TreeValue::File { id, executable }))
-        },
-    )?;
-    Ok(tree_builder.write_tree(store)?)
-}
-
 #[cfg(test)]
 mod tests {
-    use jj_lib::backend::FileId;
-    use jj_lib::conflicts::extract_as_single_hunk;
     use jj_lib::matchers::EverythingMatcher;
-    use jj_lib::merge::MergedTreeValue;
     use jj_lib::repo::Repo as _;
     use testutils::repo_path;
     use testutils::TestRepo;
@@ -1258,92 +1117,4 @@ mod tests {
             "all-changes tree was different",
         );
     }
-
-    #[test]
-    fn test_make_merge_sections() {
-        let test_repo = TestRepo::init();
-        let store = test_repo.repo.store();
-
-        let path = repo_path("file");
-        let base_tree = testutils::create_tree(
-            &test_repo.repo,
-            &[(path, "base 1\nbase 2\nbase 3\nbase 4\nbase 5\n")],
-        );
-        let left_tree = testutils::create_tree(
-            &test_repo.repo,
-            &[(path, "left 1\nbase 2\nbase 3\nbase 4\nleft 5\n")],
-        );
-        let right_tree = testutils::create_tree(
-            &test_repo.repo,
-            &[(path, "right 1\nbase 2\nbase 3\nbase 4\nright 5\n")],
-        );
-
-        fn to_file_id(tree_value: MergedTreeValue) -> Option<FileId> {
-            match tree_value.into_resolved() {
-                Ok(Some(TreeValue::File { id, executable: _ })) => Some(id.clone()),
-                other => {
-                    panic!("merge should have been a FileId: {other:?}")
-                }
-            }
-        }
-        let merge = Merge::from_vec(vec![
-            to_file_id(left_tree.path_value(path).unwrap()),
-            to_file_id(base_tree.path_value(path).unwrap()),
-            to_file_id(right_tree.path_value(path).unwrap()),
-        ]);
-        let content = extract_as_single_hunk(&merge, store, path)
-            .block_on()
-            .unwrap();
-        let merge_result = files::merge_hunks(&content);
-        let sections = make_merge_sections(merge_result).unwrap();
-        insta::assert_debug_snapshot!(sections, @r#"
-        [
-            Changed {
-                lines: [
-                    SectionChangedLine {
-                        is_checked: false,
-                        change_type: Added,
-                        line: "left 1\n",
-                    },
-                    SectionChangedLine {
-                        is_checked: false,
-                        change_type: Removed,
-                        line: "base 1\n",
-                    },
-                    SectionChangedLine {
-                        is_checked: false,
-                        change_type: Added,
-                        line: "right 1\n",
-                    },
-                ],
-            },
-            Unchanged {
-                lines: [
-                    "base 2\n",
-                    "base 3\n",
-                    "base 4\n",
-                ],
-            },
-            Changed {
-                lines: [
-                    SectionChangedLine {
-                        is_checked: false,
-                        change_type: Added,
-                        line: "left 5\n",
-                    },
-                    SectionChangedLine {
-                        is_checked: false,
-                        change_type: Removed,
-                        line: "base 5\n",
-                    },
-                    SectionChangedLine {
-                        is_checked: false,
-                        change_type: Added,
-                        line: "right 5\n",
-                    },
-                ],
-            },
-        ]
-        "#);
-    }
 }
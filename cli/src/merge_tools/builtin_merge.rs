@@ -0,0 +1,648 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Builtin 3-way merge editor used by `jj resolve --tool :builtin`.
+
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use bstr::BString;
+use bstr::ByteSlice as _;
+use crossterm::cursor;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use crossterm::queue;
+use crossterm::style::Attribute;
+use crossterm::style::SetAttribute;
+use crossterm::terminal;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::backend::TreeValue;
+use jj_lib::conflicts;
+use jj_lib::conflicts::choose_materialized_conflict_marker_len;
+use jj_lib::conflicts::materialize_merge_result_to_bytes_with_marker_len;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::files;
+use jj_lib::files::MergeResult;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use pollster::FutureExt as _;
+
+use super::builtin::BuiltinToolError;
+use super::MergeToolFile;
+use crate::description_util::TextEditor;
+use crate::full_screen::KeyInput;
+
+const HELP_TEXT: &str = "j/k: move  1-9: pick side  b: base  a/r: both sides  e: edit  u: \
+                         unresolve  enter: save  q: cancel";
+
+/// Number of lines of surrounding text shown around the selected conflict.
+const CONTEXT_LINES: usize = 3;
+
+/// Part of a file as presented in the merge editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum EditorHunk {
+    /// Content that all sides agree on.
+    Resolved(BString),
+    /// Conflicting content, and the text chosen to replace it if any.
+    Conflict {
+        sides: Merge<BString>,
+        resolution: Option<BString>,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct EditorFile {
+    /// Path for displaying purposes.
+    path: String,
+    num_terms: usize,
+    hunks: Vec<EditorHunk>,
+}
+
+impl EditorFile {
+    fn new(path: String, contents: &Merge<BString>) -> Self {
+        let hunks = match files::merge_hunks(contents) {
+            MergeResult::Resolved(content) => vec![EditorHunk::Resolved(content)],
+            MergeResult::Conflict(hunks) => hunks
+                .into_iter()
+                .map(|hunk| match hunk.into_resolved() {
+                    Ok(content) => EditorHunk::Resolved(content),
+                    Err(sides) => EditorHunk::Conflict {
+                        sides,
+                        resolution: None,
+                    },
+                })
+                .collect(),
+        };
+        EditorFile {
+            path,
+            num_terms: contents.as_slice().len(),
+            hunks,
+        }
+    }
+
+    /// Returns the file contents with the chosen resolutions applied. Hunks
+    /// that are still unresolved remain conflicted.
+    fn to_merge(&self) -> Merge<BString> {
+        let mut terms = vec![BString::default(); self.num_terms];
+        for hunk in &self.hunks {
+            match hunk {
+                EditorHunk::Resolved(content)
+                | EditorHunk::Conflict {
+                    resolution: Some(content),
+                    ..
+                } => {
+                    for term in &mut terms {
+                        term.extend_from_slice(content);
+                    }
+                }
+                EditorHunk::Conflict {
+                    sides,
+                    resolution: None,
+                } => {
+                    for (term, side) in terms.iter_mut().zip(sides.iter()) {
+                        term.extend_from_slice(side);
+                    }
+                }
+            }
+        }
+        Merge::from_vec(terms)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ViewCommand {
+    None,
+    Edit,
+    Save,
+    Cancel,
+}
+
+/// State of the merge editor, independent of the terminal.
+#[derive(Debug)]
+struct MergeView {
+    files: Vec<EditorFile>,
+    /// File and hunk indices of each conflict, in display order.
+    conflicts: Vec<(usize, usize)>,
+    selected: usize,
+    scroll: usize,
+    message: Option<String>,
+}
+
+impl MergeView {
+    fn new(files: Vec<EditorFile>) -> Self {
+        let conflicts = files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_index, file)| {
+                file.hunks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, hunk)| matches!(hunk, EditorHunk::Conflict { .. }))
+                    .map(move |(hunk_index, _)| (file_index, hunk_index))
+            })
+            .collect();
+        MergeView {
+            files,
+            conflicts,
+            selected: 0,
+            scroll: 0,
+            message: None,
+        }
+    }
+
+    fn selected_hunk(&self) -> Option<(&Merge<BString>, &Option<BString>)> {
+        let &(file_index, hunk_index) = self.conflicts.get(self.selected)?;
+        match &self.files[file_index].hunks[hunk_index] {
+            EditorHunk::Resolved(_) => None,
+            EditorHunk::Conflict { sides, resolution } => Some((sides, resolution)),
+        }
+    }
+
+    fn set_resolution(&mut self, new_resolution: Option<BString>) {
+        let Some(&(file_index, hunk_index)) = self.conflicts.get(self.selected) else {
+            return;
+        };
+        if let EditorHunk::Conflict { resolution, .. } =
+            &mut self.files[file_index].hunks[hunk_index]
+        {
+            *resolution = new_resolution;
+        }
+    }
+
+    fn num_resolved(&self) -> usize {
+        self.conflicts
+            .iter()
+            .filter(|&&(file_index, hunk_index)| {
+                matches!(
+                    &self.files[file_index].hunks[hunk_index],
+                    EditorHunk::Conflict {
+                        resolution: Some(_),
+                        ..
+                    }
+                )
+            })
+            .count()
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.conflicts.len().saturating_sub(1);
+        let selected = self.selected.saturating_add_signed(offset).min(last);
+        if selected != self.selected {
+            self.selected = selected;
+            self.scroll = 0;
+        }
+    }
+
+    /// Resolves the selected conflict with `content` and moves on to the next
+    /// one.
+    fn resolve(&mut self, content: BString) {
+        self.set_resolution(Some(content));
+        self.move_selection(1);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, page_height: usize) -> ViewCommand {
+        self.message = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return ViewCommand::Cancel,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return ViewCommand::Cancel;
+            }
+            KeyCode::Enter | KeyCode::Char('s') => return ViewCommand::Save,
+            KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => self.move_selection(1),
+            KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => self.move_selection(-1),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(page_height / 2),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page_height / 2),
+            _ => {}
+        }
+        let Some(sides) = self.selected_hunk().map(|(sides, _)| sides.clone()) else {
+            return ViewCommand::None;
+        };
+        match key.code {
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c.to_digit(10).unwrap() as usize - 1;
+                if let Some(side) = sides.get_add(index) {
+                    self.resolve(side.clone());
+                } else {
+                    self.message = Some(format!("There is no side #{c}"));
+                }
+            }
+            KeyCode::Char('b') => {
+                if sides.removes().len() == 1 {
+                    self.resolve(sides.get_remove(0).unwrap().clone());
+                } else {
+                    self.message = Some("The conflict has more than one base".to_owned());
+                }
+            }
+            KeyCode::Char('a') => {
+                let content = sides.adds().flat_map(|side| side.iter().copied()).collect();
+                self.resolve(BString::new(content));
+            }
+            KeyCode::Char('r') => {
+                let adds = sides.adds().collect::<Vec<_>>();
+                let content = adds
+                    .iter()
+                    .rev()
+                    .flat_map(|side| side.iter().copied())
+                    .collect();
+                self.resolve(BString::new(content));
+            }
+            KeyCode::Char('u') => self.set_resolution(None),
+            KeyCode::Char('e') => return ViewCommand::Edit,
+            _ => {}
+        }
+        ViewCommand::None
+    }
+
+    /// Returns the text to open in the editor for the selected conflict.
+    fn text_to_edit(&self, conflict_marker_style: ConflictMarkerStyle) -> Option<BString> {
+        let (sides, resolution) = self.selected_hunk()?;
+        Some(resolution.clone().unwrap_or_else(|| {
+            let marker_len = choose_materialized_conflict_marker_len(sides);
+            materialize_merge_result_to_bytes_with_marker_len(
+                sides,
+                conflict_marker_style,
+                marker_len,
+            )
+        }))
+    }
+
+    /// Renders the selected conflict and its surroundings as lines of text.
+    fn panel_lines(&self) -> Vec<String> {
+        let Some(&(file_index, hunk_index)) = self.conflicts.get(self.selected) else {
+            return vec!["No conflicts to resolve".to_owned()];
+        };
+        let file = &self.files[file_index];
+        let mut lines = vec![format!(
+            "{}: conflict {} of {} ({} resolved)",
+            file.path,
+            self.selected + 1,
+            self.conflicts.len(),
+            self.num_resolved(),
+        )];
+        let hunk_content = |hunk: &EditorHunk| match hunk {
+            EditorHunk::Resolved(content)
+            | EditorHunk::Conflict {
+                resolution: Some(content),
+                ..
+            } => content.clone(),
+            EditorHunk::Conflict {
+                sides,
+                resolution: None,
+            } => materialize_merge_result_to_bytes_with_marker_len(
+                sides,
+                ConflictMarkerStyle::Snapshot,
+                choose_materialized_conflict_marker_len(sides),
+            ),
+        };
+        let mut push_section = |title: &str, content: &[u8]| {
+            lines.push(format!("--- {title} ---"));
+            lines.extend(content.lines().map(|line| line.to_str_lossy().into_owned()));
+        };
+        if let Some(hunk) = hunk_index.checked_sub(1).map(|index| &file.hunks[index]) {
+            let content = hunk_content(hunk);
+            let before = content.lines().collect::<Vec<_>>();
+            let start = before.len().saturating_sub(CONTEXT_LINES);
+            push_section("before", &before[start..].join(&b'\n'));
+        }
+        let EditorHunk::Conflict { sides, resolution } = &file.hunks[hunk_index] else {
+            unreachable!("conflict index should point to a conflict hunk");
+        };
+        let num_bases = sides.removes().len();
+        for (index, side) in sides.iter().enumerate() {
+            let title = match (index % 2, num_bases) {
+                (0, _) => format!("side #{}", index / 2 + 1),
+                (_, 1) => "base".to_owned(),
+                _ => format!("base #{}", index / 2 + 1),
+            };
+            push_section(&title, side);
+        }
+        match resolution {
+            Some(content) => push_section("resolution", content),
+            None => push_section("unresolved", b""),
+        }
+        if let Some(hunk) = file.hunks.get(hunk_index + 1) {
+            let content = hunk_content(hunk);
+            let after = content.lines().take(CONTEXT_LINES).collect::<Vec<_>>();
+            push_section("after", &after.join(&b'\n'));
+        }
+        lines
+    }
+
+    fn draw(&mut self, output: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let page_height = usize::from(height.saturating_sub(1));
+        queue!(
+            output,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+        let lines = self.panel_lines();
+        self.scroll = self.scroll.min(lines.len().saturating_sub(page_height));
+        for (row, line) in lines.iter().skip(self.scroll).take(page_height).enumerate() {
+            let line: String = line.chars().take(usize::from(width)).collect();
+            queue!(output, cursor::MoveTo(0, row.try_into().unwrap()))?;
+            if line.starts_with("--- ") || row + self.scroll == 0 {
+                queue!(output, SetAttribute(Attribute::Bold))?;
+            }
+            write!(output, "{line}")?;
+            queue!(output, SetAttribute(Attribute::Reset))?;
+        }
+        let status = self.message.as_deref().unwrap_or(HELP_TEXT);
+        let status: String = status.chars().take(usize::from(width)).collect();
+        queue!(
+            output,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            SetAttribute(Attribute::Reverse)
+        )?;
+        write!(output, "{status:width$}", width = usize::from(width))?;
+        queue!(output, SetAttribute(Attribute::Reset))?;
+        output.flush()
+    }
+}
+
+/// Lets the user pick a resolution for each conflicting hunk in a
+/// full-screen terminal interface.
+///
+/// Hunks left unresolved are written back as conflicts.
+pub fn edit_merge_builtin(
+    tree: &MergedTree,
+    merge_tool_files: &[MergeToolFile],
+    text_editor: &TextEditor,
+    key_input: &KeyInput,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<MergedTreeId, BuiltinToolError> {
+    let files = merge_tool_files
+        .iter()
+        .map(|merge_tool_file| {
+            let path = merge_tool_file
+                .repo_path
+                .to_fs_path_unchecked(Path::new(""))
+                .to_string_lossy()
+                .into_owned();
+            EditorFile::new(path, &merge_tool_file.file.contents)
+        })
+        .collect();
+    let mut view = MergeView::new(files);
+    let mut input = key_input.clone();
+    loop {
+        let command = {
+            let screen = input.enter_screen()?;
+            loop {
+                if screen.is_some() {
+                    view.draw(&mut io::stdout())?;
+                }
+                let key = input.read_key()?;
+                match view.handle_key(key, input.page_height()?) {
+                    ViewCommand::None => {}
+                    command => break command,
+                }
+            }
+        };
+        match command {
+            ViewCommand::None => {}
+            ViewCommand::Edit => {
+                // The editor runs outside of the full-screen mode.
+                if let Some(content) = view.text_to_edit(conflict_marker_style) {
+                    let edited = text_editor
+                        .edit_str(content, Some(".txt"))
+                        .map_err(|err| err.with_name("conflict"))?;
+                    view.set_resolution(Some(edited.into()));
+                }
+            }
+            ViewCommand::Save => break,
+            ViewCommand::Cancel => return Err(BuiltinToolError::Cancelled),
+        }
+    }
+
+    let store = tree.store();
+    let mut tree_builder = MergedTreeBuilder::new(tree.id());
+    for (merge_tool_file, file) in merge_tool_files.iter().zip(&view.files) {
+        let repo_path = &merge_tool_file.repo_path;
+        let original = &merge_tool_file.file;
+        let conflict_marker_len = choose_materialized_conflict_marker_len(&original.contents);
+        let content = materialize_merge_result_to_bytes_with_marker_len(
+            &file.to_merge(),
+            conflict_marker_style,
+            conflict_marker_len,
+        );
+        let new_file_ids = conflicts::update_from_content(
+            &original.unsimplified_ids,
+            store,
+            repo_path,
+            &content,
+            conflict_marker_style,
+            conflict_marker_len,
+        )
+        .block_on()?;
+        let new_tree_value = match new_file_ids.into_resolved() {
+            Ok(file_id) => {
+                let executable = original.executable.expect("should have been resolved");
+                Merge::resolved(file_id.map(|id| TreeValue::File { id, executable }))
+            }
+            // Update the file ids only, leaving the executable flags unchanged
+            Err(file_ids) => merge_tool_file.conflict.with_new_file_ids(&file_ids),
+        };
+        tree_builder.set_or_remove(repo_path.clone(), new_tree_value);
+    }
+    Ok(tree_builder.write_tree(store)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use jj_lib::backend::FileId;
+    use jj_lib::conflicts::extract_as_single_hunk;
+    use jj_lib::merge::MergedTreeValue;
+    use jj_lib::repo::Repo as _;
+    use testutils::repo_path;
+    use testutils::TestRepo;
+
+    use super::*;
+
+    fn conflicted_file() -> EditorFile {
+        let contents = Merge::from_vec(vec![
+            BString::from("a\nleft 1\nb\nleft 2\n"),
+            BString::from("a\nbase 1\nb\nbase 2\n"),
+            BString::from("a\nright 1\nb\nright 2\n"),
+        ]);
+        EditorFile::new("file".to_owned(), &contents)
+    }
+
+    fn press(view: &mut MergeView, c: char) -> ViewCommand {
+        view.handle_key(KeyEvent::from(KeyCode::Char(c)), 10)
+    }
+
+    fn resolved_content(view: &MergeView) -> BString {
+        view.files[0]
+            .to_merge()
+            .resolve_trivial()
+            .cloned()
+            .expect("all hunks should be resolved")
+    }
+
+    #[test]
+    fn test_pick_sides() {
+        let mut view = MergeView::new(vec![conflicted_file()]);
+        assert_eq!(view.conflicts, [(0, 1), (0, 3)]);
+        assert_eq!(press(&mut view, '2'), ViewCommand::None);
+        assert_eq!(view.selected, 1);
+        assert_eq!(press(&mut view, 'b'), ViewCommand::None);
+        assert_eq!(view.num_resolved(), 2);
+        assert_eq!(resolved_content(&view), "a\nright 1\nb\nbase 2\n");
+
+        press(&mut view, 'k');
+        press(&mut view, 'a');
+        assert_eq!(resolved_content(&view), "a\nleft 1\nright 1\nb\nbase 2\n");
+        press(&mut view, 'k');
+        press(&mut view, 'r');
+        assert_eq!(resolved_content(&view), "a\nright 1\nleft 1\nb\nbase 2\n");
+
+        press(&mut view, 'u');
+        assert_eq!(view.num_resolved(), 1);
+        assert_eq!(
+            view.files[0].to_merge(),
+            Merge::from_vec(vec![
+                BString::from("a\nright 1\nleft 1\nb\nleft 2\n"),
+                BString::from("a\nright 1\nleft 1\nb\nbase 2\n"),
+                BString::from("a\nright 1\nleft 1\nb\nright 2\n"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_side() {
+        let mut view = MergeView::new(vec![conflicted_file()]);
+        press(&mut view, '3');
+        assert_eq!(view.message.as_deref(), Some("There is no side #3"));
+        assert_eq!(view.num_resolved(), 0);
+        assert_eq!(view.selected, 0);
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut view = MergeView::new(vec![conflicted_file()]);
+        assert_eq!(press(&mut view, 'e'), ViewCommand::Edit);
+        assert_eq!(press(&mut view, 's'), ViewCommand::Save);
+        assert_eq!(press(&mut view, 'q'), ViewCommand::Cancel);
+        assert_eq!(
+            view.handle_key(KeyEvent::from(KeyCode::Enter), 10),
+            ViewCommand::Save
+        );
+        assert_eq!(
+            view.text_to_edit(ConflictMarkerStyle::Snapshot).unwrap(),
+            indoc::indoc! {"
+                <<<<<<< Conflict 1 of 1
+                +++++++ Contents of side #1
+                left 1
+                ------- Contents of base
+                base 1
+                +++++++ Contents of side #2
+                right 1
+                >>>>>>> Conflict 1 of 1 ends
+            "}
+        );
+    }
+
+    #[test]
+    fn test_panel_lines() {
+        let mut view = MergeView::new(vec![conflicted_file()]);
+        press(&mut view, 'j');
+        press(&mut view, 'k');
+        press(&mut view, '1');
+        press(&mut view, 'k');
+        insta::assert_snapshot!(view.panel_lines().join("\n"), @r"
+        file: conflict 1 of 2 (1 resolved)
+        --- before ---
+        a
+        --- side #1 ---
+        left 1
+        --- base ---
+        base 1
+        --- side #2 ---
+        right 1
+        --- resolution ---
+        left 1
+        --- after ---
+        b
+        ");
+    }
+
+    #[test]
+    fn test_editor_file_hunks() {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let path = repo_path("file");
+        let base_tree = testutils::create_tree(
+            &test_repo.repo,
+            &[(path, "base 1\nbase 2\nbase 3\nbase 4\nbase 5\n")],
+        );
+        let left_tree = testutils::create_tree(
+            &test_repo.repo,
+            &[(path, "left 1\nbase 2\nbase 3\nbase 4\nleft 5\n")],
+        );
+        let right_tree = testutils::create_tree(
+            &test_repo.repo,
+            &[(path, "right 1\nbase 2\nbase 3\nbase 4\nright 5\n")],
+        );
+
+        fn to_file_id(tree_value: MergedTreeValue) -> Option<FileId> {
+            match tree_value.into_resolved() {
+                Ok(Some(TreeValue::File { id, executable: _ })) => Some(id.clone()),
+                other => {
+                    panic!("merge should have been a FileId: {other:?}")
+                }
+            }
+        }
+        let merge = Merge::from_vec(vec![
+            to_file_id(left_tree.path_value(path).unwrap()),
+            to_file_id(base_tree.path_value(path).unwrap()),
+            to_file_id(right_tree.path_value(path).unwrap()),
+        ]);
+        let content = extract_as_single_hunk(&merge, store, path)
+            .block_on()
+            .unwrap();
+        let file = EditorFile::new("file".to_owned(), &content);
+        insta::assert_debug_snapshot!(file.hunks, @r#"
+        [
+            Conflict {
+                sides: Conflicted(
+                    [
+                        "left 1\n",
+                        "base 1\n",
+                        "right 1\n",
+                    ],
+                ),
+                resolution: None,
+            },
+            Resolved(
+                "base 2\nbase 3\nbase 4\n",
+            ),
+            Conflict {
+                sides: Conflicted(
+                    [
+                        "left 5\n",
+                        "base 5\n",
+                        "right 5\n",
+                    ],
+                ),
+                resolution: None,
+            },
+        ]
+        "#);
+        assert_eq!(file.to_merge(), content);
+    }
+}
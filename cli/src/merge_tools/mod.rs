@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod builtin;
+mod builtin_merge;
 mod diff_working_copies;
 mod external;
 
@@ -44,8 +45,8 @@ use pollster::FutureExt as _;
 use thiserror::Error;
 
 use self::builtin::edit_diff_builtin;
-use self::builtin::edit_merge_builtin;
 use self::builtin::BuiltinToolError;
+use self::builtin_merge::edit_merge_builtin;
 pub(crate) use self::diff_working_copies::new_utf8_temp_dir;
 use self::diff_working_copies::DiffCheckoutError;
 use self::external::edit_diff_external;
@@ -55,6 +56,8 @@ pub use self::external::DiffToolMode;
 pub use self::external::ExternalMergeTool;
 use self::external::ExternalToolError;
 use crate::config::CommandNameAndArgs;
+use crate::description_util::TextEditor;
+use crate::full_screen::KeyInput;
 use crate::ui::Ui;
 
 const BUILTIN_EDITOR_NAME: &str = ":builtin";
@@ -364,6 +367,8 @@ pub struct MergeEditor {
     tool: MergeTool,
     path_converter: RepoPathUiConverter,
     conflict_marker_style: ConflictMarkerStyle,
+    text_editor: TextEditor,
+    key_input: KeyInput,
 }
 
 impl MergeEditor {
@@ -377,7 +382,7 @@ impl MergeEditor {
     ) -> Result<Self, MergeToolConfigError> {
         let tool = MergeTool::get_tool_config(settings, name)?
             .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_program(name)));
-        Self::new_inner(name, tool, path_converter, conflict_marker_style)
+        Self::new_inner(name, tool, settings, path_converter, conflict_marker_style)
     }
 
     /// Loads the default 3-way merge editor from the settings.
@@ -394,12 +399,13 @@ impl MergeEditor {
             None
         }
         .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_merge_args(&args)));
-        Self::new_inner(&args, tool, path_converter, conflict_marker_style)
+        Self::new_inner(&args, tool, settings, path_converter, conflict_marker_style)
     }
 
     fn new_inner(
         name: impl ToString,
         tool: MergeTool,
+        settings: &UserSettings,
         path_converter: RepoPathUiConverter,
         conflict_marker_style: ConflictMarkerStyle,
     ) -> Result<Self, MergeToolConfigError> {
@@ -412,6 +418,8 @@ impl MergeEditor {
             tool,
             path_converter,
             conflict_marker_style,
+            text_editor: TextEditor::from_settings(settings)?,
+            key_input: KeyInput::from_settings(settings)?,
         })
     }
 
@@ -429,7 +437,14 @@ impl MergeEditor {
 
         match &self.tool {
             MergeTool::Builtin => {
-                let tree_id = edit_merge_builtin(tree, &merge_tool_files).map_err(Box::new)?;
+                let tree_id = edit_merge_builtin(
+                    tree,
+                    &merge_tool_files,
+                    &self.text_editor,
+                    &self.key_input,
+                    self.conflict_marker_style,
+                )
+                .map_err(Box::new)?;
                 Ok((tree_id, None))
             }
             MergeTool::Ours => {
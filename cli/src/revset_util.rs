@@ -14,9 +14,21 @@
 
 //! Utility for parsing and evaluating user-provided revset expressions.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::io;
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::Write as _;
+use std::process::Child;
+use std::process::ChildStdout;
+use std::process::Stdio;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
@@ -25,11 +37,18 @@ use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
+use jj_lib::fileset::FilePredicateExtension;
+use jj_lib::fileset::FilesetExtensions;
 use jj_lib::id_prefix::IdPrefixContext;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::ref_name::RefNameBuf;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset;
 use jj_lib::revset::DefaultSymbolResolver;
+use jj_lib::revset::FunctionCallNode;
+use jj_lib::revset::LoweringContext;
 use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetAliasesMap;
@@ -37,18 +56,24 @@ use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetExtensions;
+use jj_lib::revset::RevsetFilterExtension;
+use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::revset::RevsetFunctionExtension;
 use jj_lib::revset::RevsetIteratorExt as _;
 use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::RevsetParseError;
 use jj_lib::revset::RevsetResolutionError;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::revset::UserRevsetExpression;
+use jj_lib::settings::UserSettings;
 use thiserror::Error;
 
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::config::CommandNameAndArgs;
 use crate::formatter::Formatter;
 use crate::templater::TemplateRenderer;
+use crate::ui::format_error_with_sources;
 use crate::ui::Ui;
 
 const USER_IMMUTABLE_HEADS: &str = "immutable_heads";
@@ -203,6 +228,358 @@ pub fn load_revset_aliases(
     Ok(aliases_map)
 }
 
+/// Entry of the `revset-functions` or `fileset-functions` config table.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExternalFunctionConfig {
+    /// Command which answers whether commits or files match the function.
+    pub command: CommandNameAndArgs,
+    /// Short description shown in help and shell completion.
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Loads the `revset-functions` or `fileset-functions` config table sorted
+/// by function name.
+pub fn load_external_functions(
+    settings: &UserSettings,
+    table_name: &'static str,
+) -> Vec<(String, Result<ExternalFunctionConfig, ConfigGetError>)> {
+    settings
+        .table_keys(table_name)
+        .sorted()
+        .map(|name| {
+            let config = settings.get([table_name, name]);
+            (name.to_owned(), config)
+        })
+        .collect()
+}
+
+/// Registers functions defined in the `revset-functions` config table.
+///
+/// Invalid entries are reported as warnings so that the config can still be
+/// fixed by `jj config edit`.
+pub fn register_external_revset_functions(
+    ui: &Ui,
+    extensions: &mut RevsetExtensions,
+    settings: &UserSettings,
+) -> io::Result<()> {
+    for (name, config) in load_external_functions(settings, "revset-functions") {
+        let config = match config {
+            Ok(config) => config,
+            Err(err) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to load `revset-functions.{name}`: {err}",
+                    err = format_error_with_sources(&err),
+                )?;
+                continue;
+            }
+        };
+        let func = ExternalRevsetFunction {
+            filter: Rc::new(ExternalRevsetFilter {
+                name: name.clone(),
+                command: config.command,
+                process: RefCell::new(None),
+                cache: RefCell::new(HashMap::new()),
+            }),
+        };
+        if extensions
+            .add_function_extension(&name, Box::new(func))
+            .is_err()
+        {
+            writeln!(
+                ui.warning_default(),
+                "Failed to load `revset-functions.{name}`: Function `{name}()` is already defined"
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Registers functions defined in the `fileset-functions` config table.
+///
+/// Invalid entries are reported as warnings in the same way as
+/// [`register_external_revset_functions()`].
+pub fn register_external_fileset_functions(
+    ui: &Ui,
+    extensions: &mut FilesetExtensions,
+    settings: &UserSettings,
+) -> io::Result<ExternalFilesetFunctions> {
+    let mut functions = ExternalFilesetFunctions::default();
+    for (name, config) in load_external_functions(settings, "fileset-functions") {
+        let config = match config {
+            Ok(config) => config,
+            Err(err) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to load `fileset-functions.{name}`: {err}",
+                    err = format_error_with_sources(&err),
+                )?;
+                continue;
+            }
+        };
+        let predicate = Arc::new(ExternalFilePredicate {
+            name: name.clone(),
+            command: config.command,
+            state: Mutex::new(ExternalFilePredicateState::default()),
+        });
+        if extensions
+            .add_predicate_extension(&name, predicate.clone())
+            .is_err()
+        {
+            writeln!(
+                ui.warning_default(),
+                "Failed to load `fileset-functions.{name}`: Function `{name}()` is already defined"
+            )?;
+            continue;
+        }
+        functions.predicates.push(predicate);
+    }
+    Ok(functions)
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to evaluate revset function `{name}()`")]
+pub struct ExternalRevsetFunctionError {
+    name: String,
+    source: io::Error,
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to evaluate fileset function `{name}()`")]
+pub struct ExternalFilesetFunctionError {
+    name: String,
+    source: io::Error,
+}
+
+/// Revset function backed by an external command.
+struct ExternalRevsetFunction {
+    filter: Rc<ExternalRevsetFilter>,
+}
+
+impl RevsetFunctionExtension for ExternalRevsetFunction {
+    fn lower(
+        &self,
+        _diagnostics: &mut RevsetDiagnostics,
+        function: &FunctionCallNode,
+        _context: &LoweringContext,
+    ) -> Result<Rc<UserRevsetExpression>, RevsetParseError> {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Extension(
+            self.filter.clone(),
+        )))
+    }
+}
+
+/// Filter which asks an external command whether commits match.
+///
+/// The command is spawned on the first query and kept running. Each query is
+/// a full commit id written to its stdin as a line, to which the command
+/// replies with a `true` or `false` line. Answers are cached per commit id
+/// since commits are immutable. The cache lives in memory for the duration of
+/// the process; it isn't persisted across `jj` invocations.
+#[derive(Debug)]
+struct ExternalRevsetFilter {
+    name: String,
+    command: CommandNameAndArgs,
+    process: RefCell<Option<ExternalFunctionProcess>>,
+    cache: RefCell<HashMap<CommitId, bool>>,
+}
+
+impl ExternalRevsetFilter {
+    fn query(&self, id: &CommitId) -> io::Result<bool> {
+        let mut process = self.process.borrow_mut();
+        let process = match &mut *process {
+            Some(process) => process,
+            None => process.insert(ExternalFunctionProcess::spawn(&self.command)?),
+        };
+        process.query(&id.hex())
+    }
+}
+
+impl RevsetFilterExtension for ExternalRevsetFilter {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn matches_commit(&self, commit: &Commit) -> bool {
+        self.try_matches_commit(commit).unwrap_or(false)
+    }
+
+    fn try_matches_commit(&self, commit: &Commit) -> Result<bool, RevsetEvaluationError> {
+        if let Some(&matched) = self.cache.borrow().get(commit.id()) {
+            return Ok(matched);
+        }
+        let matched = self.query(commit.id()).map_err(|source| {
+            // Don't talk to the process which may be out of sync.
+            self.process.take();
+            let err = ExternalRevsetFunctionError {
+                name: self.name.clone(),
+                source,
+            };
+            RevsetEvaluationError::Other(err.into())
+        })?;
+        self.cache.borrow_mut().insert(commit.id().clone(), matched);
+        Ok(matched)
+    }
+}
+
+/// Fileset functions registered from the `fileset-functions` config table.
+#[derive(Debug, Default)]
+pub struct ExternalFilesetFunctions {
+    predicates: Vec<Arc<ExternalFilePredicate>>,
+}
+
+impl ExternalFilesetFunctions {
+    /// Reports functions which failed to evaluate. Since matchers can't fail,
+    /// a failed function stops matching any files.
+    pub fn report_errors(&self, ui: &Ui) -> io::Result<()> {
+        for predicate in &self.predicates {
+            if let Some(err) = &predicate.state.lock().unwrap().error {
+                writeln!(
+                    ui.warning_default(),
+                    "{err}",
+                    err = format_error_with_sources(err)
+                )?;
+                writeln!(
+                    ui.hint_default(),
+                    "Files weren't matched by `{name}()` after the error.",
+                    name = predicate.name
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// File predicate which asks an external command whether files match.
+///
+/// This uses the same protocol as `ExternalRevsetFilter`, but each query is a
+/// file path relative to the workspace root, with `/` as separator. Answers
+/// are cached per path.
+#[derive(Debug)]
+struct ExternalFilePredicate {
+    name: String,
+    command: CommandNameAndArgs,
+    state: Mutex<ExternalFilePredicateState>,
+}
+
+#[derive(Debug, Default)]
+struct ExternalFilePredicateState {
+    process: Option<ExternalFunctionProcess>,
+    cache: HashMap<RepoPathBuf, bool>,
+    error: Option<ExternalFilesetFunctionError>,
+}
+
+impl ExternalFilePredicate {
+    fn query(&self, state: &mut ExternalFilePredicateState, path: &RepoPath) -> io::Result<bool> {
+        let path = path.as_internal_file_string();
+        if path.contains('\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Path can't be queried: {path:?}"),
+            ));
+        }
+        let process = match &mut state.process {
+            Some(process) => process,
+            None => state
+                .process
+                .insert(ExternalFunctionProcess::spawn(&self.command)?),
+        };
+        process.query(path)
+    }
+}
+
+impl FilePredicateExtension for ExternalFilePredicate {
+    fn matches_file(&self, path: &RepoPath) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.error.is_some() {
+            return false;
+        }
+        if let Some(&matched) = state.cache.get(path) {
+            return matched;
+        }
+        match self.query(&mut state, path) {
+            Ok(matched) => {
+                state.cache.insert(path.to_owned(), matched);
+                matched
+            }
+            Err(source) => {
+                // Don't talk to the process which may be out of sync.
+                state.process.take();
+                state.error = Some(ExternalFilesetFunctionError {
+                    name: self.name.clone(),
+                    source,
+                });
+                false
+            }
+        }
+    }
+}
+
+/// Command answering queries of an external revset or fileset function.
+#[derive(Debug)]
+struct ExternalFunctionProcess {
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ExternalFunctionProcess {
+    fn spawn(command: &CommandNameAndArgs) -> io::Result<Self> {
+        let mut child = command
+            .to_command()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Ok(ExternalFunctionProcess { child, stdout })
+    }
+
+    fn query(&mut self, line: &str) -> io::Result<bool> {
+        let stdin = self.child.stdin.as_mut().unwrap();
+        match writeln!(stdin, "{line}").and_then(|()| stdin.flush()) {
+            // The command may have answered before exiting without reading
+            // the query, so report its answer instead.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+            result => result?,
+        }
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Command exited without answering",
+            ));
+        }
+        match line.trim_end() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            answer => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Expected `true` or `false`, but got {answer:?}"),
+            )),
+        }
+    }
+}
+
+impl Drop for ExternalFunctionProcess {
+    fn drop(&mut self) {
+        /// How long the command is given to exit before it gets killed.
+        const EXIT_TIMEOUT: Duration = Duration::from_secs(1);
+        // Closing stdin tells the command that there are no more queries.
+        drop(self.child.stdin.take());
+        let deadline = Instant::now() + EXIT_TIMEOUT;
+        while let Ok(None) = self.child.try_wait() {
+            if Instant::now() >= deadline {
+                self.child.kill().ok();
+                self.child.wait().ok();
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
 /// Wraps the given `IdPrefixContext` in `SymbolResolver` to be passed in to
 /// `evaluate()`.
 pub fn default_symbol_resolver<'a>(
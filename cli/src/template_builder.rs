@@ -1242,6 +1242,24 @@ fn builtin_timestamp_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
         },
     );
     map.insert("before", map["after"]);
+    map.insert(
+        "within",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            let [date_pattern_node] = function.expect_exact_arguments()?;
+            let now = chrono::Local::now();
+            let date_pattern = template_parser::expect_string_literal_with(
+                date_pattern_node,
+                |date_pattern, span| {
+                    DatePattern::from_str_kind(date_pattern, "date", now).map_err(|err| {
+                        TemplateParseError::expression("Invalid date pattern", span)
+                            .with_source(err)
+                    })
+                },
+            )?;
+            let out_property = self_property.map(move |timestamp| date_pattern.matches(&timestamp));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
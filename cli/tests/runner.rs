@@ -39,8 +39,10 @@ mod test_generate_md_cli_help;
 mod test_git_clone;
 mod test_git_colocated;
 mod test_git_fetch;
+mod test_git_hooks;
 mod test_git_import_export;
 mod test_git_init;
+mod test_git_notes;
 mod test_git_private_commits;
 mod test_git_push;
 mod test_git_remotes;
@@ -216,6 +216,49 @@ fn test_log_author_timestamp_after_before() {
     "#);
 }
 
+#[test]
+fn test_log_author_timestamp_within() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+
+    let template = r#"
+    separate(" ",
+      author.timestamp(),
+      ":",
+      if(author.timestamp().within("2001-02-03 +07:00"), "(on 2001-02-03 +07:00)"),
+      if(author.timestamp().within("2001-02-03 UTC"), "(on 2001-02-03 UTC)"),
+      if(author.timestamp().within("2000..2001-02 UTC"), "(in 2000..2001-02 UTC)"),
+      if(author.timestamp().within("1970 UTC"), "(in 1970 UTC)")
+    ) ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    2001-02-03 04:05:08.000 +07:00 : (on 2001-02-03 +07:00) (in 2000..2001-02 UTC)
+    1970-01-01 00:00:00.000 +00:00 : (in 1970 UTC)
+    [EOF]
+    ");
+
+    // Should display error with invalid date.
+    let template = r#"author.timestamp().within("2001..invalid date")"#;
+    let output = work_dir.run_jj(["log", "-r@", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to parse template: Invalid date pattern
+    Caused by:
+    1:  --> 1:27
+      |
+    1 | author.timestamp().within("2001..invalid date")
+      |                           ^------------------^
+      |
+      = Invalid date pattern
+    2: expected unsupported identifier as position 0..7
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
 #[test]
 fn test_mine_is_true_when_author_is_user() {
     let test_env = TestEnvironment::default();
@@ -994,7 +1037,7 @@ fn test_log_contained_in() {
       |
       = Invalid string pattern
     3: Invalid string pattern kind `x:`
-    Hint: Try prefixing with one of `exact:`, `glob:`, `regex:`, `substring:`, or one of these with `-i` suffix added (e.g. `glob-i:`) for case-insensitive matching
+    Hint: Try prefixing with one of `exact:`, `glob:`, `regex:`, `substring:`, `exact-line:`, `line-glob:`, or one of the first four with `-i` suffix added (e.g. `glob-i:`) for case-insensitive matching
     [EOF]
     [exit status: 1]
     "#);
@@ -887,6 +887,30 @@ fn test_revisions() {
     ");
 }
 
+#[test]
+fn test_revisions_external_revset_functions() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config(
+        r#"
+        [revset-functions.reviewed]
+        command = ["review-db", "query"]
+        description = "Commits approved in code review"
+
+        [revset-functions.rebuilt]
+        command = ["ci-status"]
+        "#,
+    );
+
+    let output = work_dir.complete_fish(["log", "-r", "::re"]);
+    insta::assert_snapshot!(output, @r"
+    ::rebuilt()
+    ::reviewed()	Commits approved in code review
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_operations() {
     let test_env = TestEnvironment::default();
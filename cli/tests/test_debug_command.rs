@@ -89,6 +89,64 @@ fn test_debug_revset() {
     });
 }
 
+#[test]
+fn test_debug_revset_explain() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "foo"]).success();
+    work_dir.run_jj(["new", "-m", "bar"]).success();
+    work_dir.run_jj(["new", "-m", "foo"]).success();
+
+    let revset = "(::@ & description(foo)) | heads(all())";
+    let output = work_dir.run_jj(["debug", "revset", "--explain", revset]);
+    insta::with_settings!({filters => vec![
+        (r"(?s)\A.*?-- Plan:", "-- Plan:"),
+    ]}, {
+        assert_snapshot!(output, @r#"
+        -- Plan:
+        FilterWithin (filter)
+          Ancestors (walk)
+            Commits(1) (materialized)
+          Union (predicate)
+            FilterWithin (filter)
+              Ancestors (walk)
+                Commits(1) (materialized)
+              Description(Substring("foo")) (predicate)
+            Heads (materialized)
+              Ancestors (walk)
+                Commits(1) (materialized)
+
+        -- Commit IDs:
+        5867170cfc645a52304db2968b0d5a42ac26f224
+        92d03254c1129fb1e8c610cbe52bea7347d81061
+        [EOF]
+        "#);
+    });
+
+    let output = work_dir.run_jj(["debug", "revset", "--profile", revset]);
+    insta::with_settings!({filters => vec![
+        (r"(?s)\A.*?-- Profile:", "-- Profile:"),
+        (r"(evaluation|iteration) [0-9.]+[a-zµ]+", "$1 [time]"),
+    ]}, {
+        assert_snapshot!(output, @r#"
+        -- Profile:
+        FilterWithin (filter): yielded 2, tested 0, evaluation [time], iteration [time]
+          Ancestors (walk): yielded 4, tested 0, evaluation [time], iteration [time]
+            Commits(1) (materialized): yielded 1, tested 0, evaluation [time], iteration [time]
+          Union (predicate): yielded 0, tested 4, evaluation [time], iteration [time]
+            FilterWithin (filter): yielded 0, tested 4, evaluation [time], iteration [time]
+              Ancestors (walk): yielded 0, tested 4, evaluation [time], iteration [time]
+                Commits(1) (materialized): yielded 1, tested 0, evaluation [time], iteration [time]
+              Description(Substring("foo")) (predicate): yielded 0, tested 4, evaluation [time], iteration [time]
+            Heads (materialized): yielded 0, tested 2, evaluation [time], iteration [time]
+              Ancestors (walk): yielded 4, tested 0, evaluation [time], iteration [time]
+                Commits(1) (materialized): yielded 1, tested 0, evaluation [time], iteration [time]
+        [EOF]
+        "#);
+    });
+}
+
 #[test]
 fn test_debug_index() {
     let test_env = TestEnvironment::default();
@@ -530,6 +530,190 @@ fn test_diff_name_only() {
     ");
 }
 
+#[test]
+fn test_diff_file_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("small", "s\n");
+    work_dir.write_file("large", "l".repeat(2000));
+    work_dir.write_file("binary", b"b\0b");
+    work_dir.write_file("todo", "foo\nTODO: bar\n");
+    work_dir.write_file("exec", "#!/bin/sh\n");
+    work_dir.run_jj(["file", "chmod", "x", "exec"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "size('>1k')"]), @r"
+    large
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "binary()"]), @r"
+    binary
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "executable()"]), @r"
+    exec
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "file_contains(TODO)"]), @r"
+    todo
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "~binary() & ~size('>=1kb')"]), @r"
+    exec
+    small
+    todo
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "size(huge)"]), @r"
+    ------- stderr -------
+    Error: Failed to parse fileset: Invalid size range
+    Caused by:
+    1:  --> 1:6
+      |
+    1 | size(huge)
+      |      ^--^
+      |
+      = Invalid size range
+    2: Invalid size: `huge`
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Predicates are evaluated against both sides of the diff
+    work_dir.run_jj(["commit", "-mfirst"]).success();
+    work_dir.remove_file("todo");
+    work_dir.write_file("small", "TODO\n");
+    work_dir.write_file("exec", "#!/bin/bash\n");
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--name-only", "file_contains(TODO)"]), @r"
+    small
+    todo
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--name-only", "~executable()"]), @r"
+    small
+    todo
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-r", "files(binary())", "-T", "description"]), @r"
+    ○  first
+    │
+    ~
+    [EOF]
+    ");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_diff_external_file_predicate() {
+    // Answers `true` for paths ending with `.gen`, and records queries to the
+    // file $1.
+    const SCRIPT: &str = r#"
+        while read path; do
+            echo "$path" >> "$1"
+            case "$path" in *.gen) echo true ;; *) echo false ;; esac
+        done
+    "#;
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let queries_path = test_env.env_root().join("queries");
+    let command = to_toml_value(toml_edit::Array::from_iter([
+        "sh",
+        "-c",
+        SCRIPT,
+        "sh",
+        queries_path.to_str().unwrap(),
+    ]));
+    test_env.add_config(format!(
+        "fileset-functions.generated = {{ command = {command}, description = 'Generated' }}"
+    ));
+
+    work_dir.write_file("a.gen", "a\n");
+    work_dir.write_file("a.rs", "a\n");
+    work_dir.write_file("dir/b.gen", "b\n");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "generated()"]), @r"
+    a.gen
+    dir/b.gen
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "dir | ~generated()"]), @r"
+    a.rs
+    dir/b.gen
+    [EOF]
+    ");
+
+    // Each path is queried once per invocation
+    std::fs::remove_file(&queries_path).unwrap();
+    work_dir.run_jj(["commit", "-mfirst"]).success();
+    work_dir.write_file("a.gen", "changed\n");
+    work_dir.write_file("a.rs", "changed\n");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "files(generated()) | files(generated() & ~a.rs)",
+        "-T",
+        r#"description.first_line() ++ "\n""#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    first
+    [EOF]
+    ");
+    let queries = std::fs::read_to_string(&queries_path).unwrap();
+    assert!(queries.lines().all_unique(), "{queries}");
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--name-only", "generated()"]), @r"
+    a.gen
+    [EOF]
+    ");
+
+    // Path-only commands don't support the function
+    insta::assert_snapshot!(work_dir.run_jj(["file", "track", "generated()"]), @r"
+    ------- stderr -------
+    Error: File predicates such as `binary()` or `ignored()` are not supported here
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Arguments aren't accepted
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "generated(x)"]), @r"
+    ------- stderr -------
+    Error: Failed to parse fileset: Function `generated`: Expected 0 arguments
+    Caused by:  --> 1:11
+      |
+    1 | generated(x)
+      |           ^
+      |
+      = Function `generated`: Expected 0 arguments
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Unexpected answer stops matching and is reported after the output
+    test_env.add_config("fileset-functions.broken.command = ['echo', 'yes']");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "broken() | a.rs"]), @r#"
+    a.rs
+    [EOF]
+    ------- stderr -------
+    Warning: Failed to evaluate fileset function `broken()`: Expected `true` or `false`, but got "yes"
+    Hint: Files weren't matched by `broken()` after the error.
+    [EOF]
+    "#);
+
+    // Builtin function can't be overridden, and invalid entries are ignored
+    test_env.add_config("fileset-functions.binary.command = ['true']");
+    test_env.add_config("fileset-functions.invalid.description = 'no command'");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "list", "generated()"]), @r"
+    a.gen
+    dir/b.gen
+    [EOF]
+    ------- stderr -------
+    Warning: Failed to load `fileset-functions.binary`: Function `binary()` is already defined
+    Warning: Failed to load `fileset-functions.invalid`: Invalid type or value for fileset-functions.invalid: missing field `command`
+
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_renamed_file_and_dir() {
     let test_env = TestEnvironment::default();
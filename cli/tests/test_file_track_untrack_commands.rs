@@ -227,3 +227,49 @@ fn test_track_ignored() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_track_file_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"snapshot.auto-track = 'none()'"#);
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "initial");
+
+    // Untracked files can't be tested by predicates
+    let output = work_dir.run_jj(["file", "track", "binary()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: File predicates such as `binary()` or `ignored()` are not supported here
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @"");
+}
+
+#[test]
+fn test_untrack_ignored_files() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "initial");
+    work_dir.write_file("file1.bak", "initial");
+    work_dir.write_file("target/file2", "initial");
+    work_dir.run_jj(["st"]).success();
+    work_dir.write_file(".gitignore", "*.bak\n");
+    work_dir.write_file(".jjignore", "/target\n");
+
+    // Only the tracked files which are now ignored are untracked
+    let output = work_dir.run_jj(["file", "untrack", "ignored()"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    .gitignore
+    .jjignore
+    file1
+    [EOF]
+    ");
+}
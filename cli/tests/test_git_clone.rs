@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
 use std::path;
 
 use indoc::formatdoc;
@@ -785,6 +786,73 @@ fn test_git_clone_with_depth() {
     ~
     [EOF]
     ");
+
+    let output = clone_dir.run_jj(["log", "-r", "shallow()", "--no-graph", "-T", "commit_id"]);
+    insta::assert_snapshot!(output, @"ebeb70d8c5f972275f0a22f7af6bc9ddb175ebd9[EOF]");
+}
+
+#[test]
+fn test_git_clone_with_filter() {
+    let test_env = TestEnvironment::default();
+    let root_dir = test_env.work_dir("");
+    let clone_dir = test_env.work_dir("clone");
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git::init(&git_repo_path);
+    let first_commit = git::add_commit(
+        &git_repo,
+        "refs/heads/main",
+        "file",
+        b"old content\n",
+        "first",
+        &[],
+    );
+    git::add_commit(
+        &git_repo,
+        "refs/heads/main",
+        "file",
+        b"new content\n",
+        "second",
+        &[first_commit.commit_id],
+    );
+    git::set_symbolic_reference(&git_repo, "HEAD", "refs/heads/main");
+    let old_blob_id = git_repo.write_blob(b"old content\n").unwrap().detach();
+    // Serving filtered packs has to be enabled explicitly
+    let mut git_config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(git_repo_path.join(".git").join("config"))
+        .unwrap();
+    writeln!(git_config, "[uploadpack]\n\tallowFilter = true").unwrap();
+
+    let output = root_dir.run_jj(["git", "clone", "--filter=blob:none", "source", "clone"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] untracked
+    Setting the revset alias `trunk()` to `main@origin`
+    Working copy  (@) now at: sqpuoqvx f2231fee (empty) (no description set)
+    Parent commit (@-)      : qmzttzms e8630ca6 main | second
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+
+    // The content of the older revision wasn't fetched
+    let clone_git_repo_path = clone_dir
+        .root()
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    assert!(!git::open(&clone_git_repo_path).has_object(old_blob_id));
+    let clone_git_config = std::fs::read_to_string(clone_git_repo_path.join("config")).unwrap();
+    assert!(clone_git_config.contains("partialclonefilter = blob:none"));
+
+    // It's fetched on demand
+    let output = clone_dir.run_jj(["file", "show", "-r", "main-", "file"]);
+    insta::assert_snapshot!(output, @r"
+    old content
+    [EOF]
+    ");
+    assert!(git::open(&clone_git_repo_path).has_object(old_blob_id));
 }
 
 #[test]
@@ -1632,6 +1632,129 @@ fn test_git_colocated_operation_cleanup() {
     insta::assert_snapshot!(String::from_utf8(output.stdout).unwrap(), @"");
 }
 
+#[test]
+fn test_git_colocated_workspace_add_worktree() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "contents");
+    work_dir.run_jj(["commit", "-m", "initial"]).success();
+
+    let output = work_dir.run_jj(["workspace", "add", "--colocate", "../second"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Created workspace in "../second"
+    Working copy  (@) now at: pmmvwywv 058f604d (empty) (no description set)
+    Parent commit (@-)      : qpvuntsm 7b22a8cb initial
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+    let second_dir = test_env.work_dir("second");
+    insta::assert_snapshot!(run_git(second_dir.root(), &["rev-parse", "HEAD"]), @"7b22a8cbe888adcb4d5ff6dd46a38049e870c6ab");
+    insta::assert_snapshot!(run_git(second_dir.root(), &["status", "--porcelain=v1"]), @"");
+
+    // Committing in the worktree moves its HEAD, but not the main HEAD
+    second_dir.write_file("file2", "contents");
+    second_dir.run_jj(["commit", "-m", "second"]).success();
+    insta::assert_snapshot!(get_log_output(&second_dir), @r"
+    @  256b2323d99e8fb8f754aa5707421cf7b5355dba
+    ○  4fec5f97ee0309760111b4e7b0552d3aeef61854 second
+    │ ○  504e3d8c1bcdac775c525920bfc8b64b2a1771e9
+    ├─╯
+    ○  7b22a8cbe888adcb4d5ff6dd46a38049e870c6ab git_head() initial
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(run_git(second_dir.root(), &["log", "--format=%s", "HEAD"]), @r"
+    second
+    initial
+    ");
+    insta::assert_snapshot!(run_git(work_dir.root(), &["log", "--format=%s", "HEAD"]), @"initial");
+    insta::assert_snapshot!(run_git(second_dir.root(), &["status", "--porcelain=v1"]), @"");
+
+    // Moving HEAD of the worktree with Git is imported
+    run_git(second_dir.root(), &["checkout", "--detach", "HEAD~"]);
+    let output = second_dir.run_jj(["log", "-r", "@-", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    ○  initial
+    │
+    ~
+    [EOF]
+    ------- stderr -------
+    Reset the working copy parent to the new Git HEAD.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_colocated_workspace_add_existing_worktree() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "contents");
+    work_dir.run_jj(["commit", "-m", "initial"]).success();
+    run_git(work_dir.root(), &["worktree", "add", "../second"]);
+    let second_dir = test_env.work_dir("second");
+
+    // The worktree isn't a workspace yet
+    let output = second_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: There is no jj repo in "."
+    Hint: It looks like this is a linked Git worktree. If the main worktree is a jj repo, you can create a workspace here by running this from the main worktree:
+    jj workspace add --colocate <path-to-this-worktree>
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    let output = work_dir.run_jj(["workspace", "add", "--colocate", "../second"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Done importing changes from the underlying Git repo.
+    Created workspace in "../second"
+    Reset the working copy parent to the new Git HEAD.
+    [EOF]
+    "#);
+    insta::assert_snapshot!(get_log_output(&second_dir), @r"
+    @  bcc858e1d93fb672da1db03b172925421b2b29ea
+    │ ○  504e3d8c1bcdac775c525920bfc8b64b2a1771e9
+    ├─╯
+    ○  7b22a8cbe888adcb4d5ff6dd46a38049e870c6ab second git_head() initial
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(run_git(second_dir.root(), &["status", "--porcelain=v1"]), @"");
+
+    // Revisions can't be specified for an existing worktree
+    run_git(work_dir.root(), &["worktree", "add", "../third"]);
+    let output = work_dir.run_jj(["workspace", "add", "--colocate", "../third", "-r", "root()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Done importing changes from the underlying Git repo.
+    Error: Cannot specify revisions for a workspace in an existing Git worktree
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
 #[must_use]
 fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
     // --quiet to suppress deleted bookmarks hint
@@ -820,10 +820,10 @@ fn test_git_fetch_some_of_many_bookmarks() {
     "#);
 
     // Test an error message
-    let output = target_dir.run_jj(["git", "fetch", "--branch", "glob:^:a*"]);
+    let output = target_dir.run_jj(["git", "fetch", "--branch", "exact:^:a"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Error: Invalid branch pattern provided. When fetching, branch names and globs may not contain the characters `:`, `^`, `?`, `[`, `]`
+    Error: Invalid branch pattern provided. When fetching, branch names may not contain the characters `:`, `^`, `?`, `[`, `]`
     [EOF]
     [exit status: 1]
     ");
@@ -1011,6 +1011,63 @@ fn test_git_fetch_some_of_many_bookmarks() {
     ");
 }
 
+#[test]
+fn test_git_fetch_bookmarks_by_non_glob_pattern() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    let source_dir = test_env.work_dir("source");
+    git::init(source_dir.root());
+    test_env
+        .run_jj_in(".", ["git", "clone", "source", "target"])
+        .success();
+    let target_dir = test_env.work_dir("target");
+    create_colocated_repo_and_bookmarks_from_trunk1(&source_dir);
+
+    // `?` can't be passed to git as a refspec
+    let output = target_dir.run_jj(["git", "fetch", "--branch", "glob:a?"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: a1@origin [new] tracked
+    bookmark: a2@origin [new] tracked
+    [EOF]
+    ");
+    let output = target_dir.run_jj(["git", "fetch", "--branch", "regex-i:^(B|TRUNK)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: b@origin      [new] tracked
+    bookmark: trunk1@origin [new] tracked
+    [EOF]
+    ");
+    let output = target_dir.run_jj(["git", "fetch", "--branch", "regex:^x"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: No branch matching `^x` found on any specified/configured remote
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Remote-tracking bookmarks deleted on the remote should be pruned
+    source_dir
+        .run_jj(["bookmark", "delete", "a1", "b"])
+        .success();
+    let output = target_dir.run_jj(["git", "fetch", "--branch", "regex:^a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: a1@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&target_dir), @r"
+    a2: yqosqzyt d4d535f1 a2
+      @origin: yqosqzyt d4d535f1 a2
+    b: yostqsxw bc83465a b
+      @origin: yostqsxw bc83465a b
+    trunk1: kkmpptxz 38288177 trunk1
+      @origin: kkmpptxz 38288177 trunk1
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_fetch_bookmarks_some_missing() {
     let test_env = TestEnvironment::default();
@@ -1756,3 +1813,79 @@ fn test_git_fetch_preserve_commits_across_repos() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_git_fetch_with_depth() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    let git_repo = init_git_remote(&test_env, "origin");
+    let first_id = git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .id()
+        .detach();
+    let second_id = git::add_commit(
+        &git_repo,
+        "refs/heads/origin",
+        "file",
+        b"second",
+        "second",
+        &[first_id],
+    )
+    .commit_id;
+    git::add_commit(
+        &git_repo,
+        "refs/heads/origin",
+        "file",
+        b"third",
+        "third",
+        &[second_id],
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../origin"])
+        .success();
+
+    // Fetch the tip only
+    let output = work_dir.run_jj(["git", "fetch", "--depth=1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: origin@origin [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r#"
+    @  e8849ae12c70 ""
+    │ ○  f96f1d4d73c8 "third" origin
+    ├─╯
+    ◆  000000000000 ""
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["log", "-r", "shallow()", "--no-graph", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    third
+    [EOF]
+    ");
+
+    // Deepen the history
+    let output = work_dir.run_jj(["git", "fetch", "--depth=2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    Reindexing to include the fetched history
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r#"
+    @  e8849ae12c70 ""
+    │ ○  f96f1d4d73c8 "third" origin
+    │ ○  cb82b9b9ae3d "second"
+    ├─╯
+    ◆  000000000000 ""
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["log", "-r", "shallow()", "--no-graph", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    second
+    [EOF]
+    ");
+}
@@ -0,0 +1,343 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt as _;
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn write_hook(hooks_dir: &Path, name: &str, script: &str) {
+    std::fs::create_dir_all(hooks_dir).unwrap();
+    let path = hooks_dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{script}")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn test_git_hooks_disabled_by_default() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    write_hook(
+        &work_dir.root().join(".git/hooks"),
+        "commit-msg",
+        "exit 1\n",
+    );
+    write_hook(
+        &work_dir.root().join(".git/hooks"),
+        "pre-commit",
+        "exit 1\n",
+    );
+
+    let output = work_dir.run_jj(["commit", "-m", "first"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: rlvkpnrz eb9fd2ab (empty) (no description set)
+    Parent commit (@-)      : qpvuntsm 68a50538 (empty) first
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_hooks_commit_msg() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.run-hooks = true");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    write_hook(
+        &work_dir.root().join(".git/hooks"),
+        "commit-msg",
+        r#"
+echo "commit-msg: $1" >&2
+echo "GIT_DIR=$GIT_DIR GIT_WORK_TREE=$GIT_WORK_TREE GIT_INDEX_FILE=$GIT_INDEX_FILE GIT_EDITOR=$GIT_EDITOR cwd=$PWD"
+if grep -q WIP "$1"; then
+  echo "WIP commits are not allowed" >&2
+  exit 1
+fi
+printf '\nReviewed-by: Hook\n' >> "$1"
+"#,
+    );
+
+    let output = work_dir.run_jj(["describe", "-m", "WIP: first"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    commit-msg: $TEST_ENV/repo/.git/COMMIT_EDITMSG
+    WIP commits are not allowed
+    GIT_DIR=$TEST_ENV/repo/.git GIT_WORK_TREE=$TEST_ENV/repo GIT_INDEX_FILE=$TEST_ENV/repo/.git/index GIT_EDITOR=: cwd=$TEST_ENV/repo
+    Error: Description rejected by commit-msg hook (exit status: 1)
+    Hint: Use --no-verify to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["describe", "-m", "first"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    commit-msg: $TEST_ENV/repo/.git/COMMIT_EDITMSG
+    GIT_DIR=$TEST_ENV/repo/.git GIT_WORK_TREE=$TEST_ENV/repo GIT_INDEX_FILE=$TEST_ENV/repo/.git/index GIT_EDITOR=: cwd=$TEST_ENV/repo
+    Working copy  (@) now at: qpvuntsm db5363ff (empty) first
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r@", "--no-graph", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    first
+
+    Reviewed-by: Hook
+    [EOF]
+    ");
+
+    // The hook can be skipped
+    let output = work_dir.run_jj(["describe", "--no-verify", "-m", "WIP: first"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: qpvuntsm 4411f0d5 (empty) WIP: first
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+
+    // The hook isn't run for empty descriptions
+    let output = work_dir.run_jj(["describe", "-m", ""]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: qpvuntsm d4b2acb9 (empty) (no description set)
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_hooks_commit() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.run-hooks = true");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let hooks_dir = work_dir.root().join("hooks");
+    // A relative core.hooksPath is resolved from the workspace root
+    std::process::Command::new("git")
+        .current_dir(work_dir.root())
+        .args(["config", "core.hooksPath", "hooks"])
+        .status()
+        .unwrap();
+    write_hook(
+        &hooks_dir,
+        "pre-commit",
+        r#"
+echo "pre-commit: $# GIT_EDITOR=$GIT_EDITOR"
+git diff --cached --name-only
+test -f allowed
+"#,
+    );
+    write_hook(
+        &hooks_dir,
+        "commit-msg",
+        "echo \"commit-msg: $(cat \"$1\")\"\n",
+    );
+    work_dir.write_file("file", "contents\n");
+
+    let output = work_dir.run_jj(["commit", "-m", "first"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    pre-commit: 0 GIT_EDITOR=:
+    file
+    hooks/commit-msg
+    hooks/pre-commit
+    Error: Commit rejected by pre-commit hook (exit status: 1)
+    Hint: Use --no-verify to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir.write_file("allowed", "");
+    let output = work_dir.run_jj(["commit", "-m", "first"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    pre-commit: 0 GIT_EDITOR=:
+    allowed
+    file
+    hooks/commit-msg
+    hooks/pre-commit
+    commit-msg: first
+    Working copy  (@) now at: kkmpptxz 623880fa (empty) (no description set)
+    Parent commit (@-)      : qpvuntsm fdc5e4ee first
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["commit", "--no-verify", "-m", "second"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: zsuskuln f260530b (empty) (no description set)
+    Parent commit (@-)      : kkmpptxz 0b9f995a (empty) second
+    [EOF]
+    ");
+
+    // The hook sees the selected changes as staged
+    work_dir.write_file("file", "modified\n");
+    work_dir.write_file("other", "contents\n");
+    let output = work_dir.run_jj(["commit", "-m", "third", "other"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    pre-commit: 0 GIT_EDITOR=:
+    other
+    commit-msg: third
+    Working copy  (@) now at: mzvwutvl 2433bfbb (no description set)
+    Parent commit (@-)      : zsuskuln 2cbf3ca2 third
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_hooks_commit_msg_interactive_log() {
+    let mut test_env = TestEnvironment::default();
+    let editor_script = test_env.set_up_fake_editor();
+    test_env.add_config("git.run-hooks = true");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    write_hook(
+        &work_dir.root().join(".git/hooks"),
+        "commit-msg",
+        r#"
+echo "commit-msg: $(cat "$1")" >&2
+if grep -q WIP "$1"; then
+  exit 1
+fi
+printf '\nReviewed-by: Hook\n' >> "$1"
+"#,
+    );
+    let run_describe =
+        || work_dir.run_jj(["log", "-i", r#"--config=debug.scripted-keys=["d", "q"]"#]);
+
+    std::fs::write(&editor_script, "write\nWIP: first\n").unwrap();
+    let output = run_describe();
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    commit-msg: WIP: first
+    Error: Description rejected by commit-msg hook (exit status: 1)
+    [EOF]
+    ");
+
+    std::fs::write(&editor_script, "write\nfirst\n").unwrap();
+    let output = run_describe();
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    commit-msg: first
+    Working copy  (@) now at: qpvuntsm db5363ff (empty) first
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    Described e8849ae12c70
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r@", "--no-graph", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    first
+
+    Reviewed-by: Hook
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_hooks_post_rewrite() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.run-hooks = true");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    // Hooks of a non-colocated repo are in the internal Git repo
+    write_hook(
+        &work_dir.root().join(".jj/repo/store/git/hooks"),
+        "post-rewrite",
+        r#"
+echo "post-rewrite: $1"
+cat
+exit 1
+"#,
+    );
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.run_jj(["new", "-m", "second"]).success();
+    work_dir.run_jj(["new", "root()", "-m", "other"]).success();
+    let output = work_dir.run_jj(["log", "-T", r#"commit_id ++ " " ++ description"#]);
+    insta::assert_snapshot!(output, @r"
+    @  93ea156ec8d5e661a82a4b1ca5adf1b068196263 other
+    │ ○  b1cb6b2f9141e6ffee18532a8bf9a2075ca02606 second
+    │ ○  68a505386f936fff6d718f55005e77ea72589bc1 first
+    ├─╯
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+
+    // Rewriting a commit without moving it is reported as an amend. Commits
+    // rebased onto it are reported as a rebase.
+    let output = work_dir.run_jj([
+        "describe",
+        "-r",
+        "description(second)",
+        "-m",
+        "second (amended)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    post-rewrite: amend
+    b1cb6b2f9141e6ffee18532a8bf9a2075ca02606 f906055f46c12d513caab94b20e757f71ca75c3d
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "describe",
+        "-r",
+        "description(first)",
+        "-m",
+        "first (amended)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 descendant commits
+    post-rewrite: rebase
+    68a505386f936fff6d718f55005e77ea72589bc1 a731f0df9a7b9d5e5645c83777be20a076f75cc3
+    f906055f46c12d513caab94b20e757f71ca75c3d 94eb481029aca807547a2586fb66ebaf86a07392
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "rebase",
+        "-r",
+        "description(other)",
+        "-d",
+        "description(second)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commits to destination
+    Working copy  (@) now at: zsuskuln 15eb27b0 (empty) other
+    Parent commit (@-)      : kkmpptxz 94eb4810 (empty) second (amended)
+    post-rewrite: rebase
+    93ea156ec8d5e661a82a4b1ca5adf1b068196263 15eb27b079f091a86b6e7975b51a273a6bc00dd8
+    [EOF]
+    ");
+
+    // Creating a commit doesn't rewrite anything
+    let output = work_dir.run_jj(["new"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: yostqsxw 9a2c93b8 (empty) (no description set)
+    Parent commit (@-)      : zsuskuln 15eb27b0 (empty) other
+    [EOF]
+    ");
+}
@@ -0,0 +1,464 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn run_git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args([
+            "-c",
+            "user.name=Git User",
+            "-c",
+            "user.email=git.user@example.com",
+        ])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_git_notes_set_show_remove() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+
+    let output = work_dir.run_jj(["git", "notes", "show", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No note found for commit 68a505386f93 in namespace 'commits'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["git", "notes", "set", "-r@-", "-m", "looks good"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Set note on qpvuntsm 68a50538 (empty) first
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "git",
+        "notes",
+        "set",
+        "-r@-",
+        "--namespace=review",
+        "-m",
+        "approved",
+        "-m",
+        "by someone",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Set note on qpvuntsm 68a50538 (empty) first
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "notes", "show", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    looks good
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "notes", "show", "-r@-", "--namespace=review"]);
+    insta::assert_snapshot!(output, @r"
+    approved
+
+    by someone
+    [EOF]
+    ");
+
+    // Setting a note doesn't rewrite the commit
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        r#"commit_id.short() ++ " " ++ description.first_line() ++ " [" ++ self.notes().trim() ++ "] [" ++ self.notes("refs/notes/review").trim() ++ "]\n""#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    @  eb9fd2ab82e7  [] []
+    ○  68a505386f93 first [looks good] [approved
+    │
+    │  by someone]
+    ◆  000000000000  [] []
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "-n1", "--no-graph", "-Tdescription"]);
+    insta::assert_snapshot!(output, @"commit e8849ae12c709f2321908879bc724fdb2ab8a781[EOF]");
+
+    // Replace a note through the editor
+    std::fs::write(edit_script, "dump editor0\0write\nneeds work").unwrap();
+    let output = work_dir.run_jj(["git", "notes", "set", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Set note on qpvuntsm 68a50538 (empty) first
+    [EOF]
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor0")).unwrap(), @r#"
+    looks good
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    let output = work_dir.run_jj(["git", "notes", "show", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    needs work
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "notes", "remove", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Removed note from qpvuntsm 68a50538 (empty) first
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "notes", "show", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No note found for commit 68a505386f93 in namespace 'commits'
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["git", "notes", "show", "-r@-", "--namespace=review"]);
+    insta::assert_snapshot!(output, @r"
+    approved
+
+    by someone
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "notes", "show", "--namespace=bad..name"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Invalid Git notes namespace 'bad..name'
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_notes_follow_rewrites() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"git.notes-namespaces = ["review"]"#);
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new", "-m", "base"]).success();
+    work_dir.run_jj(["new", "-m", "child"]).success();
+    work_dir
+        .run_jj([
+            "git",
+            "notes",
+            "set",
+            "-r@-",
+            "--namespace=review",
+            "-m",
+            "review note",
+        ])
+        .success();
+    work_dir
+        .run_jj([
+            "git",
+            "notes",
+            "set",
+            "-r@",
+            "--namespace=review",
+            "-m",
+            "child note",
+        ])
+        .success();
+    work_dir
+        .run_jj(["git", "notes", "set", "-r@-", "-m", "default note"])
+        .success();
+    let template = r#"description.first_line() ++ " [" ++ self.notes("review").trim() ++ "] [" ++ self.notes().trim() ++ "]\n""#;
+
+    // Notes in the configured namespaces are copied to the rewritten commit,
+    // including to descendants that were rebased.
+    work_dir
+        .run_jj(["describe", "-r@-", "-m", "base (amended)"])
+        .success();
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  child [child note] []
+    ○  base (amended) [review note] []
+    ○   [] []
+    ◆   [] []
+    [EOF]
+    ");
+
+    // When squashing, the note of the destination commit takes precedence
+    work_dir.run_jj(["new", "root()", "-m", "other"]).success();
+    work_dir
+        .run_jj([
+            "git",
+            "notes",
+            "set",
+            "-r@",
+            "--namespace=review",
+            "-m",
+            "other note",
+        ])
+        .success();
+    work_dir
+        .run_jj([
+            "squash",
+            "--from=description(child)",
+            "--into=@",
+            "-m",
+            "squashed",
+        ])
+        .success();
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  squashed [other note] []
+    │ ○  base (amended) [review note] []
+    │ ○   [] []
+    ├─╯
+    ◆   [] []
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_notes_colocated() {
+    let test_env = TestEnvironment::default();
+    let work_dir = test_env.work_dir("repo");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    let commit_id = work_dir
+        .run_jj(["log", "--no-graph", "-r@-", "-Tcommit_id"])
+        .success()
+        .stdout
+        .into_raw();
+
+    // Notes written by Git can be read by jj, and vice versa
+    run_git(
+        work_dir.root(),
+        &[
+            "notes",
+            "--ref=review",
+            "add",
+            "-m",
+            "written by git",
+            &commit_id,
+        ],
+    );
+    let output = work_dir.run_jj(["git", "notes", "show", "-r@-", "--namespace=review"]);
+    insta::assert_snapshot!(output, @r"
+    written by git
+    [EOF]
+    ");
+    work_dir
+        .run_jj(["git", "notes", "set", "-r@-", "-m", "written by jj"])
+        .success();
+    insta::assert_snapshot!(run_git(work_dir.root(), &["notes", "show", &commit_id]), @"written by jj");
+    insta::assert_snapshot!(
+        run_git(work_dir.root(), &["log", "-1", "--format=%an <%ae>%n%s", "refs/notes/commits"]),
+        @r"
+    Test User <test.user@example.com>
+    Notes added by 'jj git notes set'
+    ");
+}
+
+#[test]
+fn test_git_notes_fetch_push() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "origin"])
+        .success();
+    let origin_dir = test_env.work_dir("origin");
+    origin_dir.run_jj(["commit", "-m", "first"]).success();
+    origin_dir.run_jj(["commit", "-m", "second"]).success();
+    origin_dir.run_jj(["commit", "-m", "third"]).success();
+    origin_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    origin_dir
+        .run_jj(["git", "notes", "set", "-rmain--", "-m", "first from origin"])
+        .success();
+    origin_dir.run_jj(["git", "export"]).success();
+    let notes_log = |dir: &Path| {
+        run_git(
+            dir,
+            &["log", "--graph", "--format=%s", "refs/notes/commits"],
+        )
+    };
+
+    // Notes aren't fetched or pushed by default
+    test_env
+        .run_jj_in(".", ["git", "clone", "origin", "clone"])
+        .success();
+    let clone_dir = test_env.work_dir("clone");
+    let template = r#"description.first_line() ++ " [" ++ self.notes().trim() ++ "]\n""#;
+    let output = clone_dir.run_jj(["log", "-T", template, "-r::main"]);
+    insta::assert_snapshot!(output, @r"
+    ◆  third []
+    ◆  second []
+    ◆  first []
+    ◆   []
+    [EOF]
+    ");
+    clone_dir
+        .run_jj(["git", "notes", "set", "-rmain", "-m", "private note"])
+        .success();
+    let output = clone_dir.run_jj(["git", "push", "--bookmark=main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bookmark main@origin already matches main
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(notes_log(origin_dir.root()), @"* Notes added by 'jj git notes set'");
+    clone_dir
+        .run_jj(["git", "notes", "remove", "-rmain"])
+        .success();
+
+    // Notes are fetched along with the bookmarks if enabled
+    test_env.add_config("git.fetch-notes = true");
+    test_env.add_config("git.push-notes = true");
+    let output = clone_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    let output = clone_dir.run_jj(["log", "-T", template, "-r::main"]);
+    insta::assert_snapshot!(output, @r"
+    ◆  third []
+    ◆  second []
+    ◆  first [first from origin]
+    ◆   []
+    [EOF]
+    ");
+
+    // Notes changed locally are pushed, even if no bookmark changed
+    clone_dir
+        .run_jj(["git", "notes", "set", "-rmain-", "-m", "second from clone"])
+        .success();
+    let output = clone_dir.run_jj(["git", "push", "--bookmark=main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bookmark main@origin already matches main
+    Nothing changed.
+    Pushed notes refs/notes/commits
+    [EOF]
+    ");
+    insta::assert_snapshot!(notes_log(origin_dir.root()), @r"
+    * Notes added by 'jj git notes set'
+    *   Notes merged by 'jj' from origin
+    |\  
+    | * Notes added by 'jj git notes set'
+    * Notes removed by 'jj git notes remove'
+    * Notes added by 'jj git notes set'
+    ");
+
+    // If the notes moved on the remote, they aren't overwritten
+    origin_dir
+        .run_jj(["git", "notes", "set", "-rmain", "-m", "third from origin"])
+        .success();
+    origin_dir
+        .run_jj(["git", "notes", "set", "-rmain-", "-m", "second from origin"])
+        .success();
+    clone_dir
+        .run_jj(["git", "notes", "set", "-rmain--", "-m", "first from clone"])
+        .success();
+    clone_dir
+        .run_jj([
+            "git",
+            "notes",
+            "set",
+            "-rmain-",
+            "-m",
+            "second changed by clone",
+        ])
+        .success();
+    let output = clone_dir.run_jj(["git", "push", "--bookmark=main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bookmark main@origin already matches main
+    Nothing changed.
+    Warning: Failed to push notes refs/notes/commits
+    Hint: Run `jj git fetch` to merge the notes from the remote, then push again.
+    [EOF]
+    ");
+
+    // Fetching merges the notes. If both sides changed the note of the same
+    // commit, their lines are combined.
+    let output = clone_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(notes_log(&clone_dir.root().join(".jj/repo/store/git")), @r"
+    *   Notes merged by 'jj' from origin
+    |\  
+    | * Notes added by 'jj git notes set'
+    | * Notes added by 'jj git notes set'
+    * | Notes added by 'jj git notes set'
+    * | Notes added by 'jj git notes set'
+    |/  
+    * Notes added by 'jj git notes set'
+    *   Notes merged by 'jj' from origin
+    |\  
+    | * Notes added by 'jj git notes set'
+    * Notes removed by 'jj git notes remove'
+    * Notes added by 'jj git notes set'
+    ");
+    let output = clone_dir.run_jj(["log", "-T", template, "-r::main"]);
+    insta::assert_snapshot!(output, @r"
+    ◆  third [third from origin]
+    ◆  second [second changed by clone
+    │  second from origin]
+    ◆  first [first from clone]
+    ◆   []
+    [EOF]
+    ");
+    let output = clone_dir.run_jj(["git", "push", "--bookmark=main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bookmark main@origin already matches main
+    Nothing changed.
+    Pushed notes refs/notes/commits
+    [EOF]
+    ");
+    insta::assert_snapshot!(notes_log(origin_dir.root()), @r"
+    *   Notes merged by 'jj' from origin
+    |\  
+    | * Notes added by 'jj git notes set'
+    | * Notes added by 'jj git notes set'
+    * | Notes added by 'jj git notes set'
+    * | Notes added by 'jj git notes set'
+    |/  
+    * Notes added by 'jj git notes set'
+    *   Notes merged by 'jj' from origin
+    |\  
+    | * Notes added by 'jj git notes set'
+    * Notes removed by 'jj git notes remove'
+    * Notes added by 'jj git notes set'
+    ");
+    insta::assert_snapshot!(
+        run_git(origin_dir.root(), &["notes", "show", "main~"]), @r"
+    second changed by clone
+    second from origin
+    ");
+}
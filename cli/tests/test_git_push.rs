@@ -14,6 +14,7 @@
 
 use testutils::git;
 
+use crate::common::to_toml_value;
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
@@ -2125,6 +2126,112 @@ fn test_git_push_sign_on_push() {
     ");
 }
 
+#[test]
+fn test_git_push_signature_policy() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    test_env.add_config(
+        r#"
+        signing.backend = "test"
+        signing.policy.require-signed = "all()"
+        signing.policy.allowed-keys = ["impeccable"]
+        "#,
+    );
+    work_dir
+        .run_jj(["new", "bookmark2", "-m", "unsigned"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+
+    let output = work_dir.run_jj(["git", "push", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't push commit f1d35530d78b since it doesn't have a trusted signature
+    Hint: Rejected commit: vruxwmqv f1d35530 bookmark2* | (empty) unsigned
+    Hint: Configured signing.policy.require-signed: 'all()'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Signed with a key that isn't allowed
+    work_dir.run_jj(["sign", "-r@", "--key", "other"]).success();
+    let output = work_dir.run_jj(["git", "push", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't push commit d71f45cf1734 since it doesn't have a trusted signature
+    Hint: Rejected commit: vruxwmqv d71f45cf bookmark2* | (empty) unsigned
+    Hint: Configured signing.policy.require-signed: 'all()'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir
+        .run_jj(["sign", "-r@", "--key", "impeccable"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 38a204733702 to 18226a173a39
+    Dry-run requested, not pushing.
+    [EOF]
+    ");
+
+    // Commits that get signed on push are allowed
+    work_dir.run_jj(["new", "-m", "signed on push"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "--dry-run",
+        "--config=signing.key=impeccable",
+        "--config=git.sign-on-push=true",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 38a204733702 to 2a0ea4b5b6c7
+    Dry-run requested, not pushing.
+    [EOF]
+    ");
+
+    // Commits signed on push with a key that isn't allowed are rejected
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "--config=signing.key=other",
+        "--config=git.sign-on-push=true",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't push commit 2a0ea4b5b6c7 since it doesn't have a trusted signature
+    Hint: Rejected commit: kxryzmor 2a0ea4b5 bookmark2* | (empty) signed on push
+    Hint: The signature created by git.sign-on-push isn't from one of the configured signing.policy.allowed-keys
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "--config=signing.key=impeccable",
+        "--config=git.sign-on-push=true",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Updated signatures of 1 commits
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 38a204733702 to 311feb5135d7
+    Working copy  (@) now at: kxryzmor 311feb51 bookmark2 | (empty) signed on push
+    Parent commit (@-)      : vruxwmqv 18226a17 (empty) unsigned
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_push_rejected_by_remote() {
     let test_env = TestEnvironment::default();
@@ -2195,6 +2302,510 @@ fn test_git_push_rejected_by_remote() {
     });
 }
 
+#[test]
+fn test_git_push_dry_run_json() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+    work_dir
+        .run_jj(["describe", "bookmark1", "-m", "modified bookmark1 commit"])
+        .success();
+    work_dir.run_jj(["new", "bookmark2", "-m", "foo"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "my-bookmark"])
+        .success();
+
+    let output = work_dir.run_jj(["git", "push", "--all", "--dry-run", "--json"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "remote": "origin",
+      "updates": [
+        {
+          "bookmark": "bookmark1",
+          "ref": "refs/heads/bookmark1",
+          "old_target": "9b2e76de39209592527956784ca134072f70850f",
+          "new_target": "e5ce6d9a0991cb61b260b78c1e1e1d7e455ea0ae",
+          "kind": "force"
+        },
+        {
+          "bookmark": "bookmark2",
+          "ref": "refs/heads/bookmark2",
+          "old_target": "38a2047337025526e8b7e79321e47168ea4996f3",
+          "new_target": "192b3122ed72afd7f61514571be213d3ec699684",
+          "kind": "fast-forward"
+        },
+        {
+          "bookmark": "my-bookmark",
+          "ref": "refs/heads/my-bookmark",
+          "old_target": null,
+          "new_target": "192b3122ed72afd7f61514571be213d3ec699684",
+          "kind": "create"
+        }
+      ],
+      "commits": [
+        "192b3122ed72afd7f61514571be213d3ec699684",
+        "e5ce6d9a0991cb61b260b78c1e1e1d7e455ea0ae"
+      ]
+    }
+    [EOF]
+    ------- stderr -------
+    Changes to push to origin:
+      Move sideways bookmark bookmark1 from 9b2e76de3920 to e5ce6d9a0991
+      Move forward bookmark bookmark2 from 38a204733702 to 192b3122ed72
+      Add bookmark my-bookmark to 192b3122ed72
+    Dry-run requested, not pushing.
+    [EOF]
+    "#);
+
+    // --json only makes sense with --dry-run
+    let output = work_dir.run_jj(["git", "push", "--all", "--json"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the following required arguments were not provided:
+      --dry-run
+
+    Usage: jj git push --dry-run --all --json
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_git_push_pre_push_hook() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    let hook_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    work_dir.run_jj(["new", "bookmark2", "-m", "foo"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+
+    // The hook receives the plan on stdin
+    test_env.add_config(format!(
+        "git.pre-push-hook = [{}, '--tee', 'plan.json']",
+        to_toml_value(hook_path.to_str().unwrap())
+    ));
+    let output = work_dir.run_jj(["git", "push", "--dry-run"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 38a204733702 to 86ea093ebb2e
+    {
+      "remote": "origin",
+      "updates": [
+        {
+          "bookmark": "bookmark2",
+          "ref": "refs/heads/bookmark2",
+          "old_target": "38a2047337025526e8b7e79321e47168ea4996f3",
+          "new_target": "86ea093ebb2e6367aa4f11df84aa7c64bc00cd7c",
+          "kind": "fast-forward"
+        }
+      ],
+      "commits": [
+        "86ea093ebb2e6367aa4f11df84aa7c64bc00cd7c"
+      ]
+    }
+    Dry-run requested, not pushing.
+    [EOF]
+    "#);
+    insta::assert_snapshot!(work_dir.read_file("plan.json"), @r#"
+    {
+      "remote": "origin",
+      "updates": [
+        {
+          "bookmark": "bookmark2",
+          "ref": "refs/heads/bookmark2",
+          "old_target": "38a2047337025526e8b7e79321e47168ea4996f3",
+          "new_target": "86ea093ebb2e6367aa4f11df84aa7c64bc00cd7c",
+          "kind": "fast-forward"
+        }
+      ],
+      "commits": [
+        "86ea093ebb2e6367aa4f11df84aa7c64bc00cd7c"
+      ]
+    }
+    "#);
+
+    // A failing hook rejects the push
+    test_env.add_config(format!(
+        "git.pre-push-hook = [{}, '--stdout', '', '--stderr', \"no pushing to $remote\\n\", \
+         '--fail']",
+        to_toml_value(hook_path.to_str().unwrap())
+    ));
+    let output = work_dir.run_jj(["git", "push"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 38a204733702 to 056d4066a415
+    no pushing to origin
+    Error: Push rejected by `git.pre-push-hook` (exit status: 1)
+    Hint: Use --no-verify to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    bookmark1: qpvuntsm 9b2e76de (empty) description 1
+      @origin: qpvuntsm 9b2e76de (empty) description 1
+    bookmark2: vruxwmqv 056d4066 foo
+      @origin (behind by 1 commits): zsuskuln 38a20473 (empty) description 2
+    [EOF]
+    ");
+
+    // The hook can be skipped
+    let output = work_dir.run_jj(["git", "push", "--no-verify"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 38a204733702 to 056d4066a415
+    [EOF]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_git_push_git_pre_push_hook() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    test_env.add_config("git.run-hooks = true");
+    let work_dir = test_env.work_dir("local");
+    let hook_path = git_repo_dir_for_jj_repo(&work_dir)
+        .join("hooks")
+        .join("pre-push");
+    std::fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hook_path,
+        "#!/bin/sh\necho \"pre-push: $1\"\ncat\nexit 1\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    work_dir.run_jj(["new", "bookmark2", "-m", "foo"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "delete", "bookmark1"])
+        .success();
+
+    // The hook receives the ref updates on stdin
+    let output = work_dir.run_jj(["git", "push", "--all", "--deleted"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Delete bookmark bookmark1 from 9b2e76de3920
+      Move forward bookmark bookmark2 from 38a204733702 to 86ea093ebb2e
+    pre-push: origin
+    (delete) 0000000000000000000000000000000000000000 refs/heads/bookmark1 9b2e76de39209592527956784ca134072f70850f
+    refs/heads/bookmark2 86ea093ebb2e6367aa4f11df84aa7c64bc00cd7c refs/heads/bookmark2 38a2047337025526e8b7e79321e47168ea4996f3
+    Error: Push rejected by pre-push hook (exit status: 1)
+    Hint: Use --no-verify to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    bookmark1 (deleted)
+      @origin: qpvuntsm 9b2e76de (empty) description 1
+    bookmark2: vruxwmqv 86ea093e (empty) foo
+      @origin (behind by 1 commits): zsuskuln 38a20473 (empty) description 2
+    [EOF]
+    ");
+
+    // The git.pre-push-hook command runs first, and the Git hook only runs if
+    // the command accepts the push
+    let fake_hook_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    test_env.add_config(format!(
+        "git.pre-push-hook = [{}, '--stdout', '', '--stderr', \"git.pre-push-hook\\n\"]",
+        to_toml_value(fake_hook_path.to_str().unwrap())
+    ));
+    let output = work_dir.run_jj(["git", "push", "--all", "--deleted", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Delete bookmark bookmark1 from 9b2e76de3920
+      Move forward bookmark bookmark2 from 38a204733702 to 86ea093ebb2e
+    git.pre-push-hook
+    pre-push: origin
+    (delete) 0000000000000000000000000000000000000000 refs/heads/bookmark1 9b2e76de39209592527956784ca134072f70850f
+    refs/heads/bookmark2 86ea093ebb2e6367aa4f11df84aa7c64bc00cd7c refs/heads/bookmark2 38a2047337025526e8b7e79321e47168ea4996f3
+    Error: Push rejected by pre-push hook (exit status: 1)
+    Hint: Use --no-verify to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+    test_env.add_config(format!(
+        "git.pre-push-hook = [{}, '--stdout', '', '--fail']",
+        to_toml_value(fake_hook_path.to_str().unwrap())
+    ));
+    let output = work_dir.run_jj(["git", "push", "--all", "--deleted", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Delete bookmark bookmark1 from 9b2e76de3920
+      Move forward bookmark bookmark2 from 38a204733702 to 86ea093ebb2e
+    Error: Push rejected by `git.pre-push-hook` (exit status: 1)
+    Hint: Use --no-verify to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The hooks can be skipped
+    let output = work_dir.run_jj(["git", "push", "--all", "--deleted", "--no-verify"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Delete bookmark bookmark1 from 9b2e76de3920
+      Move forward bookmark bookmark2 from 38a204733702 to 86ea093ebb2e
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_push_to_ref() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    let origin_git_repo = git::open(git_repo_dir_for_jj_repo(&test_env.work_dir("origin")));
+    work_dir
+        .run_jj(["new", "bookmark2", "-m", "first"])
+        .success();
+    work_dir.write_file("file", "first");
+    work_dir.run_jj(["new", "-m", "second"]).success();
+    work_dir.write_file("file", "second");
+    work_dir.run_jj(["new"]).success();
+
+    // The destination must be a full ref name
+    let output = work_dir.run_jj(["git", "push", "--to", "main", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Invalid destination ref: main
+    Hint: The destination must be a full ref name, e.g. refs/for/main
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["git", "push", "--to", "refs/for/main", "-r@-", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Push commit 654809ebdc06 to refs/for/main
+    Dry-run requested, not pushing.
+    [EOF]
+    ");
+
+    // Change-Id trailers are added to the pushed commits
+    let output = work_dir.run_jj(["git", "push", "--to", "refs/for/main%topic=foo", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Added Change-Id trailers to 2 commits
+    Rebased 1 descendant commits
+    Changes to push to origin:
+      Push commit 74037854fe49 to refs/for/main%topic=foo
+    Working copy  (@) now at: znkkpsqq a7dd0cc8 (empty) (no description set)
+    Parent commit (@-)      : yostqsxw 74037854 second
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-r::@",
+        "-T",
+        "commit_id.short() ++ ' ' ++ description",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    @  a7dd0cc8b262
+    ○  74037854fe49 second
+    │
+    │  Change-Id: I6a6a69641b76972398e6b49e8e0701307e57d55a
+    ○  fbe2b48ef889 first
+    │
+    │  Change-Id: I6a6a696448523d946ad29f88f7d018421799e72a
+    ○  38a204733702 description 2
+    ◆  000000000000
+    [EOF]
+    ");
+    let pushed_id = origin_git_repo
+        .find_reference("refs/for/main%topic=foo")
+        .unwrap()
+        .id()
+        .to_string();
+    let output = work_dir.run_jj(["log", "--no-graph", "-r@-", "-T", "commit_id"]);
+    assert_eq!(output.stdout.raw(), pushed_id);
+
+    // Pushing again keeps the existing Change-Id trailers
+    work_dir
+        .run_jj(["describe", "-r@-", "-m", "second\n\nChange-Id: I1234\n"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--to", "refs/for/other", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Push commit 91cb6d752194 to refs/for/other
+    [EOF]
+    ");
+
+    // No bookmarks are created
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    bookmark1: qpvuntsm 9b2e76de (empty) description 1
+      @origin: qpvuntsm 9b2e76de (empty) description 1
+    bookmark2: zsuskuln 38a20473 (empty) description 2
+      @origin: zsuskuln 38a20473 (empty) description 2
+    [EOF]
+    ");
+
+    // Pushing to a bookmark updates the remote bookmark, so it can be pushed
+    // to again without fetching
+    let output = work_dir.run_jj(["git", "push", "--to", "refs/heads/bookmark2", "-r@--"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Push commit fbe2b48ef889 to refs/heads/bookmark2
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "push", "--to", "refs/heads/bookmark2", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Push commit 91cb6d752194 to refs/heads/bookmark2
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    bookmark1: qpvuntsm 9b2e76de (empty) description 1
+      @origin: qpvuntsm 9b2e76de (empty) description 1
+    bookmark2: zsuskuln 38a20473 (empty) description 2
+      @origin (ahead by 2 commits): yostqsxw 91cb6d75 second
+    [EOF]
+    ");
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@-"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--bookmark=bookmark2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bookmark bookmark2@origin already matches bookmark2
+    Nothing changed.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_push_atomic() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+
+    // create a hook on the remote that rejects updates of bookmark1
+    let hook_path = git_repo_dir_for_jj_repo(&test_env.work_dir("origin"))
+        .join("hooks")
+        .join("update");
+    std::fs::write(
+        &hook_path,
+        "#!/bin/sh\ntest \"$1\" = refs/heads/bookmark1 && exit 1\nexit 0\n",
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o700)).unwrap();
+    }
+
+    work_dir
+        .run_jj(["describe", "bookmark1", "-m", "modified bookmark1 commit"])
+        .success();
+    work_dir
+        .run_jj(["describe", "bookmark2", "-m", "modified bookmark2 commit"])
+        .success();
+
+    let output = work_dir.run_jj(["git", "push", "--all", "--atomic"]);
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"\s*\n", "\n");
+    settings.bind(|| {
+        insta::assert_snapshot!(output, @r"
+        ------- stderr -------
+        Changes to push to origin:
+          Move sideways bookmark bookmark1 from 9b2e76de3920 to e5ce6d9a0991
+          Move sideways bookmark bookmark2 from 38a204733702 to eb5bbacb3a5f
+        remote: error: hook declined to update refs/heads/bookmark1
+        Error: Failed to push some bookmarks
+        Hint: The remote rejected the following updates:
+          refs/heads/bookmark1 (reason: hook declined)
+        Hint: Try checking if you have permission to push to all the bookmarks.
+        Hint: The following references were not updated because the push is atomic:
+          refs/heads/bookmark2
+        [EOF]
+        [exit status: 1]
+        ");
+    });
+    // Neither bookmark was updated
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    bookmark1: qpvuntsm e5ce6d9a (empty) modified bookmark1 commit
+      @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm hidden 9b2e76de (empty) description 1
+    bookmark2: zsuskuln eb5bbacb (empty) modified bookmark2 commit
+      @origin (ahead by 1 commits, behind by 1 commits): zsuskuln hidden 38a20473 (empty) description 2
+    [EOF]
+    ");
+    // bookmark2 wasn't updated on the remote either
+    let origin_git_repo = git::open(git_repo_dir_for_jj_repo(&test_env.work_dir("origin")));
+    let bookmark2_target = origin_git_repo
+        .find_reference("refs/heads/bookmark2")
+        .unwrap()
+        .id()
+        .to_string();
+    assert_eq!(bookmark2_target, "38a2047337025526e8b7e79321e47168ea4996f3");
+}
+
+#[test]
+fn test_git_push_options() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    let origin_git_repo_path = git_repo_dir_for_jj_repo(&test_env.work_dir("origin"));
+    std::fs::write(
+        origin_git_repo_path.join("config"),
+        std::fs::read_to_string(origin_git_repo_path.join("config")).unwrap()
+            + "[receive]\n\tadvertisePushOptions = true\n",
+    )
+    .unwrap();
+    // create a hook on the remote that prints the push options
+    let hook_path = origin_git_repo_path.join("hooks").join("pre-receive");
+    std::fs::write(
+        &hook_path,
+        "#!/bin/sh\necho \"options: $GIT_PUSH_OPTION_0 $GIT_PUSH_OPTION_1\"\n",
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o700)).unwrap();
+    }
+
+    work_dir.run_jj(["new", "bookmark1", "-m", "foo"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark1", "-r@"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "-o", "ci.skip", "--option", "topic=foo"]);
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"\s*\n", "\n");
+    settings.bind(|| {
+        insta::assert_snapshot!(output, @r"
+        ------- stderr -------
+        Changes to push to origin:
+          Move forward bookmark bookmark1 from 9b2e76de3920 to 18ba325df20c
+        remote: options: ci.skip topic=foo
+        [EOF]
+        ");
+    });
+}
+
 #[must_use]
 fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
     // --quiet to suppress deleted bookmarks hint
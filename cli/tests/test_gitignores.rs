@@ -153,3 +153,82 @@ fn test_gitignores_ignored_file_in_target_commit() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_jjignores() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // Track a file before it gets ignored
+    work_dir.write_file("secret", "");
+    work_dir.run_jj(["st"]).success();
+
+    // .jjignore can un-ignore paths ignored by .gitignore, and vice versa
+    work_dir.write_file(".gitignore", "*.log\n/build\nsecret\n");
+    work_dir.write_file(".jjignore", "!keep.log\n*.tmp\n");
+    work_dir.write_file("a.log", "");
+    work_dir.write_file("keep.log", "");
+    work_dir.write_file("a.tmp", "");
+    work_dir.write_file("file", "");
+    work_dir.create_dir("build").write_file("out", "");
+    let sub_dir = work_dir.create_dir("sub");
+    sub_dir.write_file(".jjignore", "!*.tmp\n");
+    sub_dir.write_file("b.log", "");
+    sub_dir.write_file("b.tmp", "");
+
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    .gitignore
+    .jjignore
+    file
+    keep.log
+    secret
+    sub/.jjignore
+    sub/b.tmp
+    [EOF]
+    ");
+
+    // Tracked files which are ignored can be found by ignored()
+    let output = work_dir.run_jj(["file", "list", "ignored()"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    secret
+    [EOF]
+    ");
+
+    // Untracked ignored paths are reported along with the matching rule
+    let output = work_dir.run_jj(["file", "list", "--ignored"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    .gitignore:1:*.log	a.log
+    .jjignore:2:*.tmp	a.tmp
+    .gitignore:2:/build	build/
+    .gitignore:1:*.log	sub/b.log
+    [EOF]
+    ");
+    let output = sub_dir.run_jj(["file", "list", "--ignored", "."]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    ../.gitignore:1:*.log	b.log
+    [EOF]
+    ");
+
+    // File predicates can't be evaluated against untracked paths
+    let output = work_dir.run_jj(["file", "list", "--ignored", "binary()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: File predicates such as `binary()` or `ignored()` are not supported here
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["file", "list", "--ignored", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--ignored' cannot be used with '--revision <REVSET>'
+
+    Usage: jj file list --ignored [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
@@ -169,4 +169,54 @@ fn test_help_keyword() {
     [EOF]
     [exit status: 2]
     ");
+
+    // It should list revset functions defined in config
+    test_env.add_config(
+        r#"
+        [revset-functions.reviewed]
+        command = ["review-db", "query"]
+        description = "Commits approved in code review"
+
+        [revset-functions.ci_passed]
+        command = ["ci-status"]
+        "#,
+    );
+    let help_cmd = test_env.run_jj_in(".", ["help", "-k", "revsets"]).success();
+    let functions_help = help_cmd
+        .stdout
+        .raw()
+        .strip_prefix(include_str!("../../docs/revsets.md"))
+        .unwrap();
+    insta::assert_snapshot!(functions_help, @r"
+    ## User-defined functions
+
+    These functions are defined in the `revset-functions` config table.
+
+    * `ci_passed()`
+    * `reviewed()`: Commits approved in code review
+    ");
+
+    // It should list fileset functions defined in config
+    test_env.add_config(
+        r#"
+        [fileset-functions.generated]
+        command = ["codegen-manifest", "--query"]
+        description = "Files produced by the code generator"
+        "#,
+    );
+    let help_cmd = test_env
+        .run_jj_in(".", ["help", "-k", "filesets"])
+        .success();
+    let functions_help = help_cmd
+        .stdout
+        .raw()
+        .strip_prefix(include_str!("../../docs/filesets.md"))
+        .unwrap();
+    insta::assert_snapshot!(functions_help, @r"
+    ## User-defined functions
+
+    These functions are defined in the `fileset-functions` config table.
+
+    * `generated()`: Files produced by the code generator
+    ");
 }
@@ -854,6 +854,194 @@ fn test_log_reversed() {
     ");
 }
 
+#[test]
+fn test_log_interactive() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // The interactive log can't be run without a terminal
+    let output = work_dir.run_jj(["log", "-i"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The interactive log requires a terminal
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["log", "-i", "--no-graph"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--interactive' cannot be used with '--no-graph'
+
+    Usage: jj log --interactive [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_log_interactive_actions() {
+    let mut test_env = TestEnvironment::default();
+    let editor_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "1\n");
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.run_jj(["new", "-m", "second"]).success();
+    work_dir.write_file("file2", "2\n");
+    work_dir.run_jj(["new", "-m", "third"]).success();
+    work_dir.write_file("file3", "3\n");
+    let get_log_output = || {
+        let template = r#"separate(" ", description.first_line(), if(empty, "(empty)")) ++ "\n""#;
+        work_dir.run_jj(["log", "-T", template])
+    };
+    let run_keys = |keys: &str| {
+        work_dir.run_jj(["log", "-i", &format!("--config=debug.scripted-keys={keys}")])
+    };
+    insta::assert_snapshot!(get_log_output(), @r"
+    @  third
+    ○  second
+    ○  first
+    ◆  (empty)
+    [EOF]
+    ");
+
+    // The working-copy commit is selected initially. Describe its parent.
+    std::fs::write(&editor_script, "write\ndescribed\n").unwrap();
+    let output = run_keys(r#"["j", "d", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: zsuskuln 22048bea third
+    Parent commit (@-)      : kkmpptxz f12b3ced described
+    Described 4c10f799191d
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(), @r"
+    @  third
+    ○  described
+    ○  first
+    ◆  (empty)
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+
+    // Create a new commit on top of the parent commit
+    let output = run_keys(r#"["j", "n", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: yostqsxw 57940f09 (empty) (no description set)
+    Parent commit (@-)      : kkmpptxz 4c10f799 second
+    Added 0 files, modified 0 files, removed 1 files
+    Created new commit on top of 4c10f799191d
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(), @r"
+    @  (empty)
+    │ ○  third
+    ├─╯
+    ○  second
+    ○  first
+    ◆  (empty)
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+
+    // Edit the grandparent commit
+    let output = run_keys(r#"["j", "j", "e", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: qpvuntsm 1cd4f179 first
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 0 files, removed 2 files
+    Editing 1cd4f179654c
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(), @r"
+    ○  third
+    ○  second
+    @  first
+    ◆  (empty)
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+
+    // Abandon the parent commit
+    let output = run_keys(r#"["j", "a", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: zsuskuln c857211d third
+    Parent commit (@-)      : qpvuntsm 1cd4f179 first
+    Added 0 files, modified 0 files, removed 1 files
+    Abandoned 4c10f799191d
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(), @r"
+    @  third
+    ○  first
+    ◆  (empty)
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+
+    // Squash the working-copy commit into its parent
+    std::fs::write(&editor_script, "write\nsquashed\n").unwrap();
+    let output = run_keys(r#"["s", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: xznxytkn 5a8ce6f4 (empty) (no description set)
+    Parent commit (@-)      : kkmpptxz 983e96d0 squashed
+    Squashed 2fac6e8415c5 into 4c10f799191d
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(), @r"
+    @  (empty)
+    ○  squashed
+    ○  first
+    ◆  (empty)
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+
+    // Rebase the working-copy commit onto the grandparent commit
+    let output = run_keys(r#"["space", "j", "j", "r", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: zsuskuln 89df1ee9 third
+    Parent commit (@-)      : qpvuntsm 1cd4f179 first
+    Added 0 files, modified 0 files, removed 1 files
+    Rebased 2fac6e8415c5 onto 1cd4f179654c
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(), @r"
+    @  third
+    │ ○  second
+    ├─╯
+    ○  first
+    ◆  (empty)
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+
+    // Errors are reported in the status line, and the log keeps running
+    let output = run_keys(r#"["j", "j", "space", "k", "r", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot rebase a revision onto itself or its descendant
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(), @r"
+    @  third
+    ○  second
+    ○  first
+    ◆  (empty)
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_filtered_by_path() {
     let test_env = TestEnvironment::default();
@@ -1706,3 +1894,341 @@ fn test_log_full_description_template() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_log_evolution_revsets() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"separate(" ", commit_id.short(), description.first_line()) ++ "\n""#;
+
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    let first_id = work_dir
+        .run_jj(["log", "--no-graph", "-r@", "-Tcommit_id"])
+        .success()
+        .stdout
+        .into_raw();
+    work_dir.write_file("file", "contents\n");
+    work_dir.run_jj(["describe", "-m", "second"]).success();
+    work_dir.run_jj(["new", "root()", "-m", "other"]).success();
+    work_dir.run_jj(["abandon", "@"]).success();
+
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        "predecessors(description(second))",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    2b3b9b98d67a first
+    68a505386f93 first
+    e8849ae12c70
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        &format!("successors({first_id})"),
+    ]);
+    insta::assert_snapshot!(output, @r"
+    5c0b2099a04d second
+    2b3b9b98d67a first
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        &format!("successors({first_id}) ~ hidden()"),
+    ]);
+    insta::assert_snapshot!(output, @r"
+    5c0b2099a04d second
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-r", "obsolete()"]);
+    insta::assert_snapshot!(output, @r"
+    2b3b9b98d67a first
+    68a505386f93 first
+    e8849ae12c70
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-r", "hidden()"]);
+    insta::assert_snapshot!(output, @r"
+    b290263a1fb9 other
+    2b3b9b98d67a first
+    68a505386f93 first
+    e8849ae12c70
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_sort_limit() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"description.first_line() ++ "\n""#;
+
+    work_dir.run_jj(["describe", "-m", "b"]).success();
+    work_dir.run_jj(["new", "-m", "c"]).success();
+    work_dir.run_jj(["new", "root()", "-m", "a"]).success();
+
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        "sort(~root(), description)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    a
+    b
+    c
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        "limit(sort(~root(), description, reverse=true), 2, offset=1)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    b
+    a
+    [EOF]
+    ");
+    // --reversed reverses the sorted order
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "--reversed",
+        "-T",
+        template,
+        "-r",
+        "sort(~root(), description)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    c
+    b
+    a
+    [EOF]
+    ");
+    // The graph is always in topological order
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "sort(~root(), description)"]);
+    insta::assert_snapshot!(output, @r"
+    @  a
+    │
+    ~
+
+    ○  c
+    ○  b
+    │
+    ~
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_first_ancestors() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"description.first_line() ++ "\n""#;
+
+    work_dir.run_jj(["describe", "-m", "base"]).success();
+    work_dir.run_jj(["new", "-m", "main"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir
+        .run_jj(["new", "description(base)", "-m", "side"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "side"])
+        .success();
+    work_dir
+        .run_jj(["new", "main", "side", "-m", "merge"])
+        .success();
+
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "first_ancestors(@)"]);
+    insta::assert_snapshot!(output, @r"
+    @  merge
+    ○  main
+    ○  base
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        template,
+        "-r",
+        "::@ ~ ancestors(@, first_parent=true)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ○  side
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "parents(@, first_parent=true)"]);
+    insta::assert_snapshot!(output, @r"
+    ○  main
+    │
+    ~
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_description_line_patterns() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"description.first_line() ++ "\n""#;
+
+    work_dir
+        .run_jj(["describe", "-m", "first\n\nFixes: #1"])
+        .success();
+    work_dir
+        .run_jj(["new", "-m", "second\n\nFixes: #12 and #13"])
+        .success();
+    work_dir.run_jj(["new", "-m", "THIRD"]).success();
+
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "description(regex-i:'^thi')"]);
+    insta::assert_snapshot!(output, @r"
+    @  THIRD
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        template,
+        "-r",
+        "description(exact-line:second)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ○  second
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        template,
+        "-r",
+        "description(line-glob:'Fixes: #?')",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ○  first
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        template,
+        "-r",
+        "description(line-glob:'Fixes: *')",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ○  second
+    ○  first
+    │
+    ~
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_diff_stat_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"description.first_line() ++ "\n""#;
+
+    work_dir.write_file("file1", "a\nb\n");
+    work_dir.write_file("file2", "a\n");
+    work_dir.write_file("rename-source", "rename");
+    work_dir.run_jj(["commit", "-m", "add"]).success();
+    work_dir.write_file("file1", "a\nb\nc\n");
+    work_dir.run_jj(["commit", "-m", "modify"]).success();
+    std::fs::rename(
+        work_dir.root().join("rename-source"),
+        work_dir.root().join("rename-target"),
+    )
+    .unwrap();
+    work_dir.run_jj(["commit", "-m", "rename"]).success();
+    work_dir.remove_file("file2");
+    work_dir.run_jj(["describe", "-m", "delete"]).success();
+
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "diff_lines('>=2')"]);
+    insta::assert_snapshot!(output, @r"
+    ○  rename
+    ~  (elided revisions)
+    ○  add
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "diff_files(1, file1)"]);
+    insta::assert_snapshot!(output, @r"
+    ○  modify
+    ○  add
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "added() | deleted()"]);
+    insta::assert_snapshot!(output, @r"
+    @  delete
+    ○  rename
+    ~  (elided revisions)
+    ○  add
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "renamed()"]);
+    insta::assert_snapshot!(output, @r"
+    ○  rename
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "renamed(file1)"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "diff_lines('>x')"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to parse revset: Invalid count range
+    Caused by:
+    1:  --> 1:12
+      |
+    1 | diff_lines('>x')
+      |            ^--^
+      |
+      = Invalid count range
+    2: Invalid count "x": invalid digit found in string
+    [EOF]
+    [exit status: 1]
+    "#);
+}
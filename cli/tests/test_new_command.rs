@@ -735,6 +735,62 @@ fn test_new_with_trailers() {
     insta::assert_snapshot!(output, @"");
 }
 
+#[test]
+fn test_new_on_untrusted_commit() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config(
+        r#"
+        signing.backend = "test"
+        signing.policy.require-signed = "description(upstream)"
+        signing.policy.allowed-keys = ["impeccable"]
+        "#,
+    );
+
+    work_dir
+        .run_jj(["describe", "-m", "upstream unsigned"])
+        .success();
+    work_dir
+        .run_jj(["new", "root()", "-m", "upstream signed"])
+        .success();
+    work_dir
+        .run_jj(["sign", "-r@", "--key", "impeccable"])
+        .success();
+    work_dir
+        .run_jj(["new", "root()", "-m", "upstream wrong key"])
+        .success();
+    work_dir.run_jj(["sign", "-r@", "--key", "other"]).success();
+
+    let output = work_dir.run_jj(["new", "description('upstream signed')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: yqosqzyt 976976b8 (empty) (no description set)
+    Parent commit (@-)      : kkmpptxz 01167c9e (empty) upstream signed
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["new", "description('upstream unsigned')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Building on commits without a trusted signature:
+      qpvuntsm 273cf686 (empty) upstream unsigned
+    Hint: Configured signing.policy.require-signed: 'description(upstream)'
+    Working copy  (@) now at: vruxwmqv b86f9046 (empty) (no description set)
+    Parent commit (@-)      : qpvuntsm 273cf686 (empty) upstream unsigned
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["new", "description('upstream wrong key')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Building on commits without a trusted signature:
+      mzvwutvl c174c8b5 (empty) upstream wrong key
+    Hint: Configured signing.policy.require-signed: 'description(upstream)'
+    Working copy  (@) now at: yostqsxw 42aa3851 (empty) (no description set)
+    Parent commit (@-)      : mzvwutvl c174c8b5 (empty) upstream wrong key
+    [EOF]
+    ");
+}
+
 fn setup_before_insertion(work_dir: &TestWorkDir) {
     work_dir
         .run_jj(["bookmark", "create", "-r@", "A"])
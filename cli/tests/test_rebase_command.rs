@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::common::create_commit;
+use crate::common::create_commit_with_files;
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
@@ -31,9 +32,9 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     error: the following required arguments were not provided:
-      <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+      <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--interactive>
 
-    Usage: jj rebase <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--interactive>
 
     For more information, try '--help'.
     [EOF]
@@ -46,7 +47,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--revisions <REVSETS>' cannot be used with '--source <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--interactive>
 
     For more information, try '--help'.
     [EOF]
@@ -59,7 +60,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--branch <REVSETS>' cannot be used with '--source <REVSETS>'
 
-    Usage: jj rebase --branch <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --branch <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--interactive>
 
     For more information, try '--help'.
     [EOF]
@@ -72,7 +73,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--destination <REVSETS>' cannot be used with '--insert-after <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--interactive>
 
     For more information, try '--help'.
     [EOF]
@@ -85,7 +86,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--destination <REVSETS>' cannot be used with '--insert-before <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--interactive>
 
     For more information, try '--help'.
     [EOF]
@@ -2968,6 +2969,416 @@ fn test_rebase_skip_if_on_destination() {
     ");
 }
 
+#[test]
+fn test_rebase_interactive() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["b"]);
+    create_commit(&work_dir, "d", &["c"]);
+    create_commit(&work_dir, "e", &["c"]);
+    let [a, b, c, d] = ["a", "b", "c", "d"].map(|rev| commit_hash(&work_dir, rev));
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  e: c
+    │ ○  d: c
+    ├─╯
+    ○  c: b
+    ○  b: a
+    ○  a
+    ◆
+    [EOF]
+    ");
+    let setup_opid = work_dir.current_operation_id();
+
+    // The todo list is left unchanged
+    std::fs::write(&edit_script, "dump todo").unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b::d"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("todo")).unwrap(), @r#"
+    JJ: Rebase 3 commits onto 7d980be7a1d4
+    JJ:
+    pick 123b4d91f6e5 b
+    pick dffaa0d4dacc c
+    pick 86be7a223919 d
+
+    JJ: Actions:
+    JJ:   p, pick   = keep the revision
+    JJ:   r, reword = keep the revision, but edit its description
+    JJ:   e, edit   = keep the revision, and make it the working-copy commit
+    JJ:   s, squash = squash the revision into the previous one, and edit the
+    JJ:               combined description
+    JJ:   f, fixup  = like "squash", but keep the previous revision's description
+    JJ:   d, drop   = abandon the revision
+    JJ:
+    JJ: Revisions are applied from top to bottom, each on top of the previous
+    JJ: one. Every revision must be listed. If all lines are removed, the rebase
+    JJ: is aborted.
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+
+    // Reorder and drop revisions. The child of the dropped revision stays on
+    // top of the rewritten parent.
+    std::fs::write(
+        &edit_script,
+        format!("write\npick {d} d\npick {b} b\ndrop {c} c\n"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b::d"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 2 commits
+    Abandoned 1 commit
+    Working copy  (@) now at: znkkpsqq 3c217b8a e | e
+    Parent commit (@-)      : zsuskuln 2651eaa6 b c | b
+    Added 1 files, modified 0 files, removed 1 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  e: b c
+    ○  b c: d
+    ○  d: a
+    ○  a
+    ◆
+    [EOF]
+    ");
+    // The whole rebase is undone at once
+    work_dir.run_jj(["undo"]).success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  e: c
+    │ ○  d: c
+    ├─╯
+    ○  c: b
+    ○  b: a
+    ○  a
+    ◆
+    [EOF]
+    ");
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+
+    // Squash and fixup, rebasing onto another destination
+    std::fs::write(
+        &edit_script,
+        format!("write\npick {b}\nfixup {c}\nsquash {d}\0next invocation\n\0write\nb and d\n"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b::d", "-d", "root()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commit
+    Squashed 2 commits
+    Working copy  (@) now at: znkkpsqq 91c29f86 e | e
+    Parent commit (@-)      : zsuskuln c5a6a932 b c d | b and d
+    Added 1 files, modified 0 files, removed 1 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", "description ++ ' ' ++ bookmarks"]);
+    insta::assert_snapshot!(output, @r"
+    @  e
+    │   e
+    ○  b and d
+    │   b c d
+    │ ○  a
+    ├─╯   a
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "-r", "d"]);
+    insta::assert_snapshot!(output, @r"
+    b
+    c
+    d
+    [EOF]
+    ");
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+
+    // Reword and edit
+    std::fs::write(
+        &edit_script,
+        format!("write\nreword {a}\nedit {b}\0next invocation\n\0write\nnew a\n"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "a|b"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 2 commits
+    Working copy  (@) now at: zsuskuln ae85114a b | b
+    Parent commit (@-)      : rlvkpnrz dc9b5b89 a | new a
+    Added 0 files, modified 0 files, removed 2 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", "description ++ ' ' ++ bookmarks"]);
+    insta::assert_snapshot!(output, @r"
+    ○  e
+    │   e
+    │ ○  d
+    ├─╯   d
+    ○  c
+    │   c
+    @  b
+    │   b
+    ○  new a
+    │   a
+    ◆
+    [EOF]
+    ");
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+
+    // An empty todo list aborts the rebase
+    let opid = work_dir.current_operation_id();
+    std::fs::write(&edit_script, "write\n").unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b::d"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebase aborted because the todo list is empty.
+    [EOF]
+    ");
+    assert_eq!(work_dir.current_operation_id(), opid);
+}
+
+#[test]
+fn test_rebase_interactive_skip_emptied() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "x\n")]);
+    create_commit_with_files(&work_dir, "c", &["a"], &[("file", "x\n")]);
+    create_commit(&work_dir, "d", &["c"]);
+    let [b, c, d] = ["b", "c", "d"].map(|rev| commit_hash(&work_dir, rev));
+    let setup_opid = work_dir.current_operation_id();
+
+    // "c" becomes empty on top of "b", so it's abandoned, and "d" is reworded
+    std::fs::write(
+        &edit_script,
+        format!("write\npick {b}\npick {c}\nreword {d}\0next invocation\n\0write\nnew d\n"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b|c::d", "--skip-emptied"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 2 commits
+    Abandoned 1 commit
+    Working copy  (@) now at: vruxwmqv 0d2fbb86 d | new d
+    Parent commit (@-)      : zsuskuln 1f0a07c2 b c | b
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-T", "description ++ ' ' ++ bookmarks"]);
+    insta::assert_snapshot!(output, @r"
+    @  new d
+    │   d
+    ○  b
+    │   b c
+    ○  a
+    │   a
+    ◆
+    [EOF]
+    ");
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+
+    // Can't squash into the abandoned revision
+    std::fs::write(
+        &edit_script,
+        format!("write\npick {b}\npick {c}\nfixup {d}\n"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b|c::d", "--skip-emptied"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Revision 96aae9a7e6b4 was abandoned because it became empty
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // A single squashed revision
+    std::fs::write(
+        &edit_script,
+        format!("write\npick {c}\nfixup {d}\ndrop {b}\n"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b|c::d"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commit
+    Squashed 1 commit
+    Abandoned 1 commit
+    Working copy  (@) now at: xznxytkn d6da9439 (empty) (no description set)
+    Parent commit (@-)      : royxmykx 452303aa c d | c
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_rebase_onto_untrusted_commit() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config(
+        r#"
+        signing.backend = "test"
+        signing.policy.require-signed = "bookmarks(upstream)"
+        "#,
+    );
+
+    create_commit(&work_dir, "upstream", &[]);
+    create_commit(&work_dir, "a", &[]);
+
+    let output = work_dir.run_jj(["rebase", "-r", "a", "-d", "upstream"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Building on commits without a trusted signature:
+      rlvkpnrz 3ada4261 upstream | upstream
+    Hint: Configured signing.policy.require-signed: 'bookmarks(upstream)'
+    Rebased 1 commits to destination
+    Working copy  (@) now at: zsuskuln 8148471d a | a
+    Parent commit (@-)      : rlvkpnrz 3ada4261 upstream | upstream
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+
+    // Any good signature is trusted if there are no allowed keys
+    work_dir.run_jj(["sign", "-r", "upstream"]).success();
+    let output = work_dir.run_jj(["rebase", "-r", "a", "-d", "root()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commits to destination
+    Working copy  (@) now at: zsuskuln af4ccd29 a | a
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 0 files, removed 1 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["rebase", "-r", "a", "-d", "upstream"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commits to destination
+    Working copy  (@) now at: zsuskuln a7b41b6b a | a
+    Parent commit (@-)      : rlvkpnrz 4e46ad0d upstream | upstream
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_rebase_interactive_invalid() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["a"]);
+    create_commit(&work_dir, "m", &["b", "c"]);
+    create_commit(&work_dir, "d", &[]);
+    let [a, b, c] = ["a", "b", "c"].map(|rev| commit_hash(&work_dir, rev));
+
+    // -i requires -r
+    let output = work_dir.run_jj(["rebase", "-i", "-s", "b"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--interactive' cannot be used with '--source <REVSETS>'
+
+    Usage: jj rebase --revisions <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--interactive>
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Merge commits can't be rebased interactively
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b::"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot rebase merge commit 393b2fad88d2 interactively
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The destination can't be inferred if the roots have different parents
+    let output = work_dir.run_jj(["rebase", "-i", "-r", "b|d"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The revisions to rebase don't have a single parent
+    Hint: Use --destination to specify where to rebase them.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let run_with_todo = |todo: String| {
+        std::fs::write(&edit_script, format!("write\n{todo}")).unwrap();
+        work_dir.run_jj(["rebase", "-i", "-r", "b|c", "-d", "a"])
+    };
+    let output = run_with_todo(format!("pick {b}\n"));
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: The following revisions are missing from the todo list: 991a7501d660
+    Hint: Use "drop" to abandon a revision.
+    [EOF]
+    [exit status: 1]
+    "#);
+    let output = run_with_todo(format!("pick {b}\npick {c}\npick {a}\n"));
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Revision 7d980be7a1d4 in the todo list is not being rebased
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = run_with_todo(format!("pick {b}\npick {b}\npick {c}\n"));
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Revision 123b4d91f6e5 is listed more than once in the todo list
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = run_with_todo(format!("pick {b}\nmove {c}\n"));
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Unknown action "move" in the todo list line: "move 991a7501d660"
+    [EOF]
+    [exit status: 1]
+    "#);
+    let output = run_with_todo(format!("drop {b}\nsquash {c}\n"));
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot squash revision 991a7501d660 without a previous revision to squash into
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = run_with_todo(format!("edit {b}\nedit {c}\n"));
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Only one revision in the todo list can be marked for editing
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = run_with_todo("pick\n".to_owned());
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Missing revision in the todo list line: "pick"
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
+fn commit_hash(work_dir: &TestWorkDir, rev: &str) -> String {
+    work_dir
+        .run_jj(["log", "--no-graph", "-r", rev, "-T", "commit_id.short()"])
+        .success()
+        .stdout
+        .into_raw()
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = "bookmarks ++ surround(': ', '', parents.map(|c| c.bookmarks()))";
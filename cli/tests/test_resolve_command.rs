@@ -2078,3 +2078,129 @@ fn test_resolve_with_contents_of_side() {
     [exit status: 2]
     "#);
 }
+
+#[test]
+fn test_resolve_builtin() {
+    let mut test_env = TestEnvironment::default();
+    let editor_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "1\n2\n3\n4\n5\n")]);
+    create_commit_with_files(&work_dir, "a", &["base"], &[("file", "a1\n2\n3\n4\na5\n")]);
+    create_commit_with_files(&work_dir, "b", &["base"], &[("file", "b1\n2\n3\n4\nb5\n")]);
+    create_commit_with_files(&work_dir, "conflict", &["a", "b"], &[]);
+    let resolve_with_keys = |keys: &str| {
+        work_dir.run_jj([
+            "resolve",
+            "--tool=:builtin",
+            &format!("--config=debug.scripted-keys={keys}"),
+        ])
+    };
+
+    // Pick a side for the first conflict only, leaving the other one conflicted
+    let output = resolve_with_keys(r#"["2", "enter"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: vruxwmqv 34a26b3c conflict | (conflict) conflict
+    Parent commit (@-)      : zsuskuln 9c928566 a | a
+    Parent commit (@-)      : royxmykx 62b713b8 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    New conflicts appeared in 1 commits:
+      vruxwmqv 34a26b3c conflict | (conflict) conflict
+    Hint: To resolve the conflicts, start by updating to it:
+      jj new vruxwmqv
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @r"
+    b1
+    2
+    3
+    4
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -5
+    +a5
+    +++++++ Contents of side #2
+    b5
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+
+    // Pick a side for the first conflict and the base for the second one
+    work_dir.run_jj(["undo"]).success();
+    let output = resolve_with_keys(r#"["1", "b", "enter"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: vruxwmqv 6211a1c8 conflict | conflict
+    Parent commit (@-)      : zsuskuln 9c928566 a | a
+    Parent commit (@-)      : royxmykx 62b713b8 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @r"
+    a1
+    2
+    3
+    4
+    5
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @r"
+    ------- stderr -------
+    Error: No conflicts found at this revision
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Edit the selected conflict in the text editor
+    work_dir.run_jj(["undo"]).success();
+    std::fs::write(&editor_script, "write\nedited\n").unwrap();
+    let output = resolve_with_keys(r#"["e", "j", "a", "enter"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: vruxwmqv 4291d2f0 conflict | conflict
+    Parent commit (@-)      : zsuskuln 9c928566 a | a
+    Parent commit (@-)      : royxmykx 62b713b8 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @r"
+    edited
+    2
+    3
+    4
+    a5
+    b5
+    ");
+
+    // Cancelling leaves the conflict as is
+    work_dir.run_jj(["undo"]).success();
+    let output = resolve_with_keys(r#"["1", "q"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to resolve conflicts
+    Caused by: Merge was cancelled
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @r"
+    file    2-sided conflict
+    [EOF]
+    ");
+
+    // The editor fails rather than waiting for input that never comes
+    let output = resolve_with_keys(r#"["space"]"#);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to resolve conflicts
+    Caused by:
+    1: Failed to access the terminal
+    2: Ran out of scripted keys
+    [EOF]
+    [exit status: 1]
+    ");
+}
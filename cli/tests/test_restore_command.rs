@@ -574,3 +574,34 @@ fn test_restore_interactive_with_paths() {
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     work_dir.run_jj(["log", "-T", "bookmarks"])
 }
+
+#[test]
+fn test_restore_file_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("text1", "a\n");
+    work_dir.write_file("text2", "a\n");
+    work_dir.write_file("binary", b"a\0");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("text1", "b\n");
+    work_dir.write_file("text2", "b\n");
+    work_dir.write_file("binary", b"b\0");
+
+    // Only the binary file is restored
+    let output = work_dir.run_jj(["restore", "binary()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: rlvkpnrz 8f53d9da (no description set)
+    Parent commit (@-)      : qpvuntsm a01afb4f (no description set)
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "-s"]);
+    insta::assert_snapshot!(output, @r"
+    M text1
+    M text2
+    [EOF]
+    ");
+}
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools as _;
+
+use crate::common::to_toml_value;
 use crate::common::TestEnvironment;
 
 #[test]
@@ -104,13 +107,13 @@ fn test_bad_function_call() {
     let output = work_dir.run_jj(["log", "-r", "parents()"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Error: Failed to parse revset: Function `parents`: Expected 1 arguments
+    Error: Failed to parse revset: Function `parents`: Expected 1 to 2 arguments
     Caused by:  --> 1:9
       |
     1 | parents()
       |         ^
       |
-      = Function `parents`: Expected 1 arguments
+      = Function `parents`: Expected 1 to 2 arguments
     [EOF]
     [exit status: 1]
     ");
@@ -118,13 +121,13 @@ fn test_bad_function_call() {
     let output = work_dir.run_jj(["log", "-r", "parents(foo, bar)"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Error: Failed to parse revset: Function `parents`: Expected 1 arguments
-    Caused by:  --> 1:9
+    Error: Failed to parse revset: Expected expression of type boolean
+    Caused by:  --> 1:14
       |
     1 | parents(foo, bar)
-      |         ^------^
+      |              ^-^
       |
-      = Function `parents`: Expected 1 arguments
+      = Expected expression of type boolean
     [EOF]
     [exit status: 1]
     ");
@@ -161,13 +164,13 @@ fn test_bad_function_call() {
     let output = work_dir.run_jj(["log", "-r", "ancestors()"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Error: Failed to parse revset: Function `ancestors`: Expected 1 to 2 arguments
+    Error: Failed to parse revset: Function `ancestors`: Expected 1 to 3 arguments
     Caused by:  --> 1:11
       |
     1 | ancestors()
       |           ^
       |
-      = Function `ancestors`: Expected 1 to 2 arguments
+      = Function `ancestors`: Expected 1 to 3 arguments
     [EOF]
     [exit status: 1]
     ");
@@ -252,7 +255,7 @@ fn test_bad_function_call() {
       |
       = Invalid string pattern
     2: Invalid string pattern kind `bad:`
-    Hint: Try prefixing with one of `exact:`, `glob:`, `regex:`, `substring:`, or one of these with `-i` suffix added (e.g. `glob-i:`) for case-insensitive matching
+    Hint: Try prefixing with one of `exact:`, `glob:`, `regex:`, `substring:`, `exact-line:`, `line-glob:`, or one of the first four with `-i` suffix added (e.g. `glob-i:`) for case-insensitive matching
     [EOF]
     [exit status: 1]
     ");
@@ -276,6 +279,25 @@ fn test_bad_function_call() {
     [exit status: 1]
     ");
 
+    let output = work_dir.run_jj(["log", "-r", "description(regex-i:'[a')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse revset: Invalid string pattern
+    Caused by:
+    1:  --> 1:13
+      |
+    1 | description(regex-i:'[a')
+      |             ^----------^
+      |
+      = Invalid string pattern
+    2: regex parse error:
+        [a
+        ^
+    error: unclosed character class
+    [EOF]
+    [exit status: 1]
+    ");
+
     let output = work_dir.run_jj(["log", "-r", "root()::whatever()"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
@@ -727,6 +749,152 @@ fn test_all_modifier() {
     ");
 }
 
+#[test]
+#[cfg(unix)]
+fn test_external_revset_function() {
+    // Answers `true` for commits listed in the file $1, and records queries to
+    // the file $2.
+    const SCRIPT: &str = r#"
+        while read id; do
+            echo "$id" >> "$2"
+            if grep -qx "$id" "$1"; then echo true; else echo false; fi
+        done
+    "#;
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir.run_jj(["new", "-m", "b"]).success();
+    work_dir.run_jj(["new", "-m", "c"]).success();
+
+    let approved_path = test_env.env_root().join("approved");
+    let queries_path = test_env.env_root().join("queries");
+    let output = work_dir
+        .run_jj([
+            "log",
+            "--no-graph",
+            "-r",
+            "description(a) | description(c)",
+            "-T",
+            r#"commit_id ++ "\n""#,
+        ])
+        .success();
+    std::fs::write(&approved_path, output.stdout.raw()).unwrap();
+    let command = to_toml_value(toml_edit::Array::from_iter([
+        "sh",
+        "-c",
+        SCRIPT,
+        "sh",
+        approved_path.to_str().unwrap(),
+        queries_path.to_str().unwrap(),
+    ]));
+    test_env.add_config(format!(
+        "revset-functions.reviewed = {{ command = {command}, description = 'Reviewed' }}"
+    ));
+
+    let template = r#"description.first_line() ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "reviewed()", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    c
+    a
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "~reviewed()", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    b
+
+    [EOF]
+    ");
+
+    // Each commit is queried once per invocation
+    std::fs::remove_file(&queries_path).unwrap();
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "reviewed() | (reviewed() & description(b))",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    c
+    a
+    [EOF]
+    ");
+    let queries = std::fs::read_to_string(&queries_path).unwrap();
+    assert_eq!(queries.lines().count(), 4, "{queries}");
+    assert!(queries.lines().all_unique(), "{queries}");
+
+    // Arguments aren't accepted
+    let output = work_dir.run_jj(["log", "-r", "reviewed(x)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse revset: Function `reviewed`: Expected 0 arguments
+    Caused by:  --> 1:10
+      |
+    1 | reviewed(x)
+      |          ^
+      |
+      = Function `reviewed`: Expected 0 arguments
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Unexpected answer
+    test_env.add_config("revset-functions.broken.command = ['echo', 'yes']");
+    let output = work_dir.run_jj(["log", "-r", "broken()"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to evaluate revset function `broken()`
+    Caused by: Expected `true` or `false`, but got "yes"
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Command which can't be spawned
+    test_env.add_config("revset-functions.missing.command = ['this-command-does-not-exist']");
+    let output = work_dir.run_jj(["log", "-r", "missing()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to evaluate revset function `missing()`
+    Caused by: No such file or directory (os error 2)
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Builtin function can't be overridden, and invalid entries are ignored
+    test_env.add_config("revset-functions.author.command = ['true']");
+    test_env.add_config("revset-functions.invalid.description = 'no command'");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "reviewed()", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    c
+    a
+    [EOF]
+    ------- stderr -------
+    Warning: Failed to load `revset-functions.author`: Function `author()` is already defined
+    Warning: Failed to load `revset-functions.invalid`: Invalid type or value for revset-functions.invalid: missing field `command`
+
+    [EOF]
+    ");
+
+    // Command which doesn't exit when its stdin is closed gets killed
+    test_env.add_config(
+        "revset-functions.stuck.command = ['sh', '-c', 'read id; echo true; exec sleep 60']",
+    );
+    let start = std::time::Instant::now();
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "stuck() & @", "-T", template]);
+    assert!(start.elapsed().as_secs() < 30);
+    insta::assert_snapshot!(output, @r"
+    c
+    [EOF]
+    ------- stderr -------
+    Warning: Failed to load `revset-functions.author`: Function `author()` is already defined
+    Warning: Failed to load `revset-functions.invalid`: Invalid type or value for revset-functions.invalid: missing field `command`
+
+    [EOF]
+    ");
+}
+
 /// Verifies that the committer_date revset honors the local time zone.
 /// This test cannot run on Windows because The TZ env var does not control
 /// chrono::Local on that platform.
@@ -884,6 +884,255 @@ fn test_split_interactive_with_paths() {
     ");
 }
 
+#[test]
+fn test_split_into_parts() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    let diff_editor = test_env.set_up_fake_diff_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "bar\n");
+    work_dir.write_file("file3", "baz\n");
+    work_dir.run_jj(["commit", "-m", "Add files"]).success();
+    work_dir.write_file("file4", "qux\n");
+    work_dir.run_jj(["describe", "-m", "Add file4"]).success();
+
+    std::fs::write(
+        &edit_script,
+        [
+            "write\nAdd file1",
+            "next invocation\n",
+            "dump editor2",
+            "write\nAdd file2",
+            "next invocation\n",
+            "write\nAdd file3",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    std::fs::write(
+        &diff_editor,
+        [
+            "reset file2",
+            "reset file3",
+            "next invocation\n",
+            "files-after JJ-INSTRUCTIONS file2 file3",
+            "reset file3",
+            "dump JJ-INSTRUCTIONS instrs",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["split", "-r@-", "--parts=3"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 descendant commits
+    Part 1 of 3: qpvuntsm 14a2ff75 Add file1
+    Part 2 of 3: zsuskuln 85694f4d Add file2
+    Part 3 of 3: rzvqmyuk 41f830bd Add file3
+    Working copy  (@) now at: rlvkpnrz a0a5a83e Add file4
+    Parent commit (@-)      : rzvqmyuk 41f830bd Add file3
+    [EOF]
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("instrs")).unwrap(), @r"
+    You are splitting a commit into 3 commits: qpvuntsm 7287b1c6 Add files
+
+    The diff initially shows the changes that are not in the previous commits.
+
+    Adjust the right side until it shows the contents you want for commit 2 of 3.
+    The remainder will be in the later commits.
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor2")).unwrap(), @r#"
+    JJ: Enter a description for commit 2 of 3.
+    Add files
+
+    JJ: This commit contains the following changes:
+    JJ:     A file2
+    JJ:
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    insta::assert_snapshot!(work_dir.run_jj(["log", "--summary", "-T", "description"]), @r"
+    @  Add file4
+    │  A file4
+    ○  Add file3
+    │  A file3
+    ○  Add file2
+    │  A file2
+    ○  Add file1
+    │  A file1
+    ◆
+    [EOF]
+    ");
+
+    // Split into parallel parts
+    work_dir.run_jj(["undo"]).success();
+    std::fs::write(&edit_script, "").unwrap();
+    std::fs::write(
+        &diff_editor,
+        [
+            "reset file2",
+            "reset file3",
+            "next invocation\n",
+            "reset file3",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["split", "-r@-", "--parts=3", "--parallel"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 descendant commits
+    Part 1 of 3: qpvuntsm def2885e Add files
+    Part 2 of 3: yqosqzyt 5b516639 Add files
+    Part 3 of 3: spxsnpux 86c117a5 Add files
+    Working copy  (@) now at: rlvkpnrz 9108a16e Add file4
+    Parent commit (@-)      : qpvuntsm def2885e Add files
+    Parent commit (@-)      : yqosqzyt 5b516639 Add files
+    Parent commit (@-)      : spxsnpux 86c117a5 Add files
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["log", "--summary", "-T", "description"]), @r"
+    @      Add file4
+    ├─┬─╮  A file4
+    │ │ ○  Add files
+    │ │ │  A file3
+    │ ○ │  Add files
+    │ ├─╯  A file2
+    ○ │  Add files
+    ├─╯  A file1
+    ◆
+    [EOF]
+    ");
+
+    // Selecting all changes leaves the remaining parts empty
+    work_dir.run_jj(["undo"]).success();
+    std::fs::write(&diff_editor, "").unwrap();
+    let output = work_dir.run_jj(["split", "-r@-", "--parts=3"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: No changes have been selected for commit 2 of 3, so it will be empty
+    Warning: No changes have been selected for commit 3 of 3, so it will be empty
+    Rebased 1 descendant commits
+    Part 1 of 3: qpvuntsm cc241b90 Add files
+    Part 2 of 3: znkkpsqq f7361fe6 (empty) Add files
+    Part 3 of 3: uuzqqzqu dec307bd (empty) Add files
+    Working copy  (@) now at: rlvkpnrz 40211e61 Add file4
+    Parent commit (@-)      : uuzqqzqu dec307bd (empty) Add files
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["split", "--parts=1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value '1' for '--parts <N>': 1 is not in 2..=65535
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_split_by_file() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "bar\n");
+    work_dir.write_file("dir/file3", "baz\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file4", "qux\n");
+
+    std::fs::write(&edit_script, "dump editor").unwrap();
+    let output = work_dir.run_jj(["split", "-r@-", "--by-file"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 descendant commits
+    Part 1 of 3: qpvuntsm 344dd9c1 (no description set)
+    Part 2 of 3: kkmpptxz 74221767 (no description set)
+    Part 3 of 3: pmmvwywv 96658bd5 (no description set)
+    Working copy  (@) now at: rlvkpnrz ca030dea (no description set)
+    Parent commit (@-)      : pmmvwywv 96658bd5 (no description set)
+    [EOF]
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+    JJ: Enter a description for commit 1 of 3.
+
+
+    JJ: This commit contains the following changes:
+    JJ:     A dir/file3
+    JJ:
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    insta::assert_snapshot!(work_dir.run_jj(["log", "--summary", "-T", "description"]), @r"
+    @
+    │  A file4
+    ○
+    │  A file2
+    ○
+    │  A file1
+    ○
+    │  A dir/file3
+    ◆
+    [EOF]
+    ");
+
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj(["split", "-r@-", "--by-file", "--parallel", "-m", "first"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 descendant commits
+    Part 1 of 3: qpvuntsm 6d5e2395 first
+    Part 2 of 3: royxmykx a99d7595 (no description set)
+    Part 3 of 3: nppvrztz a7fc2af5 (no description set)
+    Working copy  (@) now at: rlvkpnrz 828f64df (no description set)
+    Parent commit (@-)      : qpvuntsm 6d5e2395 first
+    Parent commit (@-)      : royxmykx a99d7595 (no description set)
+    Parent commit (@-)      : nppvrztz a7fc2af5 (no description set)
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["log", "--summary", "-T", "description"]), @r"
+    @
+    ├─┬─╮  A file4
+    │ │ ○
+    │ │ │  A file2
+    │ ○ │
+    │ ├─╯  A file1
+    ○ │  first
+    ├─╯  A dir/file3
+    ◆
+    [EOF]
+    ");
+
+    // A commit changing a single file can't be split by file
+    let output = work_dir.run_jj(["split", "--by-file"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Refusing to split commit 828f64df8aec696d5662397b2a483448b6620cc6 by file because it changes only one file.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["split", "--by-file", "file4"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--by-file' cannot be used with '[FILESETS]...'
+
+    Usage: jj split --by-file [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 // When a commit is split, the second commit produced by the split becomes the
 // working copy commit for all workspaces whose working copy commit was the
 // target of the split. This test does a split where the target commit is the
@@ -909,7 +1158,7 @@ fn test_split_with_multiple_workspaces_same_working_copy() {
     secondary_dir
         .run_jj(["edit", "-r", "description(first-commit)"])
         .success();
-    // Check the working-copy commit in each workspace in the log output. The "@"
+    // Check the working-copy commit in each workspace in the log output. The "@r"
     // node in the graph indicates the current workspace's working-copy commit.
     insta::assert_snapshot!(get_workspace_log_output(&main_dir), @r"
     @  qpvuntsmwlqt default@ second@ first-commit
@@ -966,7 +1215,7 @@ fn test_split_with_multiple_workspaces_different_working_copy() {
     main_dir
         .run_jj(["workspace", "add", "--name", "second", "../secondary"])
         .success();
-    // Check the working-copy commit in each workspace in the log output. The "@"
+    // Check the working-copy commit in each workspace in the log output. The "@r"
     // node in the graph indicates the current workspace's working-copy commit.
     insta::assert_snapshot!(get_workspace_log_output(&main_dir), @r"
     @  qpvuntsmwlqt default@ first-commit
@@ -1266,3 +1515,36 @@ fn test_split_with_bookmarks(bookmark_behavior: BookmarkBehavior) {
         }
     }
 }
+
+#[test]
+fn test_split_file_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("text1", "a\n");
+    work_dir.write_file("text2", "a\n");
+    work_dir.write_file("binary", b"a\0");
+
+    // The binary file goes to the first commit, text files to the second
+    let output = work_dir.run_jj(["split", "-m", "binary", "binary()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    First part: qpvuntsm 8a91d848 binary
+    Second part: rlvkpnrz cdc10788 (no description set)
+    Working copy  (@) now at: rlvkpnrz cdc10788 (no description set)
+    Parent commit (@-)      : qpvuntsm 8a91d848 binary
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "-s", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    A binary
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "-s"]);
+    insta::assert_snapshot!(output, @r"
+    A text1
+    A text2
+    [EOF]
+    ");
+}
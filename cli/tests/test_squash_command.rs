@@ -1562,3 +1562,39 @@ fn get_log_output_with_description(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"separate(" ", commit_id.short(), description)"#;
     work_dir.run_jj(["log", "-T", template])
 }
+
+#[test]
+fn test_squash_file_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("text", "a\n");
+    work_dir.write_file("binary", b"a\0");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("text", "b\n");
+    work_dir.write_file("binary", b"b\0");
+    work_dir.write_file("large", "l".repeat(2000));
+
+    // Only the matching files are moved to the parent
+    let output = work_dir.run_jj(["squash", "binary() | size('>1k')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 descendant commits
+    Working copy  (@) now at: rlvkpnrz dbe98d64 (no description set)
+    Parent commit (@-)      : qpvuntsm c25e8d91 (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "-s"]);
+    insta::assert_snapshot!(output, @r"
+    M text
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "-s", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    A binary
+    A large
+    A text
+    [EOF]
+    ");
+}
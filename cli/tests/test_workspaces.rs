@@ -45,7 +45,7 @@ fn test_workspaces_add_second_workspace() {
     [EOF]
     "#);
 
-    // Can see the working-copy commit in each workspace in the log output. The "@"
+    // Can see the working-copy commit in each workspace in the log output. The "@r"
     // node in the graph indicates the current workspace's working-copy commit.
     insta::assert_snapshot!(get_log_output(&main_dir), @r"
     @  504e3d8c1bcd default@
@@ -291,7 +291,7 @@ fn test_workspaces_add_workspace_at_revision() {
     [EOF]
     "#);
 
-    // Can see the working-copy commit in each workspace in the log output. The "@"
+    // Can see the working-copy commit in each workspace in the log output. The "@r"
     // node in the graph indicates the current workspace's working-copy commit.
     insta::assert_snapshot!(get_log_output(&main_dir), @r"
     @  5ac9178da8b2 default@
@@ -1358,6 +1358,167 @@ fn test_workspaces_rename_workspace() {
     ");
 }
 
+/// Test that workspaces with problems are reported by `jj workspace list`
+#[test]
+fn test_workspaces_list_health() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir.write_file("file", "contents\n");
+    main_dir.run_jj(["new"]).success();
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+    main_dir
+        .run_jj(["workspace", "add", "--name", "third", "../third"])
+        .success();
+
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: rlvkpnrz 393250c5 (empty) (no description set)
+    second: pmmvwywv 54703666 (empty) (no description set)
+    third: rzvqmyuk 49d1a560 (empty) (no description set)
+    [EOF]
+    ");
+
+    // Rewriting the working-copy commit of another workspace makes it stale,
+    // and deleting a workspace directory makes it missing.
+    main_dir.write_file("file", "changed in main\n");
+    main_dir.run_jj(["squash"]).success();
+    test_env.work_dir("").remove_dir_all("third");
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: royxmykx 3d1efe1d (empty) (no description set)
+    second: pmmvwywv ae2022d9 (empty) (no description set) (stale)
+    third: rzvqmyuk 07784025 (empty) (no description set) (missing)
+    [EOF]
+    ");
+
+    // A workspace pointing to another repo has a broken link.
+    test_env.run_jj_in(".", ["git", "init", "other"]).success();
+    let secondary_dir = test_env.work_dir("secondary");
+    secondary_dir
+        .run_jj(["workspace", "relink", "../other"])
+        .success();
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: royxmykx 3d1efe1d (empty) (no description set)
+    second: pmmvwywv ae2022d9 (empty) (no description set) (broken link)
+    third: rzvqmyuk 07784025 (empty) (no description set) (missing)
+    [EOF]
+    ");
+}
+
+/// Test that a workspace updated after the listed operation isn't stale
+#[test]
+fn test_workspaces_list_health_at_op() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+    let secondary_dir = test_env.work_dir("secondary");
+    secondary_dir.write_file("file", "contents\n");
+    secondary_dir.run_jj(["debug", "snapshot"]).success();
+
+    // The working-copy commit at the operation doesn't have the change yet
+    let output = main_dir.run_jj(["workspace", "list", "--at-op=@-"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    second: uuqppmxq 94f41578 (empty) (no description set)
+    [EOF]
+    ");
+
+    // Rewriting the working-copy commit from another workspace makes it stale
+    main_dir.run_jj(["abandon", "second@"]).success();
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    second: mzvwutvl 1c212ff7 (empty) (no description set) (stale)
+    [EOF]
+    ");
+}
+
+/// Test repairing a workspace after the repo was moved
+#[test]
+fn test_workspaces_relink() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+    let secondary_dir = test_env.work_dir("secondary");
+
+    // Moving the repo and its workspaces together doesn't break anything
+    // since the repo is referenced by relative path.
+    std::fs::create_dir(test_env.env_root().join("dir")).unwrap();
+    std::fs::rename(
+        test_env.env_root().join("main"),
+        test_env.env_root().join("dir").join("main"),
+    )
+    .unwrap();
+    std::fs::rename(
+        test_env.env_root().join("secondary"),
+        test_env.env_root().join("dir").join("secondary"),
+    )
+    .unwrap();
+    let secondary_dir_moved = test_env.work_dir("dir/secondary");
+    let output = secondary_dir_moved.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    second: uuqppmxq 94f41578 (empty) (no description set)
+    [EOF]
+    ");
+
+    // Moving only the repo breaks the other workspace
+    std::fs::rename(
+        test_env.env_root().join("dir").join("secondary"),
+        test_env.env_root().join("secondary"),
+    )
+    .unwrap();
+    let output = secondary_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    ------- stderr -------
+    Error: The repository directory at $TEST_ENV/main/.jj/repo is missing. Was it moved?
+    Hint: Run `jj workspace relink <path-to-repo>` to point this workspace to the new location of the repo.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = secondary_dir.run_jj(["workspace", "relink", "../dir/main"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    ------- stderr -------
+    Workspace now uses the repo in "../dir/main/.jj/repo"
+    [EOF]
+    "#);
+    let output = secondary_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    second: uuqppmxq 94f41578 (empty) (no description set)
+    [EOF]
+    ");
+
+    // The repo directory itself can also be specified
+    let output = secondary_dir.run_jj(["workspace", "relink", "../dir/main/.jj/repo"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    ------- stderr -------
+    Workspace now uses the repo in "../dir/main/.jj/repo"
+    [EOF]
+    "#);
+
+    // The workspace containing the repo cannot be relinked
+    let main_dir_moved = test_env.work_dir("dir/main");
+    let output = main_dir_moved.run_jj(["workspace", "relink", "../../secondary"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    ------- stderr -------
+    Error: The workspace at $TEST_ENV/dir/main contains the repo and cannot be relinked
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"
@@ -413,9 +413,10 @@ pub trait Backend: Send + Sync + Debug {
     fn empty_tree_id(&self) -> &TreeId;
 
     /// An estimate of how many concurrent requests this backend handles well. A
-    /// local backend like the Git backend (at until it supports partial clones)
-    /// may want to set this to 1. A cloud-backed backend may want to set it to
-    /// 100 or so.
+    /// local backend like the Git backend may want to set this to 1. Note that
+    /// the Git backend may still need to fetch blobs from the network if the
+    /// repository is a partial clone, but it does so one object at a time. A
+    /// cloud-backed backend may want to set it to 100 or so.
     ///
     /// It is not guaranteed that at most this number of concurrent requests are
     /// sent.
@@ -91,6 +91,10 @@ pub(super) trait IndexSegment: Send + Sync {
     fn num_parents(&self, local_pos: LocalPosition) -> u32;
 
     fn parent_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec;
+
+    /// Returns the positions of the predecessors of the entry. Predecessors
+    /// that weren't indexed when the entry was added are omitted.
+    fn predecessor_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec;
 }
 
 pub(super) type DynIndexSegment = dyn IndexSegment;
@@ -340,6 +340,85 @@ mod tests {
         assert_eq!(entry_6.generation_number(), 2);
     }
 
+    #[test_case(false, false; "full in memory")]
+    #[test_case(false, true; "full on disk")]
+    #[test_case(true, false; "incremental in memory")]
+    #[test_case(true, true; "incremental on disk")]
+    fn index_predecessors(incremental: bool, on_disk: bool) {
+        let temp_dir = new_temp_dir();
+        let mut new_change_id = change_id_generator();
+        let mut mutable_segment = MutableIndexSegment::full(3, 16);
+        // 1 -> 2 -+-> 4
+        // 3 ------+
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_5 = CommitId::from_hex("555555");
+        let id_missing = CommitId::from_hex("999999");
+        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()]);
+        mutable_segment.add_commit_data_with_predecessors(
+            id_2.clone(),
+            new_change_id(),
+            &[id_0.clone()],
+            &[id_1.clone()],
+        );
+        mutable_segment.add_commit_data(id_3.clone(), new_change_id(), &[id_0.clone()]);
+
+        // If testing incremental indexing, write the first four commits to one file
+        // now and build the remainder as another segment on top.
+        if incremental {
+            let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
+            mutable_segment = MutableIndexSegment::incremental(initial_file);
+        }
+
+        mutable_segment.add_commit_data_with_predecessors(
+            id_4.clone(),
+            new_change_id(),
+            &[id_0.clone()],
+            &[id_2.clone(), id_3.clone()],
+        );
+        // Predecessors that aren't indexed are omitted
+        mutable_segment.add_commit_data_with_predecessors(
+            id_5.clone(),
+            new_change_id(),
+            &[id_0.clone()],
+            &[id_missing],
+        );
+        let index_segment: Box<DynIndexSegment> = if on_disk {
+            let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
+            Box::new(Arc::try_unwrap(saved_index).unwrap())
+        } else {
+            Box::new(mutable_segment)
+        };
+        let index = CompositeIndex::new(index_segment.as_ref());
+
+        let predecessors = |id: &CommitId| index.entry_by_id(id).unwrap().predecessor_positions();
+        assert!(predecessors(&id_0).is_empty());
+        assert!(predecessors(&id_1).is_empty());
+        assert_eq!(predecessors(&id_2), smallvec_inline![IndexPosition(1)]);
+        assert!(predecessors(&id_3).is_empty());
+        assert_eq!(
+            predecessors(&id_4),
+            smallvec_inline![IndexPosition(2), IndexPosition(3)]
+        );
+        assert!(predecessors(&id_5).is_empty());
+
+        // Predecessors are preserved when squashing segments
+        let mut squashed_segment = MutableIndexSegment::full(3, 16);
+        if let Some(parent_file) = index_segment.parent_file() {
+            squashed_segment.add_commits_from(parent_file.as_ref());
+        }
+        squashed_segment.add_commits_from(index_segment.as_ref());
+        let index = squashed_segment.as_composite();
+        assert_eq!(
+            index.entry_by_id(&id_4).unwrap().predecessor_positions(),
+            smallvec_inline![IndexPosition(2), IndexPosition(3)]
+        );
+    }
+
     #[test]
     fn resolve_commit_id_prefix() {
         let temp_dir = new_temp_dir();
@@ -68,6 +68,7 @@ struct MutableGraphEntry {
     change_id: ChangeId,
     generation_number: u32,
     parent_positions: SmallIndexPositionsVec,
+    predecessor_positions: SmallIndexPositionsVec,
 }
 
 pub(super) struct MutableIndexSegment {
@@ -113,18 +114,30 @@ impl MutableIndexSegment {
     }
 
     pub(super) fn add_commit(&mut self, commit: &Commit) {
-        self.add_commit_data(
+        self.add_commit_data_with_predecessors(
             commit.id().clone(),
             commit.change_id().clone(),
             commit.parent_ids(),
+            commit.predecessor_ids(),
         );
     }
 
+    #[cfg(test)]
     pub(super) fn add_commit_data(
         &mut self,
         commit_id: CommitId,
         change_id: ChangeId,
         parent_ids: &[CommitId],
+    ) {
+        self.add_commit_data_with_predecessors(commit_id, change_id, parent_ids, &[]);
+    }
+
+    pub(super) fn add_commit_data_with_predecessors(
+        &mut self,
+        commit_id: CommitId,
+        change_id: ChangeId,
+        parent_ids: &[CommitId],
+        predecessor_ids: &[CommitId],
     ) {
         if self.as_composite().has_id(&commit_id) {
             return;
@@ -134,6 +147,7 @@ impl MutableIndexSegment {
             change_id,
             generation_number: 0,
             parent_positions: SmallVec::new(),
+            predecessor_positions: SmallVec::new(),
         };
         for parent_id in parent_ids {
             let parent_entry = self
@@ -146,6 +160,12 @@ impl MutableIndexSegment {
             );
             entry.parent_positions.push(parent_entry.position());
         }
+        // Predecessors are usually indexed before their successors, but they
+        // may have been lost if the operation log was truncated.
+        entry.predecessor_positions = predecessor_ids
+            .iter()
+            .filter_map(|id| self.as_composite().commit_id_to_pos(id))
+            .collect();
         let local_pos = LocalPosition(u32::try_from(self.graph.len()).unwrap());
         self.commit_lookup
             .insert(entry.commit_id.clone(), local_pos);
@@ -162,7 +182,17 @@ impl MutableIndexSegment {
         for pos in other_segment.num_parent_commits()..other.num_commits() {
             let entry = other.entry_by_pos(IndexPosition(pos));
             let parent_ids = entry.parents().map(|entry| entry.commit_id()).collect_vec();
-            self.add_commit_data(entry.commit_id(), entry.change_id(), &parent_ids);
+            let predecessor_ids = entry
+                .predecessor_positions()
+                .into_iter()
+                .map(|pos| other.entry_by_pos(pos).commit_id())
+                .collect_vec();
+            self.add_commit_data_with_predecessors(
+                entry.commit_id(),
+                entry.change_id(),
+                &parent_ids,
+                &predecessor_ids,
+            );
         }
     }
 
@@ -228,6 +258,12 @@ impl MutableIndexSegment {
         buf.extend(0_u32.to_le_bytes());
         let change_overflow_offset = buf.len();
         buf.extend(0_u32.to_le_bytes());
+        let num_predecessors = self
+            .graph
+            .iter()
+            .map(|entry| entry.predecessor_positions.len())
+            .sum::<usize>();
+        buf.extend(u32::try_from(num_predecessors).unwrap().to_le_bytes());
 
         // Positions of change ids in the sorted table
         let change_id_pos_map: HashMap<&ChangeId, u32> = self
@@ -312,6 +348,17 @@ impl MutableIndexSegment {
         for LocalPosition(pos) in change_overflow {
             buf.extend(pos.to_le_bytes());
         }
+
+        let mut predecessor_end = 0_u32;
+        for entry in &self.graph {
+            predecessor_end += u32::try_from(entry.predecessor_positions.len()).unwrap();
+            buf.extend(predecessor_end.to_le_bytes());
+        }
+        for entry in &self.graph {
+            for IndexPosition(pos) in &entry.predecessor_positions {
+                buf.extend(pos.to_le_bytes());
+            }
+        }
     }
 
     /// If the MutableIndex has more than half the commits of its parent
@@ -453,6 +500,12 @@ impl IndexSegment for MutableIndexSegment {
     fn parent_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec {
         self.graph[local_pos.0 as usize].parent_positions.clone()
     }
+
+    fn predecessor_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec {
+        self.graph[local_pos.0 as usize]
+            .predecessor_positions
+            .clone()
+    }
 }
 
 /// In-memory mutable records for the on-disk commit index backend.
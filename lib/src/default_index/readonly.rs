@@ -104,7 +104,7 @@ impl ReadonlyIndexLoadError {
 }
 
 /// Current format version of the index segment file.
-pub(crate) const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 6;
+pub(crate) const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 7;
 
 /// If set, the value is stored in the overflow table.
 pub(crate) const OVERFLOW_FLAG: u32 = 0x8000_0000;
@@ -186,6 +186,7 @@ impl CommitGraphEntry<'_> {
 /// u32: number of local change ids
 /// u32: number of overflow parent entries
 /// u32: number of overflow change id positions
+/// u32: number of predecessor positions
 /// for each entry, in some topological order with parents first:
 ///   u32: generation number
 ///   if number of parents <= 2:
@@ -211,6 +212,10 @@ impl CommitGraphEntry<'_> {
 ///   u32: global index position
 /// for each overflow change id entry:
 ///   u32: local position in the graph entries table
+/// for each entry, in the same order as the graph entries table:
+///   u32: end position of its predecessors in the predecessors table
+/// for each predecessor:
+///   u32: global index position
 /// ```
 ///
 /// Note that u32 fields are 4-byte aligned so long as the parent file name
@@ -234,6 +239,8 @@ pub(super) struct ReadonlyIndexSegment {
     change_pos_table_base: usize,
     parent_overflow_base: usize,
     change_overflow_base: usize,
+    predecessor_end_base: usize,
+    predecessor_table_base: usize,
     data: Vec<u8>,
 }
 
@@ -329,6 +336,7 @@ impl ReadonlyIndexSegment {
         let num_local_change_ids = read_u32(file)?;
         let num_parent_overflow_entries = read_u32(file)?;
         let num_change_overflow_entries = read_u32(file)?;
+        let num_predecessor_entries = read_u32(file)?;
         let mut data = vec![];
         file.read_to_end(&mut data).map_err(from_io_err)?;
 
@@ -339,6 +347,8 @@ impl ReadonlyIndexSegment {
         let change_pos_table_size = (num_local_change_ids as usize) * 4;
         let parent_overflow_size = (num_parent_overflow_entries as usize) * 4;
         let change_overflow_size = (num_change_overflow_entries as usize) * 4;
+        let predecessor_end_size = (num_local_commits as usize) * 4;
+        let predecessor_table_size = (num_predecessor_entries as usize) * 4;
 
         let graph_base = 0;
         let commit_lookup_base = graph_base + graph_size;
@@ -346,7 +356,9 @@ impl ReadonlyIndexSegment {
         let change_pos_table_base = change_id_table_base + change_id_table_size;
         let parent_overflow_base = change_pos_table_base + change_pos_table_size;
         let change_overflow_base = parent_overflow_base + parent_overflow_size;
-        let expected_size = change_overflow_base + change_overflow_size;
+        let predecessor_end_base = change_overflow_base + change_overflow_size;
+        let predecessor_table_base = predecessor_end_base + predecessor_end_size;
+        let expected_size = predecessor_table_base + predecessor_table_size;
 
         if data.len() != expected_size {
             return Err(ReadonlyIndexLoadError::invalid_data(
@@ -369,6 +381,8 @@ impl ReadonlyIndexSegment {
             change_pos_table_base,
             parent_overflow_base,
             change_overflow_base,
+            predecessor_end_base,
+            predecessor_table_base,
             data,
         }))
     }
@@ -436,13 +450,19 @@ impl ReadonlyIndexSegment {
         &self,
         overflow_pos: u32,
     ) -> impl Iterator<Item = LocalPosition> + use<'_> {
-        let table = &self.data[self.change_overflow_base..];
+        let table = &self.data[self.change_overflow_base..self.predecessor_end_base];
         let offset = (overflow_pos as usize) * 4;
         table[offset..]
             .chunks_exact(4)
             .map(|chunk| LocalPosition(u32::from_le_bytes(chunk.try_into().unwrap())))
     }
 
+    fn predecessor_end(&self, local_pos: LocalPosition) -> usize {
+        let table = &self.data[self.predecessor_end_base..self.predecessor_table_base];
+        let offset = (local_pos.0 as usize) * 4;
+        u32::from_le_bytes(table[offset..][..4].try_into().unwrap()) as usize
+    }
+
     /// Binary searches commit id by `prefix`. Returns the lookup position.
     fn commit_id_byte_prefix_to_lookup_pos(&self, prefix: &[u8]) -> PositionLookupResult {
         binary_search_pos_by(self.num_local_commits, |pos| {
@@ -587,6 +607,20 @@ impl IndexSegment for ReadonlyIndexSegment {
             self.overflow_parents(overflow_pos, num_parents)
         }
     }
+
+    fn predecessor_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec {
+        let start = if local_pos.0 == 0 {
+            0
+        } else {
+            self.predecessor_end(LocalPosition(local_pos.0 - 1))
+        };
+        let end = self.predecessor_end(local_pos);
+        let table = &self.data[self.predecessor_table_base..];
+        table[start * 4..end * 4]
+            .chunks_exact(4)
+            .map(|chunk| IndexPosition(u32::from_le_bytes(chunk.try_into().unwrap())))
+            .collect()
+    }
 }
 
 /// Commit index backend which stores data on local disk.
@@ -257,6 +257,18 @@ impl RevWalkIndex for CompositeIndex {
     }
 }
 
+/// Adapter to walk the first parent of each entry only.
+pub(super) struct RevWalkFirstParentIndex<'a>(&'a CompositeIndex);
+
+impl RevWalkIndex for RevWalkFirstParentIndex<'_> {
+    type Position = IndexPosition;
+    type AdjacentPositions = Option<IndexPosition>;
+
+    fn adjacent_positions(&self, pos: Self::Position) -> Self::AdjacentPositions {
+        self.0.entry_by_pos(pos).parent_positions().first().copied()
+    }
+}
+
 #[derive(Clone)]
 pub(super) struct RevWalkDescendantsIndex {
     children_map: HashMap<IndexPosition, DescendantIndexPositionsVec>,
@@ -453,6 +465,33 @@ impl<'a> RevWalkBuilder<'a> {
         }
     }
 
+    /// Walks ancestors following the first parent of each entry only.
+    ///
+    /// Unwanted roots are also walked by following the first parents.
+    pub fn first_parent_ancestors(self) -> RevWalkFirstParentAncestors<'a> {
+        let RevWalkBorrowedIndexIter { index, walk } = self.ancestors();
+        RevWalkBorrowedIndexIter {
+            index,
+            walk: RevWalkFirstParentImpl(walk),
+        }
+    }
+
+    /// Walks ancestors following the first parent of each entry only, within
+    /// the `generation_range`.
+    ///
+    /// A generation number counts from the heads.
+    pub fn first_parent_ancestors_filtered_by_generation(
+        self,
+        generation_range: Range<u32>,
+    ) -> RevWalkFirstParentAncestorsGenerationRange<'a> {
+        let RevWalkBorrowedIndexIter { index, walk } =
+            self.ancestors_filtered_by_generation(generation_range);
+        RevWalkBorrowedIndexIter {
+            index,
+            walk: RevWalkFirstParentImpl(walk),
+        }
+    }
+
     /// Walks ancestors until all of the reachable roots in `root_positions` get
     /// visited.
     ///
@@ -562,6 +601,34 @@ impl<I: RevWalkIndex + ?Sized> RevWalk<I> for RevWalkImpl<I::Position> {
     }
 }
 
+pub(super) type RevWalkFirstParentAncestors<'a> = RevWalkBorrowedIndexIter<
+    'a,
+    CompositeIndex,
+    RevWalkFirstParentImpl<RevWalkImpl<IndexPosition>>,
+>;
+
+pub(super) type RevWalkFirstParentAncestorsGenerationRange<'a> = RevWalkBorrowedIndexIter<
+    'a,
+    CompositeIndex,
+    RevWalkFirstParentImpl<RevWalkGenerationRangeImpl<IndexPosition>>,
+>;
+
+/// Runs the inner walk over the first parents of the `CompositeIndex`.
+#[derive(Clone)]
+#[must_use]
+pub(super) struct RevWalkFirstParentImpl<W>(W);
+
+impl<W> RevWalk<CompositeIndex> for RevWalkFirstParentImpl<W>
+where
+    W: for<'i> RevWalk<RevWalkFirstParentIndex<'i>, Item = IndexPosition>,
+{
+    type Item = IndexPosition;
+
+    fn next(&mut self, index: &CompositeIndex) -> Option<Self::Item> {
+        self.0.next(&RevWalkFirstParentIndex(index))
+    }
+}
+
 pub(super) type RevWalkAncestorsGenerationRange<'a> =
     RevWalkBorrowedIndexIter<'a, CompositeIndex, RevWalkGenerationRangeImpl<IndexPosition>>;
 pub(super) type RevWalkDescendantsGenerationRange = RevWalkOwnedIndexIter<
@@ -952,6 +1019,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_walk_first_parent_ancestors() {
+        let mut new_change_id = change_id_generator();
+        let mut index = DefaultMutableIndex::full(3, 16);
+        // 6
+        // |\
+        // 5 4
+        // |\|
+        // 3 2
+        // |/
+        // 1
+        // 0
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_5 = CommitId::from_hex("555555");
+        let id_6 = CommitId::from_hex("666666");
+        index.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        index.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()]);
+        index.add_commit_data(id_2.clone(), new_change_id(), &[id_1.clone()]);
+        index.add_commit_data(id_3.clone(), new_change_id(), &[id_1.clone()]);
+        index.add_commit_data(id_4.clone(), new_change_id(), &[id_2.clone()]);
+        index.add_commit_data(id_5.clone(), new_change_id(), &[id_3.clone(), id_2.clone()]);
+        index.add_commit_data(id_6.clone(), new_change_id(), &[id_5.clone(), id_4.clone()]);
+
+        let index = index.as_composite();
+        let walk_commit_ids = |wanted: &[CommitId], unwanted: &[CommitId]| {
+            RevWalkBuilder::new(index)
+                .wanted_heads(to_positions_vec(index, wanted))
+                .unwanted_roots(to_positions_vec(index, unwanted))
+                .first_parent_ancestors()
+                .map(|pos| index.entry_by_pos(pos).commit_id())
+                .collect_vec()
+        };
+        let walk_commit_ids_in_range = |wanted: &[CommitId], range: Range<u32>| {
+            RevWalkBuilder::new(index)
+                .wanted_heads(to_positions_vec(index, wanted))
+                .first_parent_ancestors_filtered_by_generation(range)
+                .map(|pos| index.entry_by_pos(pos).commit_id())
+                .collect_vec()
+        };
+
+        assert!(walk_commit_ids(&[], &[]).is_empty());
+        assert_eq!(
+            walk_commit_ids(&[id_6.clone()], &[]),
+            vec![
+                id_6.clone(),
+                id_5.clone(),
+                id_3.clone(),
+                id_1.clone(),
+                id_0.clone(),
+            ]
+        );
+        // Wanted heads are walked even if they aren't first parents
+        assert_eq!(
+            walk_commit_ids(&[id_6.clone(), id_4.clone()], &[]),
+            vec![
+                id_6.clone(),
+                id_5.clone(),
+                id_4.clone(),
+                id_3.clone(),
+                id_2.clone(),
+                id_1.clone(),
+                id_0.clone(),
+            ]
+        );
+        // Unwanted roots are walked by first parents as well
+        assert_eq!(
+            walk_commit_ids(&[id_6.clone()], &[id_4.clone()]),
+            vec![id_6.clone(), id_5.clone(), id_3.clone()]
+        );
+
+        assert_eq!(
+            walk_commit_ids_in_range(&[id_6.clone()], 1..2),
+            vec![id_5.clone()]
+        );
+        assert_eq!(
+            walk_commit_ids_in_range(&[id_6.clone(), id_4.clone()], 1..3),
+            vec![id_5.clone(), id_3.clone(), id_2.clone(), id_1.clone()]
+        );
+    }
+
     #[test]
     fn test_walk_ancestors_until_roots() {
         let mut new_change_id = change_id_generator();
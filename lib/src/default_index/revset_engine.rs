@@ -14,6 +14,7 @@
 
 #![allow(missing_docs)]
 
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::cmp::Reverse;
@@ -26,8 +27,12 @@ use std::ops::Range;
 use std::rc::Rc;
 use std::str;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use bstr::BString;
+use futures::future;
+use futures::stream::BoxStream;
 use futures::StreamExt as _;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
@@ -42,19 +47,27 @@ use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::commit::Commit;
+use crate::conflicts::materialize_merge_result_to_bytes;
 use crate::conflicts::materialize_tree_value;
+use crate::conflicts::ConflictMarkerStyle;
 use crate::conflicts::MaterializedTreeValue;
+use crate::copies::CopiesTreeDiffEntry;
+use crate::copies::CopyOperation;
+use crate::copies::CopyRecords;
 use crate::default_index::AsCompositeIndex;
 use crate::default_index::CompositeIndex;
 use crate::default_index::IndexPosition;
 use crate::diff::Diff;
 use crate::diff::DiffHunkKind;
 use crate::files;
+use crate::fileset::FilesetExpression;
 use crate::graph::GraphNode;
 use crate::matchers::Matcher;
 use crate::matchers::Visit;
 use crate::merge::Merge;
 use crate::merged_tree::resolve_file_values;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::TreeDiffStream;
 use crate::object_id::ObjectId as _;
 use crate::repo_path::RepoPath;
 use crate::revset::ResolvedExpression;
@@ -63,8 +76,10 @@ use crate::revset::Revset;
 use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetFilterPredicate;
+use crate::revset::RevsetSortKey;
 use crate::revset::GENERATION_RANGE_FULL;
 use crate::rewrite;
+use crate::signing::SigStatus;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::union_find;
@@ -98,6 +113,15 @@ trait InternalRevset: fmt::Debug + ToPredicateFn {
     where
         Self: 'a;
 
+    /// Iterates positions in the order requested by `sort()` or `limit()`.
+    /// Defaults to the index order.
+    fn ordered_positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        self.positions()
+    }
+
     fn into_predicate<'a>(self: Box<Self>) -> Box<dyn ToPredicateFn + 'a>
     where
         Self: 'a;
@@ -111,6 +135,13 @@ impl<T: InternalRevset + ?Sized> InternalRevset for Box<T> {
         <T as InternalRevset>::positions(self)
     }
 
+    fn ordered_positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        <T as InternalRevset>::ordered_positions(self)
+    }
+
     fn into_predicate<'a>(self: Box<Self>) -> Box<dyn ToPredicateFn + 'a>
     where
         Self: 'a,
@@ -167,6 +198,20 @@ impl<I: AsCompositeIndex + Clone> Revset for RevsetImpl<I> {
         Box::new(iter::from_fn(move || walk.next(index.as_composite())))
     }
 
+    fn iter_ordered<'a>(
+        &self,
+    ) -> Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>> + 'a>
+    where
+        Self: 'a,
+    {
+        let index = self.index.clone();
+        let mut walk = self
+            .inner
+            .ordered_positions()
+            .map(|index, pos| Ok(index.entry_by_pos(pos?).commit_id()));
+        Box::new(iter::from_fn(move || walk.next(index.as_composite())))
+    }
+
     fn commit_change_ids<'a>(
         &self,
     ) -> Box<dyn Iterator<Item = Result<(CommitId, ChangeId), RevsetEvaluationError>> + 'a>
@@ -329,6 +374,59 @@ impl ToPredicateFn for EagerRevset {
     }
 }
 
+/// Adapter for precomputed `IndexPosition`s in arbitrary order.
+#[derive(Debug)]
+struct OrderedRevset {
+    /// Positions in descending order.
+    positions: EagerRevset,
+    /// Positions in the requested order.
+    ordered_positions: Vec<IndexPosition>,
+}
+
+impl OrderedRevset {
+    fn new(ordered_positions: Vec<IndexPosition>) -> Self {
+        let mut positions = ordered_positions.clone();
+        positions.sort_unstable_by_key(|&pos| Reverse(pos));
+        OrderedRevset {
+            positions: EagerRevset { positions },
+            ordered_positions,
+        }
+    }
+}
+
+impl InternalRevset for OrderedRevset {
+    fn positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        self.positions.positions()
+    }
+
+    fn ordered_positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        let walk = EagerRevWalk::new(self.ordered_positions.clone().into_iter());
+        Box::new(walk.map(|_index, pos| Ok(pos)))
+    }
+
+    fn into_predicate<'a>(self: Box<Self>) -> Box<dyn ToPredicateFn + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(self.positions)
+    }
+}
+
+impl ToPredicateFn for OrderedRevset {
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        self.positions.to_predicate_fn()
+    }
+}
+
 /// Adapter for infallible `RevWalk` of `IndexPosition`s.
 struct RevWalkRevset<W> {
     walk: W,
@@ -765,6 +863,93 @@ where
     }
 }
 
+/// How a revset node is evaluated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EvaluationStrategy {
+    /// All positions are computed upfront.
+    Materialized,
+    /// Positions are generated lazily by walking the graph.
+    Walk,
+    /// Positions of the child sets are merged lazily.
+    Combined,
+    /// Candidates are filtered lazily by predicate.
+    Filter,
+    /// Each commit is tested by predicate function.
+    Predicate,
+}
+
+impl fmt::Display for EvaluationStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EvaluationStrategy::Materialized => "materialized",
+            EvaluationStrategy::Walk => "walk",
+            EvaluationStrategy::Combined => "combined",
+            EvaluationStrategy::Filter => "filter",
+            EvaluationStrategy::Predicate => "predicate",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Statistics of a revset node collected while the revset is evaluated and
+/// iterated.
+#[derive(Debug, Default)]
+pub struct RevsetProfileStats {
+    evaluation_time: Cell<Duration>,
+    iteration_time: Cell<Duration>,
+    num_yielded: Cell<u64>,
+    num_tested: Cell<u64>,
+}
+
+impl RevsetProfileStats {
+    /// Time spent to build the node including the child nodes. Materialized
+    /// nodes are computed at this stage.
+    pub fn evaluation_time(&self) -> Duration {
+        self.evaluation_time.get()
+    }
+
+    /// Time spent to generate positions or to test commits, including the
+    /// child nodes.
+    pub fn iteration_time(&self) -> Duration {
+        self.iteration_time.get()
+    }
+
+    /// Number of positions generated by this node.
+    pub fn num_yielded(&self) -> u64 {
+        self.num_yielded.get()
+    }
+
+    /// Number of commits tested by this node as a predicate.
+    pub fn num_tested(&self) -> u64 {
+        self.num_tested.get()
+    }
+
+    fn add_iteration_time(&self, start: Instant) {
+        self.iteration_time
+            .set(self.iteration_time.get() + start.elapsed());
+    }
+}
+
+/// Evaluation plan of a revset node. The statistics are updated as the
+/// evaluated revset is iterated.
+#[derive(Debug)]
+pub struct RevsetProfile {
+    /// Description of the expression node.
+    pub label: String,
+    /// How the node is evaluated.
+    pub strategy: EvaluationStrategy,
+    /// Profiles of the child nodes.
+    pub children: Vec<RevsetProfile>,
+    stats: Rc<RevsetProfileStats>,
+}
+
+impl RevsetProfile {
+    /// Statistics collected so far.
+    pub fn stats(&self) -> &RevsetProfileStats {
+        &self.stats
+    }
+}
+
 pub fn evaluate<I: AsCompositeIndex + Clone>(
     expression: &ResolvedExpression,
     store: &Arc<Store>,
@@ -773,14 +958,250 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
     let context = EvaluationContext {
         store: store.clone(),
         index: index.as_composite(),
+        profiler: None,
     };
     let internal_revset = context.evaluate(expression)?;
     Ok(RevsetImpl::new(internal_revset, index))
 }
 
+/// Evaluates the `expression` recording the evaluation plan. The returned
+/// profile is updated while the revset is iterated.
+pub fn evaluate_with_profile<I: AsCompositeIndex + Clone>(
+    expression: &ResolvedExpression,
+    store: &Arc<Store>,
+    index: I,
+) -> Result<(RevsetImpl<I>, RevsetProfile), RevsetEvaluationError> {
+    let context = EvaluationContext {
+        store: store.clone(),
+        index: index.as_composite(),
+        profiler: Some(RevsetProfiler::default()),
+    };
+    let internal_revset = context.evaluate(expression)?;
+    let profile = context.profiler.unwrap().into_root();
+    Ok((RevsetImpl::new(internal_revset, index), profile))
+}
+
 struct EvaluationContext<'index> {
     store: Arc<Store>,
     index: &'index CompositeIndex,
+    profiler: Option<RevsetProfiler>,
+}
+
+/// Builds tree of [`RevsetProfile`]s as the expression is evaluated.
+#[derive(Default)]
+struct RevsetProfiler {
+    /// Child nodes collected per level of the nodes being evaluated.
+    stack: RefCell<Vec<Vec<RevsetProfile>>>,
+}
+
+impl RevsetProfiler {
+    fn enter(&self) {
+        self.stack.borrow_mut().push(vec![]);
+    }
+
+    fn leave(
+        &self,
+        label: String,
+        strategy: EvaluationStrategy,
+        evaluation_time: Duration,
+    ) -> Rc<RevsetProfileStats> {
+        let mut stack = self.stack.borrow_mut();
+        let children = stack.pop().unwrap();
+        let stats = Rc::new(RevsetProfileStats {
+            evaluation_time: Cell::new(evaluation_time),
+            ..Default::default()
+        });
+        let profile = RevsetProfile {
+            label,
+            strategy,
+            children,
+            stats: stats.clone(),
+        };
+        if let Some(siblings) = stack.last_mut() {
+            siblings.push(profile);
+        } else {
+            *stack = vec![vec![profile]];
+        }
+        stats
+    }
+
+    fn into_root(self) -> RevsetProfile {
+        let mut stack = self.stack.into_inner();
+        assert_eq!(stack.len(), 1);
+        let mut roots = stack.pop().unwrap();
+        assert_eq!(roots.len(), 1);
+        roots.pop().unwrap()
+    }
+}
+
+fn describe_expression(expression: &ResolvedExpression) -> (String, EvaluationStrategy) {
+    use EvaluationStrategy::*;
+    match expression {
+        ResolvedExpression::Commits(commit_ids) => {
+            (format!("Commits({})", commit_ids.len()), Materialized)
+        }
+        ResolvedExpression::Ancestors { generation, .. } => {
+            (describe_generation("Ancestors", generation), Walk)
+        }
+        ResolvedExpression::FirstAncestors { generation, .. } => {
+            (describe_generation("FirstAncestors", generation), Walk)
+        }
+        ResolvedExpression::Range { generation, .. } => {
+            (describe_generation("Range", generation), Walk)
+        }
+        ResolvedExpression::DagRange {
+            generation_from_roots,
+            ..
+        } => {
+            let label = describe_generation("DagRange", generation_from_roots);
+            if generation_from_roots == &(1..2) {
+                (label, Filter)
+            } else {
+                (label, Materialized)
+            }
+        }
+        ResolvedExpression::Reachable { .. } => ("Reachable".to_owned(), Materialized),
+        ResolvedExpression::Heads(_) => ("Heads".to_owned(), Materialized),
+        ResolvedExpression::Roots(_) => ("Roots".to_owned(), Materialized),
+        ResolvedExpression::ForkPoint(_) => ("ForkPoint".to_owned(), Materialized),
+        ResolvedExpression::Predecessors(_) => ("Predecessors".to_owned(), Materialized),
+        ResolvedExpression::Successors(_) => ("Successors".to_owned(), Materialized),
+        ResolvedExpression::Obsolete => ("Obsolete".to_owned(), Materialized),
+        ResolvedExpression::Indexed => ("Indexed".to_owned(), Materialized),
+        ResolvedExpression::Latest { count, .. } => (format!("Latest({count})"), Materialized),
+        ResolvedExpression::Sort { key, reverse, .. } => {
+            let label = if *reverse {
+                format!("Sort({key:?}, reverse)")
+            } else {
+                format!("Sort({key:?})")
+            };
+            (label, Materialized)
+        }
+        ResolvedExpression::Limit { count, offset, .. } => {
+            (format!("Limit({count}, offset={offset})"), Materialized)
+        }
+        ResolvedExpression::Coalesce(..) => ("Coalesce".to_owned(), Combined),
+        ResolvedExpression::Union(..) => ("Union".to_owned(), Combined),
+        ResolvedExpression::FilterWithin { .. } => ("FilterWithin".to_owned(), Filter),
+        ResolvedExpression::Intersection(..) => ("Intersection".to_owned(), Combined),
+        ResolvedExpression::Difference(..) => ("Difference".to_owned(), Combined),
+    }
+}
+
+fn describe_generation(name: &str, generation: &Range<u64>) -> String {
+    if generation == &GENERATION_RANGE_FULL {
+        name.to_owned()
+    } else if generation.end == u64::MAX {
+        format!("{name}(generation={}..)", generation.start)
+    } else {
+        format!("{name}(generation={generation:?})")
+    }
+}
+
+/// Adapter that records statistics of the inner revset.
+struct ProfiledRevset {
+    inner: Box<dyn InternalRevset>,
+    stats: Rc<RevsetProfileStats>,
+}
+
+impl fmt::Debug for ProfiledRevset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl InternalRevset for ProfiledRevset {
+    fn positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        Box::new(ProfiledRevWalk {
+            walk: self.inner.positions(),
+            stats: self.stats.clone(),
+        })
+    }
+
+    fn ordered_positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        Box::new(ProfiledRevWalk {
+            walk: self.inner.ordered_positions(),
+            stats: self.stats.clone(),
+        })
+    }
+
+    fn into_predicate<'a>(self: Box<Self>) -> Box<dyn ToPredicateFn + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(ProfiledPredicate {
+            inner: self.inner.into_predicate(),
+            stats: self.stats,
+        })
+    }
+}
+
+impl ToPredicateFn for ProfiledRevset {
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        profiled_predicate_fn(self.inner.to_predicate_fn(), self.stats.clone())
+    }
+}
+
+/// Adapter that records statistics of the inner predicate.
+struct ProfiledPredicate<P> {
+    inner: P,
+    stats: Rc<RevsetProfileStats>,
+}
+
+impl<P: fmt::Debug> fmt::Debug for ProfiledPredicate<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<P: ToPredicateFn> ToPredicateFn for ProfiledPredicate<P> {
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        profiled_predicate_fn(self.inner.to_predicate_fn(), self.stats.clone())
+    }
+}
+
+fn profiled_predicate_fn<'a>(
+    mut f: BoxedPredicateFn<'a>,
+    stats: Rc<RevsetProfileStats>,
+) -> BoxedPredicateFn<'a> {
+    Box::new(move |index, pos| {
+        let start = Instant::now();
+        let matched = f(index, pos);
+        stats.add_iteration_time(start);
+        stats.num_tested.set(stats.num_tested.get() + 1);
+        matched
+    })
+}
+
+struct ProfiledRevWalk<'a> {
+    walk: BoxedRevWalk<'a>,
+    stats: Rc<RevsetProfileStats>,
+}
+
+impl RevWalk<CompositeIndex> for ProfiledRevWalk<'_> {
+    type Item = Result<IndexPosition, RevsetEvaluationError>;
+
+    fn next(&mut self, index: &CompositeIndex) -> Option<Self::Item> {
+        let start = Instant::now();
+        let item = self.walk.next(index);
+        self.stats.add_iteration_time(start);
+        if let Some(Ok(_)) = &item {
+            self.stats.num_yielded.set(self.stats.num_yielded.get() + 1);
+        }
+        item
+    }
 }
 
 fn to_u32_generation_range(range: &Range<u64>) -> Result<Range<u32>, RevsetEvaluationError> {
@@ -797,6 +1218,22 @@ impl EvaluationContext<'_> {
     fn evaluate(
         &self,
         expression: &ResolvedExpression,
+    ) -> Result<Box<dyn InternalRevset>, RevsetEvaluationError> {
+        let Some(profiler) = &self.profiler else {
+            return self.evaluate_node(expression);
+        };
+        profiler.enter();
+        let start = Instant::now();
+        let result = self.evaluate_node(expression);
+        let (label, strategy) = describe_expression(expression);
+        let stats = profiler.leave(label, strategy, start.elapsed());
+        let inner = result?;
+        Ok(Box::new(ProfiledRevset { inner, stats }))
+    }
+
+    fn evaluate_node(
+        &self,
+        expression: &ResolvedExpression,
     ) -> Result<Box<dyn InternalRevset>, RevsetEvaluationError> {
         let index = self.index;
         match expression {
@@ -819,6 +1256,22 @@ impl EvaluationContext<'_> {
                     Ok(Box::new(RevWalkRevset { walk }))
                 }
             }
+            ResolvedExpression::FirstAncestors { heads, generation } => {
+                let head_set = self.evaluate(heads)?;
+                let head_positions = head_set.positions().attach(index);
+                let builder =
+                    RevWalkBuilder::new(index).wanted_heads(head_positions.try_collect()?);
+                if generation == &GENERATION_RANGE_FULL {
+                    let walk = builder.first_parent_ancestors().detach();
+                    Ok(Box::new(RevWalkRevset { walk }))
+                } else {
+                    let generation = to_u32_generation_range(generation)?;
+                    let walk = builder
+                        .first_parent_ancestors_filtered_by_generation(generation)
+                        .detach();
+                    Ok(Box::new(RevWalkRevset { walk }))
+                }
+            }
             ResolvedExpression::Range {
                 roots,
                 heads,
@@ -974,10 +1427,91 @@ impl EvaluationContext<'_> {
                 positions.reverse();
                 Ok(Box::new(EagerRevset { positions }))
             }
+            ResolvedExpression::Predecessors(candidates) => {
+                let candidate_set = self.evaluate(candidates)?;
+                let mut work: Vec<_> = candidate_set.positions().attach(index).try_collect()?;
+                let mut predecessors = BTreeSet::new();
+                while let Some(pos) = work.pop() {
+                    for predecessor_pos in index.entry_by_pos(pos).predecessor_positions() {
+                        if predecessors.insert(predecessor_pos) {
+                            work.push(predecessor_pos);
+                        }
+                    }
+                }
+                let positions = predecessors.into_iter().rev().collect();
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::Successors(candidates) => {
+                let candidate_set = self.evaluate(candidates)?;
+                let candidate_positions: Vec<_> =
+                    candidate_set.positions().attach(index).try_collect()?;
+                let Some(&IndexPosition(min_pos)) = candidate_positions.last() else {
+                    return Ok(Box::new(EagerRevset::empty()));
+                };
+                // Predecessors are always indexed before their successors, so
+                // a single forward scan can collect all transitive successors.
+                let mut reached: HashSet<_> = candidate_positions.into_iter().collect();
+                let mut positions = vec![];
+                for pos in (min_pos + 1..index.num_commits()).map(IndexPosition) {
+                    let is_successor = index
+                        .entry_by_pos(pos)
+                        .predecessor_positions()
+                        .iter()
+                        .any(|predecessor_pos| reached.contains(predecessor_pos));
+                    if is_successor {
+                        reached.insert(pos);
+                        positions.push(pos);
+                    }
+                }
+                positions.reverse();
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::Obsolete => {
+                let obsolete: BTreeSet<_> = (0..index.num_commits())
+                    .flat_map(|pos| {
+                        index
+                            .entry_by_pos(IndexPosition(pos))
+                            .predecessor_positions()
+                    })
+                    .collect();
+                let positions = obsolete.into_iter().rev().collect();
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::Indexed => {
+                let positions = (0..index.num_commits()).rev().map(IndexPosition).collect();
+                Ok(Box::new(EagerRevset { positions }))
+            }
             ResolvedExpression::Latest { candidates, count } => {
                 let candidate_set = self.evaluate(candidates)?;
                 Ok(Box::new(self.take_latest_revset(&*candidate_set, *count)?))
             }
+            ResolvedExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            } => {
+                let candidate_set = self.evaluate(candidates)?;
+                Ok(Box::new(self.sort_revset(
+                    &*candidate_set,
+                    *key,
+                    *reverse,
+                )?))
+            }
+            ResolvedExpression::Limit {
+                candidates,
+                count,
+                offset,
+            } => {
+                let candidate_set = self.evaluate(candidates)?;
+                // Only the requested page of the candidates is evaluated.
+                let positions = candidate_set
+                    .ordered_positions()
+                    .attach(index)
+                    .skip(*offset)
+                    .take(*count)
+                    .try_collect()?;
+                Ok(Box::new(OrderedRevset::new(positions)))
+            }
             ResolvedExpression::Coalesce(expression1, expression2) => {
                 let set1 = self.evaluate(expression1)?;
                 if set1.positions().attach(index).next().is_some() {
@@ -1014,6 +1548,30 @@ impl EvaluationContext<'_> {
     fn evaluate_predicate(
         &self,
         expression: &ResolvedPredicateExpression,
+    ) -> Result<Box<dyn ToPredicateFn>, RevsetEvaluationError> {
+        let Some(profiler) = &self.profiler else {
+            return self.evaluate_predicate_node(expression);
+        };
+        let label = match expression {
+            // The set node is recorded by evaluate()
+            ResolvedPredicateExpression::Set(_) => {
+                return self.evaluate_predicate_node(expression);
+            }
+            ResolvedPredicateExpression::Filter(predicate) => format!("{predicate:?}"),
+            ResolvedPredicateExpression::NotIn(_) => "NotIn".to_owned(),
+            ResolvedPredicateExpression::Union(..) => "Union".to_owned(),
+        };
+        profiler.enter();
+        let start = Instant::now();
+        let result = self.evaluate_predicate_node(expression);
+        let stats = profiler.leave(label, EvaluationStrategy::Predicate, start.elapsed());
+        let inner = result?;
+        Ok(Box::new(ProfiledPredicate { inner, stats }))
+    }
+
+    fn evaluate_predicate_node(
+        &self,
+        expression: &ResolvedPredicateExpression,
     ) -> Result<Box<dyn ToPredicateFn>, RevsetEvaluationError> {
         match expression {
             ResolvedPredicateExpression::Filter(predicate) => {
@@ -1110,6 +1668,55 @@ impl EvaluationContext<'_> {
         positions.sort_unstable_by_key(|&pos| Reverse(pos));
         Ok(EagerRevset { positions })
     }
+
+    fn sort_revset(
+        &self,
+        candidate_set: &dyn InternalRevset,
+        key: RevsetSortKey,
+        reverse: bool,
+    ) -> Result<OrderedRevset, RevsetEvaluationError> {
+        let candidate_iter = candidate_set.positions().attach(self.index);
+        // Candidates are in topological order, which is used as tie-breaker.
+        let mut positions: Vec<_> = match key {
+            RevsetSortKey::CommitterDate | RevsetSortKey::AuthorDate => {
+                let mut items: Vec<_> = candidate_iter
+                    .map(|pos| -> Result<_, RevsetEvaluationError> {
+                        let pos = pos?;
+                        let commit = self
+                            .store
+                            .get_commit(&self.index.entry_by_pos(pos).commit_id())?;
+                        let signature = if key == RevsetSortKey::CommitterDate {
+                            commit.committer()
+                        } else {
+                            commit.author()
+                        };
+                        Ok((signature.timestamp.timestamp, pos))
+                    })
+                    .try_collect()?;
+                items.sort_by_key(|&(timestamp, _)| Reverse(timestamp));
+                items.into_iter().map(|(_, pos)| pos).collect()
+            }
+            RevsetSortKey::Description => {
+                let mut items: Vec<_> = candidate_iter
+                    .map(|pos| -> Result<_, RevsetEvaluationError> {
+                        let pos = pos?;
+                        let commit = self
+                            .store
+                            .get_commit(&self.index.entry_by_pos(pos).commit_id())?;
+                        Ok((commit.description().to_owned(), pos))
+                    })
+                    .try_collect()?;
+                items
+                    .sort_by(|(description1, _), (description2, _)| description1.cmp(description2));
+                items.into_iter().map(|(_, pos)| pos).collect()
+            }
+            RevsetSortKey::Topological => candidate_iter.try_collect()?,
+        };
+        if reverse {
+            positions.reverse();
+        }
+        Ok(OrderedRevset::new(positions))
+    }
 }
 
 struct PurePredicateFn<F>(F);
@@ -1224,15 +1831,19 @@ fn build_predicate_fn(
             })
         }
         RevsetFilterPredicate::File(expr) => {
+            let expr = expr.clone();
             let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
             box_pure_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
-                Ok(has_diff_from_parent(&store, index, &commit, &*matcher)?)
+                Ok(has_diff_from_parent(
+                    &store, index, &commit, &expr, &*matcher,
+                )?)
             })
         }
         RevsetFilterPredicate::DiffContains { text, files } => {
             let text_pattern = text.clone();
+            let files = files.clone();
             let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
             box_pure_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
@@ -1242,6 +1853,90 @@ fn build_predicate_fn(
                     index,
                     &commit,
                     &text_pattern,
+                    &files,
+                    &*files_matcher,
+                )?)
+            })
+        }
+        RevsetFilterPredicate::DiffLines { range, files } => {
+            let range = range.clone();
+            let files = files.clone();
+            let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let count = count_diff_lines_from_parent(
+                    &store,
+                    index,
+                    &commit,
+                    &files,
+                    &*files_matcher,
+                    range.end,
+                )?;
+                Ok(range.contains(&count))
+            })
+        }
+        RevsetFilterPredicate::DiffFiles { range, files } => {
+            let range = range.clone();
+            let files = files.clone();
+            let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let count = count_diff_files_from_parent(
+                    &store,
+                    index,
+                    &commit,
+                    &files,
+                    &*files_matcher,
+                    range.end,
+                )?;
+                Ok(range.contains(&count))
+            })
+        }
+        RevsetFilterPredicate::Added(files) => {
+            let files = files.clone();
+            let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                Ok(has_presence_change_from_parent(
+                    &store,
+                    index,
+                    &commit,
+                    &files,
+                    &*files_matcher,
+                    |from_present, to_present| !from_present && to_present,
+                )?)
+            })
+        }
+        RevsetFilterPredicate::Deleted(files) => {
+            let files = files.clone();
+            let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                Ok(has_presence_change_from_parent(
+                    &store,
+                    index,
+                    &commit,
+                    &files,
+                    &*files_matcher,
+                    |from_present, to_present| from_present && !to_present,
+                )?)
+            })
+        }
+        RevsetFilterPredicate::Renamed(files) => {
+            let files = files.clone();
+            let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                Ok(has_rename_from_parent(
+                    &store,
+                    index,
+                    &commit,
+                    &files,
                     &*files_matcher,
                 )?)
             })
@@ -1256,28 +1951,59 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.is_signed())
         }),
+        RevsetFilterPredicate::SignedBy(pattern) => {
+            let pattern = pattern.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                // Signatures that can't be verified (e.g. because the signing
+                // program isn't installed) aren't good signatures.
+                let verification = commit.verification().ok().flatten();
+                Ok(verification.is_some_and(|verification| {
+                    verification.status == SigStatus::Good
+                        && pattern.matches(verification.key.as_deref().unwrap_or_default())
+                }))
+            })
+        }
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
             box_pure_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
-                Ok(ext.matches_commit(&commit))
+                ext.try_matches_commit(&commit)
             })
         }
     }
 }
 
+/// Returns the matcher for `files`, evaluating file predicates against the
+/// trees if needed. `path_matcher` is `files.to_matcher()`.
+fn tree_diff_matcher<'a>(
+    files: &FilesetExpression,
+    path_matcher: &'a dyn Matcher,
+    from_tree: &MergedTree,
+    to_tree: &MergedTree,
+) -> Box<dyn Matcher + 'a> {
+    if files.has_file_predicates() {
+        files.to_tree_matcher(vec![from_tree.clone(), to_tree.clone()])
+    } else {
+        Box::new(path_matcher)
+    }
+}
+
 fn has_diff_from_parent(
     store: &Arc<Store>,
     index: &CompositeIndex,
     commit: &Commit,
+    files: &FilesetExpression,
     matcher: &dyn Matcher,
 ) -> BackendResult<bool> {
     let parents: Vec<_> = commit.parents().try_collect()?;
     if let [parent] = parents.as_slice() {
         // Fast path: no need to load the root tree
         let unchanged = commit.tree_id() == parent.tree_id();
-        if matcher.visit(RepoPath::root()) == Visit::AllRecursively {
+        if !files.has_file_predicates() && matcher.visit(RepoPath::root()) == Visit::AllRecursively
+        {
             return Ok(!unchanged);
         } else if unchanged {
             return Ok(false);
@@ -1287,8 +2013,9 @@ fn has_diff_from_parent(
     // Conflict resolution is expensive, try that only for matched files.
     let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
     let to_tree = commit.tree()?;
+    let matcher = tree_diff_matcher(files, matcher, &from_tree, &to_tree);
     // TODO: handle copy tracking
-    let mut tree_diff = from_tree.diff_stream(&to_tree, matcher);
+    let mut tree_diff = from_tree.diff_stream(&to_tree, matcher.as_ref());
     async {
         // TODO: Resolve values concurrently
         while let Some(entry) = tree_diff.next().await {
@@ -1309,14 +2036,16 @@ fn matches_diff_from_parent(
     index: &CompositeIndex,
     commit: &Commit,
     text_pattern: &StringPattern,
+    files: &FilesetExpression,
     files_matcher: &dyn Matcher,
 ) -> BackendResult<bool> {
     let parents: Vec<_> = commit.parents().try_collect()?;
     // Conflict resolution is expensive, try that only for matched files.
     let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
     let to_tree = commit.tree()?;
+    let files_matcher = tree_diff_matcher(files, files_matcher, &from_tree, &to_tree);
     // TODO: handle copy tracking
-    let mut tree_diff = from_tree.diff_stream(&to_tree, files_matcher);
+    let mut tree_diff = from_tree.diff_stream(&to_tree, files_matcher.as_ref());
     async {
         // TODO: Resolve values concurrently
         while let Some(entry) = tree_diff.next().await {
@@ -1339,6 +2068,189 @@ fn matches_diff_from_parent(
     .block_on()
 }
 
+/// Commit diffed against its merged parents, restricted to the matched files.
+struct ParentDiff<'a> {
+    parents: Vec<Commit>,
+    from_tree: MergedTree,
+    to_tree: MergedTree,
+    matcher: Box<dyn Matcher + 'a>,
+}
+
+impl<'a> ParentDiff<'a> {
+    fn new(
+        store: &Arc<Store>,
+        index: &CompositeIndex,
+        commit: &Commit,
+        files: &FilesetExpression,
+        files_matcher: &'a dyn Matcher,
+    ) -> BackendResult<Self> {
+        let parents: Vec<_> = commit.parents().try_collect()?;
+        let from_tree =
+            rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
+        let to_tree = commit.tree()?;
+        let matcher = tree_diff_matcher(files, files_matcher, &from_tree, &to_tree);
+        Ok(ParentDiff {
+            parents,
+            from_tree,
+            to_tree,
+            matcher,
+        })
+    }
+
+    /// Returns stream of changed entries. The parent side isn't resolved yet.
+    fn stream(&self) -> TreeDiffStream<'_> {
+        // TODO: handle copy tracking
+        self.from_tree
+            .diff_stream(&self.to_tree, self.matcher.as_ref())
+    }
+
+    /// Returns stream of changed entries, with copies and renames detected
+    /// from the `copy_records`.
+    fn stream_with_copies<'b>(
+        &'b self,
+        copy_records: &'b CopyRecords,
+    ) -> BoxStream<'b, CopiesTreeDiffEntry> {
+        self.from_tree
+            .diff_stream_with_copies(&self.to_tree, self.matcher.as_ref(), copy_records)
+    }
+}
+
+/// Counts files changed from the parents, stopping at `limit`.
+fn count_diff_files_from_parent(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+    files: &FilesetExpression,
+    files_matcher: &dyn Matcher,
+    limit: u64,
+) -> BackendResult<u64> {
+    let diff = ParentDiff::new(store, index, commit, files, files_matcher)?;
+    let mut tree_diff = diff.stream();
+    async {
+        let mut count = 0;
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value).await?;
+            if from_value == to_value {
+                continue;
+            }
+            count += 1;
+            if count >= limit {
+                break;
+            }
+        }
+        Ok(count)
+    }
+    .block_on()
+}
+
+/// Counts added and removed lines in the same way as `jj diff --stat`,
+/// stopping at `limit`.
+fn count_diff_lines_from_parent(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+    files: &FilesetExpression,
+    files_matcher: &dyn Matcher,
+    limit: u64,
+) -> BackendResult<u64> {
+    let diff = ParentDiff::new(store, index, commit, files, files_matcher)?;
+    let mut tree_diff = diff.stream();
+    async {
+        let mut count = 0;
+        while let Some(entry) = tree_diff.next().await {
+            let (left_value, right_value) = entry.values?;
+            let left_value = resolve_file_values(store, &entry.path, left_value).await?;
+            if left_value == right_value {
+                continue;
+            }
+            let left_future = materialize_tree_value(store, &entry.path, left_value);
+            let right_future = materialize_tree_value(store, &entry.path, right_value);
+            let (left_value, right_value) = futures::try_join!(left_future, right_future)?;
+            let left_contents = to_file_content(&entry.path, left_value)?;
+            let right_contents = to_file_content(&entry.path, right_value)?;
+            let left = materialize_merge_result_to_bytes(&left_contents, ConflictMarkerStyle::Diff);
+            let right =
+                materialize_merge_result_to_bytes(&right_contents, ConflictMarkerStyle::Diff);
+            count += count_changed_lines(&left, &right);
+            if count >= limit {
+                break;
+            }
+        }
+        Ok(count)
+    }
+    .block_on()
+}
+
+fn count_changed_lines(left: &[u8], right: &[u8]) -> u64 {
+    let diff = Diff::by_line([left, right]);
+    let count_lines = |text: &[u8]| text.split_inclusive(|b| *b == b'\n').count() as u64;
+    diff.hunks()
+        .filter(|hunk| hunk.kind == DiffHunkKind::Different)
+        .map(|hunk| count_lines(hunk.contents[0]) + count_lines(hunk.contents[1]))
+        .sum()
+}
+
+/// Returns true if any file changed from the parents satisfies
+/// `is_change(from_present, to_present)`.
+fn has_presence_change_from_parent(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+    files: &FilesetExpression,
+    files_matcher: &dyn Matcher,
+    is_change: impl Fn(bool, bool) -> bool,
+) -> BackendResult<bool> {
+    let diff = ParentDiff::new(store, index, commit, files, files_matcher)?;
+    let mut tree_diff = diff.stream();
+    async {
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value).await?;
+            if is_change(from_value.is_present(), to_value.is_present()) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    .block_on()
+}
+
+fn has_rename_from_parent(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+    files: &FilesetExpression,
+    files_matcher: &dyn Matcher,
+) -> BackendResult<bool> {
+    let diff = ParentDiff::new(store, index, commit, files, files_matcher)?;
+    let mut copy_records = CopyRecords::default();
+    for parent in &diff.parents {
+        let records = store
+            .get_copy_records(None, parent.id(), commit.id())?
+            .filter(|record| {
+                let matched = match record {
+                    Ok(record) => diff.matcher.matches(&record.target),
+                    Err(_) => true,
+                };
+                future::ready(matched)
+            })
+            .collect::<Vec<_>>()
+            .block_on();
+        copy_records.add_records(records)?;
+    }
+    let mut tree_diff = diff.stream_with_copies(&copy_records);
+    async {
+        while let Some(entry) = tree_diff.next().await {
+            if entry.path.copy_operation() == Some(CopyOperation::Rename) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    .block_on()
+}
+
 fn diff_match_lines(
     lefts: &Merge<BString>,
     rights: &Merge<BString>,
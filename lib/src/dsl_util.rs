@@ -17,6 +17,7 @@
 use std::ascii;
 use std::collections::HashMap;
 use std::fmt;
+use std::ops::Range;
 use std::slice;
 
 use itertools::Itertools as _;
@@ -852,6 +853,27 @@ where
     expander.fold_expression(node)
 }
 
+/// Parses comparison such as `">100"`, `"<=10"`, or `"42"` into range of
+/// numbers. The number part is parsed by `parse_value`.
+pub fn parse_comparison_range(
+    text: &str,
+    parse_value: impl FnOnce(&str) -> Result<u64, String>,
+) -> Result<Range<u64>, String> {
+    let text = text.trim();
+    let (op, value_text) = [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|op| Some((op, text.strip_prefix(op)?)))
+        .unwrap_or(("=", text));
+    let value = parse_value(value_text.trim())?;
+    match op {
+        ">=" => Ok(value..u64::MAX),
+        ">" => Ok(value.saturating_add(1)..u64::MAX),
+        "<=" => Ok(0..value.saturating_add(1)),
+        "<" => Ok(0..value),
+        _ => Ok(value..value.saturating_add(1)),
+    }
+}
+
 /// Collects similar names from the `candidates` list.
 pub fn collect_similar<I>(name: &str, candidates: I) -> Vec<String>
 where
@@ -873,6 +895,18 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_comparison_range() {
+        let parse = |text| parse_comparison_range(text, |s| s.parse().map_err(|_| s.to_owned()));
+        assert_eq!(parse("0"), Ok(0..1));
+        assert_eq!(parse("=5"), Ok(5..6));
+        assert_eq!(parse(">5"), Ok(6..u64::MAX));
+        assert_eq!(parse(">= 5"), Ok(5..u64::MAX));
+        assert_eq!(parse("<5"), Ok(0..5));
+        assert_eq!(parse("<=5"), Ok(0..6));
+        assert_eq!(parse(">x"), Err("x".to_owned()));
+    }
+
     #[test]
     fn test_expect_arguments() {
         fn empty_span() -> pest::Span<'static> {
@@ -14,15 +14,27 @@
 
 //! Functional language for selecting a set of paths.
 
+use std::collections::hash_map;
 use std::collections::HashMap;
+use std::fmt;
+use std::io::Read as _;
 use std::iter;
+use std::ops::Range;
 use std::path;
+use std::path::Path;
 use std::slice;
+use std::str;
+use std::sync::Arc;
+use std::sync::Mutex;
 
 use itertools::Itertools as _;
 use once_cell::sync::Lazy;
 use thiserror::Error;
 
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::TreeValue;
+use crate::dsl_util;
 use crate::dsl_util::collect_similar;
 use crate::fileset_parser;
 use crate::fileset_parser::BinaryOp;
@@ -34,6 +46,8 @@ pub use crate::fileset_parser::FilesetParseErrorKind;
 pub use crate::fileset_parser::FilesetParseResult;
 use crate::fileset_parser::FunctionCallNode;
 use crate::fileset_parser::UnaryOp;
+use crate::gitignore::GitIgnoreFile;
+use crate::gitignore::IGNORE_FILE_NAMES;
 use crate::matchers::DifferenceMatcher;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::FileGlobsMatcher;
@@ -43,11 +57,19 @@ use crate::matchers::Matcher;
 use crate::matchers::NothingMatcher;
 use crate::matchers::PrefixMatcher;
 use crate::matchers::UnionMatcher;
+use crate::matchers::Visit;
+use crate::matchers::VisitDirs;
+use crate::matchers::VisitFiles;
+use crate::merge::MergedTreeValue;
+use crate::merged_tree::MergedTree;
 use crate::repo_path::RelativePathParseError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathComponent;
 use crate::repo_path::RepoPathUiConverter;
 use crate::repo_path::UiPathParseError;
+use crate::store::Store;
+use crate::str_util::StringPattern;
 
 /// Error occurred during file pattern parsing.
 #[derive(Debug, Error)]
@@ -197,6 +219,169 @@ fn split_glob_path(input: &str) -> (&str, &str) {
     input.split_at(prefix_len)
 }
 
+/// Predicate on the metadata or content of the tree entry at a path.
+///
+/// Unlike `FilePattern`, these can't be evaluated from the path alone.
+#[derive(Clone, Debug)]
+pub enum FilePredicate {
+    /// Matches regular files whose size in bytes is within the range.
+    Size(Range<u64>),
+    /// Matches executable files.
+    Executable,
+    /// Matches symlinks.
+    Symlink,
+    /// Matches conflicted entries.
+    Conflicted,
+    /// Matches regular files that look like binary files.
+    Binary,
+    /// Matches regular files containing a line that matches the pattern.
+    Contains(StringPattern),
+    /// Matches paths ignored by the `.gitignore` and `.jjignore` files in the
+    /// tree. Only paths and ignore files present in the tree are considered.
+    Ignored,
+    /// Matches paths accepted by the predicate defined at runtime.
+    Extension(Arc<dyn FilePredicateExtension>),
+}
+
+impl FilePredicate {
+    fn matches_entry(&self, entry: &mut TreeEntryContent) -> BackendResult<bool> {
+        let resolved = entry.value.as_resolved();
+        match self {
+            FilePredicate::Size(range) => {
+                let content = entry.file_content()?;
+                Ok(content.is_some_and(|content| range.contains(&(content.len() as u64))))
+            }
+            FilePredicate::Executable => Ok(matches!(
+                resolved,
+                Some(Some(TreeValue::File {
+                    executable: true,
+                    ..
+                }))
+            )),
+            FilePredicate::Symlink => Ok(matches!(resolved, Some(Some(TreeValue::Symlink(_))))),
+            FilePredicate::Conflicted => Ok(resolved.is_none()),
+            FilePredicate::Binary => {
+                // Same heuristic as Git and `jj diff`: look for a NUL byte in
+                // the first 8k bytes.
+                const PEEK_SIZE: usize = 8000;
+                let content = entry.file_content()?;
+                Ok(content
+                    .is_some_and(|content| content[..PEEK_SIZE.min(content.len())].contains(&0)))
+            }
+            FilePredicate::Contains(pattern) => {
+                let content = entry.file_content()?;
+                Ok(content.is_some_and(|content| {
+                    content
+                        .split(|b| *b == b'\n')
+                        .any(|line| str::from_utf8(line).is_ok_and(|line| pattern.matches(line)))
+                }))
+            }
+            FilePredicate::Ignored => {
+                let ignores = entry.ignores()?;
+                Ok(ignores.matches(entry.path.as_internal_file_string()))
+            }
+            FilePredicate::Extension(extension) => Ok(extension.matches_file(entry.path)),
+        }
+    }
+}
+
+/// File predicate defined at runtime.
+pub trait FilePredicateExtension: fmt::Debug + Send + Sync {
+    /// Returns true if the file at `path` should be included.
+    ///
+    /// Since matchers can't fail, the implementation should treat errors as
+    /// non-matching.
+    fn matches_file(&self, path: &RepoPath) -> bool;
+}
+
+/// Ignore files read from a tree, cached per directory.
+type TreeIgnoresCache = Mutex<HashMap<RepoPathBuf, Arc<GitIgnoreFile>>>;
+
+/// Tree entry being tested by `FilePredicate`s, with its content read on
+/// demand.
+struct TreeEntryContent<'a> {
+    tree: &'a MergedTree,
+    ignores_cache: &'a TreeIgnoresCache,
+    path: &'a RepoPath,
+    value: MergedTreeValue,
+    content: Option<Option<Vec<u8>>>,
+}
+
+impl TreeEntryContent<'_> {
+    /// Returns the content if the entry is a resolved regular file.
+    fn file_content(&mut self) -> BackendResult<Option<&[u8]>> {
+        if self.content.is_none() {
+            let content = read_file_content(self.tree.store(), self.path, &self.value)?;
+            self.content = Some(content);
+        }
+        Ok(self.content.as_ref().unwrap().as_deref())
+    }
+
+    /// Returns the ignore patterns applicable to the entry.
+    fn ignores(&self) -> BackendResult<Arc<GitIgnoreFile>> {
+        match self.path.parent() {
+            Some(dir) => tree_dir_ignores(self.tree, self.ignores_cache, dir),
+            None => Ok(GitIgnoreFile::empty()),
+        }
+    }
+}
+
+/// Reads the content of the `value` if it is a resolved regular file.
+fn read_file_content(
+    store: &Store,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+) -> BackendResult<Option<Vec<u8>>> {
+    match value.as_resolved() {
+        Some(Some(TreeValue::File { id, .. })) => {
+            let mut content = vec![];
+            store
+                .read_file(path, id)?
+                .read_to_end(&mut content)
+                .map_err(|err| BackendError::ReadFile {
+                    path: path.to_owned(),
+                    id: id.clone(),
+                    source: err.into(),
+                })?;
+            Ok(Some(content))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Builds the ignore patterns of the `dir` from the ignore files in the `tree`.
+fn tree_dir_ignores(
+    tree: &MergedTree,
+    cache: &TreeIgnoresCache,
+    dir: &RepoPath,
+) -> BackendResult<Arc<GitIgnoreFile>> {
+    if let Some(ignores) = cache.lock().unwrap().get(dir) {
+        return Ok(ignores.clone());
+    }
+    let mut ignores = match dir.parent() {
+        Some(parent) => tree_dir_ignores(tree, cache, parent)?,
+        None => GitIgnoreFile::empty(),
+    };
+    for name in IGNORE_FILE_NAMES {
+        let path = dir.join(RepoPathComponent::new(name).unwrap());
+        let value = tree.path_value(&path)?;
+        if let Some(content) = read_file_content(tree.store(), &path, &value)? {
+            ignores = ignores
+                .chain(
+                    &dir.to_internal_dir_string(),
+                    Path::new(path.as_internal_file_string()),
+                    &content,
+                )
+                .map_err(|err| BackendError::Other(err.into()))?;
+        }
+    }
+    cache
+        .lock()
+        .unwrap()
+        .insert(dir.to_owned(), ignores.clone());
+    Ok(ignores)
+}
+
 /// AST-level representation of the fileset expression.
 #[derive(Clone, Debug)]
 pub enum FilesetExpression {
@@ -206,6 +391,8 @@ pub enum FilesetExpression {
     All,
     /// Matches basic pattern.
     Pattern(FilePattern),
+    /// Matches tree entries by metadata or content.
+    Predicate(FilePredicate),
     /// Matches any of the expressions.
     ///
     /// Use `FilesetExpression::union_all()` to construct a union expression.
@@ -243,6 +430,11 @@ impl FilesetExpression {
         FilesetExpression::Pattern(FilePattern::PrefixPath(path))
     }
 
+    /// Expression that matches tree entries by the given `predicate`.
+    pub fn predicate(predicate: FilePredicate) -> Self {
+        FilesetExpression::Predicate(predicate)
+    }
+
     /// Expression that matches any of the given `expressions`.
     pub fn union_all(expressions: Vec<FilesetExpression>) -> Self {
         match expressions.len() {
@@ -278,7 +470,8 @@ impl FilesetExpression {
             match expr {
                 FilesetExpression::None
                 | FilesetExpression::All
-                | FilesetExpression::Pattern(_) => {}
+                | FilesetExpression::Pattern(_)
+                | FilesetExpression::Predicate(_) => {}
                 FilesetExpression::UnionAll(exprs) => stack.extend(exprs.iter().rev()),
                 FilesetExpression::Intersection(expr1, expr2)
                 | FilesetExpression::Difference(expr1, expr2) => {
@@ -303,9 +496,186 @@ impl FilesetExpression {
         })
     }
 
+    /// Returns true if the expression contains predicates that need the tree
+    /// entries to be evaluated.
+    pub fn has_file_predicates(&self) -> bool {
+        self.dfs_pre()
+            .any(|expr| matches!(expr, FilesetExpression::Predicate(_)))
+    }
+
+    /// Replaces file predicates with `all()` if `value` is true, or with
+    /// `none()` otherwise, taking negation by difference into account.
+    fn replace_file_predicates(&self, value: bool) -> Self {
+        match self {
+            FilesetExpression::None | FilesetExpression::All | FilesetExpression::Pattern(_) => {
+                self.clone()
+            }
+            FilesetExpression::Predicate(_) => {
+                if value {
+                    FilesetExpression::All
+                } else {
+                    FilesetExpression::None
+                }
+            }
+            FilesetExpression::UnionAll(exprs) => FilesetExpression::UnionAll(
+                exprs
+                    .iter()
+                    .map(|expr| expr.replace_file_predicates(value))
+                    .collect(),
+            ),
+            FilesetExpression::Intersection(expr1, expr2) => expr1
+                .replace_file_predicates(value)
+                .intersection(expr2.replace_file_predicates(value)),
+            FilesetExpression::Difference(expr1, expr2) => expr1
+                .replace_file_predicates(value)
+                .difference(expr2.replace_file_predicates(!value)),
+        }
+    }
+
     /// Transforms the expression tree to `Matcher` object.
+    ///
+    /// Since file predicates can't be evaluated from paths, the returned
+    /// matcher matches any path that could match the predicates. It's only
+    /// suitable for pre-filtering paths if the expression contains predicates.
+    /// Use `to_tree_matcher()` to evaluate them.
     pub fn to_matcher(&self) -> Box<dyn Matcher> {
-        build_union_matcher(self.as_union_all())
+        if self.has_file_predicates() {
+            let expr = self.replace_file_predicates(true);
+            build_union_matcher(expr.as_union_all())
+        } else {
+            build_union_matcher(self.as_union_all())
+        }
+    }
+
+    /// Transforms the expression tree to `Matcher` object which evaluates file
+    /// predicates against the entries in `trees`.
+    ///
+    /// A path matches if the expression matches its entry in any of the
+    /// `trees`. The entries are looked up and read lazily as paths are tested.
+    pub fn to_tree_matcher(&self, trees: Vec<MergedTree>) -> Box<dyn Matcher> {
+        if !self.has_file_predicates() {
+            return self.to_matcher();
+        }
+        Box::new(TreeEntryMatcher {
+            paths: self.to_matcher(),
+            node: TreeEntryMatcherNode::new(self),
+            ignores_caches: trees.iter().map(|_| Mutex::default()).collect(),
+            trees,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum TreeEntryMatcherNode {
+    Paths(Box<dyn Matcher>),
+    Predicate(FilePredicate),
+    Union(Vec<TreeEntryMatcherNode>),
+    Intersection(Box<TreeEntryMatcherNode>, Box<TreeEntryMatcherNode>),
+    Difference(Box<TreeEntryMatcherNode>, Box<TreeEntryMatcherNode>),
+}
+
+impl TreeEntryMatcherNode {
+    fn new(expr: &FilesetExpression) -> Self {
+        if !expr.has_file_predicates() {
+            return TreeEntryMatcherNode::Paths(expr.to_matcher());
+        }
+        match expr {
+            FilesetExpression::Predicate(predicate) => {
+                TreeEntryMatcherNode::Predicate(predicate.clone())
+            }
+            FilesetExpression::UnionAll(exprs) => {
+                TreeEntryMatcherNode::Union(exprs.iter().map(Self::new).collect())
+            }
+            FilesetExpression::Intersection(expr1, expr2) => TreeEntryMatcherNode::Intersection(
+                Box::new(Self::new(expr1)),
+                Box::new(Self::new(expr2)),
+            ),
+            FilesetExpression::Difference(expr1, expr2) => TreeEntryMatcherNode::Difference(
+                Box::new(Self::new(expr1)),
+                Box::new(Self::new(expr2)),
+            ),
+            FilesetExpression::None | FilesetExpression::All | FilesetExpression::Pattern(_) => {
+                unreachable!("leaf expression should have no predicates")
+            }
+        }
+    }
+
+    fn matches(&self, entry: &mut TreeEntryContent) -> BackendResult<bool> {
+        match self {
+            TreeEntryMatcherNode::Paths(matcher) => Ok(matcher.matches(entry.path)),
+            TreeEntryMatcherNode::Predicate(predicate) => predicate.matches_entry(entry),
+            TreeEntryMatcherNode::Union(nodes) => {
+                for node in nodes {
+                    if node.matches(entry)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            TreeEntryMatcherNode::Intersection(node1, node2) => {
+                Ok(node1.matches(entry)? && node2.matches(entry)?)
+            }
+            TreeEntryMatcherNode::Difference(node1, node2) => {
+                Ok(node1.matches(entry)? && !node2.matches(entry)?)
+            }
+        }
+    }
+}
+
+/// Matcher that evaluates file predicates against tree entries.
+#[derive(Debug)]
+struct TreeEntryMatcher {
+    /// Matches any path that could match `node`.
+    paths: Box<dyn Matcher>,
+    node: TreeEntryMatcherNode,
+    trees: Vec<MergedTree>,
+    /// Ignore patterns of directories, per tree.
+    ignores_caches: Vec<TreeIgnoresCache>,
+}
+
+impl TreeEntryMatcher {
+    fn matches_in_tree(
+        &self,
+        tree: &MergedTree,
+        ignores_cache: &TreeIgnoresCache,
+        path: &RepoPath,
+    ) -> BackendResult<bool> {
+        let mut entry = TreeEntryContent {
+            tree,
+            ignores_cache,
+            path,
+            value: tree.path_value(path)?,
+            content: None,
+        };
+        if entry.value.is_absent() {
+            return Ok(false);
+        }
+        self.node.matches(&mut entry)
+    }
+}
+
+impl Matcher for TreeEntryMatcher {
+    fn matches(&self, file: &RepoPath) -> bool {
+        if !self.paths.matches(file) {
+            return false;
+        }
+        // If the entry can't be read, match it so the caller will report the
+        // error when it reads the entry.
+        self.trees
+            .iter()
+            .zip(&self.ignores_caches)
+            .any(|(tree, cache)| self.matches_in_tree(tree, cache, file).unwrap_or(true))
+    }
+
+    fn visit(&self, dir: &RepoPath) -> Visit {
+        match self.paths.visit(dir) {
+            // Files still need to be tested one by one.
+            Visit::AllRecursively => Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::All,
+            },
+            visit => visit,
+        }
     }
 }
 
@@ -323,6 +693,8 @@ fn build_union_matcher(expressions: &[FilesetExpression]) -> Box<dyn Matcher> {
             // None and All are supposed to be simplified by caller.
             FilesetExpression::None => Box::new(NothingMatcher),
             FilesetExpression::All => Box::new(EverythingMatcher),
+            // Predicates are supposed to be replaced by caller.
+            FilesetExpression::Predicate(_) => Box::new(EverythingMatcher),
             FilesetExpression::Pattern(pattern) => {
                 match pattern {
                     FilePattern::FilePath(path) => file_paths.push(path),
@@ -397,21 +769,157 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, FilesetFunction>> = Lazy
         function.expect_no_arguments()?;
         Ok(FilesetExpression::all())
     });
+    map.insert("size", |_diagnostics, _path_converter, function| {
+        let [arg] = function.expect_exact_arguments()?;
+        let text = expect_string_literal(arg)?;
+        let range = parse_size_range(text).map_err(|err| {
+            FilesetParseError::expression("Invalid size range", arg.span).with_source(err)
+        })?;
+        Ok(FilesetExpression::predicate(FilePredicate::Size(range)))
+    });
+    map.insert("executable", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Executable))
+    });
+    map.insert("symlink", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Symlink))
+    });
+    map.insert("conflicted", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Conflicted))
+    });
+    map.insert("binary", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Binary))
+    });
+    map.insert("ignored", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Ignored))
+    });
+    map.insert(
+        "file_contains",
+        |_diagnostics, _path_converter, function| {
+            let [arg] = function.expect_exact_arguments()?;
+            let pattern = expect_string_pattern(arg)?;
+            Ok(FilesetExpression::predicate(FilePredicate::Contains(
+                pattern,
+            )))
+        },
+    );
     map
 });
 
+fn expect_string_literal<'a>(node: &'a ExpressionNode) -> FilesetParseResult<&'a str> {
+    match &node.kind {
+        ExpressionKind::Identifier(name) => Ok(name),
+        ExpressionKind::String(name) => Ok(name),
+        _ => Err(FilesetParseError::expression(
+            "Expected string literal",
+            node.span,
+        )),
+    }
+}
+
+fn expect_string_pattern(node: &ExpressionNode) -> FilesetParseResult<StringPattern> {
+    match &node.kind {
+        ExpressionKind::Identifier(value) => Ok(StringPattern::substring(*value)),
+        ExpressionKind::String(value) => Ok(StringPattern::substring(value)),
+        ExpressionKind::StringPattern { kind, value } => StringPattern::from_str_kind(value, kind)
+            .map_err(|err| {
+                FilesetParseError::expression("Invalid string pattern", node.span).with_source(err)
+            }),
+        _ => Err(FilesetParseError::expression(
+            "Expected string pattern",
+            node.span,
+        )),
+    }
+}
+
+/// Parses size range such as `">1MB"`, `"<=100"`, or `"4KiB"`.
+fn parse_size_range(text: &str) -> Result<Range<u64>, String> {
+    dsl_util::parse_comparison_range(text, parse_size)
+}
+
+/// Parses size in bytes. Units are multiples of 1024.
+fn parse_size(text: &str) -> Result<u64, String> {
+    let digits_len = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (digits, unit) = text.split_at(digits_len);
+    let number: u64 = digits
+        .parse()
+        .map_err(|_| format!("Invalid size: `{text}`"))?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return Err(format!("Invalid size unit: `{}`", unit.trim())),
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("Size is too large: `{text}`"))
+}
+
+/// A set of extensions for fileset parsing.
+#[derive(Debug, Default)]
+pub struct FilesetExtensions {
+    predicates: HashMap<String, Arc<dyn FilePredicateExtension>>,
+}
+
+impl FilesetExtensions {
+    /// Creates a set with only the built-in functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers predicate function defined at runtime. The function takes no
+    /// arguments. Returns the predicate back if the `name` is already taken.
+    pub fn add_predicate_extension(
+        &mut self,
+        name: impl Into<String>,
+        predicate: Arc<dyn FilePredicateExtension>,
+    ) -> Result<(), Arc<dyn FilePredicateExtension>> {
+        let name = name.into();
+        if BUILTIN_FUNCTION_MAP.contains_key(name.as_str()) {
+            return Err(predicate);
+        }
+        match self.predicates.entry(name) {
+            hash_map::Entry::Occupied(_) => Err(predicate),
+            hash_map::Entry::Vacant(v) => {
+                v.insert(predicate);
+                Ok(())
+            }
+        }
+    }
+
+    /// Iterates names of all functions including the built-in ones.
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        let static_names = BUILTIN_FUNCTION_MAP.keys().copied();
+        let extension_names = self.predicates.keys().map(String::as_str);
+        static_names.chain(extension_names)
+    }
+}
+
 fn resolve_function(
     diagnostics: &mut FilesetDiagnostics,
     path_converter: &RepoPathUiConverter,
+    extensions: &FilesetExtensions,
     function: &FunctionCallNode,
 ) -> FilesetParseResult<FilesetExpression> {
     if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
         func(diagnostics, path_converter, function)
+    } else if let Some(predicate) = extensions.predicates.get(function.name) {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Extension(
+            predicate.clone(),
+        )))
     } else {
         Err(FilesetParseError::new(
             FilesetParseErrorKind::NoSuchFunction {
                 name: function.name.to_owned(),
-                candidates: collect_similar(function.name, BUILTIN_FUNCTION_MAP.keys()),
+                candidates: collect_similar(function.name, extensions.function_names()),
             },
             function.name_span,
         ))
@@ -421,6 +929,7 @@ fn resolve_function(
 fn resolve_expression(
     diagnostics: &mut FilesetDiagnostics,
     path_converter: &RepoPathUiConverter,
+    extensions: &FilesetExtensions,
     node: &ExpressionNode,
 ) -> FilesetParseResult<FilesetExpression> {
     let wrap_pattern_error =
@@ -442,14 +951,14 @@ fn resolve_expression(
             Ok(FilesetExpression::pattern(pattern))
         }
         ExpressionKind::Unary(op, arg_node) => {
-            let arg = resolve_expression(diagnostics, path_converter, arg_node)?;
+            let arg = resolve_expression(diagnostics, path_converter, extensions, arg_node)?;
             match op {
                 UnaryOp::Negate => Ok(FilesetExpression::all().difference(arg)),
             }
         }
         ExpressionKind::Binary(op, lhs_node, rhs_node) => {
-            let lhs = resolve_expression(diagnostics, path_converter, lhs_node)?;
-            let rhs = resolve_expression(diagnostics, path_converter, rhs_node)?;
+            let lhs = resolve_expression(diagnostics, path_converter, extensions, lhs_node)?;
+            let rhs = resolve_expression(diagnostics, path_converter, extensions, rhs_node)?;
             match op {
                 BinaryOp::Intersection => Ok(lhs.intersection(rhs)),
                 BinaryOp::Difference => Ok(lhs.difference(rhs)),
@@ -458,12 +967,12 @@ fn resolve_expression(
         ExpressionKind::UnionAll(nodes) => {
             let expressions = nodes
                 .iter()
-                .map(|node| resolve_expression(diagnostics, path_converter, node))
+                .map(|node| resolve_expression(diagnostics, path_converter, extensions, node))
                 .try_collect()?;
             Ok(FilesetExpression::union_all(expressions))
         }
         ExpressionKind::FunctionCall(function) => {
-            resolve_function(diagnostics, path_converter, function)
+            resolve_function(diagnostics, path_converter, extensions, function)
         }
     }
 }
@@ -473,10 +982,11 @@ pub fn parse(
     diagnostics: &mut FilesetDiagnostics,
     text: &str,
     path_converter: &RepoPathUiConverter,
+    extensions: &FilesetExtensions,
 ) -> FilesetParseResult<FilesetExpression> {
     let node = fileset_parser::parse_program(text)?;
     // TODO: add basic tree substitution pass to eliminate redundant expressions
-    resolve_expression(diagnostics, path_converter, &node)
+    resolve_expression(diagnostics, path_converter, extensions, &node)
 }
 
 /// Parses text into `FilesetExpression` with bare string fallback.
@@ -487,10 +997,11 @@ pub fn parse_maybe_bare(
     diagnostics: &mut FilesetDiagnostics,
     text: &str,
     path_converter: &RepoPathUiConverter,
+    extensions: &FilesetExtensions,
 ) -> FilesetParseResult<FilesetExpression> {
     let node = fileset_parser::parse_program_or_bare_string(text)?;
     // TODO: add basic tree substitution pass to eliminate redundant expressions
-    resolve_expression(diagnostics, path_converter, &node)
+    resolve_expression(diagnostics, path_converter, extensions, &node)
 }
 
 #[cfg(test)]
@@ -529,7 +1040,14 @@ mod tests {
             cwd: PathBuf::from("/ws/cur"),
             base: PathBuf::from("/ws"),
         };
-        let parse = |text| parse_maybe_bare(&mut FilesetDiagnostics::new(), text, &path_converter);
+        let parse = |text| {
+            parse_maybe_bare(
+                &mut FilesetDiagnostics::new(),
+                text,
+                &path_converter,
+                &FilesetExtensions::new(),
+            )
+        };
 
         // cwd-relative patterns
         insta::assert_debug_snapshot!(
@@ -574,7 +1092,14 @@ mod tests {
             cwd: PathBuf::from("/ws/cur*"),
             base: PathBuf::from("/ws"),
         };
-        let parse = |text| parse_maybe_bare(&mut FilesetDiagnostics::new(), text, &path_converter);
+        let parse = |text| {
+            parse_maybe_bare(
+                &mut FilesetDiagnostics::new(),
+                text,
+                &path_converter,
+                &FilesetExtensions::new(),
+            )
+        };
 
         // cwd-relative, without meta characters
         insta::assert_debug_snapshot!(
@@ -747,7 +1272,14 @@ mod tests {
             cwd: PathBuf::from("/ws/cur"),
             base: PathBuf::from("/ws"),
         };
-        let parse = |text| parse_maybe_bare(&mut FilesetDiagnostics::new(), text, &path_converter);
+        let parse = |text| {
+            parse_maybe_bare(
+                &mut FilesetDiagnostics::new(),
+                text,
+                &path_converter,
+                &FilesetExtensions::new(),
+            )
+        };
 
         insta::assert_debug_snapshot!(parse("all()").unwrap(), @"All");
         insta::assert_debug_snapshot!(parse("none()").unwrap(), @"None");
@@ -767,6 +1299,109 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_parse_file_predicate_function() {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: PathBuf::from("/ws/cur"),
+            base: PathBuf::from("/ws"),
+        };
+        let parse = |text| {
+            parse_maybe_bare(
+                &mut FilesetDiagnostics::new(),
+                text,
+                &path_converter,
+                &FilesetExtensions::new(),
+            )
+        };
+
+        insta::assert_debug_snapshot!(parse("executable()").unwrap(), @"Predicate(Executable)");
+        insta::assert_debug_snapshot!(parse("symlink()").unwrap(), @"Predicate(Symlink)");
+        insta::assert_debug_snapshot!(parse("conflicted()").unwrap(), @"Predicate(Conflicted)");
+        insta::assert_debug_snapshot!(parse("binary()").unwrap(), @"Predicate(Binary)");
+        insta::assert_debug_snapshot!(parse("ignored()").unwrap(), @"Predicate(Ignored)");
+        insta::assert_debug_snapshot!(parse("size('>1k')").unwrap(), @"Predicate(Size(1025..18446744073709551615))");
+        insta::assert_debug_snapshot!(parse("size(10)").unwrap(), @"Predicate(Size(10..11))");
+        insta::assert_debug_snapshot!(parse("size('x')").unwrap_err().kind(), @r#"Expression("Invalid size range")"#);
+        insta::assert_debug_snapshot!(parse("file_contains(TODO)").unwrap(), @r#"Predicate(Contains(Substring("TODO")))"#);
+        insta::assert_debug_snapshot!(parse("file_contains('glob:*x?')").unwrap(), @r#"Predicate(Contains(Substring("glob:*x?")))"#);
+        insta::assert_debug_snapshot!(parse("file_contains(glob:'*x?')").unwrap(), @r#"Predicate(Contains(Glob(GlobPattern("*x?"))))"#);
+        insta::assert_debug_snapshot!(parse("file_contains()").unwrap_err().kind(), @r#"
+        InvalidArguments {
+            name: "file_contains",
+            message: "Expected 1 arguments",
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_parse_predicate_extension() {
+        #[derive(Debug)]
+        struct Generated;
+
+        impl FilePredicateExtension for Generated {
+            fn matches_file(&self, path: &RepoPath) -> bool {
+                path.as_internal_file_string().ends_with(".gen")
+            }
+        }
+
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: PathBuf::from("/ws/cur"),
+            base: PathBuf::from("/ws"),
+        };
+        let mut extensions = FilesetExtensions::new();
+        extensions
+            .add_predicate_extension("generated", Arc::new(Generated))
+            .unwrap();
+        assert!(extensions
+            .add_predicate_extension("generated", Arc::new(Generated))
+            .is_err());
+        assert!(extensions
+            .add_predicate_extension("binary", Arc::new(Generated))
+            .is_err());
+        let parse = |text| {
+            parse_maybe_bare(
+                &mut FilesetDiagnostics::new(),
+                text,
+                &path_converter,
+                &extensions,
+            )
+        };
+
+        insta::assert_debug_snapshot!(parse("generated()").unwrap(), @"Predicate(Extension(Generated))");
+        insta::assert_debug_snapshot!(parse("generated(x)").unwrap_err().kind(), @r#"
+        InvalidArguments {
+            name: "generated",
+            message: "Expected 0 arguments",
+        }
+        "#);
+        insta::assert_debug_snapshot!(parse("generate()").unwrap_err().kind(), @r#"
+        NoSuchFunction {
+            name: "generate",
+            candidates: [
+                "generated",
+            ],
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_parse_size_range() {
+        assert_eq!(parse_size_range("0"), Ok(0..1));
+        assert_eq!(parse_size_range("=12b"), Ok(12..13));
+        assert_eq!(parse_size_range(">1k"), Ok(1025..u64::MAX));
+        assert_eq!(parse_size_range(">=1KiB"), Ok(1024..u64::MAX));
+        assert_eq!(parse_size_range("<2MB"), Ok(0..2 * 1024 * 1024));
+        assert_eq!(parse_size_range("<= 1g"), Ok(0..1024 * 1024 * 1024 + 1));
+        assert!(parse_size_range("").is_err());
+        assert!(parse_size_range(">").is_err());
+        assert!(parse_size_range("1x").is_err());
+        assert_eq!(parse_size_range("<0"), Ok(0..0));
+    }
+
     #[test]
     fn test_parse_compound_expression() {
         let settings = insta_settings();
@@ -775,7 +1410,14 @@ mod tests {
             cwd: PathBuf::from("/ws/cur"),
             base: PathBuf::from("/ws"),
         };
-        let parse = |text| parse_maybe_bare(&mut FilesetDiagnostics::new(), text, &path_converter);
+        let parse = |text| {
+            parse_maybe_bare(
+                &mut FilesetDiagnostics::new(),
+                text,
+                &path_converter,
+                &FilesetExtensions::new(),
+            )
+        };
 
         insta::assert_debug_snapshot!(parse("~x").unwrap(), @r#"
         Difference(
@@ -990,6 +1632,44 @@ mod tests {
         ");
     }
 
+    #[test]
+    fn test_build_matcher_file_predicates() {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+
+        // File predicates are approximated by path-only superset.
+        let expr = FilesetExpression::difference(
+            FilesetExpression::prefix_path(repo_path_buf("foo")),
+            FilesetExpression::predicate(FilePredicate::Binary),
+        );
+        assert!(expr.has_file_predicates());
+        insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
+        DifferenceMatcher {
+            wanted: PrefixMatcher {
+                tree: Dir {
+                    "foo": Prefix {},
+                },
+            },
+            unwanted: NothingMatcher,
+        }
+        "#);
+
+        let expr = FilesetExpression::intersection(
+            FilesetExpression::prefix_path(repo_path_buf("foo")),
+            FilesetExpression::predicate(FilePredicate::Executable),
+        );
+        insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
+        IntersectionMatcher {
+            input1: PrefixMatcher {
+                tree: Dir {
+                    "foo": Prefix {},
+                },
+            },
+            input2: EverythingMatcher,
+        }
+        "#);
+    }
+
     #[test]
     fn test_build_matcher_combined() {
         let settings = insta_settings();
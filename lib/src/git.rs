@@ -16,11 +16,13 @@
 
 use std::borrow::Borrow;
 use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
 use std::fs::File;
 use std::num::NonZeroU32;
+use std::path::Path;
 use std::path::PathBuf;
 use std::str;
 use std::sync::Arc;
@@ -35,10 +37,12 @@ use thiserror::Error;
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
+use crate::backend::Signature;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
+use crate::git_backend::signature_to_git;
 use crate::git_backend::GitBackend;
 use crate::git_subprocess::GitSubprocessContext;
 use crate::git_subprocess::GitSubprocessError;
@@ -115,11 +119,15 @@ pub struct GitPushStats {
     pub rejected: Vec<(GitRefNameBuf, Option<String>)>,
     /// reference rejected by the remote, with an optional reason
     pub remote_rejected: Vec<(GitRefNameBuf, Option<String>)>,
+    /// reference not updated because another update of the atomic push failed
+    pub atomic_rejected: Vec<GitRefNameBuf>,
 }
 
 impl GitPushStats {
     pub fn all_ok(&self) -> bool {
-        self.rejected.is_empty() && self.remote_rejected.is_empty()
+        self.rejected.is_empty()
+            && self.remote_rejected.is_empty()
+            && self.atomic_rejected.is_empty()
     }
 }
 
@@ -296,6 +304,16 @@ pub fn get_git_repo(store: &Store) -> Result<gix::Repository, UnexpectedGitBacke
     get_git_backend(store).map(|backend| backend.git_repo())
 }
 
+/// Returns the boundary commits of a shallow Git repository.
+///
+/// Returns an empty list if the repo isn't shallow or isn't backed by Git.
+pub fn shallow_commit_ids(store: &Store) -> BackendResult<Vec<CommitId>> {
+    match get_git_backend(store) {
+        Ok(git_backend) => git_backend.shallow_commit_ids(),
+        Err(UnexpectedGitBackendError) => Ok(vec![]),
+    }
+}
+
 /// Checks if `git_ref` points to a Git commit object, and returns its id.
 ///
 /// If the ref points to the previously `known_target` (i.e. unchanged), this
@@ -817,6 +835,93 @@ pub fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportError> {
     Ok(())
 }
 
+/// Imports HEAD of a linked Git worktree (created by `git worktree add`) whose
+/// working directory is shared with a workspace.
+///
+/// Unlike the HEAD of the main worktree, the HEAD of a linked worktree isn't
+/// recorded in the view. It's compared against the first parent of the
+/// workspace's working-copy commit instead. If they differ, the new HEAD
+/// commit is imported and returned. The caller is expected to check it out.
+pub fn import_worktree_head(
+    mut_repo: &mut MutableRepo,
+    worktree: &gix::Repository,
+    wc_commit: &Commit,
+) -> Result<Option<Commit>, GitImportError> {
+    let store = mut_repo.store().clone();
+    let git_backend = get_git_backend(&store)?;
+    let Ok(oid) = worktree.head_id() else {
+        // Unborn HEAD is left alone. It will be updated on export.
+        return Ok(None);
+    };
+    let head_id = CommitId::from_bytes(oid.as_bytes());
+    if wc_commit.parent_ids()[0] == head_id {
+        return Ok(None);
+    }
+
+    if !mut_repo.index().has_id(&head_id) {
+        git_backend.import_head_commits([&head_id]).map_err(|err| {
+            GitImportError::MissingHeadTarget {
+                id: head_id.clone(),
+                err,
+            }
+        })?;
+    }
+    let commit = store
+        .get_commit(&head_id)
+        .map_err(GitImportError::Backend)?;
+    mut_repo
+        .add_head(&commit)
+        .map_err(GitImportError::Backend)?;
+    Ok(Some(commit))
+}
+
+/// Opens the linked Git worktree (created by `git worktree add`) whose working
+/// directory is `workspace_root`.
+///
+/// Returns `None` if the repo isn't backed by Git, or if `workspace_root` isn't
+/// a linked worktree of the underlying Git repo.
+pub fn open_linked_worktree(store: &Store, workspace_root: &Path) -> Option<gix::Repository> {
+    let git_repo = get_git_repo(store).ok()?;
+    let workspace_root = dunce::canonicalize(workspace_root).ok()?;
+    let proxy = git_repo.worktrees().ok()?.into_iter().find(|proxy| {
+        proxy
+            .base()
+            .and_then(dunce::canonicalize)
+            .is_ok_and(|base| base == workspace_root)
+    })?;
+    proxy.into_repo().ok()
+}
+
+#[derive(Debug, Error)]
+pub enum GitWorktreeAddError {
+    #[error("Cannot create a Git worktree without a commit to check out")]
+    NoCommit,
+    #[error(transparent)]
+    Subprocess(#[from] GitSubprocessError),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+}
+
+/// Creates a linked Git worktree at `path` with HEAD detached at `commit_id`.
+///
+/// Files aren't checked out. The worktree is supposed to be populated by a
+/// workspace created at the same path.
+pub fn add_worktree(
+    store: &Store,
+    git_settings: &GitSettings,
+    path: &Path,
+    commit_id: &CommitId,
+) -> Result<(), GitWorktreeAddError> {
+    let git_backend = get_git_backend(store)?;
+    if commit_id == store.root_commit_id() {
+        return Err(GitWorktreeAddError::NoCommit);
+    }
+    let git_ctx =
+        GitSubprocessContext::from_git_backend(git_backend, &git_settings.executable_path);
+    git_ctx.spawn_worktree_add(path, &commit_id.hex())?;
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum GitExportError {
     #[error(transparent)]
@@ -1284,6 +1389,45 @@ pub fn reset_head(mut_repo: &mut MutableRepo, wc_commit: &Commit) -> Result<(),
         mut_repo.set_git_head_target(new_head_target);
     }
 
+    reset_index(mut_repo, &git_repo, wc_commit)
+}
+
+/// Sets HEAD of a linked Git worktree to the parent of the given working-copy
+/// commit and resets the worktree's Git index.
+///
+/// Unlike `reset_head()`, the HEAD of a linked worktree isn't recorded in the
+/// view. See `import_worktree_head()`.
+pub fn reset_worktree_head(
+    repo: &dyn Repo,
+    worktree: &gix::Repository,
+    wc_commit: &Commit,
+) -> Result<(), GitResetHeadError> {
+    let first_parent_id = &wc_commit.parent_ids()[0];
+    let new_oid = (first_parent_id != repo.store().root_commit_id())
+        .then(|| gix::ObjectId::from_bytes_or_panic(first_parent_id.as_bytes()));
+    let actual_head = worktree.head().map_err(GitResetHeadError::from_git)?;
+    let old_oid = actual_head.id().map(|id| id.detach());
+    if old_oid != new_oid {
+        let expected_ref = match old_oid {
+            Some(oid) if actual_head.is_detached() => {
+                gix::refs::transaction::PreviousValue::MustExistAndMatch(oid.into())
+            }
+            _ => gix::refs::transaction::PreviousValue::MustExist,
+        };
+        update_git_head(worktree, expected_ref, new_oid)
+            .map_err(|err| GitResetHeadError::UpdateHeadRef(err.into()))?;
+    }
+
+    reset_index(repo, worktree, wc_commit)
+}
+
+/// Resets the Git index to the parent tree of the working-copy commit, and
+/// cleans up the state of an ongoing Git operation.
+fn reset_index(
+    repo: &dyn Repo,
+    git_repo: &gix::Repository,
+    wc_commit: &Commit,
+) -> Result<(), GitResetHeadError> {
     // If there is an ongoing operation (merge, rebase, etc.), we need to clean it
     // up.
     //
@@ -1319,30 +1463,12 @@ pub fn reset_head(mut_repo: &mut MutableRepo, wc_commit: &Commit) -> Result<(),
         }
     }
 
-    let parent_tree = wc_commit.parent_tree(mut_repo)?;
+    let parent_tree = wc_commit.parent_tree(repo)?;
 
     // Use the merged parent tree as the Git index, allowing `git diff` to show the
     // same changes as `jj diff`. If the merged parent tree has conflicts, then the
     // Git index will also be conflicted.
-    let mut index = if let Some(tree) = parent_tree.as_merge().as_resolved() {
-        if tree.id() == mut_repo.store().empty_tree_id() {
-            // If the tree is empty, gix can fail to load the object (since Git doesn't
-            // require the empty tree to actually be present in the object database), so we
-            // just use an empty index directly.
-            gix::index::File::from_state(
-                gix::index::State::new(git_repo.object_hash()),
-                git_repo.index_path(),
-            )
-        } else {
-            // If the parent tree is resolved, we can use gix's `index_from_tree` method.
-            // This is more efficient than iterating over the tree and adding each entry.
-            git_repo
-                .index_from_tree(&gix::ObjectId::from_bytes_or_panic(tree.id().as_bytes()))
-                .map_err(GitResetHeadError::from_git)?
-        }
-    } else {
-        build_index_from_merged_tree(&git_repo, parent_tree.clone())?
-    };
+    let mut index = index_from_merged_tree(git_repo, &parent_tree)?;
 
     let wc_tree = wc_commit.tree()?;
     update_intent_to_add_impl(&mut index, &parent_tree, &wc_tree, git_repo.object_hash())
@@ -1372,6 +1498,50 @@ pub fn reset_head(mut_repo: &mut MutableRepo, wc_commit: &Commit) -> Result<(),
     Ok(())
 }
 
+/// Writes `tree` to a Git index file at `index_path`. Unlike [`reset_head()`],
+/// this leaves Git HEAD and the index of the repo alone.
+///
+/// Conflicts are represented in the same way as in the index written by
+/// [`reset_head()`].
+pub fn write_index_file(
+    git_repo: &gix::Repository,
+    tree: &MergedTree,
+    index_path: &Path,
+) -> Result<(), GitResetHeadError> {
+    let mut index = index_from_merged_tree(git_repo, tree)?;
+    index.set_path(index_path);
+    index
+        .write(gix::index::write::Options::default())
+        .map_err(GitResetHeadError::from_git)
+}
+
+fn index_from_merged_tree(
+    git_repo: &gix::Repository,
+    tree: &MergedTree,
+) -> Result<gix::index::File, GitResetHeadError> {
+    if let Some(resolved_tree) = tree.as_merge().as_resolved() {
+        if resolved_tree.id() == tree.store().empty_tree_id() {
+            // If the tree is empty, gix can fail to load the object (since Git doesn't
+            // require the empty tree to actually be present in the object database), so we
+            // just use an empty index directly.
+            Ok(gix::index::File::from_state(
+                gix::index::State::new(git_repo.object_hash()),
+                git_repo.index_path(),
+            ))
+        } else {
+            // If the tree is resolved, we can use gix's `index_from_tree` method.
+            // This is more efficient than iterating over the tree and adding each entry.
+            git_repo
+                .index_from_tree(&gix::ObjectId::from_bytes_or_panic(
+                    resolved_tree.id().as_bytes(),
+                ))
+                .map_err(GitResetHeadError::from_git)
+        }
+    } else {
+        build_index_from_merged_tree(git_repo, tree.clone())
+    }
+}
+
 fn build_index_from_merged_tree(
     git_repo: &gix::Repository,
     merged_tree: MergedTree,
@@ -1495,6 +1665,16 @@ pub fn update_intent_to_add(
     new_tree: &MergedTree,
 ) -> Result<(), GitResetHeadError> {
     let git_repo = get_git_repo(repo.store())?;
+    update_worktree_intent_to_add(&git_repo, old_tree, new_tree)
+}
+
+/// Like `update_intent_to_add()`, but updates the Git index of the given
+/// (possibly linked) worktree.
+pub fn update_worktree_intent_to_add(
+    git_repo: &gix::Repository,
+    old_tree: &MergedTree,
+    new_tree: &MergedTree,
+) -> Result<(), GitResetHeadError> {
     let mut index = git_repo
         .index_or_empty()
         .map_err(GitResetHeadError::from_git)?;
@@ -1570,6 +1750,535 @@ async fn update_intent_to_add_impl(
     Ok(())
 }
 
+#[derive(Debug, Error)]
+pub enum GitNotesError {
+    #[error("Invalid Git notes namespace '{0}'")]
+    InvalidNamespace(String),
+    #[error("Unexpected Git error when accessing notes")]
+    InternalGitError(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+}
+
+impl GitNotesError {
+    fn from_git(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        GitNotesError::InternalGitError(source.into())
+    }
+}
+
+/// Location of a note in the tree of a Git notes ref.
+struct GitNoteEntry {
+    path: String,
+    blob_id: gix::ObjectId,
+}
+
+/// Returns the Git ref that stores the notes in the given namespace.
+///
+/// A namespace such as `review` maps to `refs/notes/review`. A full ref name
+/// under `refs/notes/` is also accepted.
+fn notes_ref_name(namespace: &str) -> Result<gix::refs::FullName, GitNotesError> {
+    let full_name = if namespace.starts_with("refs/notes/") {
+        namespace.to_owned()
+    } else {
+        format!("refs/notes/{namespace}")
+    };
+    gix::refs::FullName::try_from(full_name)
+        .map_err(|_| GitNotesError::InvalidNamespace(namespace.to_owned()))
+}
+
+/// Returns the ref that records where the notes ref of `namespace` on `remote`
+/// was as of the last fetch or push.
+fn remote_notes_ref_name(
+    remote: &RemoteName,
+    namespace: &str,
+) -> Result<gix::refs::FullName, GitNotesError> {
+    let name = namespace.strip_prefix("refs/notes/").unwrap_or(namespace);
+    let prefix = remote_notes_ref_prefix(remote);
+    gix::refs::FullName::try_from(format!("{prefix}{name}"))
+        .map_err(|_| GitNotesError::InvalidNamespace(namespace.to_owned()))
+}
+
+fn remote_notes_ref_prefix(remote: &RemoteName) -> String {
+    format!("refs/jj/remote-notes/{remote}/", remote = remote.as_str())
+}
+
+fn find_ref_id(
+    git_repo: &gix::Repository,
+    name: &gix::refs::FullName,
+) -> Result<Option<gix::ObjectId>, GitNotesError> {
+    let Some(mut git_ref) = git_repo
+        .try_find_reference(name.as_ref())
+        .map_err(GitNotesError::from_git)?
+    else {
+        return Ok(None);
+    };
+    let id = git_ref
+        .peel_to_id_in_place()
+        .map_err(GitNotesError::from_git)?;
+    Ok(Some(id.detach()))
+}
+
+/// Returns the first common ancestor of `one` and `two` found when walking
+/// back from `one`.
+fn find_git_merge_base(
+    git_repo: &gix::Repository,
+    one: gix::ObjectId,
+    two: gix::ObjectId,
+) -> Result<Option<gix::ObjectId>, GitNotesError> {
+    let mut ancestors_of_two = HashSet::new();
+    for info in git_repo
+        .rev_walk([two])
+        .all()
+        .map_err(GitNotesError::from_git)?
+    {
+        ancestors_of_two.insert(info.map_err(GitNotesError::from_git)?.id);
+    }
+    for info in git_repo
+        .rev_walk([one])
+        .all()
+        .map_err(GitNotesError::from_git)?
+    {
+        let id = info.map_err(GitNotesError::from_git)?.id;
+        if ancestors_of_two.contains(&id) {
+            return Ok(Some(id));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns true if `ancestor` is reachable from `descendant`.
+fn is_git_ancestor(
+    git_repo: &gix::Repository,
+    ancestor: gix::ObjectId,
+    descendant: gix::ObjectId,
+) -> Result<bool, GitNotesError> {
+    let walk = git_repo
+        .rev_walk([descendant])
+        .all()
+        .map_err(GitNotesError::from_git)?;
+    for info in walk {
+        if info.map_err(GitNotesError::from_git)?.id == ancestor {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Reads the note entries of the given notes ref, and returns them along with
+/// the ids of the notes commit and its tree.
+#[expect(clippy::type_complexity)]
+fn read_note_entries(
+    git_repo: &gix::Repository,
+    ref_name: &gix::refs::FullName,
+) -> Result<
+    (
+        Option<(gix::ObjectId, gix::ObjectId)>,
+        HashMap<CommitId, GitNoteEntry>,
+    ),
+    GitNotesError,
+> {
+    let Some(mut git_ref) = git_repo
+        .try_find_reference(ref_name.as_ref())
+        .map_err(GitNotesError::from_git)?
+    else {
+        return Ok((None, HashMap::new()));
+    };
+    let notes_commit = git_ref.peel_to_commit().map_err(GitNotesError::from_git)?;
+    let tree_id = notes_commit
+        .tree_id()
+        .map_err(GitNotesError::from_git)?
+        .detach();
+    let mut entries = HashMap::new();
+    collect_note_entries(git_repo, tree_id, "", "", &mut entries)?;
+    Ok((Some((notes_commit.id, tree_id)), entries))
+}
+
+/// Collects notes from the tree recursively. Notes may be stored in fan-out
+/// directories named after the leading hex digits of the annotated object.
+fn collect_note_entries(
+    git_repo: &gix::Repository,
+    tree_id: gix::ObjectId,
+    dir: &str,
+    hex_prefix: &str,
+    entries: &mut HashMap<CommitId, GitNoteEntry>,
+) -> Result<(), GitNotesError> {
+    let hex_len = git_repo.object_hash().len_in_hex();
+    let tree = git_repo
+        .find_tree(tree_id)
+        .map_err(GitNotesError::from_git)?;
+    for entry in tree.iter() {
+        let entry = entry.map_err(GitNotesError::from_git)?;
+        let Ok(name) = str::from_utf8(entry.filename()) else {
+            continue;
+        };
+        if !name.bytes().all(|b| b.is_ascii_hexdigit()) {
+            continue;
+        }
+        let hex = format!("{hex_prefix}{name}");
+        if entry.mode().is_tree() && name.len() == 2 && hex.len() < hex_len {
+            let dir = format!("{dir}{name}/");
+            collect_note_entries(git_repo, entry.object_id(), &dir, &hex, entries)?;
+        } else if entry.mode().is_blob() && hex.len() == hex_len {
+            let Ok(commit_id) = CommitId::try_from_hex(&hex) else {
+                continue;
+            };
+            let entry = GitNoteEntry {
+                path: format!("{dir}{name}"),
+                blob_id: entry.object_id(),
+            };
+            entries.insert(commit_id, entry);
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if the notes ref of any of the given namespaces exists.
+pub fn has_notes(store: &Store, namespaces: &[String]) -> Result<bool, GitNotesError> {
+    let git_repo = get_git_repo(store)?;
+    for namespace in namespaces {
+        if find_ref_id(&git_repo, &notes_ref_name(namespace)?)?.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Returns the path of a new note for `commit_id`, using the same fan-out as
+/// the existing `entries`.
+fn new_note_path(entries: &HashMap<CommitId, GitNoteEntry>, commit_id: &CommitId) -> String {
+    let fanout = entries
+        .values()
+        .next()
+        .map_or(0, |entry| entry.path.matches('/').count());
+    let hex = commit_id.hex();
+    let (dirs, name) = hex.split_at(fanout * 2);
+    let mut path = String::new();
+    for dir in dirs.as_bytes().chunks(2) {
+        path.push_str(str::from_utf8(dir).unwrap());
+        path.push('/');
+    }
+    path.push_str(name);
+    path
+}
+
+/// Reads all notes in the given namespace.
+///
+/// Notes are read directly from the `refs/notes/<namespace>` ref of the
+/// backing Git repo. Returns an empty map if the ref doesn't exist.
+pub fn read_notes(
+    store: &Store,
+    namespace: &str,
+) -> Result<HashMap<CommitId, String>, GitNotesError> {
+    let git_repo = get_git_repo(store)?;
+    let ref_name = notes_ref_name(namespace)?;
+    let (_, entries) = read_note_entries(&git_repo, &ref_name)?;
+    entries
+        .into_iter()
+        .map(|(commit_id, entry)| {
+            let blob = git_repo
+                .find_blob(entry.blob_id)
+                .map_err(GitNotesError::from_git)?;
+            let text = String::from_utf8_lossy(&blob.data).into_owned();
+            Ok((commit_id, text))
+        })
+        .try_collect()
+}
+
+/// Adds, replaces, or removes (if `None`) notes in the given namespace.
+///
+/// The changes are recorded as a single commit on top of the notes ref, in the
+/// same format as `git notes` uses.
+pub fn write_notes(
+    store: &Store,
+    namespace: &str,
+    updates: impl IntoIterator<Item = (CommitId, Option<String>)>,
+    signature: &Signature,
+    message: &str,
+) -> Result<(), GitNotesError> {
+    let git_repo = get_git_repo(store)?;
+    let ref_name = notes_ref_name(namespace)?;
+    let (old_ids, entries) = read_note_entries(&git_repo, &ref_name)?;
+    let old_commit_id = old_ids.map(|(commit_id, _)| commit_id);
+    let old_tree_id = old_ids.map_or_else(
+        || gix::ObjectId::empty_tree(git_repo.object_hash()),
+        |(_, tree_id)| tree_id,
+    );
+    let mut editor = git_repo
+        .edit_tree(old_tree_id)
+        .map_err(GitNotesError::from_git)?;
+    for (commit_id, note) in updates {
+        let path = match entries.get(&commit_id) {
+            Some(entry) => {
+                editor
+                    .remove(&entry.path)
+                    .map_err(GitNotesError::from_git)?;
+                entry.path.clone()
+            }
+            None => new_note_path(&entries, &commit_id),
+        };
+        if let Some(text) = note {
+            let blob_id = git_repo.write_blob(text).map_err(GitNotesError::from_git)?;
+            editor
+                .upsert(&path, gix::object::tree::EntryKind::Blob, blob_id)
+                .map_err(GitNotesError::from_git)?;
+        }
+    }
+    let new_tree_id = editor.write().map_err(GitNotesError::from_git)?;
+    if new_tree_id == old_tree_id {
+        return Ok(());
+    }
+    let signature = signature_to_git(signature);
+    git_repo
+        .commit_as(
+            signature,
+            signature,
+            ref_name,
+            message,
+            new_tree_id,
+            old_commit_id,
+        )
+        .map_err(GitNotesError::from_git)?;
+    Ok(())
+}
+
+/// Copies notes from rewritten commits to their successors, so notes follow
+/// the change ID when a commit is amended or rebased.
+///
+/// `rewrites` maps new commits to the commits they were rewritten from, as
+/// returned by [`MutableRepo::rewritten_commits_since_base()`]. The notes of
+/// the old commits are copied to the new commits, unless the new commit already
+/// has a note. Returns the number of copied notes.
+pub fn rewrite_notes(
+    store: &Store,
+    rewrites: &[(CommitId, Vec<CommitId>)],
+    namespaces: &[String],
+    signature: &Signature,
+) -> Result<usize, GitNotesError> {
+    if rewrites.is_empty() {
+        return Ok(0);
+    }
+    let mut notes_by_namespace = vec![];
+    for namespace in namespaces {
+        let notes = read_notes(store, namespace)?;
+        if !notes.is_empty() {
+            notes_by_namespace.push((namespace, notes));
+        }
+    }
+    if notes_by_namespace.is_empty() {
+        return Ok(0);
+    }
+
+    let mut num_copied = 0;
+    for (namespace, notes) in notes_by_namespace {
+        let updates = rewrites
+            .iter()
+            .filter(|(new_id, _)| !notes.contains_key(new_id))
+            .filter_map(|(new_id, old_ids)| {
+                let note = old_ids.iter().find_map(|old_id| notes.get(old_id))?;
+                Some((new_id.clone(), Some(note.clone())))
+            })
+            .collect_vec();
+        if updates.is_empty() {
+            continue;
+        }
+        num_copied += updates.len();
+        write_notes(
+            store,
+            namespace,
+            updates,
+            signature,
+            "Notes added by 'jj' for rewritten commits",
+        )?;
+    }
+    Ok(num_copied)
+}
+
+/// Merges the notes fetched from `remote` into the local notes refs of the
+/// given namespaces.
+///
+/// The local ref is fast-forwarded if possible. Otherwise, a merge commit is
+/// created with the notes changed on either side since the merge base. Like the
+/// `cat_sort_uniq` strategy of `git notes merge`, if a note was changed on both
+/// sides, the lines of both notes are combined, sorted, and deduplicated.
+fn merge_remote_notes(
+    git_repo: &gix::Repository,
+    remote: &RemoteName,
+    namespaces: &[String],
+    signature: &Signature,
+) -> Result<(), GitNotesError> {
+    for namespace in namespaces {
+        let local_ref = notes_ref_name(namespace)?;
+        let remote_ref = remote_notes_ref_name(remote, namespace)?;
+        let Some(remote_id) = find_ref_id(git_repo, &remote_ref)? else {
+            continue;
+        };
+        let local_id = match find_ref_id(git_repo, &local_ref)? {
+            Some(local_id) if is_git_ancestor(git_repo, remote_id, local_id)? => continue,
+            Some(local_id) if !is_git_ancestor(git_repo, local_id, remote_id)? => local_id,
+            local_id => {
+                let constraint = match local_id {
+                    Some(id) => gix::refs::transaction::PreviousValue::MustExistAndMatch(id.into()),
+                    None => gix::refs::transaction::PreviousValue::MustNotExist,
+                };
+                git_repo
+                    .reference(
+                        local_ref,
+                        remote_id,
+                        constraint,
+                        format!("notes: fast-forward from {}", remote.as_str()),
+                    )
+                    .map_err(GitNotesError::from_git)?;
+                continue;
+            }
+        };
+
+        let (local_ids, local_entries) = read_note_entries(git_repo, &local_ref)?;
+        let (_, local_tree_id) = local_ids.expect("local notes ref should exist");
+        let (_, remote_entries) = read_note_entries(git_repo, &remote_ref)?;
+        let mut base_entries = HashMap::new();
+        if let Some(base_id) = find_git_merge_base(git_repo, local_id, remote_id)? {
+            let base_commit = git_repo
+                .find_commit(base_id)
+                .map_err(GitNotesError::from_git)?;
+            let base_tree_id = base_commit
+                .tree_id()
+                .map_err(GitNotesError::from_git)?
+                .detach();
+            collect_note_entries(git_repo, base_tree_id, "", "", &mut base_entries)?;
+        }
+        let read_blob = |id| {
+            git_repo
+                .find_blob(id)
+                .map(|blob| blob.detach().data)
+                .map_err(GitNotesError::from_git)
+        };
+
+        let mut editor = git_repo
+            .edit_tree(local_tree_id)
+            .map_err(GitNotesError::from_git)?;
+        let commit_ids: HashSet<&CommitId> =
+            local_entries.keys().chain(remote_entries.keys()).collect();
+        for commit_id in commit_ids {
+            let local_entry = local_entries.get(commit_id);
+            let local_blob_id = local_entry.map(|entry| entry.blob_id);
+            let remote_blob_id = remote_entries.get(commit_id).map(|entry| entry.blob_id);
+            let base_blob_id = base_entries.get(commit_id).map(|entry| entry.blob_id);
+            // Only the notes changed on both sides need to be combined.
+            let new_blob_id = if remote_blob_id == local_blob_id || remote_blob_id == base_blob_id {
+                continue;
+            } else if local_blob_id == base_blob_id {
+                remote_blob_id
+            } else if let (Some(local_blob_id), Some(remote_blob_id)) =
+                (local_blob_id, remote_blob_id)
+            {
+                let text = cat_sort_uniq(&read_blob(local_blob_id)?, &read_blob(remote_blob_id)?);
+                let blob_id = git_repo.write_blob(text).map_err(GitNotesError::from_git)?;
+                Some(blob_id.detach())
+            } else {
+                // If the note was removed on one side and changed on the
+                // other, keep the changed note.
+                local_blob_id.or(remote_blob_id)
+            };
+            let path = match local_entry {
+                Some(entry) => entry.path.clone(),
+                None => new_note_path(&local_entries, commit_id),
+            };
+            match new_blob_id {
+                Some(blob_id) => editor
+                    .upsert(path, gix::object::tree::EntryKind::Blob, blob_id)
+                    .map_err(GitNotesError::from_git)?,
+                None => editor.remove(path).map_err(GitNotesError::from_git)?,
+            };
+        }
+        let new_tree_id = editor.write().map_err(GitNotesError::from_git)?;
+        let signature = signature_to_git(signature);
+        git_repo
+            .commit_as(
+                signature,
+                signature,
+                local_ref,
+                format!("Notes merged by 'jj' from {}", remote.as_str()),
+                new_tree_id,
+                [local_id, remote_id],
+            )
+            .map_err(GitNotesError::from_git)?;
+    }
+    Ok(())
+}
+
+/// Combines the lines of two notes, sorted and without duplicates or empty
+/// lines.
+fn cat_sort_uniq(local: &[u8], remote: &[u8]) -> Vec<u8> {
+    let lines: BTreeSet<&[u8]> =
+        itertools::chain(local.split(|&b| b == b'\n'), remote.split(|&b| b == b'\n'))
+            .filter(|line| !line.is_empty())
+            .collect();
+    let mut text = vec![];
+    for line in lines {
+        text.extend_from_slice(line);
+        text.push(b'\n');
+    }
+    text
+}
+
+/// Pushes the notes refs of the namespaces listed in `git.notes-namespaces`
+/// that changed since they were last fetched from or pushed to `remote`.
+///
+/// Like bookmarks, a notes ref is only updated if it's still where it was when
+/// last fetched. Returns the stats of the push, which is empty if there was
+/// nothing to push.
+pub fn push_notes(
+    repo: &dyn Repo,
+    git_settings: &GitSettings,
+    remote: &RemoteName,
+    callbacks: RemoteCallbacks<'_>,
+    options: &GitPushOptions,
+) -> Result<GitPushStats, GitPushError> {
+    validate_remote_name(remote)?;
+    let git_repo = get_git_repo(repo.store())?;
+    let mut pushed_namespaces = vec![];
+    let mut updates = vec![];
+    for namespace in &git_settings.notes_namespaces {
+        let local_ref = notes_ref_name(namespace)?;
+        let remote_ref = remote_notes_ref_name(remote, namespace)?;
+        let Some(local_id) = find_ref_id(&git_repo, &local_ref)? else {
+            continue;
+        };
+        let remote_id = find_ref_id(&git_repo, &remote_ref)?;
+        if remote_id == Some(local_id) {
+            continue;
+        }
+        pushed_namespaces.push(namespace);
+        updates.push(GitRefUpdate {
+            qualified_name: local_ref.as_bstr().to_string().into(),
+            expected_current_target: remote_id.map(|id| CommitId::from_bytes(id.as_bytes())),
+            new_target: Some(CommitId::from_bytes(local_id.as_bytes())),
+        });
+    }
+    if updates.is_empty() {
+        return Ok(GitPushStats::default());
+    }
+
+    let push_stats = push_updates(repo, git_settings, remote, &updates, callbacks, options)?;
+    for (namespace, update) in std::iter::zip(pushed_namespaces, &updates) {
+        if !push_stats.pushed.contains(&update.qualified_name) {
+            continue;
+        }
+        let new_id = update.new_target.as_ref().unwrap();
+        git_repo
+            .reference(
+                remote_notes_ref_name(remote, namespace)?,
+                gix::ObjectId::from_bytes_or_panic(new_id.as_bytes()),
+                gix::refs::transaction::PreviousValue::Any,
+                "notes: pushed",
+            )
+            .map_err(GitNotesError::from_git)?;
+    }
+    Ok(push_stats)
+}
+
 #[derive(Debug, Error)]
 pub enum GitRemoteManagementError {
     #[error("No git remote named '{}'", .0.as_symbol())]
@@ -1758,7 +2467,10 @@ fn remove_remote_git_config_sections(
         })
         .map(|section| {
             if section.value_names().any(|name| {
-                !name.eq_ignore_ascii_case(b"url") && !name.eq_ignore_ascii_case(b"fetch")
+                !name.eq_ignore_ascii_case(b"url")
+                    && !name.eq_ignore_ascii_case(b"fetch")
+                    && !name.eq_ignore_ascii_case(b"promisor")
+                    && !name.eq_ignore_ascii_case(b"partialclonefilter")
             }) {
                 return Err(GitRemoteManagementError::NonstandardConfiguration(
                     remote_name.to_owned(),
@@ -1823,6 +2535,43 @@ pub fn add_remote(
     Ok(())
 }
 
+/// Makes the remote a promisor remote of a partial clone.
+///
+/// Subsequent fetches from the remote will omit the objects matched by the
+/// `filter` spec (e.g. `blob:none`), and missing objects will be fetched on
+/// demand.
+pub fn set_up_partial_clone(
+    store: &Store,
+    remote_name: &RemoteName,
+    filter: &str,
+) -> Result<(), GitRemoteManagementError> {
+    let git_repo = get_git_repo(store)?;
+
+    if git_repo.try_find_remote(remote_name.as_str()).is_none() {
+        return Err(GitRemoteManagementError::NoSuchRemote(
+            remote_name.to_owned(),
+        ));
+    }
+
+    let mut config = git_repo.config_snapshot().clone();
+    let local_meta = config.meta().clone();
+    let is_local = |meta: &gix::config::file::Metadata| *meta == local_meta;
+    let subsection = Some(BStr::new(remote_name.as_str()));
+    let values = [
+        ("remote", subsection, "promisor", "true"),
+        ("remote", subsection, "partialclonefilter", filter),
+        ("extensions", None, "partialClone", remote_name.as_str()),
+    ];
+    for (section, subsection, key, value) in values {
+        config
+            .set_raw_value_filter_by(section, subsection, key, value, is_local)
+            .map_err(GitRemoteManagementError::from_git)?;
+    }
+    save_git_config(&config).map_err(GitRemoteManagementError::GitConfigSaveError)?;
+
+    Ok(())
+}
+
 pub fn remove_remote(
     mut_repo: &mut MutableRepo,
     remote_name: &RemoteName,
@@ -1854,11 +2603,15 @@ fn remove_remote_git_refs(
     git_repo: &mut gix::Repository,
     remote: &RemoteName,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let edits: Vec<_> = git_repo
+    let mut edits: Vec<_> = git_repo
         .references()?
         .prefixed(format!("refs/remotes/{remote}/", remote = remote.as_str()))?
         .map_ok(remove_ref)
         .try_collect()?;
+    let references = git_repo.references()?;
+    for git_ref in references.prefixed(remote_notes_ref_prefix(remote))? {
+        edits.push(remove_ref(git_ref?));
+    }
     git_repo.edit_references(edits)?;
     Ok(())
 }
@@ -2074,7 +2827,7 @@ pub enum GitFetchError {
     #[error("No git remote named '{}'", .0.as_symbol())]
     NoSuchRemote(RemoteNameBuf),
     #[error(
-        "Invalid branch pattern provided. When fetching, branch names and globs may not contain the characters `{chars}`",
+        "Invalid branch pattern provided. When fetching, branch names may not contain the characters `{chars}`",
         chars = INVALID_REFSPEC_CHARS.iter().join("`, `")
     )]
     InvalidBranchPattern(StringPattern),
@@ -2082,6 +2835,8 @@ pub enum GitFetchError {
     RemoteName(#[from] GitRemoteNameError),
     #[error(transparent)]
     Subprocess(#[from] GitSubprocessError),
+    #[error("Failed to merge fetched Git notes")]
+    Notes(#[from] GitNotesError),
 }
 
 struct FetchedBranches {
@@ -2089,29 +2844,48 @@ struct FetchedBranches {
     branches: Vec<StringPattern>,
 }
 
+/// Converts the `pattern` to a glob which can be used in refspec.
+fn to_refspec_glob(pattern: &StringPattern) -> Option<Cow<'_, str>> {
+    pattern.to_glob().filter(
+        /* This triggered by non-glob `*`s in addition to INVALID_REFSPEC_CHARS
+         * because `to_glob()` escapes such `*`s as `[*]`. */
+        |glob| !glob.contains(INVALID_REFSPEC_CHARS),
+    )
+}
+
+/// Returns true if the `pattern` has to be resolved against the list of remote
+/// branches because it can't be expressed as a refspec glob.
+fn needs_remote_branch_list(pattern: &StringPattern) -> bool {
+    !pattern.is_exact() && to_refspec_glob(pattern).is_none()
+}
+
 fn expand_fetch_refspecs(
     remote: &RemoteName,
     branch_names: &[StringPattern],
+    remote_branches: &[String],
 ) -> Result<Vec<RefSpec>, GitFetchError> {
-    branch_names
-        .iter()
-        .map(|pattern| {
-            pattern
-                .to_glob()
-                .filter(
-                    /* This triggered by non-glob `*`s in addition to INVALID_REFSPEC_CHARS
-                     * because `to_glob()` escapes such `*`s as `[*]`. */
-                    |glob| !glob.contains(INVALID_REFSPEC_CHARS),
-                )
-                .map(|glob| {
-                    RefSpec::forced(
-                        format!("refs/heads/{glob}"),
-                        format!("refs/remotes/{remote}/{glob}", remote = remote.as_str()),
-                    )
-                })
-                .ok_or_else(|| GitFetchError::InvalidBranchPattern(pattern.clone()))
-        })
-        .collect()
+    let to_refspec = |name: &str| {
+        RefSpec::forced(
+            format!("refs/heads/{name}"),
+            format!("refs/remotes/{remote}/{name}", remote = remote.as_str()),
+        )
+    };
+    let mut refspecs = Vec::new();
+    for pattern in branch_names {
+        if let Some(glob) = to_refspec_glob(pattern) {
+            refspecs.push(to_refspec(&glob));
+        } else if needs_remote_branch_list(pattern) {
+            refspecs.extend(
+                remote_branches
+                    .iter()
+                    .filter(|name| pattern.matches(name))
+                    .map(|name| to_refspec(name)),
+            );
+        } else {
+            return Err(GitFetchError::InvalidBranchPattern(pattern.clone()));
+        }
+    }
+    Ok(refspecs)
 }
 
 /// Helper struct to execute multiple `git fetch` operations
@@ -2164,15 +2938,51 @@ impl<'a> GitFetch<'a> {
         {
             return Err(GitFetchError::NoSuchRemote(remote_name.to_owned()));
         }
+        // Patterns such as regex can't be passed to git, so match them against
+        // the branches on the remote.
+        let remote_branches = if branch_names.iter().any(needs_remote_branch_list) {
+            self.git_ctx.spawn_ls_remote_branches(remote_name)?
+        } else {
+            vec![]
+        };
         // At this point, we are only updating Git's remote tracking branches, not the
         // local branches.
-        let mut remaining_refspecs: Vec<_> = expand_fetch_refspecs(remote_name, branch_names)?;
-        if remaining_refspecs.is_empty() {
+        let mut remaining_refspecs: Vec<_> =
+            expand_fetch_refspecs(remote_name, branch_names, &remote_branches)?;
+
+        // Remote-tracking branches matching such patterns wouldn't be pruned
+        // by git fetch if they were deleted on the remote.
+        let remote_prefix = format!("refs/remotes/{remote}/", remote = remote_name.as_str());
+        let mut branches_to_prune = self
+            .mut_repo
+            .view()
+            .git_refs()
+            .keys()
+            .filter_map(|git_ref| git_ref.as_str().strip_prefix(&remote_prefix))
+            .filter(|name| {
+                branch_names
+                    .iter()
+                    .filter(|pattern| needs_remote_branch_list(pattern))
+                    .any(|pattern| pattern.matches(name))
+            })
+            .filter(|name| !remote_branches.iter().any(|branch| branch == name))
+            .map(|name| format!("{remote}/{name}", remote = remote_name.as_str()))
+            .collect_vec();
+        if remaining_refspecs.is_empty() && branches_to_prune.is_empty() {
             // Don't fall back to the base refspecs.
             return Ok(());
         }
+        // Unlike a missing branch, a glob matching no notes refs isn't an
+        // error, so the remote doesn't have to have notes.
+        let fetch_notes =
+            self.git_settings.fetch_notes && !self.git_settings.notes_namespaces.is_empty();
+        if fetch_notes {
+            remaining_refspecs.push(RefSpec::forced(
+                "refs/notes/*",
+                format!("{prefix}*", prefix = remote_notes_ref_prefix(remote_name)),
+            ));
+        }
 
-        let mut branches_to_prune = Vec::new();
         // git unfortunately errors out if one of the many refspecs is not found
         //
         // our approach is to filter out failures and retry,
@@ -2199,6 +3009,16 @@ impl<'a> GitFetch<'a> {
         // pruned on fetch
         self.git_ctx.spawn_branch_prune(&branches_to_prune)?;
 
+        if fetch_notes {
+            let signature = self.mut_repo.base_repo().settings().signature();
+            merge_remote_notes(
+                &self.git_repo,
+                remote_name,
+                &self.git_settings.notes_namespaces,
+                &signature,
+            )?;
+        }
+
         self.fetched.push(FetchedBranches {
             remote: remote_name.to_owned(),
             branches: branch_names.to_vec(),
@@ -2268,6 +3088,8 @@ pub enum GitPushError {
     #[error(transparent)]
     Subprocess(#[from] GitSubprocessError),
     #[error(transparent)]
+    Notes(#[from] GitNotesError),
+    #[error(transparent)]
     UnexpectedBackend(#[from] UnexpectedGitBackendError),
 }
 
@@ -2276,6 +3098,15 @@ pub struct GitBranchPushTargets {
     pub branch_updates: Vec<(RefNameBuf, BookmarkPushUpdate)>,
 }
 
+/// Options for [`push_branches()`] and [`push_updates()`].
+#[derive(Clone, Debug, Default)]
+pub struct GitPushOptions {
+    /// Whether the remote should apply either all ref updates or none of them.
+    pub atomic: bool,
+    /// Options transmitted to the server (`git push --push-option`).
+    pub remote_push_options: Vec<String>,
+}
+
 pub struct GitRefUpdate {
     pub qualified_name: GitRefNameBuf,
     /// Expected position on the remote or None if we expect the ref to not
@@ -2293,6 +3124,7 @@ pub fn push_branches(
     remote: &RemoteName,
     targets: &GitBranchPushTargets,
     callbacks: RemoteCallbacks<'_>,
+    options: &GitPushOptions,
 ) -> Result<GitPushStats, GitPushError> {
     validate_remote_name(remote)?;
 
@@ -2306,7 +3138,14 @@ pub fn push_branches(
         })
         .collect_vec();
 
-    let push_stats = push_updates(mut_repo, git_settings, remote, &ref_updates, callbacks)?;
+    let push_stats = push_updates(
+        mut_repo,
+        git_settings,
+        remote,
+        &ref_updates,
+        callbacks,
+        options,
+    )?;
     tracing::debug!(?push_stats);
 
     // TODO: add support for partially pushed refs? we could update the view
@@ -2339,6 +3178,7 @@ pub fn push_updates(
     remote_name: &RemoteName,
     updates: &[GitRefUpdate],
     mut callbacks: RemoteCallbacks<'_>,
+    options: &GitPushOptions,
 ) -> Result<GitPushStats, GitPushError> {
     let mut qualified_remote_refs_expected_locations = HashMap::new();
     let mut refspecs = vec![];
@@ -2375,10 +3215,11 @@ pub fn push_updates(
         .map(|full_refspec| RefToPush::new(full_refspec, &qualified_remote_refs_expected_locations))
         .collect();
 
-    let mut push_stats = git_ctx.spawn_push(remote_name, &refs_to_push, &mut callbacks)?;
+    let mut push_stats = git_ctx.spawn_push(remote_name, &refs_to_push, &mut callbacks, options)?;
     push_stats.pushed.sort();
     push_stats.rejected.sort();
     push_stats.remote_rejected.sort();
+    push_stats.atomic_rejected.sort();
     Ok(push_stats)
 }
 
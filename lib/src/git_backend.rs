@@ -28,6 +28,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 use std::process::ExitStatus;
+use std::process::Stdio;
 use std::str;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -341,6 +342,32 @@ impl GitBackend {
         self.base_repo.path()
     }
 
+    /// Returns the boundary commits of a shallow repository.
+    ///
+    /// The parents of these commits haven't been fetched, so they are
+    /// represented as children of the root commit.
+    pub fn shallow_commit_ids(&self) -> BackendResult<Vec<CommitId>> {
+        let locked_repo = self.lock_git_repo();
+        let shallow_commits = locked_repo
+            .shallow_commits()
+            .map_err(|err| BackendError::Other(err.into()))?;
+        let ids = shallow_commits
+            .iter()
+            .flat_map(|ids| ids.iter())
+            .map(|id| CommitId::from_bytes(id.as_bytes()))
+            .collect();
+        Ok(ids)
+    }
+
+    /// Whether the repository is a partial clone, i.e. objects may be missing
+    /// locally and have to be fetched from the promisor remote on demand.
+    pub fn is_partial_clone(&self) -> bool {
+        self.lock_git_repo()
+            .config_snapshot()
+            .string("extensions.partialClone")
+            .is_some()
+    }
+
     /// Path to the working directory if the repository isn't bare.
     pub fn git_workdir(&self) -> Option<&Path> {
         self.base_repo.work_dir()
@@ -426,14 +453,44 @@ impl GitBackend {
     }
 
     fn read_file_sync(&self, id: &FileId) -> BackendResult<Box<dyn Read>> {
+        let data = self.read_blob(id)?;
+        Ok(Box::new(Cursor::new(data)))
+    }
+
+    /// Reads the content of a blob. If the repository is a partial clone and
+    /// the blob isn't available locally, it will be fetched from the promisor
+    /// remote.
+    ///
+    /// Only file and symlink reads go through this. Other readers of blobs,
+    /// such as copy detection in `get_copy_records()`, don't fetch missing
+    /// blobs.
+    fn read_blob(&self, id: &impl ObjectId) -> BackendResult<Vec<u8>> {
         let git_blob_id = validate_git_object_id(id)?;
-        let locked_repo = self.lock_git_repo();
-        let mut blob = locked_repo
-            .find_object(git_blob_id)
-            .map_err(|err| map_not_found_err(err, id))?
-            .try_into_blob()
-            .map_err(|err| to_read_object_err(err, id))?;
-        Ok(Box::new(Cursor::new(blob.take_data())))
+        let err = {
+            let locked_repo = self.lock_git_repo();
+            let result = locked_repo.find_object(git_blob_id);
+            match result {
+                Ok(object) => {
+                    let mut blob = object
+                        .try_into_blob()
+                        .map_err(|err| to_read_object_err(err, id))?;
+                    return Ok(blob.take_data());
+                }
+                Err(err) => err,
+            }
+        };
+        if matches!(err, gix::object::find::existing::Error::NotFound { .. })
+            && self.is_partial_clone()
+        {
+            tracing::info!(%git_blob_id, "fetching missing blob from promisor remote");
+            return fetch_promised_blob(
+                self.git_executable.as_ref(),
+                self.git_repo_path(),
+                &git_blob_id,
+            )
+            .map_err(|source| to_read_object_err(source, id));
+        }
+        Err(map_not_found_err(err, id))
     }
 
     fn new_diff_platform(&self) -> BackendResult<gix::diff::blob::Platform> {
@@ -571,8 +628,8 @@ fn commit_from_git_without_root_parent(
         });
 
     // shallow commits don't have parents their parents actually fetched, so we
-    // discard them here
-    // TODO: This causes issues when a shallow repository is deepened/unshallowed
+    // discard them here. If the repository is deepened later, the index has to
+    // be rebuilt to pick up the real parents.
     let parents = if is_shallow {
         vec![]
     } else {
@@ -660,7 +717,7 @@ fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
     }
 }
 
-fn signature_to_git(signature: &Signature) -> gix::actor::SignatureRef<'_> {
+pub(crate) fn signature_to_git(signature: &Signature) -> gix::actor::SignatureRef<'_> {
     // git does not support empty names or emails
     let name = if !signature.name.is_empty() {
         &signature.name
@@ -847,6 +904,34 @@ fn run_git_gc(program: &OsStr, git_dir: &Path) -> Result<(), GitGcError> {
     Ok(())
 }
 
+/// Runs `git cat-file` to read a blob missing from a partial clone. Git
+/// fetches the blob from the promisor remote as a side effect.
+fn fetch_promised_blob(
+    program: &OsStr,
+    git_dir: &Path,
+    id: &gix::ObjectId,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut git = Command::new(program);
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["cat-file", "blob"]);
+    git.arg(id.to_string());
+    // Don't specify it by GIT_DIR/--git-dir. On Windows, the path could be
+    // canonicalized as UNC path, which wouldn't be supported by git.
+    git.current_dir(git_dir);
+    git.stdin(Stdio::null());
+    let output = git.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Failed to fetch missing blob ({}): {}",
+            output.status,
+            stderr.trim_end()
+        )
+        .into());
+    }
+    Ok(output.stdout)
+}
+
 fn validate_git_object_id(id: &impl ObjectId) -> BackendResult<gix::ObjectId> {
     if id.as_bytes().len() != HASH_LENGTH {
         return Err(BackendError::InvalidHashLength {
@@ -991,15 +1076,9 @@ impl Backend for GitBackend {
     }
 
     async fn read_symlink(&self, _path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
-        let git_blob_id = validate_git_object_id(id)?;
-        let locked_repo = self.lock_git_repo();
-        let mut blob = locked_repo
-            .find_object(git_blob_id)
-            .map_err(|err| map_not_found_err(err, id))?
-            .try_into_blob()
-            .map_err(|err| to_read_object_err(err, id))?;
-        let target = String::from_utf8(blob.take_data())
-            .map_err(|err| to_invalid_utf8_err(err.utf8_error(), id))?;
+        let data = self.read_blob(id)?;
+        let target =
+            String::from_utf8(data).map_err(|err| to_invalid_utf8_err(err.utf8_error(), id))?;
         Ok(target)
     }
 
@@ -28,6 +28,7 @@ use bstr::ByteSlice as _;
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::git::GitPushOptions;
 use crate::git::GitPushStats;
 use crate::git::Progress;
 use crate::git::RefSpec;
@@ -195,6 +196,22 @@ impl<'a> GitSubprocessContext<'a> {
         Ok(())
     }
 
+    /// Registers a new linked worktree at `path` with detached HEAD pointing
+    /// to `commit`. Files aren't checked out.
+    pub(crate) fn spawn_worktree_add(
+        &self,
+        path: &Path,
+        commit: &str,
+    ) -> Result<(), GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args(["worktree", "add", "--no-checkout", "--detach", "--"]);
+        command.arg(path).arg(commit);
+        let output = wait_with_output(self.spawn_cmd(command)?)?;
+
+        parse_git_worktree_add_output(output)
+    }
+
     /// How we retrieve the remote's default branch:
     ///
     /// `git remote show <remote_name>`
@@ -217,6 +234,23 @@ impl<'a> GitSubprocessContext<'a> {
         Ok(maybe_branch.map(Into::into))
     }
 
+    /// Lists branch names on the remote:
+    ///
+    /// `git ls-remote --heads <remote_name>`
+    pub(crate) fn spawn_ls_remote_branches(
+        &self,
+        remote_name: &RemoteName,
+    ) -> Result<Vec<String>, GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::piped());
+        command.args(["ls-remote", "--heads", "--", remote_name.as_str()]);
+        let output = wait_with_output(self.spawn_cmd(command)?)?;
+
+        // git ls-remote fails in the same way as git remote show
+        let output = parse_git_remote_show_output(output)?;
+        parse_git_ls_remote_branches(&output.stdout)
+    }
+
     /// Push references to git
     ///
     /// All pushes are forced, using --force-with-lease to perform a test&set
@@ -230,6 +264,7 @@ impl<'a> GitSubprocessContext<'a> {
         remote_name: &RemoteName,
         references: &[RefToPush],
         callbacks: &mut RemoteCallbacks<'_>,
+        options: &GitPushOptions,
     ) -> Result<GitPushStats, GitSubprocessError> {
         let mut command = self.create_command();
         command.stdout(Stdio::piped());
@@ -242,6 +277,15 @@ impl<'a> GitSubprocessContext<'a> {
         if callbacks.progress.is_some() {
             command.arg("--progress");
         }
+        if options.atomic {
+            command.arg("--atomic");
+        }
+        command.args(
+            options
+                .remote_push_options
+                .iter()
+                .map(|option| format!("--push-option={option}")),
+        );
         command.args(
             references
                 .iter()
@@ -394,6 +438,18 @@ fn parse_git_branch_prune_output(output: Output) -> Result<(), GitSubprocessErro
     Err(external_git_error(&output.stderr))
 }
 
+fn parse_git_worktree_add_output(output: Output) -> Result<(), GitSubprocessError> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    if let Some(option) = parse_unknown_option(&output.stderr) {
+        return Err(GitSubprocessError::UnsupportedGitOption(option));
+    }
+
+    Err(external_git_error(&output.stderr))
+}
+
 fn parse_git_remote_show_output(output: Output) -> Result<Output, GitSubprocessError> {
     if output.status.success() {
         return Ok(output);
@@ -427,6 +483,21 @@ fn parse_git_remote_show_default_branch(
         .map(|b| b.map(|x| x.to_string()))
 }
 
+// git-ls-remote has the following format (per line)
+// `<oid>\t<ref>`
+fn parse_git_ls_remote_branches(stdout: &[u8]) -> Result<Vec<String>, GitSubprocessError> {
+    stdout
+        .lines()
+        .filter_map(|line| line.split_once_str("\t").map(|(_, name)| name))
+        .filter_map(|name| name.strip_prefix(b"refs/heads/"))
+        .map(|name| {
+            name.to_str().map(ToOwned::to_owned).map_err(|e| {
+                GitSubprocessError::External(format!("git ls-remote output is not utf-8: {e:?}"))
+            })
+        })
+        .collect()
+}
+
 // git-push porcelain has the following format (per line)
 // `<flag>\t<from>:<to>\t<summary> (<reason>)`
 //
@@ -497,7 +568,14 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<GitPushStats, GitSubprocessError> {
             }
             // ! for a ref that was rejected or failed to push; and
             b"!" => {
-                if let Some(reason) = summary.strip_prefix(b"[remote rejected]") {
+                // With --atomic, the remaining refs are rejected by the client
+                // ("atomic push failed") or by the server ("atomic push
+                // failure") once one update fails.
+                if summary == b"[rejected] (atomic push failed)"
+                    || summary == b"[remote rejected] (atomic push failure)"
+                {
+                    push_stats.atomic_rejected.push(reference);
+                } else if let Some(reason) = summary.strip_prefix(b"[remote rejected]") {
                     let reason = reason
                         .strip_prefix(b" (")
                         .and_then(|r| r.strip_suffix(b")"))
@@ -741,6 +819,7 @@ fn trim_sideband_line(line: &[u8]) -> (&[u8], Option<u8>) {
 #[cfg(test)]
 mod test {
     use indoc::formatdoc;
+    use indoc::indoc;
 
     use super::*;
 
@@ -769,6 +848,8 @@ and the repository exists. "###;
 !\tdeadbeef:refs/heads/bookmark7\t[rejected]
 !\tdeadbeef:refs/heads/bookmark8\t[remote rejected] (hook failure)
 !\tdeadbeef:refs/heads/bookmark9\t[remote rejected]
+!\tdeadbeef:refs/heads/bookmark10\t[rejected] (atomic push failed)
+!\tdeadbeef:refs/heads/bookmark11\t[remote rejected] (atomic push failure)
 Done";
     const SAMPLE_OK_STDERR: &[u8] = b"";
 
@@ -845,6 +926,7 @@ Done";
             pushed,
             rejected,
             remote_rejected,
+            atomic_rejected,
         } = parse_ref_pushes(SAMPLE_PUSH_REFS_PORCELAIN_OUTPUT).unwrap();
         assert_eq!(
             pushed,
@@ -877,6 +959,10 @@ Done";
                 ("refs/heads/bookmark9".into(), None)
             ]
         );
+        assert_eq!(
+            atomic_rejected,
+            ["refs/heads/bookmark10", "refs/heads/bookmark11"].map(GitRefNameBuf::from)
+        );
         assert!(parse_ref_pushes(SAMPLE_OK_STDERR).is_err());
     }
 
@@ -947,6 +1033,20 @@ Done";
         assert_eq!(read_progress_line(b"fatal: this is a git error\n"), None);
     }
 
+    #[test]
+    fn test_parse_git_ls_remote_branches() {
+        let output = indoc! {b"
+            1111111111111111111111111111111111111111\trefs/heads/main
+            2222222222222222222222222222222222222222\trefs/heads/feature/a
+            3333333333333333333333333333333333333333\trefs/tags/v1
+        "};
+        assert_eq!(
+            parse_git_ls_remote_branches(output).unwrap(),
+            ["main", "feature/a"]
+        );
+        assert!(parse_git_ls_remote_branches(b"").unwrap().is_empty());
+    }
+
     #[test]
     fn test_parse_unknown_option() {
         assert_eq!(
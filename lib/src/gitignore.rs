@@ -14,6 +14,7 @@
 
 #![allow(missing_docs)]
 
+use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::iter;
@@ -42,11 +43,32 @@ pub enum GitIgnoreError {
     },
 }
 
+/// Names of the ignore files which are read from each directory, in order of
+/// increasing precedence. Patterns in `.jjignore` override `.gitignore`.
+pub const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".jjignore"];
+
 /// Models the effective contents of multiple .gitignore files.
 #[derive(Debug)]
 pub struct GitIgnoreFile {
     parent: Option<Arc<GitIgnoreFile>>,
     matcher: gitignore::Gitignore,
+    /// Path to the file the patterns were read from.
+    source: PathBuf,
+    /// Maps pattern to the (last) line number where it appears.
+    line_nums: HashMap<String, usize>,
+}
+
+/// Ignore pattern which determined whether a path is ignored.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitIgnoreRule {
+    /// Path to the file containing the pattern.
+    pub source: PathBuf,
+    /// 1-based line number of the pattern.
+    pub line_num: usize,
+    /// The pattern as written in the file.
+    pub pattern: String,
+    /// Whether this is a negative `!pattern` which un-ignores the path.
+    pub is_negative: bool,
 }
 
 impl GitIgnoreFile {
@@ -54,6 +76,8 @@ impl GitIgnoreFile {
         Arc::new(GitIgnoreFile {
             parent: None,
             matcher: gitignore::Gitignore::empty(),
+            source: PathBuf::new(),
+            line_nums: HashMap::new(),
         })
     }
 
@@ -68,6 +92,7 @@ impl GitIgnoreFile {
         input: &[u8],
     ) -> Result<Arc<GitIgnoreFile>, GitIgnoreError> {
         let mut builder = gitignore::GitignoreBuilder::new(prefix);
+        let mut line_nums = HashMap::new();
         for (i, input_line) in input.split(|b| *b == b'\n').enumerate() {
             let line =
                 std::str::from_utf8(input_line).map_err(|err| GitIgnoreError::InvalidUtf8 {
@@ -78,13 +103,20 @@ impl GitIgnoreFile {
                 })?;
             // The `from` argument doesn't provide any diagnostics or correctness, so it is
             // not required. It only allows retrieving the path from the `Glob` later, which
-            // we never do.
+            // we never do. The path is recorded once per file instead.
             builder
                 .add_line(None, line)
                 .map_err(|err| GitIgnoreError::Underlying {
                     path: ignore_path.to_path_buf(),
                     source: err,
                 })?;
+            // Same as the `Glob::original()` computed by `add_line()`.
+            let original = if line.ends_with("\\ ") {
+                line
+            } else {
+                line.trim_end()
+            };
+            line_nums.insert(original.to_owned(), i + 1);
         }
         let matcher = builder.build().map_err(|err| GitIgnoreError::Underlying {
             path: ignore_path.to_path_buf(),
@@ -95,7 +127,12 @@ impl GitIgnoreFile {
         } else {
             Some(self.clone())
         };
-        Ok(Arc::new(GitIgnoreFile { parent, matcher }))
+        Ok(Arc::new(GitIgnoreFile {
+            parent,
+            matcher,
+            source: ignore_path.to_path_buf(),
+            line_nums,
+        }))
     }
 
     /// Concatenates new `.gitignore` file at the `prefix` directory.
@@ -118,19 +155,38 @@ impl GitIgnoreFile {
         }
     }
 
-    fn matches_helper(&self, path: &str, is_dir: bool) -> bool {
-        iter::successors(Some(self), |file| file.parent.as_deref())
-            .find_map(|file| {
-                // TODO: the documentation warns that
-                // `matched_path_or_any_parents` is slower than `matched`;
-                // ideally, we would switch to that.
-                match file.matcher.matched_path_or_any_parents(path, is_dir) {
-                    ignore::Match::None => None,
-                    ignore::Match::Ignore(_) => Some(true),
-                    ignore::Match::Whitelist(_) => Some(false),
-                }
+    /// Concatenates ignore files in the `dir` directory, which are
+    /// `.gitignore` and then `.jjignore`.
+    ///
+    /// The `prefix` should be a slash-separated path relative to the workspace
+    /// root.
+    pub fn chain_with_dir(
+        self: &Arc<GitIgnoreFile>,
+        prefix: &str,
+        dir: &Path,
+    ) -> Result<Arc<GitIgnoreFile>, GitIgnoreError> {
+        IGNORE_FILE_NAMES
+            .iter()
+            .try_fold(self.clone(), |ignore, name| {
+                ignore.chain_with_file(prefix, dir.join(name))
             })
-            .unwrap_or_default()
+    }
+
+    fn matched_glob(&self, path: &str, is_dir: bool) -> Option<(&Self, &gitignore::Glob)> {
+        iter::successors(Some(self), |file| file.parent.as_deref()).find_map(|file| {
+            // TODO: the documentation warns that
+            // `matched_path_or_any_parents` is slower than `matched`;
+            // ideally, we would switch to that.
+            match file.matcher.matched_path_or_any_parents(path, is_dir) {
+                ignore::Match::None => None,
+                ignore::Match::Ignore(glob) | ignore::Match::Whitelist(glob) => Some((file, glob)),
+            }
+        })
+    }
+
+    fn matches_helper(&self, path: &str, is_dir: bool) -> bool {
+        self.matched_glob(path, is_dir)
+            .is_some_and(|(_, glob)| !glob.is_whitelist())
     }
 
     /// Returns whether specified path (not just file!) should be ignored. This
@@ -148,6 +204,23 @@ impl GitIgnoreFile {
         };
         self.matches_helper(path, is_dir)
     }
+
+    /// Returns the pattern which determines whether the specified path is
+    /// ignored, similar to `git check-ignore -v`. The path is ignored if the
+    /// returned rule isn't negative.
+    pub fn matched_rule(&self, path: &str) -> Option<GitIgnoreRule> {
+        let (path, is_dir) = match path.strip_suffix('/') {
+            Some(path) => (path, true),
+            None => (path, false),
+        };
+        let (file, glob) = self.matched_glob(path, is_dir)?;
+        Some(GitIgnoreRule {
+            source: file.source.clone(),
+            line_num: file.line_nums.get(glob.original()).copied().unwrap_or(0),
+            pattern: glob.original().to_owned(),
+            is_negative: glob.is_whitelist(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -429,6 +502,49 @@ mod tests {
         assert!(!file3.matches("foo/bar/qux"));
     }
 
+    #[test]
+    fn test_gitignore_matched_rule() {
+        let file1 = GitIgnoreFile::empty()
+            .chain(
+                "",
+                Path::new(".gitignore"),
+                b"# comment\n*.o  \n/foo\n*.o\n",
+            )
+            .unwrap();
+        let file2 = file1
+            .chain("foo/", Path::new("foo/.jjignore"), b"!bar\n")
+            .unwrap();
+        assert_eq!(file2.matched_rule("baz"), None);
+        assert_eq!(
+            file2.matched_rule("baz.o"),
+            Some(GitIgnoreRule {
+                source: ".gitignore".into(),
+                line_num: 4,
+                pattern: "*.o".to_owned(),
+                is_negative: false,
+            })
+        );
+        assert_eq!(
+            file2.matched_rule("foo/"),
+            Some(GitIgnoreRule {
+                source: ".gitignore".into(),
+                line_num: 3,
+                pattern: "/foo".to_owned(),
+                is_negative: false,
+            })
+        );
+        assert_eq!(
+            file2.matched_rule("foo/bar"),
+            Some(GitIgnoreRule {
+                source: "foo/.jjignore".into(),
+                line_num: 1,
+                pattern: "!bar".to_owned(),
+                is_negative: true,
+            })
+        );
+        assert!(!file2.matches("foo/bar"));
+    }
+
     #[test]
     fn test_gitignore_negative_parent_directory() {
         // The following script shows that Git ignores the file:
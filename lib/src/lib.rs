@@ -56,7 +56,10 @@ pub mod git;
 /// A stub module that provides a no-op implementation of some of the functions
 /// in the `git` module.
 pub mod git {
+    use crate::backend::BackendResult;
+    use crate::backend::CommitId;
     use crate::ref_name::RemoteName;
+    use crate::store::Store;
     /// Determine, by its name, if a remote refers to the special local-only
     /// "git" remote that is used in the Git backend.
     ///
@@ -64,6 +67,13 @@ pub mod git {
     pub fn is_special_git_remote(_remote: &RemoteName) -> bool {
         false
     }
+    /// Returns the boundary commits of a shallow Git repository.
+    ///
+    /// This function always returns an empty list if the "git" feature is not
+    /// enabled.
+    pub fn shallow_commit_ids(_store: &Store) -> BackendResult<Vec<CommitId>> {
+        Ok(vec![])
+    }
 }
 #[cfg(feature = "git")]
 pub mod git_backend;
@@ -80,6 +90,8 @@ pub mod lock;
 pub mod matchers;
 pub mod merge;
 pub mod merged_tree;
+#[cfg(feature = "native-signing")]
+pub mod native_signing;
 pub mod object_id;
 pub mod op_heads_store;
 pub mod op_store;
@@ -120,6 +132,8 @@ pub mod union_find;
 pub mod view;
 pub mod working_copy;
 pub mod workspace;
+pub mod workspace_store;
+pub mod x509_signing;
 
 #[cfg(test)]
 mod tests {
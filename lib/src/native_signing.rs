@@ -0,0 +1,675 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing backends that create and verify signatures in-process, without
+//! running `gpg` or `ssh-keygen`.
+
+#![allow(missing_docs)]
+
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::SubsecRound as _;
+use chrono::Utc;
+use pgp::composed::ArmorOptions;
+use pgp::composed::Deserializable as _;
+use pgp::composed::SignedPublicKey;
+use pgp::composed::SignedPublicSubKey;
+use pgp::composed::SignedSecretKey;
+use pgp::composed::StandaloneSignature;
+use pgp::crypto::hash::HashAlgorithm;
+use pgp::packet::SignatureConfig;
+use pgp::packet::SignatureType;
+use pgp::packet::Subpacket;
+use pgp::packet::SubpacketData;
+use pgp::types::KeyId;
+use pgp::types::PublicKeyTrait;
+use ssh_key::public::KeyData;
+use ssh_key::HashAlg;
+use ssh_key::LineEnding;
+use ssh_key::PrivateKey;
+use ssh_key::PublicKey;
+use ssh_key::SshSig;
+use thiserror::Error;
+
+use crate::config::ConfigGetError;
+use crate::config::ConfigGetResultExt as _;
+use crate::file_util::expand_home_path;
+use crate::settings::UserSettings;
+use crate::signing::SigStatus;
+use crate::signing::SignError;
+use crate::signing::SignResult;
+use crate::signing::SigningBackend;
+use crate::signing::Verification;
+
+/// Namespace of SSH signatures on commits, as used by Git.
+const SSH_NAMESPACE: &str = "git";
+
+#[derive(Debug, Error)]
+pub enum NativeSigningError {
+    #[error("Signing key required")]
+    MissingKey,
+    #[error("Failed to read {}", path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("Failed to parse key {}", path.display())]
+    ParseSshKey {
+        path: PathBuf,
+        source: ssh_key::Error,
+    },
+    #[error("Failed to parse key {}", path.display())]
+    ParseOpenPgpKey {
+        path: PathBuf,
+        source: pgp::errors::Error,
+    },
+    #[error("Key {} is encrypted, which is not supported without an agent", path.display())]
+    EncryptedKey { path: PathBuf },
+    #[error("Expected the path to a private key file, got a public key")]
+    PublicKey,
+    #[error("Failed to create SSH signature")]
+    Ssh(#[from] ssh_key::Error),
+    #[error("Failed to create OpenPGP signature")]
+    OpenPgp(#[from] pgp::errors::Error),
+}
+
+impl From<NativeSigningError> for SignError {
+    fn from(e: NativeSigningError) -> Self {
+        SignError::Backend(Box::new(e))
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, NativeSigningError> {
+    fs::read(path).map_err(|source| NativeSigningError::Read {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Entry of an SSH `allowed_signers` file.
+#[derive(Debug)]
+struct AllowedSigner {
+    principals: Vec<String>,
+    options: AllowedSignerOptions,
+    key: PublicKey,
+}
+
+/// Options of an `allowed_signers` entry.
+#[derive(Debug, Default)]
+struct AllowedSignerOptions {
+    cert_authority: bool,
+    namespaces: Option<String>,
+    valid_after: Option<DateTime<Utc>>,
+    valid_before: Option<DateTime<Utc>>,
+}
+
+impl AllowedSigner {
+    fn allows_namespace(&self, namespace: &str) -> bool {
+        self.options
+            .namespaces
+            .as_ref()
+            .is_none_or(|namespaces| namespaces.split(',').any(|n| n.trim() == namespace))
+    }
+
+    fn is_valid_at(&self, time: DateTime<Utc>) -> bool {
+        let options = &self.options;
+        options.valid_after.is_none_or(|after| time >= after)
+            && options.valid_before.is_none_or(|before| time <= before)
+    }
+
+    /// Returns true if the `key` is allowed to sign data in the `namespace` at
+    /// `time`. The key of a certificate authority isn't allowed to sign data
+    /// directly.
+    fn allows_key(&self, key: &KeyData, namespace: &str, time: DateTime<Utc>) -> bool {
+        !self.options.cert_authority
+            && self.key.key_data() == key
+            && self.allows_namespace(namespace)
+            && self.is_valid_at(time)
+    }
+}
+
+/// Parses the `allowed_signers` file format described in `ssh-keygen(1)`.
+///
+/// Lines that can't be parsed are skipped, as `ssh-keygen` does.
+fn parse_allowed_signers(text: &str) -> Vec<AllowedSigner> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let (principals, rest) = split_field(line);
+            let principals = unquote(principals).split(',').map(str::to_owned).collect();
+            // Options, if any, are between the principals and the key.
+            let (options, key) = match PublicKey::from_openssh(rest) {
+                Ok(key) => (AllowedSignerOptions::default(), key),
+                Err(_) => {
+                    let (options, rest) = split_field(rest);
+                    (parse_options(options)?, PublicKey::from_openssh(rest).ok()?)
+                }
+            };
+            Some(AllowedSigner {
+                principals,
+                options,
+                key,
+            })
+        })
+        .collect()
+}
+
+/// Parses comma-separated options. Returns `None` if any of the options is
+/// unknown or invalid.
+fn parse_options(text: &str) -> Option<AllowedSignerOptions> {
+    let mut options = AllowedSignerOptions::default();
+    for option in split_quoted(text, ',') {
+        let (name, value) = match option.split_once('=') {
+            Some((name, value)) => (name, Some(unquote(value))),
+            None => (option, None),
+        };
+        match (name.to_ascii_lowercase().as_str(), value) {
+            ("cert-authority", None) => options.cert_authority = true,
+            ("namespaces", Some(value)) => options.namespaces = Some(value),
+            ("valid-after", Some(value)) => options.valid_after = Some(parse_time(&value)?),
+            ("valid-before", Some(value)) => options.valid_before = Some(parse_time(&value)?),
+            _ => return None,
+        }
+    }
+    Some(options)
+}
+
+/// Parses a time in the `YYYYMMDD[HHMM[SS]][Z]` format. The time is in the
+/// local time zone unless it ends with `Z`.
+fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    let (text, is_utc) = match text.strip_suffix(['Z', 'z']) {
+        Some(text) => (text, true),
+        None => (text, false),
+    };
+    let time = match text.len() {
+        8 => NaiveDate::parse_from_str(text, "%Y%m%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)?,
+        12 => NaiveDateTime::parse_from_str(text, "%Y%m%d%H%M").ok()?,
+        14 => NaiveDateTime::parse_from_str(text, "%Y%m%d%H%M%S").ok()?,
+        _ => return None,
+    };
+    if is_utc {
+        Some(time.and_utc())
+    } else {
+        Some(time.and_local_timezone(Local).earliest()?.to_utc())
+    }
+}
+
+/// Removes double quotes which group characters in a field.
+fn unquote(text: &str) -> String {
+    text.replace('"', "")
+}
+
+/// Splits the `text` at `separator`s which aren't quoted.
+fn split_quoted(text: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut in_quotes = false;
+    text.split(move |c| {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        c == separator && !in_quotes
+    })
+}
+
+/// Splits the first whitespace-separated field, which may contain quoted
+/// whitespace, from the rest of the line.
+fn split_field(line: &str) -> (&str, &str) {
+    let mut in_quotes = false;
+    let end = line
+        .char_indices()
+        .find(|&(_, c)| {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            }
+            c.is_whitespace() && !in_quotes
+        })
+        .map_or(line.len(), |(i, _)| i);
+    (&line[..end], line[end..].trim_start())
+}
+
+/// Creates and verifies SSH signatures (in the `sshsig` format) without
+/// running `ssh-keygen`.
+///
+/// Signing requires an unencrypted private key. Signatures are verified
+/// against the same `allowed_signers` file as the `ssh` backend.
+#[derive(Debug)]
+pub struct SshNativeBackend {
+    allowed_signers: Option<PathBuf>,
+    allowed_signers_entries: OnceLock<Vec<AllowedSigner>>,
+}
+
+impl SshNativeBackend {
+    pub fn new(allowed_signers: Option<PathBuf>) -> Self {
+        Self {
+            allowed_signers,
+            allowed_signers_entries: OnceLock::new(),
+        }
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let allowed_signers = settings
+            .get_string("signing.backends.ssh.allowed-signers")
+            .optional()?
+            .map(|v| expand_home_path(v.as_str()));
+        Ok(Self::new(allowed_signers))
+    }
+
+    fn allowed_signers(&self) -> &[AllowedSigner] {
+        self.allowed_signers_entries.get_or_init(|| {
+            let Some(path) = &self.allowed_signers else {
+                return vec![];
+            };
+            match fs::read_to_string(path) {
+                Ok(text) => parse_allowed_signers(&text),
+                Err(err) => {
+                    tracing::warn!(?path, ?err, "failed to read allowed signers file");
+                    vec![]
+                }
+            }
+        })
+    }
+
+    fn read_private_key(key: &str) -> Result<PrivateKey, NativeSigningError> {
+        if key.starts_with("ssh-") {
+            return Err(NativeSigningError::PublicKey);
+        }
+        let path = expand_home_path(key);
+        // Allow pointing at the public key like with the `ssh` backend, as
+        // long as the private key sits next to it.
+        let path = match path.to_str().and_then(|path| path.strip_suffix(".pub")) {
+            Some(private_path) => PathBuf::from(private_path),
+            None => path,
+        };
+        let private_key = PrivateKey::from_openssh(read_file(&path)?).map_err(|source| {
+            NativeSigningError::ParseSshKey {
+                path: path.clone(),
+                source,
+            }
+        })?;
+        if private_key.is_encrypted() {
+            return Err(NativeSigningError::EncryptedKey { path });
+        }
+        Ok(private_key)
+    }
+}
+
+impl SigningBackend for SshNativeBackend {
+    fn name(&self) -> &str {
+        "ssh-native"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        signature.starts_with(b"-----BEGIN SSH SIGNATURE-----")
+    }
+
+    fn sign(&self, data: &[u8], key: Option<&str>) -> SignResult<Vec<u8>> {
+        let key = key.ok_or(NativeSigningError::MissingKey)?;
+        let private_key = Self::read_private_key(key)?;
+        let signature = private_key
+            .sign(SSH_NAMESPACE, HashAlg::Sha512, data)
+            .map_err(NativeSigningError::Ssh)?;
+        let pem = signature
+            .to_pem(LineEnding::LF)
+            .map_err(NativeSigningError::Ssh)?;
+        Ok(pem.into_bytes())
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification> {
+        let signature =
+            SshSig::from_pem(signature).map_err(|_| SignError::InvalidSignatureFormat)?;
+        let public_key = PublicKey::new(signature.public_key().clone(), "");
+        let fingerprint = public_key.fingerprint(HashAlg::Sha256).to_string();
+        let now = Utc::now();
+        let principals = self
+            .allowed_signers()
+            .iter()
+            .find(|signer| signer.allows_key(signature.public_key(), SSH_NAMESPACE, now))
+            .map(|signer| signer.principals.join(","));
+        match public_key.verify(SSH_NAMESPACE, data, &signature) {
+            Ok(()) => match principals {
+                Some(principals) => Ok(Verification::new(
+                    SigStatus::Good,
+                    Some(fingerprint),
+                    Some(principals),
+                )),
+                None => Ok(Verification::new(
+                    SigStatus::Unknown,
+                    Some(fingerprint),
+                    Some("Signature OK. Unknown principal".into()),
+                )),
+            },
+            Err(ssh_key::Error::Crypto | ssh_key::Error::Namespace) => Ok(Verification::new(
+                SigStatus::Bad,
+                Some(fingerprint),
+                principals,
+            )),
+            // Let other backends try signatures we don't know how to handle.
+            Err(_) => Ok(Verification::unknown()),
+        }
+    }
+}
+
+/// Creates and verifies OpenPGP signatures without running `gpg`.
+///
+/// Signing requires an unencrypted secret key file. Signatures are verified
+/// against the public keys in the configured keyring file.
+#[derive(Debug)]
+pub struct GpgNativeBackend {
+    keyring: Option<PathBuf>,
+    allow_expired_keys: bool,
+    keyring_keys: OnceLock<Vec<SignedPublicKey>>,
+}
+
+impl GpgNativeBackend {
+    pub fn new(keyring: Option<PathBuf>, allow_expired_keys: bool) -> Self {
+        Self {
+            keyring,
+            allow_expired_keys,
+            keyring_keys: OnceLock::new(),
+        }
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let keyring = settings
+            .get_string("signing.backends.gpg-native.keyring")
+            .optional()?
+            .map(|v| expand_home_path(v.as_str()));
+        let allow_expired_keys = settings.get_bool("signing.backends.gpg.allow-expired-keys")?;
+        Ok(Self::new(keyring, allow_expired_keys))
+    }
+
+    fn keyring(&self) -> &[SignedPublicKey] {
+        self.keyring_keys.get_or_init(|| {
+            let Some(path) = &self.keyring else {
+                return vec![];
+            };
+            let keys = fs::File::open(path).map(|file| {
+                SignedPublicKey::from_reader_many(file)
+                    .map(|(keys, _)| keys.filter_map(Result::ok).collect())
+            });
+            match keys {
+                Ok(Ok(keys)) => keys,
+                Ok(Err(err)) => {
+                    tracing::warn!(?path, ?err, "failed to parse OpenPGP keyring");
+                    vec![]
+                }
+                Err(err) => {
+                    tracing::warn!(?path, ?err, "failed to read OpenPGP keyring");
+                    vec![]
+                }
+            }
+        })
+    }
+
+    fn read_secret_key(key: &str) -> Result<SignedSecretKey, NativeSigningError> {
+        let path = expand_home_path(key);
+        let content = read_file(&path)?;
+        let (secret_key, _) =
+            SignedSecretKey::from_reader_single(content.as_slice()).map_err(|source| {
+                NativeSigningError::ParseOpenPgpKey {
+                    path: path.clone(),
+                    source,
+                }
+            })?;
+        if secret_key.primary_key.secret_params().is_encrypted() {
+            return Err(NativeSigningError::EncryptedKey { path });
+        }
+        Ok(secret_key)
+    }
+}
+
+/// Formats the key ID the same way as `gpg --status-fd` does.
+fn format_key_id(key_id: &KeyId) -> String {
+    hex::encode_upper(key_id.as_ref())
+}
+
+/// Validity of an OpenPGP key at the time of verification.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum KeyValidity {
+    Valid,
+    Expired,
+    Revoked,
+}
+
+fn primary_key_validity(key: &SignedPublicKey, now: DateTime<Utc>) -> KeyValidity {
+    let revoked = key
+        .details
+        .revocation_signatures
+        .iter()
+        .any(|sig| sig.verify_key(&key.primary_key).is_ok());
+    if revoked {
+        KeyValidity::Revoked
+    } else if key.expires_at().is_some_and(|expires_at| expires_at <= now) {
+        KeyValidity::Expired
+    } else {
+        KeyValidity::Valid
+    }
+}
+
+fn subkey_validity(
+    key: &SignedPublicKey,
+    subkey: &SignedPublicSubKey,
+    now: DateTime<Utc>,
+) -> KeyValidity {
+    let validity = primary_key_validity(key, now);
+    if validity != KeyValidity::Valid {
+        return validity;
+    }
+    let verified_signatures = || {
+        subkey.signatures.iter().filter(|sig| {
+            sig.verify_key_binding(&key.primary_key, &subkey.key)
+                .is_ok()
+        })
+    };
+    let revoked = verified_signatures().any(|sig| sig.typ() == SignatureType::SubkeyRevocation);
+    let expires_at = verified_signatures()
+        .filter(|sig| sig.typ() == SignatureType::SubkeyBinding)
+        .filter_map(|sig| sig.key_expiration_time())
+        .max()
+        .map(|&expiration| *subkey.key.created_at() + expiration);
+    if revoked {
+        KeyValidity::Revoked
+    } else if expires_at.is_some_and(|expires_at| expires_at <= now) {
+        KeyValidity::Expired
+    } else {
+        KeyValidity::Valid
+    }
+}
+
+impl GpgNativeBackend {
+    /// Verifies the signature with the `key`, taking the `validity` of the key
+    /// into account the same way as the `gpg` backend does.
+    fn verify_with_key(
+        &self,
+        signature: &StandaloneSignature,
+        key: &impl PublicKeyTrait,
+        validity: KeyValidity,
+        data: &[u8],
+    ) -> Option<SigStatus> {
+        match signature.verify(key, data) {
+            Ok(()) => match validity {
+                KeyValidity::Valid => Some(SigStatus::Good),
+                KeyValidity::Expired if self.allow_expired_keys => Some(SigStatus::Good),
+                KeyValidity::Expired | KeyValidity::Revoked => Some(SigStatus::Bad),
+            },
+            // Let other backends try signatures we don't know how to handle.
+            Err(pgp::errors::Error::Unimplemented(_) | pgp::errors::Error::Unsupported(_)) => None,
+            Err(_) => Some(SigStatus::Bad),
+        }
+    }
+}
+
+impl SigningBackend for GpgNativeBackend {
+    fn name(&self) -> &str {
+        "gpg-native"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        signature.starts_with(b"-----BEGIN PGP SIGNATURE-----")
+    }
+
+    fn sign(&self, data: &[u8], key: Option<&str>) -> SignResult<Vec<u8>> {
+        let key = key.ok_or(NativeSigningError::MissingKey)?;
+        let secret_key = Self::read_secret_key(key)?;
+        let mut config = SignatureConfig::v4(
+            SignatureType::Binary,
+            secret_key.algorithm(),
+            HashAlgorithm::SHA2_256,
+        );
+        config.hashed_subpackets = vec![
+            Subpacket::regular(SubpacketData::SignatureCreationTime(
+                Utc::now().trunc_subsecs(0),
+            )),
+            Subpacket::regular(SubpacketData::IssuerFingerprint(secret_key.fingerprint())),
+        ];
+        config.unhashed_subpackets = vec![Subpacket::regular(SubpacketData::Issuer(
+            secret_key.key_id(),
+        ))];
+        let signature = config
+            .sign(&secret_key, String::new, data)
+            .map_err(NativeSigningError::OpenPgp)?;
+        let armored = StandaloneSignature::new(signature)
+            .to_armored_bytes(ArmorOptions::default())
+            .map_err(NativeSigningError::OpenPgp)?;
+        Ok(armored)
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification> {
+        let (signature, _) = StandaloneSignature::from_armor_single(signature)
+            .map_err(|_| SignError::InvalidSignatureFormat)?;
+        let issuers = signature.signature.issuer();
+        let Some(&issuer) = issuers.first() else {
+            return Ok(Verification::unknown());
+        };
+        let now = Utc::now();
+        for public_key in self.keyring() {
+            let status = if issuers.contains(&&public_key.primary_key.key_id()) {
+                let validity = primary_key_validity(public_key, now);
+                let status =
+                    self.verify_with_key(&signature, &public_key.primary_key, validity, data);
+                Some((status, public_key.primary_key.key_id()))
+            } else {
+                public_key
+                    .public_subkeys
+                    .iter()
+                    .find(|subkey| issuers.contains(&&subkey.key.key_id()))
+                    .map(|subkey| {
+                        let validity = subkey_validity(public_key, subkey, now);
+                        let status = self.verify_with_key(&signature, &subkey.key, validity, data);
+                        (status, subkey.key.key_id())
+                    })
+            };
+            match status {
+                Some((Some(status), key_id)) => {
+                    let display = public_key
+                        .details
+                        .users
+                        .first()
+                        .map(|user| user.id.id().to_string());
+                    return Ok(Verification::new(
+                        status,
+                        Some(format_key_id(&key_id)),
+                        display,
+                    ));
+                }
+                Some((None, _)) => return Ok(Verification::unknown()),
+                None => {}
+            }
+        }
+        Ok(Verification::new(
+            SigStatus::Unknown,
+            Some(format_key_id(issuer)),
+            None,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_allowed_signers() {
+        let signers = parse_allowed_signers(indoc::indoc! {r#"
+            # comment
+            alice@example.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h alice
+
+            bob@example.com,"carol example" namespaces="file,git" ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h
+            dave@example.com cert-authority,namespaces="file" ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h
+            invalid@example.com INVALID PUBLIC KEY
+        "#});
+        let summary: Vec<_> = signers
+            .iter()
+            .map(|signer| {
+                (
+                    signer.principals.clone(),
+                    signer.options.cert_authority,
+                    signer.options.namespaces.as_deref(),
+                    signer.allows_namespace("git"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (vec!["alice@example.com".to_owned()], false, None, true),
+                (
+                    vec!["bob@example.com".to_owned(), "carol example".to_owned()],
+                    false,
+                    Some("file,git"),
+                    true
+                ),
+                (
+                    vec!["dave@example.com".to_owned()],
+                    true,
+                    Some("file"),
+                    false
+                ),
+            ]
+        );
+        let key = signers[0].key.key_data();
+        let now = Utc::now();
+        assert!(signers[0].allows_key(key, "git", now));
+        assert!(signers[1].allows_key(key, "git", now));
+        assert!(!signers[1].allows_key(key, "file-not-listed", now));
+        // A certificate authority can't sign directly.
+        assert!(!signers[2].allows_key(key, "file", now));
+    }
+
+    #[test]
+    fn test_parse_allowed_signers_validity() {
+        let signers = parse_allowed_signers(indoc::indoc! {r#"
+            alice@example.com valid-after="20200102Z",valid-before="202001030405Z" ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h
+            bob@example.com valid-after=2020 ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h
+            carol@example.com unknown-option ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h
+        "#});
+        assert_eq!(signers.len(), 1);
+        let signer = &signers[0];
+        let time = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+        assert!(!signer.is_valid_at(time("2020-01-01T23:59:59Z")));
+        assert!(signer.is_valid_at(time("2020-01-02T00:00:00Z")));
+        assert!(signer.is_valid_at(time("2020-01-03T04:05:00Z")));
+        assert!(!signer.is_valid_at(time("2020-01-03T04:05:01Z")));
+    }
+}
@@ -987,6 +987,51 @@ impl MutableRepo {
         !self.parent_mapping.is_empty()
     }
 
+    /// Returns the commits that became visible in this transaction along with
+    /// the commits in the base repo they were rewritten from.
+    ///
+    /// Unlike `parent_mapping`, this is derived from the predecessors of the
+    /// new commits, so it also covers commits rewritten by
+    /// `rebase_descendants()`. Predecessors that were created and rewritten
+    /// again within this transaction are followed back to the base repo.
+    /// Commits that weren't rewritten from an existing commit are omitted.
+    pub fn rewritten_commits_since_base(&self) -> BackendResult<Vec<(CommitId, Vec<CommitId>)>> {
+        let base_heads = self.base_repo.view().heads().iter().cloned().collect();
+        let new_commit_ids: Vec<CommitId> = RevsetExpression::commits(base_heads)
+            .range(&RevsetExpression::visible_heads())
+            .evaluate(self)
+            .map_err(|err| err.into_backend_error())?
+            .iter()
+            .try_collect()
+            .map_err(|err| err.into_backend_error())?;
+        let base_index = self.base_repo.index();
+        let mut rewrites = vec![];
+        for new_id in new_commit_ids {
+            let new_commit = self.store().get_commit(&new_id)?;
+            let mut pending_ids = new_commit
+                .predecessor_ids()
+                .iter()
+                .rev()
+                .cloned()
+                .collect_vec();
+            let mut old_ids = vec![];
+            while let Some(id) = pending_ids.pop() {
+                if base_index.has_id(&id) {
+                    old_ids.push(id);
+                } else {
+                    let commit = self.store().get_commit(&id)?;
+                    pending_ids.extend(commit.predecessor_ids().iter().rev().cloned());
+                }
+            }
+            if !old_ids.is_empty() {
+                rewrites.push((new_id, old_ids));
+            }
+        }
+        // Oldest first, in the order the commits were (re)written
+        rewrites.reverse();
+        Ok(rewrites)
+    }
+
     /// Calculates new parents for a commit that's currently based on the given
     /// parents. It does that by considering how previous commits have been
     /// rewritten and abandoned.
@@ -21,6 +21,7 @@ use std::convert::Infallible;
 use std::fmt;
 use std::ops::Range;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use itertools::Itertools as _;
@@ -37,6 +38,7 @@ use crate::dsl_util::AliasExpandError as _;
 use crate::fileset;
 use crate::fileset::FilesetDiagnostics;
 use crate::fileset::FilesetExpression;
+use crate::fileset::FilesetExtensions;
 use crate::graph::GraphNode;
 use crate::hex_util::to_forward_hex;
 use crate::id_prefix::IdPrefixContext;
@@ -144,6 +146,7 @@ pub enum RevsetCommitRef {
     Tags(StringPattern),
     GitRefs,
     GitHead,
+    Shallow,
 }
 
 /// A custom revset filter expression, defined by an extension.
@@ -152,6 +155,14 @@ pub trait RevsetFilterExtension: std::fmt::Debug + Any {
 
     /// Returns true iff this filter matches the specified commit.
     fn matches_commit(&self, commit: &Commit) -> bool;
+
+    /// Returns true iff this filter matches the specified commit, or an error
+    /// if the filter couldn't be evaluated.
+    ///
+    /// The default implementation delegates to `matches_commit()`.
+    fn try_matches_commit(&self, commit: &Commit) -> Result<bool, RevsetEvaluationError> {
+        Ok(self.matches_commit(commit))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -181,14 +192,61 @@ pub enum RevsetFilterPredicate {
         text: StringPattern,
         files: FilesetExpression,
     },
+    /// Commits whose number of added and removed lines within the `files` is
+    /// in the range.
+    DiffLines {
+        range: Range<u64>,
+        files: FilesetExpression,
+    },
+    /// Commits whose number of modified files within the `files` is in the
+    /// range.
+    DiffFiles {
+        range: Range<u64>,
+        files: FilesetExpression,
+    },
+    /// Commits adding files specified by the fileset.
+    Added(FilesetExpression),
+    /// Commits deleting files specified by the fileset.
+    Deleted(FilesetExpression),
+    /// Commits renaming files to the paths specified by the fileset.
+    Renamed(FilesetExpression),
     /// Commits with conflicts
     HasConflict,
     /// Commits that are cryptographically signed.
     Signed,
+    /// Commits with a good signature from a key matching the pattern.
+    SignedBy(StringPattern),
     /// Custom predicates provided by extensions
     Extension(Rc<dyn RevsetFilterExtension>),
 }
 
+/// Key to order commits by in `sort()`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RevsetSortKey {
+    /// Newest committer timestamp first.
+    CommitterDate,
+    /// Newest author timestamp first.
+    AuthorDate,
+    /// Descriptions in lexicographical order.
+    Description,
+    /// Children before parents, which is the default iteration order.
+    Topological,
+}
+
+impl FromStr for RevsetSortKey {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "committer_date" => Ok(RevsetSortKey::CommitterDate),
+            "author_date" => Ok(RevsetSortKey::AuthorDate),
+            "description" => Ok(RevsetSortKey::Description),
+            "topological" => Ok(RevsetSortKey::Topological),
+            _ => Err(()),
+        }
+    }
+}
+
 mod private {
     /// Defines [`RevsetExpression`] variants depending on resolution state.
     pub trait ExpressionState {
@@ -238,6 +296,11 @@ pub enum RevsetExpression<St: ExpressionState> {
         heads: Rc<Self>,
         generation: Range<u64>,
     },
+    /// Ancestors of `heads` reachable by following first parents only.
+    FirstAncestors {
+        heads: Rc<Self>,
+        generation: Range<u64>,
+    },
     Descendants {
         roots: Rc<Self>,
         generation: Range<u64>,
@@ -262,10 +325,34 @@ pub enum RevsetExpression<St: ExpressionState> {
     Heads(Rc<Self>),
     Roots(Rc<Self>),
     ForkPoint(Rc<Self>),
+    /// Commits that `candidates` evolved from, transitively.
+    Predecessors(Rc<Self>),
+    /// Commits that evolved from `candidates`, transitively.
+    Successors(Rc<Self>),
+    /// Commits that have been rewritten into other commits.
+    Obsolete,
+    /// Indexed commits that aren't visible.
+    Hidden,
     Latest {
         candidates: Rc<Self>,
         count: usize,
     },
+    /// Commits in `candidates` ordered by `key`.
+    ///
+    /// The order is preserved only if the expression is evaluated at top
+    /// level or as the candidates of `Limit`. Set operations iterate commits
+    /// in topological order.
+    Sort {
+        candidates: Rc<Self>,
+        key: RevsetSortKey,
+        reverse: bool,
+    },
+    /// Up to `count` commits of `candidates` after skipping `offset` commits.
+    Limit {
+        candidates: Rc<Self>,
+        count: usize,
+        offset: usize,
+    },
     Filter(RevsetFilterPredicate),
     /// Marker for subtree that should be intersected as filter.
     AsFilter(Rc<Self>),
@@ -307,6 +394,14 @@ impl<St: ExpressionState> RevsetExpression<St> {
         Rc::new(Self::Root)
     }
 
+    pub fn obsolete() -> Rc<Self> {
+        Rc::new(Self::Obsolete)
+    }
+
+    pub fn hidden() -> Rc<Self> {
+        Rc::new(Self::Hidden)
+    }
+
     pub fn commit(commit_id: CommitId) -> Rc<Self> {
         Self::commits(vec![commit_id])
     }
@@ -371,6 +466,10 @@ impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
     pub fn git_head() -> Rc<Self> {
         Rc::new(Self::CommitRef(RevsetCommitRef::GitHead))
     }
+
+    pub fn shallow() -> Rc<Self> {
+        Rc::new(Self::CommitRef(RevsetCommitRef::Shallow))
+    }
 }
 
 // Compound expression
@@ -382,6 +481,25 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// Commits in `self` ordered by `key`.
+    pub fn sorted(self: &Rc<Self>, key: RevsetSortKey, reverse: bool) -> Rc<Self> {
+        Rc::new(Self::Sort {
+            candidates: self.clone(),
+            key,
+            reverse,
+        })
+    }
+
+    /// Up to `count` commits in `self` after skipping the first `offset`
+    /// commits.
+    pub fn limit(self: &Rc<Self>, count: usize, offset: usize) -> Rc<Self> {
+        Rc::new(Self::Limit {
+            candidates: self.clone(),
+            count,
+            offset,
+        })
+    }
+
     /// Commits in `self` that don't have descendants in `self`.
     pub fn heads(self: &Rc<Self>) -> Rc<Self> {
         Rc::new(Self::Heads(self.clone()))
@@ -416,6 +534,26 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// First parents of `self`.
+    pub fn first_parent(self: &Rc<Self>) -> Rc<Self> {
+        self.first_ancestors_range(1..2)
+    }
+
+    /// Ancestors of `self` reachable by following first parents only,
+    /// including `self`.
+    pub fn first_ancestors(self: &Rc<Self>) -> Rc<Self> {
+        self.first_ancestors_range(GENERATION_RANGE_FULL)
+    }
+
+    /// Ancestors of `self` reachable by following first parents only, in the
+    /// given range.
+    pub fn first_ancestors_range(self: &Rc<Self>, generation_range: Range<u64>) -> Rc<Self> {
+        Rc::new(Self::FirstAncestors {
+            heads: self.clone(),
+            generation: generation_range,
+        })
+    }
+
     /// Children of `self`.
     pub fn children(self: &Rc<Self>) -> Rc<Self> {
         self.descendants_at(1)
@@ -445,6 +583,16 @@ impl<St: ExpressionState> RevsetExpression<St> {
         Rc::new(Self::ForkPoint(self.clone()))
     }
 
+    /// Commits that `self` evolved from, not including `self`.
+    pub fn predecessors(self: &Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Predecessors(self.clone()))
+    }
+
+    /// Commits that evolved from `self`, not including `self`.
+    pub fn successors(self: &Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::Successors(self.clone()))
+    }
+
     /// Filter all commits by `predicate` in `self`.
     pub fn filtered(self: &Rc<Self>, predicate: RevsetFilterPredicate) -> Rc<Self> {
         self.intersection(&Self::filter(predicate))
@@ -614,6 +762,11 @@ pub enum ResolvedExpression {
         heads: Box<Self>,
         generation: Range<u64>,
     },
+    /// Ancestors of `heads` reachable by following first parents only.
+    FirstAncestors {
+        heads: Box<Self>,
+        generation: Range<u64>,
+    },
     /// Commits that are ancestors of `heads` but not ancestors of `roots`.
     Range {
         roots: Box<Self>,
@@ -634,10 +787,28 @@ pub enum ResolvedExpression {
     Heads(Box<Self>),
     Roots(Box<Self>),
     ForkPoint(Box<Self>),
+    /// Commits that `candidates` evolved from, transitively.
+    Predecessors(Box<Self>),
+    /// Commits that evolved from `candidates`, transitively.
+    Successors(Box<Self>),
+    /// Commits that are predecessors of any indexed commit.
+    Obsolete,
+    /// All indexed commits, including hidden ones.
+    Indexed,
     Latest {
         candidates: Box<Self>,
         count: usize,
     },
+    Sort {
+        candidates: Box<Self>,
+        key: RevsetSortKey,
+        reverse: bool,
+    },
+    Limit {
+        candidates: Box<Self>,
+        count: usize,
+        offset: usize,
+    },
     Coalesce(Box<Self>, Box<Self>),
     Union(Box<Self>, Box<Self>),
     /// Intersects `candidates` with `predicate` by filtering.
@@ -661,9 +832,19 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
     // code completion inside macro is quite restricted.
     let mut map: HashMap<&'static str, RevsetFunction> = HashMap::new();
     map.insert("parents", |diagnostics, function, context| {
-        let [arg] = function.expect_exact_arguments()?;
+        let ([arg], [first_parent_opt_arg]) =
+            function.expect_named_arguments(&["", "first_parent"])?;
         let expression = lower_expression(diagnostics, arg, context)?;
-        Ok(expression.parents())
+        let first_parent = if let Some(first_parent_arg) = first_parent_opt_arg {
+            expect_literal(diagnostics, "boolean", first_parent_arg)?
+        } else {
+            false
+        };
+        if first_parent {
+            Ok(expression.first_parent())
+        } else {
+            Ok(expression.parents())
+        }
     });
     map.insert("children", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
@@ -671,6 +852,27 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         Ok(expression.children())
     });
     map.insert("ancestors", |diagnostics, function, context| {
+        let ([heads_arg], [depth_opt_arg, first_parent_opt_arg]) =
+            function.expect_named_arguments(&["", "depth", "first_parent"])?;
+        let heads = lower_expression(diagnostics, heads_arg, context)?;
+        let generation = if let Some(depth_arg) = depth_opt_arg {
+            let depth = expect_literal(diagnostics, "integer", depth_arg)?;
+            0..depth
+        } else {
+            GENERATION_RANGE_FULL
+        };
+        let first_parent = if let Some(first_parent_arg) = first_parent_opt_arg {
+            expect_literal(diagnostics, "boolean", first_parent_arg)?
+        } else {
+            false
+        };
+        if first_parent {
+            Ok(heads.first_ancestors_range(generation))
+        } else {
+            Ok(heads.ancestors_range(generation))
+        }
+    });
+    map.insert("first_ancestors", |diagnostics, function, context| {
         let ([heads_arg], [depth_opt_arg]) = function.expect_arguments()?;
         let heads = lower_expression(diagnostics, heads_arg, context)?;
         let generation = if let Some(depth_arg) = depth_opt_arg {
@@ -679,7 +881,7 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         } else {
             GENERATION_RANGE_FULL
         };
-        Ok(heads.ancestors_range(generation))
+        Ok(heads.first_ancestors_range(generation))
     });
     map.insert("descendants", |diagnostics, function, context| {
         let ([roots_arg], [depth_opt_arg]) = function.expect_arguments()?;
@@ -774,6 +976,28 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         function.expect_no_arguments()?;
         Ok(RevsetExpression::git_head())
     });
+    map.insert("shallow", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::shallow())
+    });
+    map.insert("predecessors", |diagnostics, function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = lower_expression(diagnostics, arg, context)?;
+        Ok(expression.predecessors())
+    });
+    map.insert("successors", |diagnostics, function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = lower_expression(diagnostics, arg, context)?;
+        Ok(expression.successors())
+    });
+    map.insert("obsolete", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::obsolete())
+    });
+    map.insert("hidden", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::hidden())
+    });
     map.insert("latest", |diagnostics, function, context| {
         let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
         let candidates = lower_expression(diagnostics, candidates_arg, context)?;
@@ -784,6 +1008,30 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         };
         Ok(candidates.latest(count))
     });
+    map.insert("sort", |diagnostics, function, context| {
+        let ([candidates_arg, key_arg], [reverse_opt_arg]) =
+            function.expect_named_arguments(&["", "by", "reverse"])?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let key = expect_literal(diagnostics, "sort key", key_arg)?;
+        let reverse = if let Some(reverse_arg) = reverse_opt_arg {
+            expect_literal(diagnostics, "boolean", reverse_arg)?
+        } else {
+            false
+        };
+        Ok(candidates.sorted(key, reverse))
+    });
+    map.insert("limit", |diagnostics, function, context| {
+        let ([candidates_arg, count_arg], [offset_opt_arg]) =
+            function.expect_named_arguments(&["", "count", "offset"])?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let count = expect_literal(diagnostics, "integer", count_arg)?;
+        let offset = if let Some(offset_arg) = offset_opt_arg {
+            expect_literal(diagnostics, "integer", offset_arg)?
+        } else {
+            0
+        };
+        Ok(candidates.limit(count, offset))
+    });
     map.insert("fork_point", |diagnostics, function, context| {
         let [expression_arg] = function.expect_exact_arguments()?;
         let expression = lower_expression(diagnostics, expression_arg, context)?;
@@ -835,9 +1083,15 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
             pattern,
         )))
     });
-    map.insert("signed", |_diagnostics, function, _context| {
-        function.expect_no_arguments()?;
-        let predicate = RevsetFilterPredicate::Signed;
+    map.insert("signed", |diagnostics, function, _context| {
+        let ([], [key_opt_arg]) = function.expect_named_arguments(&["key"])?;
+        let predicate = if let Some(key_arg) = key_opt_arg {
+            // Key IDs are identifiers, so they're matched exactly by default.
+            let pattern = expect_exact_string_pattern(diagnostics, key_arg)?;
+            RevsetFilterPredicate::SignedBy(pattern)
+        } else {
+            RevsetFilterPredicate::Signed
+        };
         Ok(RevsetExpression::filter(predicate))
     });
     map.insert("mine", |_diagnostics, function, context| {
@@ -888,29 +1142,61 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
             )
         })?;
         let [arg] = function.expect_exact_arguments()?;
-        let expr = expect_fileset_expression(diagnostics, arg, ctx.path_converter)?;
+        let expr = expect_fileset_expression(
+            diagnostics,
+            arg,
+            ctx.path_converter,
+            context.extensions.fileset_extensions(),
+        )?;
         Ok(RevsetExpression::filter(RevsetFilterPredicate::File(expr)))
     });
     map.insert("diff_contains", |diagnostics, function, context| {
         let ([text_arg], [files_opt_arg]) = function.expect_arguments()?;
         let text = expect_string_pattern(diagnostics, text_arg)?;
-        let files = if let Some(files_arg) = files_opt_arg {
-            let ctx = context.workspace.as_ref().ok_or_else(|| {
-                RevsetParseError::with_span(
-                    RevsetParseErrorKind::FsPathWithoutWorkspace,
-                    files_arg.span,
-                )
-            })?;
-            expect_fileset_expression(diagnostics, files_arg, ctx.path_converter)?
-        } else {
-            // TODO: defaults to CLI path arguments?
-            // https://github.com/jj-vcs/jj/issues/2933#issuecomment-1925870731
-            FilesetExpression::all()
-        };
+        let files = lower_optional_files_argument(diagnostics, files_opt_arg, context)?;
         Ok(RevsetExpression::filter(
             RevsetFilterPredicate::DiffContains { text, files },
         ))
     });
+    map.insert("diff_lines", |diagnostics, function, context| {
+        let ([range_arg], [files_opt_arg]) = function.expect_arguments()?;
+        let range = expect_count_range(diagnostics, range_arg)?;
+        let files = lower_optional_files_argument(diagnostics, files_opt_arg, context)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::DiffLines {
+            range,
+            files,
+        }))
+    });
+    map.insert("diff_files", |diagnostics, function, context| {
+        let ([range_arg], [files_opt_arg]) = function.expect_arguments()?;
+        let range = expect_count_range(diagnostics, range_arg)?;
+        let files = lower_optional_files_argument(diagnostics, files_opt_arg, context)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::DiffFiles {
+            range,
+            files,
+        }))
+    });
+    map.insert("added", |diagnostics, function, context| {
+        let ([], [files_opt_arg]) = function.expect_arguments()?;
+        let files = lower_optional_files_argument(diagnostics, files_opt_arg, context)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Added(
+            files,
+        )))
+    });
+    map.insert("deleted", |diagnostics, function, context| {
+        let ([], [files_opt_arg]) = function.expect_arguments()?;
+        let files = lower_optional_files_argument(diagnostics, files_opt_arg, context)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Deleted(
+            files,
+        )))
+    });
+    map.insert("renamed", |diagnostics, function, context| {
+        let ([], [files_opt_arg]) = function.expect_arguments()?;
+        let files = lower_optional_files_argument(diagnostics, files_opt_arg, context)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Renamed(
+            files,
+        )))
+    });
     map.insert("conflicts", |_diagnostics, function, _context| {
         function.expect_no_arguments()?;
         Ok(RevsetExpression::filter(RevsetFilterPredicate::HasConflict))
@@ -949,16 +1235,22 @@ pub fn expect_fileset_expression(
     diagnostics: &mut RevsetDiagnostics,
     node: &ExpressionNode,
     path_converter: &RepoPathUiConverter,
+    extensions: &FilesetExtensions,
 ) -> Result<FilesetExpression, RevsetParseError> {
     // Alias handling is a bit tricky. The outermost expression `alias` is
     // substituted, but inner expressions `x & alias` aren't. If this seemed
     // weird, we can either transform AST or turn off revset aliases completely.
     revset_parser::expect_expression_with(diagnostics, node, |diagnostics, node| {
         let mut inner_diagnostics = FilesetDiagnostics::new();
-        let expression = fileset::parse(&mut inner_diagnostics, node.span.as_str(), path_converter)
-            .map_err(|err| {
-                RevsetParseError::expression("In fileset expression", node.span).with_source(err)
-            })?;
+        let expression = fileset::parse(
+            &mut inner_diagnostics,
+            node.span.as_str(),
+            path_converter,
+            extensions,
+        )
+        .map_err(|err| {
+            RevsetParseError::expression("In fileset expression", node.span).with_source(err)
+        })?;
         diagnostics.extend_with(inner_diagnostics, |diag| {
             RevsetParseError::expression("In fileset expression", node.span).with_source(diag)
         });
@@ -966,6 +1258,52 @@ pub fn expect_fileset_expression(
     })
 }
 
+/// Parses the optional `files` argument of diff predicates. Defaults to
+/// `all()`.
+fn lower_optional_files_argument(
+    diagnostics: &mut RevsetDiagnostics,
+    files_opt_arg: Option<&ExpressionNode>,
+    context: &LoweringContext,
+) -> Result<FilesetExpression, RevsetParseError> {
+    if let Some(files_arg) = files_opt_arg {
+        let ctx = context.workspace.as_ref().ok_or_else(|| {
+            RevsetParseError::with_span(
+                RevsetParseErrorKind::FsPathWithoutWorkspace,
+                files_arg.span,
+            )
+        })?;
+        expect_fileset_expression(
+            diagnostics,
+            files_arg,
+            ctx.path_converter,
+            context.extensions.fileset_extensions(),
+        )
+    } else {
+        // TODO: defaults to CLI path arguments?
+        // https://github.com/jj-vcs/jj/issues/2933#issuecomment-1925870731
+        Ok(FilesetExpression::all())
+    }
+}
+
+/// Parses the given `node` as a count comparison such as `">500"`.
+fn expect_count_range(
+    diagnostics: &mut RevsetDiagnostics,
+    node: &ExpressionNode,
+) -> Result<Range<u64>, RevsetParseError> {
+    revset_parser::expect_pattern_with(
+        diagnostics,
+        "count range",
+        node,
+        |_diagnostics, value, kind| match kind {
+            Some(_) => Err("Count range must not have pattern prefix".to_owned()),
+            None => dsl_util::parse_comparison_range(value, |text| {
+                text.parse()
+                    .map_err(|err| format!("Invalid count {text:?}: {err}"))
+            }),
+        },
+    )
+}
+
 pub fn expect_string_pattern(
     diagnostics: &mut RevsetDiagnostics,
     node: &ExpressionNode,
@@ -981,6 +1319,21 @@ pub fn expect_string_pattern(
     )
 }
 
+fn expect_exact_string_pattern(
+    diagnostics: &mut RevsetDiagnostics,
+    node: &ExpressionNode,
+) -> Result<StringPattern, RevsetParseError> {
+    revset_parser::expect_pattern_with(
+        diagnostics,
+        "string pattern",
+        node,
+        |_diagnostics, value, kind| match kind {
+            Some(kind) => StringPattern::from_str_kind(value, kind),
+            None => Ok(StringPattern::exact(value)),
+        },
+    )
+}
+
 pub fn expect_date_pattern(
     diagnostics: &mut RevsetDiagnostics,
     node: &ExpressionNode,
@@ -991,10 +1344,7 @@ pub fn expect_date_pattern(
         "date pattern",
         node,
         |_diagnostics, value, kind| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
-            match kind {
-                None => Err("Date pattern must specify 'after' or 'before'".into()),
-                Some(kind) => Ok(context.parse_relative(value, kind)?),
-            }
+            Ok(context.parse_relative(value, kind.unwrap_or("date"))?)
         },
     )
 }
@@ -1029,14 +1379,16 @@ fn lower_function_call(
     function: &FunctionCallNode,
     context: &LoweringContext,
 ) -> Result<Rc<UserRevsetExpression>, RevsetParseError> {
-    let function_map = &context.extensions.function_map;
-    if let Some(func) = function_map.get(function.name) {
+    let extensions = context.extensions;
+    if let Some(func) = extensions.function_map.get(function.name) {
         func(diagnostics, function, context)
+    } else if let Some(func) = extensions.function_extensions.get(function.name) {
+        func.lower(diagnostics, function, context)
     } else {
         Err(RevsetParseError::with_span(
             RevsetParseErrorKind::NoSuchFunction {
                 name: function.name.to_owned(),
-                candidates: collect_similar(function.name, function_map.keys()),
+                candidates: collect_similar(function.name, extensions.function_names()),
             },
             function.name_span,
         ))
@@ -1214,6 +1566,12 @@ fn try_transform_expression<St: ExpressionState, E>(
                     heads,
                     generation: generation.clone(),
                 }),
+            RevsetExpression::FirstAncestors { heads, generation } => {
+                transform_rec(heads, pre, post)?.map(|heads| RevsetExpression::FirstAncestors {
+                    heads,
+                    generation: generation.clone(),
+                })
+            }
             RevsetExpression::Descendants { roots, generation } => transform_rec(roots, pre, post)?
                 .map(|roots| RevsetExpression::Descendants {
                     roots,
@@ -1247,11 +1605,37 @@ fn try_transform_expression<St: ExpressionState, E>(
             RevsetExpression::ForkPoint(expression) => {
                 transform_rec(expression, pre, post)?.map(RevsetExpression::ForkPoint)
             }
+            RevsetExpression::Predecessors(expression) => {
+                transform_rec(expression, pre, post)?.map(RevsetExpression::Predecessors)
+            }
+            RevsetExpression::Successors(expression) => {
+                transform_rec(expression, pre, post)?.map(RevsetExpression::Successors)
+            }
+            RevsetExpression::Obsolete => None,
+            RevsetExpression::Hidden => None,
             RevsetExpression::Latest { candidates, count } => transform_rec(candidates, pre, post)?
                 .map(|candidates| RevsetExpression::Latest {
                     candidates,
                     count: *count,
                 }),
+            RevsetExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            } => transform_rec(candidates, pre, post)?.map(|candidates| RevsetExpression::Sort {
+                candidates,
+                key: *key,
+                reverse: *reverse,
+            }),
+            RevsetExpression::Limit {
+                candidates,
+                count,
+                offset,
+            } => transform_rec(candidates, pre, post)?.map(|candidates| RevsetExpression::Limit {
+                candidates,
+                count: *count,
+                offset: *offset,
+            }),
             RevsetExpression::Filter(_) => None,
             RevsetExpression::AsFilter(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::AsFilter)
@@ -1399,6 +1783,11 @@ where
             let generation = generation.clone();
             RevsetExpression::Ancestors { heads, generation }.into()
         }
+        RevsetExpression::FirstAncestors { heads, generation } => {
+            let heads = folder.fold_expression(heads)?;
+            let generation = generation.clone();
+            RevsetExpression::FirstAncestors { heads, generation }.into()
+        }
         RevsetExpression::Descendants { roots, generation } => {
             let roots = folder.fold_expression(roots)?;
             let generation = generation.clone();
@@ -1441,11 +1830,51 @@ where
             let expression = folder.fold_expression(expression)?;
             RevsetExpression::ForkPoint(expression).into()
         }
+        RevsetExpression::Predecessors(expression) => {
+            let expression = folder.fold_expression(expression)?;
+            RevsetExpression::Predecessors(expression).into()
+        }
+        RevsetExpression::Successors(expression) => {
+            let expression = folder.fold_expression(expression)?;
+            RevsetExpression::Successors(expression).into()
+        }
+        RevsetExpression::Obsolete => RevsetExpression::Obsolete.into(),
+        RevsetExpression::Hidden => RevsetExpression::Hidden.into(),
         RevsetExpression::Latest { candidates, count } => {
             let candidates = folder.fold_expression(candidates)?;
             let count = *count;
             RevsetExpression::Latest { candidates, count }.into()
         }
+        RevsetExpression::Sort {
+            candidates,
+            key,
+            reverse,
+        } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let key = *key;
+            let reverse = *reverse;
+            RevsetExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            }
+            .into()
+        }
+        RevsetExpression::Limit {
+            candidates,
+            count,
+            offset,
+        } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let count = *count;
+            let offset = *offset;
+            RevsetExpression::Limit {
+                candidates,
+                count,
+                offset,
+            }
+            .into()
+        }
         RevsetExpression::Filter(predicate) => RevsetExpression::Filter(predicate.clone()).into(),
         RevsetExpression::AsFilter(candidates) => {
             let candidates = folder.fold_expression(candidates)?;
@@ -2161,6 +2590,15 @@ fn resolve_commit_ref(
             Ok(commit_ids)
         }
         RevsetCommitRef::GitHead => Ok(repo.view().git_head().added_ids().cloned().collect()),
+        RevsetCommitRef::Shallow => {
+            // The shallow file may list commits that aren't imported yet.
+            let commit_ids = crate::git::shallow_commit_ids(repo.store())
+                .map_err(RevsetResolutionError::Backend)?
+                .into_iter()
+                .filter(|id| repo.index().has_id(id))
+                .collect();
+            Ok(commit_ids)
+        }
     }
 }
 
@@ -2291,6 +2729,12 @@ impl VisibilityResolutionContext<'_> {
                 heads: self.resolve(heads).into(),
                 generation: generation.clone(),
             },
+            RevsetExpression::FirstAncestors { heads, generation } => {
+                ResolvedExpression::FirstAncestors {
+                    heads: self.resolve(heads).into(),
+                    generation: generation.clone(),
+                }
+            }
             RevsetExpression::Descendants { roots, generation } => ResolvedExpression::DagRange {
                 roots: self.resolve(roots).into(),
                 heads: self.resolve_visible_heads().into(),
@@ -2323,10 +2767,39 @@ impl VisibilityResolutionContext<'_> {
             RevsetExpression::ForkPoint(expression) => {
                 ResolvedExpression::ForkPoint(self.resolve(expression).into())
             }
+            RevsetExpression::Predecessors(expression) => {
+                ResolvedExpression::Predecessors(self.resolve(expression).into())
+            }
+            RevsetExpression::Successors(expression) => {
+                ResolvedExpression::Successors(self.resolve(expression).into())
+            }
+            RevsetExpression::Obsolete => ResolvedExpression::Obsolete,
+            RevsetExpression::Hidden => ResolvedExpression::Difference(
+                ResolvedExpression::Indexed.into(),
+                self.resolve_all().into(),
+            ),
             RevsetExpression::Latest { candidates, count } => ResolvedExpression::Latest {
                 candidates: self.resolve(candidates).into(),
                 count: *count,
             },
+            RevsetExpression::Sort {
+                candidates,
+                key,
+                reverse,
+            } => ResolvedExpression::Sort {
+                candidates: self.resolve(candidates).into(),
+                key: *key,
+                reverse: *reverse,
+            },
+            RevsetExpression::Limit {
+                candidates,
+                count,
+                offset,
+            } => ResolvedExpression::Limit {
+                candidates: self.resolve(candidates).into(),
+                count: *count,
+                offset: *offset,
+            },
             RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_) => {
                 // Top-level filter without intersection: e.g. "~author(_)" is represented as
                 // `AsFilter(NotIn(Filter(Author(_))))`.
@@ -2421,6 +2894,7 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::Commits(_)
             | RevsetExpression::CommitRef(_)
             | RevsetExpression::Ancestors { .. }
+            | RevsetExpression::FirstAncestors { .. }
             | RevsetExpression::Descendants { .. }
             | RevsetExpression::Range { .. }
             | RevsetExpression::DagRange { .. }
@@ -2428,7 +2902,13 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::Heads(_)
             | RevsetExpression::Roots(_)
             | RevsetExpression::ForkPoint(_)
-            | RevsetExpression::Latest { .. } => {
+            | RevsetExpression::Predecessors(_)
+            | RevsetExpression::Successors(_)
+            | RevsetExpression::Obsolete
+            | RevsetExpression::Hidden
+            | RevsetExpression::Latest { .. }
+            | RevsetExpression::Sort { .. }
+            | RevsetExpression::Limit { .. } => {
                 ResolvedPredicateExpression::Set(self.resolve(expression).into())
             }
             RevsetExpression::Filter(predicate) => {
@@ -2468,6 +2948,18 @@ pub trait Revset: fmt::Debug {
     where
         Self: 'a;
 
+    /// Iterates in the order requested by `sort()` and `limit()` at the top
+    /// level of the expression, or in topological order like [`Self::iter()`]
+    /// otherwise.
+    fn iter_ordered<'a>(
+        &self,
+    ) -> Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>> + 'a>
+    where
+        Self: 'a,
+    {
+        self.iter()
+    }
+
     /// Iterates commit/change id pairs in topological order.
     fn commit_change_ids<'a>(
         &self,
@@ -2536,10 +3028,26 @@ impl<I: Iterator<Item = Result<CommitId, RevsetEvaluationError>>> Iterator
     }
 }
 
+/// Revset function defined at runtime, e.g. from user configuration.
+///
+/// Unlike [`RevsetFunction`], an implementation of this trait can carry state
+/// such as a cache shared by all calls of the function.
+pub trait RevsetFunctionExtension {
+    /// Transforms the function call into expression.
+    fn lower(
+        &self,
+        diagnostics: &mut RevsetDiagnostics,
+        function: &FunctionCallNode,
+        context: &LoweringContext,
+    ) -> Result<Rc<UserRevsetExpression>, RevsetParseError>;
+}
+
 /// A set of extensions for revset evaluation.
 pub struct RevsetExtensions {
     symbol_resolvers: Vec<Box<dyn SymbolResolverExtension>>,
     function_map: HashMap<&'static str, RevsetFunction>,
+    function_extensions: HashMap<String, Box<dyn RevsetFunctionExtension>>,
+    fileset_extensions: FilesetExtensions,
 }
 
 impl Default for RevsetExtensions {
@@ -2553,6 +3061,8 @@ impl RevsetExtensions {
         Self {
             symbol_resolvers: vec![],
             function_map: BUILTIN_FUNCTION_MAP.clone(),
+            function_extensions: HashMap::new(),
+            fileset_extensions: FilesetExtensions::new(),
         }
     }
 
@@ -2565,6 +3075,9 @@ impl RevsetExtensions {
     }
 
     pub fn add_custom_function(&mut self, name: &'static str, func: RevsetFunction) {
+        if self.function_extensions.contains_key(name) {
+            panic!("Conflict registering revset function '{name}'");
+        }
         match self.function_map.entry(name) {
             hash_map::Entry::Occupied(_) => {
                 panic!("Conflict registering revset function '{name}'")
@@ -2572,6 +3085,42 @@ impl RevsetExtensions {
             hash_map::Entry::Vacant(v) => v.insert(func),
         };
     }
+
+    /// Registers function defined at runtime. Returns the function back if
+    /// the `name` is already taken.
+    pub fn add_function_extension(
+        &mut self,
+        name: impl Into<String>,
+        func: Box<dyn RevsetFunctionExtension>,
+    ) -> Result<(), Box<dyn RevsetFunctionExtension>> {
+        let name = name.into();
+        if self.function_map.contains_key(name.as_str()) {
+            return Err(func);
+        }
+        match self.function_extensions.entry(name) {
+            hash_map::Entry::Occupied(_) => Err(func),
+            hash_map::Entry::Vacant(v) => {
+                v.insert(func);
+                Ok(())
+            }
+        }
+    }
+
+    /// Iterates names of all functions including the built-in ones.
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        let static_names = self.function_map.keys().copied();
+        let extension_names = self.function_extensions.keys().map(String::as_str);
+        static_names.chain(extension_names)
+    }
+
+    /// Extensions to fileset expressions embedded in revsets.
+    pub fn fileset_extensions(&self) -> &FilesetExtensions {
+        &self.fileset_extensions
+    }
+
+    pub fn fileset_extensions_mut(&mut self) -> &mut FilesetExtensions {
+        &mut self.fileset_extensions
+    }
 }
 
 /// Information needed to parse revset expression.
@@ -3202,12 +3751,7 @@ mod tests {
         }
         "#);
         insta::assert_debug_snapshot!(
-            parse("parents(foo,foo)").unwrap_err().kind(), @r#"
-        InvalidFunctionArguments {
-            name: "parents",
-            message: "Expected 1 arguments",
-        }
-        "#);
+            parse("parents(foo,foo)").unwrap_err().kind(), @r#"Expression("Expected expression of type boolean")"#);
         insta::assert_debug_snapshot!(
             parse("root()").unwrap(),
             @"Root");
@@ -3256,6 +3800,147 @@ mod tests {
         )
         "#);
         insta::assert_debug_snapshot!(parse("signed()").unwrap(), @"Filter(Signed)");
+        insta::assert_debug_snapshot!(
+            parse("signed(key=ABCD)").unwrap(),
+            @r#"Filter(SignedBy(Exact("ABCD")))"#);
+        insta::assert_debug_snapshot!(
+            parse("signed(key=substring:ABCD)").unwrap(),
+            @r#"Filter(SignedBy(Substring("ABCD")))"#);
+        insta::assert_debug_snapshot!(
+            parse("signed(key=exact:'')").unwrap(),
+            @r#"Filter(SignedBy(Exact("")))"#);
+        insta::assert_debug_snapshot!(parse("first_ancestors(foo)").unwrap(), @r#"
+        FirstAncestors {
+            heads: CommitRef(Symbol("foo")),
+            generation: 0..18446744073709551615,
+        }
+        "#);
+        insta::assert_debug_snapshot!(parse("first_ancestors(foo, 3)").unwrap(), @r#"
+        FirstAncestors {
+            heads: CommitRef(Symbol("foo")),
+            generation: 0..3,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("ancestors(foo, first_parent=true)").unwrap(), @r#"
+        FirstAncestors {
+            heads: CommitRef(Symbol("foo")),
+            generation: 0..18446744073709551615,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("parents(foo, first_parent=true)").unwrap(), @r#"
+        FirstAncestors {
+            heads: CommitRef(Symbol("foo")),
+            generation: 1..2,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("parents(foo, first_parent=1)").unwrap_err().kind(),
+            @r#"Expression("Expected expression of type boolean")"#);
+    }
+
+    #[test]
+    fn test_parse_revset_sort_limit_functions() {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+
+        insta::assert_debug_snapshot!(parse("sort(foo, committer_date)").unwrap(), @r#"
+        Sort {
+            candidates: CommitRef(Symbol("foo")),
+            key: CommitterDate,
+            reverse: false,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("sort(foo, by=description, reverse=true)").unwrap(), @r#"
+        Sort {
+            candidates: CommitRef(Symbol("foo")),
+            key: Description,
+            reverse: true,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("sort(foo, by=size)").unwrap_err().kind(),
+            @r#"Expression("Expected expression of type sort key")"#);
+        insta::assert_debug_snapshot!(
+            parse("sort(foo, author_date, reverse=yes)").unwrap_err().kind(),
+            @r#"Expression("Expected expression of type boolean")"#);
+        insta::assert_debug_snapshot!(parse("sort(foo)").unwrap_err().kind(), @r#"
+        InvalidFunctionArguments {
+            name: "sort",
+            message: "Expected 2 to 3 arguments",
+        }
+        "#);
+        insta::assert_debug_snapshot!(parse("limit(foo, 10)").unwrap(), @r#"
+        Limit {
+            candidates: CommitRef(Symbol("foo")),
+            count: 10,
+            offset: 0,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("limit(sort(foo, topological), count=10, offset=20)").unwrap(), @r#"
+        Limit {
+            candidates: Sort {
+                candidates: CommitRef(Symbol("foo")),
+                key: Topological,
+                reverse: false,
+            },
+            count: 10,
+            offset: 20,
+        }
+        "#);
+        insta::assert_debug_snapshot!(parse("limit(foo, -1)").unwrap_err().kind(), @"SyntaxError");
+    }
+
+    #[test]
+    fn test_parse_revset_diff_stat_functions() {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+
+        insta::assert_debug_snapshot!(parse(r#"diff_lines(">500")"#).unwrap(), @r"
+        Filter(
+            DiffLines {
+                range: 501..18446744073709551615,
+                files: All,
+            },
+        )
+        ");
+        insta::assert_debug_snapshot!(
+            parse_with_workspace(r#"diff_files("<= 2", foo)"#, WorkspaceName::DEFAULT).unwrap(),
+            @r#"
+        Filter(
+            DiffFiles {
+                range: 0..3,
+                files: Pattern(PrefixPath("foo")),
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(parse("diff_files(3)").unwrap(), @r"
+        Filter(
+            DiffFiles {
+                range: 3..4,
+                files: All,
+            },
+        )
+        ");
+        insta::assert_debug_snapshot!(
+            parse(r#"diff_lines(">x")"#).unwrap_err().kind(),
+            @r#"Expression("Invalid count range")"#);
+        insta::assert_debug_snapshot!(
+            parse(r#"diff_lines(glob:">1")"#).unwrap_err().kind(),
+            @r#"Expression("Invalid count range")"#);
+        insta::assert_debug_snapshot!(
+            parse("diff_lines(1, foo)").unwrap_err().kind(),
+            @"FsPathWithoutWorkspace");
+        insta::assert_debug_snapshot!(parse("added()").unwrap(), @"Filter(Added(All))");
+        insta::assert_debug_snapshot!(
+            parse_with_workspace("deleted(foo)", WorkspaceName::DEFAULT).unwrap(),
+            @r#"Filter(Deleted(Pattern(PrefixPath("foo"))))"#);
+        insta::assert_debug_snapshot!(
+            parse_with_workspace("renamed(foo)", WorkspaceName::DEFAULT).unwrap(),
+            @r#"Filter(Renamed(Pattern(PrefixPath("foo"))))"#);
     }
 
     #[test]
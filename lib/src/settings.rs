@@ -63,6 +63,12 @@ pub struct GitSettings {
     pub abandon_unreachable_commits: bool,
     pub executable_path: PathBuf,
     pub write_change_id_header: bool,
+    /// Namespaces of Git notes that follow rewritten commits.
+    pub notes_namespaces: Vec<String>,
+    /// Whether the notes refs of `notes_namespaces` are fetched.
+    pub fetch_notes: bool,
+    /// Whether the notes refs of `notes_namespaces` are pushed.
+    pub push_notes: bool,
 }
 
 impl GitSettings {
@@ -72,6 +78,9 @@ impl GitSettings {
             abandon_unreachable_commits: settings.get_bool("git.abandon-unreachable-commits")?,
             executable_path: settings.get("git.executable-path")?,
             write_change_id_header: settings.get("git.write-change-id-header")?,
+            notes_namespaces: settings.get("git.notes-namespaces")?,
+            fetch_notes: settings.get_bool("git.fetch-notes")?,
+            push_notes: settings.get_bool("git.push-notes")?,
         })
     }
 }
@@ -83,6 +92,9 @@ impl Default for GitSettings {
             abandon_unreachable_commits: true,
             executable_path: PathBuf::from("git"),
             write_change_id_header: true,
+            notes_namespaces: vec!["commits".to_owned()],
+            fetch_notes: false,
+            push_notes: false,
         }
     }
 }
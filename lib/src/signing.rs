@@ -26,11 +26,16 @@ use crate::backend::CommitId;
 use crate::config::ConfigGetError;
 use crate::gpg_signing::GpgBackend;
 use crate::gpg_signing::GpgsmBackend;
+#[cfg(feature = "native-signing")]
+use crate::native_signing::GpgNativeBackend;
+#[cfg(feature = "native-signing")]
+use crate::native_signing::SshNativeBackend;
 use crate::settings::UserSettings;
 use crate::ssh_signing::SshBackend;
 use crate::store::COMMIT_CACHE_CAPACITY;
 #[cfg(feature = "testing")]
 use crate::test_signing_backend::TestSigningBackend;
+use crate::x509_signing::X509Backend;
 
 /// A status of the signature, part of the [Verification] type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -178,17 +183,32 @@ impl Signer {
     /// Creates a signer based on user settings. Uses all known backends, and
     /// chooses one of them to be used for signing depending on the config.
     pub fn from_settings(settings: &UserSettings) -> Result<Self, SignInitError> {
+        let backend_name = settings
+            .signing_backend()
+            .map_err(SignInitError::BackendConfig)?;
         let mut backends: Vec<Box<dyn SigningBackend>> = vec![
             Box::new(GpgBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
             Box::new(GpgsmBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
             Box::new(SshBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
+            Box::new(X509Backend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
             #[cfg(feature = "testing")]
             Box::new(TestSigningBackend),
         ];
+        // The in-process backends don't implement everything the external
+        // programs do (e.g. trust databases), so they're only used when
+        // explicitly configured.
+        #[cfg(feature = "native-signing")]
+        match backend_name.as_deref() {
+            Some("gpg-native") => backends.push(Box::new(
+                GpgNativeBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?,
+            )),
+            Some("ssh-native") => backends.push(Box::new(
+                SshNativeBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?,
+            )),
+            _ => {}
+        }
 
-        let main_backend = settings
-            .signing_backend()
-            .map_err(SignInitError::BackendConfig)?
+        let main_backend = backend_name
             .map(|backend| {
                 backends
                     .iter()
@@ -240,19 +260,33 @@ impl Signer {
             return Ok(check);
         }
 
-        let verification = self
+        // Skip unknown and invalid sigs to allow other backends that can read
+        // to try. For example, we might have gpg and sq, both of which could
+        // read a PGP signature. If no backend knows the signature, keep the
+        // unknown result with details (e.g. why a certificate isn't trusted)
+        // from the first backend that recognized it.
+        let mut unknown = None;
+        let mut verification = None;
+        for backend in self
             .main_backend
             .iter()
             .chain(self.backends.iter())
             .filter(|b| b.can_read(signature))
-            // skip unknown and invalid sigs to allow other backends that can read to try
-            // for example, we might have gpg and sq, both of which could read a PGP signature
-            .find_map(|backend| match backend.verify(data, signature) {
-                Ok(check) if check.status == SigStatus::Unknown => None,
-                Err(SignError::InvalidSignatureFormat) => None,
-                e => Some(e),
-            })
-            .transpose()?;
+        {
+            match backend.verify(data, signature) {
+                Ok(check) if check.status == SigStatus::Unknown => {
+                    if unknown.is_none() && (check.key.is_some() || check.display.is_some()) {
+                        unknown = Some(check);
+                    }
+                }
+                Err(SignError::InvalidSignatureFormat) => {}
+                result => {
+                    verification = Some(result?);
+                    break;
+                }
+            }
+        }
+        let verification = verification.or(unknown);
 
         if let Some(verification) = verification {
             // a key might get imported before next call?.
@@ -78,7 +78,13 @@ pub enum StringPattern {
     GlobI(GlobPattern),
     /// Matches substrings with a regular expression.
     Regex(regex::Regex),
-    // TODO: Should we add RegexI and "regex-i" prefix?
+    /// Matches substrings with a case‐insensitive regular expression.
+    RegexI(regex::Regex),
+    /// Matches strings containing a line that matches exactly.
+    ExactLine(String),
+    /// Matches strings containing a line that matches a Unix‐style shell
+    /// wildcard pattern.
+    LineGlob(GlobPattern),
 }
 
 impl StringPattern {
@@ -89,8 +95,9 @@ impl StringPattern {
 
     /// Parses the given string as a [`StringPattern`]. Everything before the
     /// first ":" is considered the string's prefix. If the prefix is
-    /// "exact[-i]:", "glob[-i]:", or "substring[-i]:", a pattern of the
-    /// specified kind is returned. Returns an error if the string has an
+    /// "exact[-i]:", "glob[-i]:", "substring[-i]:", "regex[-i]:",
+    /// "exact-line:", or "line-glob:", a pattern of the specified kind is
+    /// returned. Returns an error if the string has an
     /// unrecognized prefix. Otherwise, a `StringPattern::Exact` is
     /// returned.
     pub fn parse(src: &str) -> Result<StringPattern, StringPatternParseError> {
@@ -140,6 +147,25 @@ impl StringPattern {
         Ok(StringPattern::Regex(pattern))
     }
 
+    /// Parses the given string as a case‐insensitive regular expression.
+    pub fn regex_i(src: &str) -> Result<Self, StringPatternParseError> {
+        let pattern = regex::RegexBuilder::new(src)
+            .case_insensitive(true)
+            .build()
+            .map_err(StringPatternParseError::Regex)?;
+        Ok(StringPattern::RegexI(pattern))
+    }
+
+    /// Constructs a pattern that matches any line exactly.
+    pub fn exact_line(src: impl Into<String>) -> Self {
+        StringPattern::ExactLine(src.into())
+    }
+
+    /// Parses the given string as a glob pattern to be tested line by line.
+    pub fn line_glob(src: &str) -> Result<Self, StringPatternParseError> {
+        Ok(StringPattern::LineGlob(parse_glob(src)?))
+    }
+
     /// Parses the given string as a pattern of the specified `kind`.
     pub fn from_str_kind(src: &str, kind: &str) -> Result<Self, StringPatternParseError> {
         match kind {
@@ -150,6 +176,9 @@ impl StringPattern {
             "glob" => StringPattern::glob(src),
             "glob-i" => StringPattern::glob_i(src),
             "regex" => StringPattern::regex(src),
+            "regex-i" => StringPattern::regex_i(src),
+            "exact-line" => Ok(StringPattern::exact_line(src)),
+            "line-glob" => StringPattern::line_glob(src),
             _ => Err(StringPatternParseError::InvalidKind(kind.to_owned())),
         }
     }
@@ -182,6 +211,9 @@ impl StringPattern {
             StringPattern::Glob(pattern) => pattern.as_str(),
             StringPattern::GlobI(pattern) => pattern.as_str(),
             StringPattern::Regex(pattern) => pattern.as_str(),
+            StringPattern::RegexI(pattern) => pattern.as_str(),
+            StringPattern::ExactLine(literal) => literal,
+            StringPattern::LineGlob(pattern) => pattern.as_str(),
         }
     }
 
@@ -205,6 +237,9 @@ impl StringPattern {
             StringPattern::SubstringI(_) => None,
             StringPattern::GlobI(_) => None,
             StringPattern::Regex(_) => None,
+            StringPattern::RegexI(_) => None,
+            StringPattern::ExactLine(_) => None,
+            StringPattern::LineGlob(_) => None,
         }
     }
 
@@ -244,6 +279,11 @@ impl StringPattern {
                 },
             ),
             StringPattern::Regex(pattern) => pattern.is_match(haystack),
+            StringPattern::RegexI(pattern) => pattern.is_match(haystack),
+            StringPattern::ExactLine(literal) => haystack.lines().any(|line| line == literal),
+            StringPattern::LineGlob(pattern) => {
+                haystack.lines().any(|line| pattern.0.matches(line))
+            }
         }
     }
 
@@ -331,6 +371,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_string_pattern_matches() {
+        let regex_i = StringPattern::regex_i("^fo+$").unwrap();
+        assert!(regex_i.matches("foo"));
+        assert!(regex_i.matches("FOO"));
+        assert!(!regex_i.matches("bar"));
+
+        let exact_line = StringPattern::exact_line("foo");
+        assert!(exact_line.matches("foo"));
+        assert!(exact_line.matches("bar\nfoo\n"));
+        assert!(!exact_line.matches("foo bar\n"));
+        assert!(!StringPattern::exact("foo").matches("bar\nfoo\n"));
+
+        let line_glob = StringPattern::line_glob("Fixes: #?").unwrap();
+        assert!(line_glob.matches("Title\n\nFixes: #1\n"));
+        assert!(!line_glob.matches("Title\n\nFixes: #12\n"));
+        assert!(!line_glob.matches("Fixes: #1 and #2"));
+    }
+
     #[test]
     fn test_parse() {
         // Parse specific pattern kinds.
@@ -374,6 +433,22 @@ mod tests {
             StringPattern::from_str_kind("foo", "regex"),
             Ok(StringPattern::Regex(p)) if p.as_str() == "foo"
         );
+        assert_matches!(
+            StringPattern::parse("regex-i:foo"),
+            Ok(StringPattern::RegexI(p)) if p.as_str() == "foo"
+        );
+        assert_matches!(
+            StringPattern::parse("exact-line:foo"),
+            Ok(StringPattern::ExactLine(s)) if s == "foo"
+        );
+        assert_matches!(
+            StringPattern::parse("line-glob:foo*"),
+            Ok(StringPattern::LineGlob(p)) if p.as_str() == "foo*"
+        );
+        assert_matches!(
+            StringPattern::parse("regex-i:("),
+            Err(StringPatternParseError::Regex(_))
+        );
 
         // Parse a pattern that contains a : itself.
         assert_matches!(
@@ -15,9 +15,16 @@
 //! Provides support for parsing and matching date ranges.
 
 use chrono::DateTime;
+use chrono::Datelike as _;
+use chrono::Days;
 use chrono::FixedOffset;
 use chrono::Local;
+use chrono::Months;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::TimeDelta;
 use chrono::TimeZone;
+use chrono::Weekday;
 use interim::parse_date_string;
 use interim::DateError;
 use interim::Dialect;
@@ -79,6 +86,13 @@ pub enum DatePattern {
     AtOrAfter(MillisSinceEpoch),
     /// Represents all dates before, but not including, the given instant.
     Before(MillisSinceEpoch),
+    /// Represents all dates at or after the `start`, and before the `end`.
+    Between {
+        /// First instant of the range.
+        start: MillisSinceEpoch,
+        /// Instant just after the range.
+        end: MillisSinceEpoch,
+    },
 }
 
 impl DatePattern {
@@ -86,8 +100,10 @@ impl DatePattern {
     ///
     /// * `s` is the string to be parsed.
     ///
-    /// * `kind` must be either "after" or "before". This determines whether the
-    ///   pattern will match dates after or before the parsed date.
+    /// * `kind` must be either "after", "before", or "date". This determines
+    ///   whether the pattern will match dates after or before the parsed date,
+    ///   or within the calendar period such as day or month. The "date" kind
+    ///   also accepts range of periods separated by `..`.
     ///
     /// * `now` is the user's current time. This is a [`DateTime<Tz>`] because
     ///   knowledge of offset changes is needed to correctly process relative
@@ -103,12 +119,38 @@ impl DatePattern {
     where
         Tz::Offset: Copy,
     {
-        let d =
-            parse_date_string(s, now, Dialect::Us).map_err(DatePatternParseError::ParseError)?;
-        let millis_since_epoch = MillisSinceEpoch(d.timestamp_millis());
+        // Trailing time zone overrides the user's, e.g. "2024-01-15 UTC".
+        match split_time_zone(s) {
+            (s, Some(offset)) => {
+                Self::from_str_kind_in_time_zone(s, kind, now.with_timezone(&offset))
+            }
+            (s, None) => Self::from_str_kind_in_time_zone(s, kind, now),
+        }
+    }
+
+    fn from_str_kind_in_time_zone<Tz: TimeZone>(
+        s: &str,
+        kind: &str,
+        now: DateTime<Tz>,
+    ) -> Result<DatePattern, DatePatternParseError>
+    where
+        Tz::Offset: Copy,
+    {
         match kind {
-            "after" => Ok(DatePattern::AtOrAfter(millis_since_epoch)),
-            "before" => Ok(DatePattern::Before(millis_since_epoch)),
+            "after" => Ok(DatePattern::AtOrAfter(parse_instant(s, now)?)),
+            "before" => Ok(DatePattern::Before(parse_instant(s, now)?)),
+            "date" => match s.split_once("..") {
+                Some((start, "")) => Ok(DatePattern::AtOrAfter(parse_period(start, now)?.0)),
+                Some(("", end)) => Ok(DatePattern::Before(parse_period(end, now)?.1)),
+                Some((start, end)) => Ok(DatePattern::Between {
+                    start: parse_period(start, now)?.0,
+                    end: parse_period(end, now)?.1,
+                }),
+                None => {
+                    let (start, end) = parse_period(s, now)?;
+                    Ok(DatePattern::Between { start, end })
+                }
+            },
             kind => Err(DatePatternParseError::InvalidKind(kind.to_owned())),
         }
     }
@@ -118,12 +160,203 @@ impl DatePattern {
         match self {
             DatePattern::AtOrAfter(earliest) => *earliest <= timestamp.timestamp,
             DatePattern::Before(latest) => timestamp.timestamp < *latest,
+            DatePattern::Between { start, end } => {
+                *start <= timestamp.timestamp && timestamp.timestamp < *end
+            }
+        }
+    }
+}
+
+/// Splits trailing time zone such as "UTC" or "+09:00" from the date string.
+fn split_time_zone(s: &str) -> (&str, Option<FixedOffset>) {
+    let Some((rest, name)) = s.trim_end().rsplit_once(' ') else {
+        return (s, None);
+    };
+    let offset = match name {
+        "UTC" | "utc" | "Z" => FixedOffset::east_opt(0),
+        _ => parse_utc_offset(name),
+    };
+    match offset {
+        Some(offset) => (rest.trim_end(), Some(offset)),
+        None => (s, None),
+    }
+}
+
+/// Parses UTC offset in `+HH:MM`, `+HHMM`, or `+HH` form.
+fn parse_utc_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, digits) = if let Some(digits) = s.strip_prefix('+') {
+        (1, digits)
+    } else if let Some(digits) = s.strip_prefix('-') {
+        (-1, digits)
+    } else {
+        return None;
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "00"),
+    };
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Parses the date string into an instant. Calendar periods which can't be
+/// parsed as an instant, such as "2024-03", are interpreted as the start of
+/// the period.
+fn parse_instant<Tz: TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Result<MillisSinceEpoch, DatePatternParseError>
+where
+    Tz::Offset: Copy,
+{
+    match parse_date_string(s, now, Dialect::Us) {
+        Ok(d) => Ok(MillisSinceEpoch(d.timestamp_millis())),
+        Err(err) => match parse_calendar_period(s, now.date_naive()) {
+            Some((start, _)) => Ok(start_of_day(&now.timezone(), start)),
+            None => Err(err.into()),
+        },
+    }
+}
+
+/// Parses the date string into a period of `(start, end)` instants. Strings
+/// other than calendar periods are interpreted as the day containing the
+/// instant.
+fn parse_period<Tz: TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Result<(MillisSinceEpoch, MillisSinceEpoch), DatePatternParseError>
+where
+    Tz::Offset: Copy,
+{
+    let tz = now.timezone();
+    let (start, end) = match parse_calendar_period(s, now.date_naive()) {
+        Some(period) => period,
+        None => {
+            let date = parse_date_string(s, now, Dialect::Us)?.date_naive();
+            (date, date + Days::new(1))
+        }
+    };
+    Ok((start_of_day(&tz, start), start_of_day(&tz, end)))
+}
+
+/// Parses calendar period such as "2024-03" or "last week" into `(start, end)`
+/// dates.
+fn parse_calendar_period(s: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let s = s.trim().to_ascii_lowercase();
+    let words: Vec<&str> = s.split_whitespace().collect();
+    let day = |date: NaiveDate| Some((date, date + Days::new(1)));
+    match words[..] {
+        ["today"] => day(today),
+        ["yesterday"] => day(today - Days::new(1)),
+        ["tomorrow"] => day(today + Days::new(1)),
+        [relative @ ("last" | "this" | "next"), unit] => {
+            let delta: i32 = match relative {
+                "last" => -1,
+                "this" => 0,
+                _ => 1,
+            };
+            let monday = today.week(Weekday::Mon).first_day();
+            match unit {
+                "week" => {
+                    let start = monday + TimeDelta::weeks(delta.into());
+                    Some((start, start + Days::new(7)))
+                }
+                "month" => {
+                    let first = today.with_day(1)?;
+                    let start = add_months(first, delta)?;
+                    Some((start, add_months(start, 1)?))
+                }
+                "year" => {
+                    let start = NaiveDate::from_ymd_opt(today.year() + delta, 1, 1)?;
+                    Some((start, start.with_year(start.year() + 1)?))
+                }
+                _ => {
+                    let weekday: Weekday = unit.parse().ok()?;
+                    let date = match delta {
+                        -1 => today - Days::new(days_between(weekday, today.weekday())),
+                        0 => monday + Days::new(weekday.num_days_from_monday().into()),
+                        _ => today + Days::new(days_between(today.weekday(), weekday)),
+                    };
+                    day(date)
+                }
+            }
         }
+        [date] => parse_partial_date(date),
+        _ => None,
     }
 }
 
+/// Parses "YYYY", "YYYY-MM", or "YYYY-MM-DD" into `(start, end)` dates.
+fn parse_partial_date(s: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let parts: Vec<&str> = s.split('-').collect();
+    let is_number = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    match parts[..] {
+        [year] if is_number(year, 4) => {
+            let start = NaiveDate::from_ymd_opt(year.parse().ok()?, 1, 1)?;
+            Some((start, start.with_year(start.year() + 1)?))
+        }
+        [year, month] if is_number(year, 4) && is_number(month, 2) => {
+            let start = NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)?;
+            Some((start, add_months(start, 1)?))
+        }
+        [year, month, day] if is_number(year, 4) && is_number(month, 2) && is_number(day, 2) => {
+            let start = NaiveDate::from_ymd_opt(
+                year.parse().ok()?,
+                month.parse().ok()?,
+                day.parse().ok()?,
+            )?;
+            Some((start, start + Days::new(1)))
+        }
+        _ => None,
+    }
+}
+
+fn add_months(date: NaiveDate, delta: i32) -> Option<NaiveDate> {
+    let months = Months::new(delta.unsigned_abs());
+    if delta < 0 {
+        date.checked_sub_months(months)
+    } else {
+        date.checked_add_months(months)
+    }
+}
+
+/// Returns the number of days from `from` to the next `to`, in 1..=7 range.
+fn days_between(from: Weekday, to: Weekday) -> u64 {
+    let days = (to.num_days_from_monday() + 7 - from.num_days_from_monday()) % 7;
+    if days == 0 {
+        7
+    } else {
+        days.into()
+    }
+}
+
+/// Returns the first instant of the `date` in the time zone.
+fn start_of_day<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> MillisSinceEpoch {
+    let midnight = date.and_time(NaiveTime::MIN);
+    // If the midnight is skipped by a transition, the day starts at the end of
+    // the gap. Offsets change in multiples of 15 minutes, and the gap may span
+    // the whole day, such as 2011-12-30 in Pacific/Apia.
+    let start = (0..=2 * 24 * 4)
+        .map(|n| midnight + TimeDelta::minutes(15 * n))
+        .find_map(|local| tz.from_local_datetime(&local).earliest())
+        .map_or_else(
+            || midnight.and_utc().timestamp_millis(),
+            |start| start.timestamp_millis(),
+        );
+    MillisSinceEpoch(start)
+}
+
 #[cfg(test)]
 mod tests {
+    use chrono::LocalResult;
+    use chrono::NaiveDateTime;
+    use itertools::Itertools as _;
+
     use super::*;
 
     fn test_equal<Tz: TimeZone>(now: DateTime<Tz>, expression: &str, should_equal_time: &str)
@@ -182,6 +415,221 @@ mod tests {
         test_equal(now, "tomorrow", "2024-01-02T08:00:00Z");
     }
 
+    #[test]
+    fn test_date_pattern_prefers_instants_over_periods() {
+        // Strings which could be parsed as an instant keep their meaning
+        let now = DateTime::parse_from_rfc3339("2024-01-10T12:34:00-08:00").unwrap();
+        test_equal(now, "today", "2024-01-10T12:34:00-08:00");
+        test_equal(now, "last week", "2024-01-03T12:34:00-08:00");
+        test_equal(now, "last friday", "2024-01-05T00:00:00-08:00");
+        test_equal(now, "next tue", "2024-01-16T00:00:00-08:00");
+        // Other calendar periods start at the beginning of the period
+        test_equal(now, "2023-03", "2023-03-01T00:00:00-08:00");
+        test_equal(now, "2023-03 UTC", "2023-03-01T00:00:00Z");
+    }
+
+    fn test_period<Tz: TimeZone>(now: DateTime<Tz>, expression: &str, start: &str, end: &str)
+    where
+        Tz::Offset: Copy,
+    {
+        let to_millis =
+            |s| MillisSinceEpoch(DateTime::parse_from_rfc3339(s).unwrap().timestamp_millis());
+        assert_eq!(
+            DatePattern::from_str_kind(expression, "date", now).unwrap(),
+            DatePattern::Between {
+                start: to_millis(start),
+                end: to_millis(end),
+            },
+            "{expression}"
+        );
+    }
+
+    #[test]
+    fn test_date_pattern_parses_calendar_periods() {
+        // Wednesday
+        let now = DateTime::parse_from_rfc3339("2024-01-10T12:00:00-08:00").unwrap();
+        let period = |expression, start, end| test_period(now, expression, start, end);
+        period("2023", "2023-01-01T08:00:00Z", "2024-01-01T08:00:00Z");
+        period("2023-02", "2023-02-01T08:00:00Z", "2023-03-01T08:00:00Z");
+        period("2023-12", "2023-12-01T08:00:00Z", "2024-01-01T08:00:00Z");
+        period("2023-02-28", "2023-02-28T08:00:00Z", "2023-03-01T08:00:00Z");
+        period("today", "2024-01-10T08:00:00Z", "2024-01-11T08:00:00Z");
+        period("Yesterday", "2024-01-09T08:00:00Z", "2024-01-10T08:00:00Z");
+        period("this week", "2024-01-08T08:00:00Z", "2024-01-15T08:00:00Z");
+        period("last week", "2024-01-01T08:00:00Z", "2024-01-08T08:00:00Z");
+        period("next week", "2024-01-15T08:00:00Z", "2024-01-22T08:00:00Z");
+        period("last month", "2023-12-01T08:00:00Z", "2024-01-01T08:00:00Z");
+        period("this year", "2024-01-01T08:00:00Z", "2025-01-01T08:00:00Z");
+        period(
+            "last monday",
+            "2024-01-08T08:00:00Z",
+            "2024-01-09T08:00:00Z",
+        );
+        period(
+            "last wednesday",
+            "2024-01-03T08:00:00Z",
+            "2024-01-04T08:00:00Z",
+        );
+        period(
+            "this friday",
+            "2024-01-12T08:00:00Z",
+            "2024-01-13T08:00:00Z",
+        );
+        period("next tue", "2024-01-16T08:00:00Z", "2024-01-17T08:00:00Z");
+        // Other dates are interpreted as the day containing the instant
+        period("2 days ago", "2024-01-08T08:00:00Z", "2024-01-09T08:00:00Z");
+        period("1/5/2024", "2024-01-05T08:00:00Z", "2024-01-06T08:00:00Z");
+    }
+
+    #[test]
+    fn test_date_pattern_parses_calendar_ranges() {
+        let now = DateTime::parse_from_rfc3339("2024-01-10T12:00:00-08:00").unwrap();
+        test_period(
+            now,
+            "2023-01..2023-03",
+            "2023-01-01T08:00:00Z",
+            "2023-04-01T08:00:00Z",
+        );
+        test_period(
+            now,
+            "last week..today",
+            "2024-01-01T08:00:00Z",
+            "2024-01-11T08:00:00Z",
+        );
+        assert_eq!(
+            DatePattern::from_str_kind("2023-03..", "date", now).unwrap(),
+            DatePattern::AtOrAfter(MillisSinceEpoch(
+                DateTime::parse_from_rfc3339("2023-03-01T08:00:00Z")
+                    .unwrap()
+                    .timestamp_millis()
+            ))
+        );
+        assert_eq!(
+            DatePattern::from_str_kind("..2023-03", "date", now).unwrap(),
+            DatePattern::Before(MillisSinceEpoch(
+                DateTime::parse_from_rfc3339("2023-04-01T08:00:00Z")
+                    .unwrap()
+                    .timestamp_millis()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_date_pattern_parses_time_zones() {
+        let now = DateTime::parse_from_rfc3339("2024-01-10T12:00:00-08:00").unwrap();
+        test_period(
+            now,
+            "2023-03-25 UTC",
+            "2023-03-25T00:00:00Z",
+            "2023-03-26T00:00:00Z",
+        );
+        test_period(
+            now,
+            "2023-03-25 +09:00",
+            "2023-03-25T00:00:00+09:00",
+            "2023-03-26T00:00:00+09:00",
+        );
+        test_period(
+            now,
+            "2023-01..2023-02 -0530",
+            "2023-01-01T00:00:00-05:30",
+            "2023-03-01T00:00:00-05:30",
+        );
+        // Now is 2024-01-10T20:00:00Z, so today is different in +09:00.
+        test_period(
+            now,
+            "today +09",
+            "2024-01-11T00:00:00+09:00",
+            "2024-01-12T00:00:00+09:00",
+        );
+        test_equal(now, "2023-03-25 Z", "2023-03-25T00:00:00Z");
+        test_equal(now, "2023-03-25 12:00 -05:00", "2023-03-25T17:00:00Z");
+    }
+
+    #[test]
+    fn test_date_pattern_matches_periods() {
+        let now = DateTime::parse_from_rfc3339("2024-01-10T12:00:00-08:00").unwrap();
+        let pattern = DatePattern::from_str_kind("2023-03-25 UTC", "date", now).unwrap();
+        let timestamp = |s| Timestamp {
+            timestamp: MillisSinceEpoch(
+                DateTime::parse_from_rfc3339(s).unwrap().timestamp_millis(),
+            ),
+            tz_offset: 0,
+        };
+        assert!(!pattern.matches(&timestamp("2023-03-24T23:59:59Z")));
+        assert!(pattern.matches(&timestamp("2023-03-25T00:00:00Z")));
+        assert!(pattern.matches(&timestamp("2023-03-25T23:59:59Z")));
+        assert!(!pattern.matches(&timestamp("2023-03-26T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_start_of_skipped_day() {
+        // Time zone which skips 2011-12-30 by moving from -10:00 to +14:00 at
+        // 2011-12-30T10:00:00Z, like Pacific/Apia.
+        #[derive(Clone, Copy, Debug)]
+        struct SkippedDay;
+
+        impl SkippedDay {
+            fn offset_at(utc: &NaiveDateTime) -> FixedOffset {
+                let transition = NaiveDate::from_ymd_opt(2011, 12, 30)
+                    .unwrap()
+                    .and_hms_opt(10, 0, 0)
+                    .unwrap();
+                let hours = if *utc < transition { -10 } else { 14 };
+                FixedOffset::east_opt(hours * 3600).unwrap()
+            }
+        }
+
+        impl TimeZone for SkippedDay {
+            type Offset = FixedOffset;
+
+            fn from_offset(_offset: &FixedOffset) -> Self {
+                SkippedDay
+            }
+
+            fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+                self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+            }
+
+            fn offset_from_local_datetime(
+                &self,
+                local: &NaiveDateTime,
+            ) -> LocalResult<FixedOffset> {
+                let offsets = [-10, 14].map(|hours| FixedOffset::east_opt(hours * 3600).unwrap());
+                let valid = offsets
+                    .into_iter()
+                    .filter(|offset| Self::offset_at(&(*local - *offset)) == *offset)
+                    .collect_vec();
+                match valid[..] {
+                    [] => LocalResult::None,
+                    [offset] => LocalResult::Single(offset),
+                    [offset1, offset2] => LocalResult::Ambiguous(offset1, offset2),
+                    _ => unreachable!(),
+                }
+            }
+
+            fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+                Self::offset_at(&utc.and_time(NaiveTime::MIN))
+            }
+
+            fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+                Self::offset_at(utc)
+            }
+        }
+
+        let start = |date: &str| {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+            start_of_day(&SkippedDay, date)
+        };
+        let timestamp =
+            |s| MillisSinceEpoch(DateTime::parse_from_rfc3339(s).unwrap().timestamp_millis());
+        assert_eq!(start("2011-12-29"), timestamp("2011-12-29T00:00:00-10:00"));
+        // The skipped day starts and ends at the transition
+        assert_eq!(start("2011-12-30"), timestamp("2011-12-30T10:00:00Z"));
+        assert_eq!(start("2011-12-31"), timestamp("2011-12-31T00:00:00+14:00"));
+        assert_eq!(start("2011-12-31"), timestamp("2011-12-30T10:00:00Z"));
+    }
+
     #[test]
     fn test_date_pattern_parses_relative_dates_with_times() {
         let now = DateTime::parse_from_rfc3339("2024-01-01T08:00:00-08:00").unwrap();
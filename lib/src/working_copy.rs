@@ -380,14 +380,30 @@ impl WorkingCopyFreshness {
         locked_wc: &dyn LockedWorkingCopy,
         wc_commit: &Commit,
         repo: &ReadonlyRepo,
+    ) -> Result<Self, OpStoreError> {
+        Self::check_stale_at(
+            locked_wc.old_tree_id(),
+            locked_wc.old_operation_id(),
+            wc_commit,
+            repo,
+        )
+    }
+
+    /// Determine the freshness of the working copy at the given tree and
+    /// operation relative to the target commit. Unlike [`Self::check_stale()`],
+    /// this doesn't need the working copy to be locked.
+    pub fn check_stale_at(
+        wc_tree_id: &MergedTreeId,
+        wc_operation_id: &OperationId,
+        wc_commit: &Commit,
+        repo: &ReadonlyRepo,
     ) -> Result<Self, OpStoreError> {
         // Check if the working copy's tree matches the repo's view
-        let wc_tree_id = locked_wc.old_tree_id();
         if wc_commit.tree_id() == wc_tree_id {
             // The working copy isn't stale, and no need to reload the repo.
             Ok(Self::Fresh)
         } else {
-            let wc_operation = repo.loader().load_operation(locked_wc.old_operation_id())?;
+            let wc_operation = repo.loader().load_operation(wc_operation_id)?;
             let repo_operation = repo.operation();
             let ancestor_op = dag_walk::closest_common_node_ok(
                 [Ok(wc_operation.clone())],
@@ -61,6 +61,7 @@ use crate::working_copy::LockedWorkingCopy;
 use crate::working_copy::WorkingCopy;
 use crate::working_copy::WorkingCopyFactory;
 use crate::working_copy::WorkingCopyStateError;
+use crate::workspace_store::SimpleWorkspaceStore;
 
 #[derive(Error, Debug)]
 pub enum WorkspaceInitError {
@@ -100,6 +101,20 @@ pub enum WorkspaceLoadError {
     Path(#[from] PathError),
 }
 
+#[derive(Error, Debug)]
+pub enum WorkspaceRelinkError {
+    #[error("There is no Jujutsu workspace in {0}")]
+    NoWorkspaceHere(PathBuf),
+    #[error("The workspace at {0} contains the repo and cannot be relinked")]
+    WorkspaceContainsRepo(PathBuf),
+    #[error("There is no Jujutsu repo in {0}")]
+    NoRepoHere(PathBuf),
+    #[error("Repo path could not be interpreted as Unicode text")]
+    NonUnicodePath,
+    #[error(transparent)]
+    Path(#[from] PathError),
+}
+
 /// The combination of a repo and a working copy.
 ///
 /// Represents the combination of a repo and working copy, i.e. what's typically
@@ -126,6 +141,17 @@ fn create_jj_dir(workspace_root: &Path) -> Result<PathBuf, WorkspaceInitError> {
     }
 }
 
+/// Returns the contents of the `.jj/repo` file pointing to `repo_dir`.
+///
+/// The path is made relative to the `.jj` directory so the workspace keeps
+/// working if it's moved together with the repo, or if the filesystem is
+/// mounted elsewhere. Returns `None` if the path isn't valid Unicode.
+fn repo_file_contents(jj_dir: &Path, repo_dir: &Path) -> Result<Option<String>, PathError> {
+    let jj_dir = dunce::canonicalize(jj_dir).context(jj_dir)?;
+    let path = crate::file_util::relative_path(&jj_dir, repo_dir);
+    Ok(path.to_str().map(|path| path.to_owned()))
+}
+
 fn init_working_copy(
     repo: &Arc<ReadonlyRepo>,
     workspace_root: &Path,
@@ -316,8 +342,9 @@ impl Workspace {
                 workspace_root,
                 &jj_dir,
                 working_copy_factory,
-                workspace_name,
+                workspace_name.clone(),
             )?;
+            SimpleWorkspaceStore::load(&repo_dir).add(&workspace_name, workspace_root)?;
             let repo_loader = repo.loader().clone();
             let workspace = Workspace::new(workspace_root, repo_dir, working_copy, repo_loader)?;
             Ok((workspace, repo))
@@ -358,14 +385,11 @@ impl Workspace {
 
         let repo_dir = dunce::canonicalize(repo_path).context(repo_path)?;
         let repo_file_path = jj_dir.join("repo");
+        let repo_file_contents =
+            repo_file_contents(&jj_dir, &repo_dir)?.ok_or(WorkspaceInitError::NonUnicodePath)?;
         let mut repo_file = File::create(&repo_file_path).context(&repo_file_path)?;
         repo_file
-            .write_all(
-                repo_dir
-                    .to_str()
-                    .ok_or(WorkspaceInitError::NonUnicodePath)?
-                    .as_bytes(),
-            )
+            .write_all(repo_file_contents.as_bytes())
             .context(&repo_file_path)?;
 
         let (working_copy, repo) = init_working_copy(
@@ -373,8 +397,9 @@ impl Workspace {
             workspace_root,
             &jj_dir,
             working_copy_factory,
-            workspace_name,
+            workspace_name.clone(),
         )?;
+        SimpleWorkspaceStore::load(&repo_dir).add(&workspace_name, workspace_root)?;
         let workspace = Workspace::new(
             workspace_root,
             repo_dir,
@@ -560,10 +585,15 @@ impl DefaultWorkspaceLoader {
             let buf = fs::read(&repo_dir).context(&repo_dir)?;
             let repo_path_str =
                 String::from_utf8(buf).map_err(|_| WorkspaceLoadError::NonUnicodePath)?;
-            repo_dir = dunce::canonicalize(jj_dir.join(&repo_path_str)).context(&repo_path_str)?;
-            if !repo_dir.is_dir() {
-                return Err(WorkspaceLoadError::RepoDoesNotExist(repo_dir));
+            let linked_repo_dir = jj_dir.join(&repo_path_str);
+            // Report a moved repo before canonicalization fails on the missing
+            // path.
+            if !linked_repo_dir.is_dir() {
+                return Err(WorkspaceLoadError::RepoDoesNotExist(
+                    crate::file_util::normalize_path(&linked_repo_dir),
+                ));
             }
+            repo_dir = dunce::canonicalize(&linked_repo_dir).context(&linked_repo_dir)?;
         }
         let working_copy_state_path = jj_dir.join("working_copy");
         Ok(Self {
@@ -611,6 +641,37 @@ impl WorkspaceLoader for DefaultWorkspaceLoader {
     }
 }
 
+/// Points the workspace at `workspace_root` to the repo at `repo_path`.
+///
+/// This rewrites the `.jj/repo` file of the workspace, which is how a
+/// workspace is repaired after its repo was moved. The workspace that contains
+/// the repo itself can't be relinked. Returns the canonical path to the repo.
+pub fn relink_workspace(
+    workspace_root: &Path,
+    repo_path: &Path,
+) -> Result<PathBuf, WorkspaceRelinkError> {
+    let jj_dir = workspace_root.join(".jj");
+    if !jj_dir.is_dir() {
+        return Err(WorkspaceRelinkError::NoWorkspaceHere(
+            workspace_root.to_owned(),
+        ));
+    }
+    let repo_file_path = jj_dir.join("repo");
+    if repo_file_path.is_dir() {
+        return Err(WorkspaceRelinkError::WorkspaceContainsRepo(
+            workspace_root.to_owned(),
+        ));
+    }
+    if !repo_path.join("store").is_dir() {
+        return Err(WorkspaceRelinkError::NoRepoHere(repo_path.to_owned()));
+    }
+    let repo_dir = dunce::canonicalize(repo_path).context(repo_path)?;
+    let repo_file_contents =
+        repo_file_contents(&jj_dir, &repo_dir)?.ok_or(WorkspaceRelinkError::NonUnicodePath)?;
+    fs::write(&repo_file_path, repo_file_contents).context(&repo_file_path)?;
+    Ok(repo_dir)
+}
+
 pub fn default_working_copy_factories() -> WorkingCopyFactories {
     let mut factories = WorkingCopyFactories::new();
     factories.insert(
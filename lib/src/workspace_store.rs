@@ -0,0 +1,109 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Records the on-disk location of the workspaces attached to a repo.
+//!
+//! The view only knows the names of the workspaces. This store maps each name
+//! to the workspace root so that commands can inspect workspaces other than
+//! the current one.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::file_util;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::ref_name::WorkspaceName;
+
+/// Stores workspace locations as one file per workspace in
+/// `.jj/repo/workspace_store/`.
+///
+/// Paths are recorded relative to the repo directory if possible, so that a
+/// repo can be moved together with its workspaces.
+#[derive(Clone, Debug)]
+pub struct SimpleWorkspaceStore {
+    repo_path: PathBuf,
+    store_path: PathBuf,
+}
+
+impl SimpleWorkspaceStore {
+    /// Opens the workspace store of the repo at `repo_path`. The store
+    /// directory is created lazily.
+    pub fn load(repo_path: &Path) -> Self {
+        Self {
+            repo_path: repo_path.to_owned(),
+            store_path: repo_path.join("workspace_store"),
+        }
+    }
+
+    fn entry_path(&self, name: &WorkspaceName) -> PathBuf {
+        self.store_path.join(hex::encode(name.as_str()))
+    }
+
+    /// Records that the workspace `name` lives at `workspace_root`.
+    ///
+    /// Locations that can't be represented as Unicode text aren't recorded.
+    pub fn add(&self, name: &WorkspaceName, workspace_root: &Path) -> Result<(), PathError> {
+        let repo_path = dunce::canonicalize(&self.repo_path).context(&self.repo_path)?;
+        let workspace_root = dunce::canonicalize(workspace_root).context(workspace_root)?;
+        let path = file_util::relative_path(&repo_path, &workspace_root);
+        let Some(path) = path.to_str() else {
+            return self.forget(name);
+        };
+        file_util::create_or_reuse_dir(&self.store_path).context(&self.store_path)?;
+        let entry_path = self.entry_path(name);
+        fs::write(&entry_path, path).context(&entry_path)
+    }
+
+    /// Forgets the location of the workspace `name`. It's not an error if the
+    /// workspace location wasn't recorded.
+    pub fn forget(&self, name: &WorkspaceName) -> Result<(), PathError> {
+        let entry_path = self.entry_path(name);
+        match fs::remove_file(&entry_path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res.context(&entry_path),
+        }
+    }
+
+    /// Moves the recorded location of the workspace `old_name` to `new_name`.
+    pub fn rename(
+        &self,
+        old_name: &WorkspaceName,
+        new_name: &WorkspaceName,
+    ) -> Result<(), PathError> {
+        let old_path = self.entry_path(old_name);
+        match fs::rename(&old_path, self.entry_path(new_name)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res.context(&old_path),
+        }
+    }
+
+    /// Returns the root of the workspace `name`, or `None` if its location
+    /// wasn't recorded. The returned path isn't guaranteed to exist.
+    pub fn get_workspace_path(&self, name: &WorkspaceName) -> Result<Option<PathBuf>, PathError> {
+        let entry_path = self.entry_path(name);
+        let buf = match fs::read(&entry_path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).context(&entry_path),
+        };
+        let Ok(path) = String::from_utf8(buf) else {
+            return Ok(None);
+        };
+        let path = self.repo_path.join(path);
+        Ok(Some(file_util::normalize_path(&path)))
+    }
+}
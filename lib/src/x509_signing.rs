@@ -0,0 +1,357 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing backend that creates and verifies X.509 (CMS) signatures by
+//! running `openssl cms`.
+
+#![allow(missing_docs)]
+
+use std::ffi::OsString;
+use std::fmt::Debug;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+
+use thiserror::Error;
+
+use crate::config::ConfigGetError;
+use crate::config::ConfigGetResultExt as _;
+use crate::file_util::expand_home_path;
+use crate::settings::UserSettings;
+use crate::signing::SigStatus;
+use crate::signing::SignError;
+use crate::signing::SigningBackend;
+use crate::signing::Verification;
+
+#[derive(Debug)]
+pub struct X509Backend {
+    program: OsString,
+    ca_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Error)]
+pub enum X509Error {
+    #[error("openssl cms failed with {exit_status}:\n{stderr}")]
+    Command {
+        exit_status: ExitStatus,
+        stderr: String,
+    },
+    #[error("Failed to parse openssl output")]
+    BadResult,
+    #[error("Failed to run openssl")]
+    Io(#[from] std::io::Error),
+    #[error("Signing key required")]
+    MissingKey,
+}
+
+impl From<X509Error> for SignError {
+    fn from(e: X509Error) -> Self {
+        SignError::Backend(Box::new(e))
+    }
+}
+
+type X509Result<T> = Result<T, X509Error>;
+
+fn run_command(command: &mut Command, stdin: &[u8]) -> X509Result<Vec<u8>> {
+    tracing::info!(?command, "running X.509 signing command");
+    let process = command.spawn()?;
+    let write_result = process.stdin.as_ref().unwrap().write_all(stdin);
+    let output = process.wait_with_output()?;
+    tracing::info!(?command, ?output.status, "X.509 signing command exited");
+    if output.status.success() {
+        write_result?;
+        Ok(output.stdout)
+    } else {
+        Err(X509Error::Command {
+            exit_status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).trim_end().into(),
+        })
+    }
+}
+
+fn write_temp_file(prefix: &str, content: &[u8]) -> X509Result<tempfile::TempPath> {
+    let mut file = tempfile::Builder::new().prefix(prefix).tempfile()?;
+    file.write_all(content)?;
+    file.flush()?;
+    // Close the file handle so that other programs can open the file on
+    // Windows.
+    Ok(file.into_temp_path())
+}
+
+/// Outcome of `openssl cms -verify`.
+#[derive(Debug, PartialEq, Eq)]
+enum CmsVerifyResult {
+    /// The signature matches the data, and the certificate chain is trusted.
+    Verified,
+    /// The signer certificate couldn't be verified for the given reason.
+    UntrustedCertificate(String),
+    /// The signature doesn't match the data.
+    BadSignature,
+    /// The signature couldn't be parsed.
+    InvalidFormat,
+}
+
+/// Interprets the result of `openssl cms -verify` based on its documented exit
+/// codes and the error messages it prints.
+fn parse_cms_verify_result(exit_code: Option<i32>, stderr: &str) -> CmsVerifyResult {
+    match exit_code {
+        Some(0) => return CmsVerifyResult::Verified,
+        // 2 and 3 mean that the input couldn't be read, 4 that the
+        // verification failed.
+        Some(4) => {}
+        _ => return CmsVerifyResult::InvalidFormat,
+    }
+    if !stderr.contains("certificate verify error") {
+        return CmsVerifyResult::BadSignature;
+    }
+    let reason = stderr
+        .lines()
+        .find_map(|line| line.split_once("Verify error:"))
+        .map_or("certificate verify error", |(_, reason)| reason.trim());
+    CmsVerifyResult::UntrustedCertificate(reason.to_owned())
+}
+
+/// Subject and SHA-256 fingerprint of a certificate.
+#[derive(Debug, PartialEq, Eq)]
+struct CertificateInfo {
+    subject: String,
+    fingerprint: String,
+}
+
+/// Parses the output of `openssl x509 -noout -subject -fingerprint`.
+fn parse_certificate_info(output: &str) -> Option<CertificateInfo> {
+    let mut subject = None;
+    let mut fingerprint = None;
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix("subject=") {
+            subject = Some(value.trim().to_owned());
+        } else if let Some((_, value)) = line.split_once("Fingerprint=") {
+            fingerprint = Some(value.trim().replace(':', "").to_ascii_uppercase());
+        }
+    }
+    Some(CertificateInfo {
+        subject: subject?,
+        fingerprint: fingerprint?,
+    })
+}
+
+impl X509Backend {
+    pub fn new(program: OsString, ca_file: Option<PathBuf>) -> Self {
+        Self { program, ca_file }
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let program = settings.get_string("signing.backends.x509.program")?;
+        let ca_file = settings
+            .get_string("signing.backends.x509.ca-file")
+            .optional()?
+            .map(|v| expand_home_path(v.as_str()));
+        Ok(Self::new(program.into(), ca_file))
+    }
+
+    fn create_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        // Hide console window on Windows (https://stackoverflow.com/a/60958956)
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        command
+    }
+
+    fn run_cms_verify(
+        &self,
+        signature_path: &Path,
+        data_path: &Path,
+        verify_chain: bool,
+        signer_path: &Path,
+    ) -> X509Result<CmsVerifyResult> {
+        let mut command = self.create_command();
+        command
+            .arg("cms")
+            .arg("-verify")
+            .arg("-binary")
+            .arg("-inform")
+            .arg("PEM")
+            .arg("-in")
+            .arg(signature_path)
+            .arg("-content")
+            .arg(data_path)
+            .arg("-signer")
+            .arg(signer_path);
+        if !verify_chain {
+            command.arg("-noverify");
+        } else if let Some(ca_file) = &self.ca_file {
+            command.arg("-CAfile").arg(ca_file);
+        }
+        command.stdin(Stdio::null());
+
+        tracing::info!(?command, "running X.509 signing command");
+        let output = command.output()?;
+        tracing::info!(?command, ?output.status, "X.509 signing command exited");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Ok(parse_cms_verify_result(output.status.code(), &stderr))
+    }
+
+    fn certificate_info(&self, certificate_path: &Path) -> X509Result<CertificateInfo> {
+        let mut command = self.create_command();
+        command
+            .arg("x509")
+            .arg("-in")
+            .arg(certificate_path)
+            .arg("-noout")
+            .arg("-subject")
+            .arg("-fingerprint")
+            .arg("-sha256")
+            .arg("-nameopt")
+            .arg("utf8,sep_comma_plus_space,sname,esc_2253");
+        let output = run_command(&mut command, &[])?;
+        let output = String::from_utf8(output).map_err(|_| X509Error::BadResult)?;
+        parse_certificate_info(&output).ok_or(X509Error::BadResult)
+    }
+}
+
+impl SigningBackend for X509Backend {
+    fn name(&self) -> &str {
+        "x509"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        signature.starts_with(b"-----BEGIN CMS-----")
+            || signature.starts_with(b"-----BEGIN PKCS7-----")
+    }
+
+    fn sign(&self, data: &[u8], key: Option<&str>) -> Result<Vec<u8>, SignError> {
+        let Some(key) = key else {
+            return Err(X509Error::MissingKey.into());
+        };
+        // The key file contains both the certificate and the private key.
+        let key_path = expand_home_path(key);
+
+        let mut command = self.create_command();
+        command
+            .arg("cms")
+            .arg("-sign")
+            .arg("-binary")
+            .arg("-md")
+            .arg("sha256")
+            .arg("-outform")
+            .arg("PEM")
+            .arg("-signer")
+            .arg(key_path);
+
+        Ok(run_command(&mut command, data)?)
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<Verification, SignError> {
+        let signature_path = write_temp_file(".jj-x509-sig-", signature)?;
+        let data_path = write_temp_file(".jj-x509-data-", data)?;
+        let signer_path = write_temp_file(".jj-x509-signer-", &[])?;
+
+        let result = self.run_cms_verify(&signature_path, &data_path, true, &signer_path)?;
+        let (status, chain_error) = match result {
+            CmsVerifyResult::Verified => (SigStatus::Good, None),
+            CmsVerifyResult::UntrustedCertificate(reason) => {
+                // Check the signature itself without the certificate chain to
+                // tell apart bad signatures from unknown signers.
+                match self.run_cms_verify(&signature_path, &data_path, false, &signer_path)? {
+                    CmsVerifyResult::Verified => (SigStatus::Unknown, Some(reason)),
+                    CmsVerifyResult::InvalidFormat => {
+                        return Err(SignError::InvalidSignatureFormat)
+                    }
+                    _ => return Ok(Verification::new(SigStatus::Bad, None, None)),
+                }
+            }
+            CmsVerifyResult::BadSignature => {
+                return Ok(Verification::new(SigStatus::Bad, None, None));
+            }
+            CmsVerifyResult::InvalidFormat => return Err(SignError::InvalidSignatureFormat),
+        };
+
+        let certificate = self.certificate_info(&signer_path)?;
+        let display = match chain_error {
+            None => certificate.subject,
+            Some(reason) => format!("{} (untrusted certificate: {reason})", certificate.subject),
+        };
+        Ok(Verification::new(
+            status,
+            Some(certificate.fingerprint),
+            Some(display),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cms_verify_result() {
+        assert_eq!(
+            parse_cms_verify_result(Some(0), "CMS Verification successful"),
+            CmsVerifyResult::Verified
+        );
+        assert_eq!(
+            parse_cms_verify_result(
+                Some(4),
+                "CMS Verification failure\n\
+                 4037D2FB377F0000:error:17000064:CMS routines:cms_signerinfo_verify_cert:\
+                 certificate verify error:crypto/cms/cms_smime.c:290:\
+                 Verify error: unable to get local issuer certificate"
+            ),
+            CmsVerifyResult::UntrustedCertificate("unable to get local issuer certificate".into())
+        );
+        assert_eq!(
+            parse_cms_verify_result(
+                Some(4),
+                "CMS Verification failure\n\
+                 40C72B33B87F0000:error:1700006D:CMS routines:CMS_verify:\
+                 content verify error:crypto/cms/cms_smime.c:462:"
+            ),
+            CmsVerifyResult::BadSignature
+        );
+        assert_eq!(
+            parse_cms_verify_result(Some(2), "Error reading SMIME Content Info"),
+            CmsVerifyResult::InvalidFormat
+        );
+    }
+
+    #[test]
+    fn test_parse_certificate_info() {
+        assert_eq!(
+            parse_certificate_info(
+                "subject=CN=Someone, emailAddress=someone@example.com\n\
+                 sha256 Fingerprint=AE:78:46:D2:44:13:DD:DE:4B:2E:CB:E2:EF:FD:F2:3B:BD:EC:DC:BA:\
+                 A5:AD:AA:55:81:72:E2:D1:46:4D:6F:D1\n"
+            ),
+            Some(CertificateInfo {
+                subject: "CN=Someone, emailAddress=someone@example.com".into(),
+                fingerprint: "AE7846D24413DDDE4B2ECBE2EFFDF23BBDECDCBAA5ADAA558172E2D1464D6FD1"
+                    .into(),
+            })
+        );
+        assert_eq!(parse_certificate_info("subject=CN=Someone\n"), None);
+    }
+}
@@ -26,6 +26,7 @@ mod test_local_working_copy_sparse;
 mod test_merge_trees;
 mod test_merged_tree;
 mod test_mut_repo;
+mod test_native_signing;
 mod test_operations;
 mod test_refs;
 mod test_revset;
@@ -36,3 +37,4 @@ mod test_signing;
 mod test_ssh_signing;
 mod test_view;
 mod test_workspace;
+mod test_x509_signing;
@@ -45,6 +45,7 @@ use jj_lib::git::GitFetch;
 use jj_lib::git::GitFetchError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitPushError;
+use jj_lib::git::GitPushOptions;
 use jj_lib::git::GitPushStats;
 use jj_lib::git::GitRefKind;
 use jj_lib::git::GitRefUpdate;
@@ -2487,7 +2488,10 @@ fn test_reset_head_detached_out_of_sync() {
 
 fn get_index_state(workspace_root: &Path) -> String {
     let git_repo = gix::open(workspace_root).unwrap();
-    let index = git_repo.index().unwrap();
+    format_index_entries(&git_repo.index().unwrap())
+}
+
+fn format_index_entries(index: &gix::index::File) -> String {
     index
         .entries()
         .iter()
@@ -2544,6 +2548,40 @@ fn test_reset_head_with_index() {
     insta::assert_snapshot!(get_index_state(&workspace_root), @"");
 }
 
+#[test]
+fn test_write_index_file() {
+    // Create colocated workspace
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let workspace_root = temp_dir.path().join("repo");
+    testutils::git::init(&workspace_root);
+    let (_workspace, repo) =
+        Workspace::init_external_git(&settings, &workspace_root, &workspace_root.join(".git"))
+            .unwrap();
+    let git_repo = git::get_git_repo(repo.store()).unwrap();
+
+    let tree = testutils::create_tree(
+        &repo,
+        &[(repo_path("file"), "a\n"), (repo_path("dir/file"), "b\n")],
+    );
+    let index_path = temp_dir.path().join("index");
+    git::write_index_file(&git_repo, &tree, &index_path).unwrap();
+    let index = gix::index::File::at(
+        &index_path,
+        git_repo.object_hash(),
+        false,
+        gix::index::decode::Options::default(),
+    )
+    .unwrap();
+    insta::assert_snapshot!(format_index_entries(&index), @r"
+    Unconflicted dir/file Mode(FILE)
+    Unconflicted file Mode(FILE)
+    ");
+
+    // The index of the repo is left alone
+    assert!(git_repo.try_index().unwrap().is_none());
+}
+
 #[test]
 fn test_reset_head_with_index_no_conflict() {
     // Create colocated workspace
@@ -3286,6 +3324,7 @@ fn test_push_bookmarks_success() {
         "origin".as_ref(),
         &targets,
         git::RemoteCallbacks::default(),
+        &GitPushOptions::default(),
     );
     assert_eq!(
         result.unwrap(),
@@ -3358,6 +3397,7 @@ fn test_push_bookmarks_deletion() {
         "origin".as_ref(),
         &targets,
         git::RemoteCallbacks::default(),
+        &GitPushOptions::default(),
     );
     assert_eq!(
         result.unwrap(),
@@ -3425,6 +3465,7 @@ fn test_push_bookmarks_mixed_deletion_and_addition() {
         "origin".as_ref(),
         &targets,
         git::RemoteCallbacks::default(),
+        &GitPushOptions::default(),
     );
     assert_eq!(
         result.unwrap(),
@@ -3495,6 +3536,7 @@ fn test_push_bookmarks_not_fast_forward() {
         "origin".as_ref(),
         &targets,
         git::RemoteCallbacks::default(),
+        &GitPushOptions::default(),
     );
     assert_eq!(
         result.unwrap(),
@@ -3544,6 +3586,7 @@ fn test_push_updates_unexpectedly_moved_sideways_on_remote() {
             "origin".as_ref(),
             &targets,
             git::RemoteCallbacks::default(),
+            &GitPushOptions::default(),
         )
     };
 
@@ -3622,6 +3665,7 @@ fn test_push_updates_unexpectedly_moved_forward_on_remote() {
             "origin".as_ref(),
             &targets,
             git::RemoteCallbacks::default(),
+            &GitPushOptions::default(),
         )
     };
 
@@ -3686,6 +3730,7 @@ fn test_push_updates_unexpectedly_exists_on_remote() {
             "origin".as_ref(),
             &targets,
             git::RemoteCallbacks::default(),
+            &GitPushOptions::default(),
         )
     };
 
@@ -3722,6 +3767,7 @@ fn test_push_updates_success() {
             new_target: Some(setup.child_of_main_commit.id().clone()),
         }],
         git::RemoteCallbacks::default(),
+        &GitPushOptions::default(),
     );
     assert_eq!(
         result.unwrap(),
@@ -3746,6 +3792,56 @@ fn test_push_updates_success() {
     assert_eq!(new_target.target().id(), new_oid);
 }
 
+#[test]
+fn test_push_updates_atomic() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let git_settings = GitSettings::default();
+    let updates = [
+        GitRefUpdate {
+            qualified_name: "refs/heads/main".into(),
+            // Stale expectation, so this update fails
+            expected_current_target: Some(setup.parent_of_main_commit.id().clone()),
+            new_target: Some(setup.child_of_main_commit.id().clone()),
+        },
+        GitRefUpdate {
+            qualified_name: "refs/heads/new".into(),
+            expected_current_target: None,
+            new_target: Some(setup.child_of_main_commit.id().clone()),
+        },
+    ];
+    let options = GitPushOptions {
+        atomic: true,
+        ..Default::default()
+    };
+    let result = git::push_updates(
+        setup.jj_repo.as_ref(),
+        &git_settings,
+        "origin".as_ref(),
+        &updates,
+        git::RemoteCallbacks::default(),
+        &options,
+    );
+    assert_eq!(
+        result.unwrap(),
+        GitPushStats {
+            rejected: vec![("refs/heads/main".into(), Some("stale info".to_owned()))],
+            atomic_rejected: vec!["refs/heads/new".into()],
+            ..Default::default()
+        }
+    );
+
+    // None of the refs got updated in the source repo
+    let source_repo = testutils::git::open(&setup.source_repo_dir);
+    let main_target = source_repo.find_reference("refs/heads/main").unwrap();
+    assert_eq!(main_target.target().id(), git_id(&setup.main_commit));
+    assert!(source_repo
+        .try_find_reference("refs/heads/new")
+        .unwrap()
+        .is_none());
+}
+
 #[test]
 fn test_push_updates_no_such_remote() {
     let settings = testutils::user_settings();
@@ -3762,6 +3858,7 @@ fn test_push_updates_no_such_remote() {
             new_target: Some(setup.child_of_main_commit.id().clone()),
         }],
         git::RemoteCallbacks::default(),
+        &GitPushOptions::default(),
     );
     assert!(matches!(result, Err(GitPushError::NoSuchRemote(_))));
 }
@@ -3782,6 +3879,7 @@ fn test_push_updates_invalid_remote() {
             new_target: Some(setup.child_of_main_commit.id().clone()),
         }],
         git::RemoteCallbacks::default(),
+        &GitPushOptions::default(),
     );
     assert!(matches!(result, Err(GitPushError::NoSuchRemote(_))));
 }
@@ -4284,3 +4382,159 @@ fn user_settings_without_change_id() -> UserSettings {
     config.add_layer(layer);
     UserSettings::from_config(config).unwrap()
 }
+
+#[test]
+fn test_notes_fan_out() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let git_repo = get_git_repo(repo);
+    let signature = testutils::user_settings().signature();
+
+    let mut tx = repo.start_transaction();
+    let commit1 = write_random_commit(tx.repo_mut());
+    let commit2 = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    // Store the note of commit1 in a fan-out directory, as Git does for large
+    // notes trees.
+    let hex1 = commit1.id().hex();
+    let blob_id = git_repo.write_blob("note 1\n").unwrap();
+    let mut editor = git_repo.edit_tree(git_repo.empty_tree().id).unwrap();
+    editor
+        .upsert(
+            format!("{}/{}", &hex1[..2], &hex1[2..]),
+            gix::object::tree::EntryKind::Blob,
+            blob_id,
+        )
+        .unwrap();
+    let tree_id = editor.write().unwrap().detach();
+    testutils::git::write_commit(&git_repo, "refs/notes/commits", tree_id, "notes", &[]);
+    assert_eq!(
+        git::read_notes(repo.store(), "commits").unwrap(),
+        [(commit1.id().clone(), "note 1\n".to_owned())].into()
+    );
+
+    // Updating the note keeps it in the fan-out directory, and new notes are
+    // added with the same fan-out
+    git::write_notes(
+        repo.store(),
+        "commits",
+        [
+            (commit1.id().clone(), Some("note 1 (updated)\n".to_owned())),
+            (commit2.id().clone(), Some("note 2\n".to_owned())),
+        ],
+        &signature,
+        "update",
+    )
+    .unwrap();
+    let notes_commit = git_repo
+        .find_reference("refs/notes/commits")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let notes_tree = notes_commit.tree().unwrap();
+    assert!(notes_tree
+        .lookup_entry_by_path(format!("{}/{}", &hex1[..2], &hex1[2..]))
+        .unwrap()
+        .is_some());
+    let hex2 = commit2.id().hex();
+    assert!(notes_tree
+        .lookup_entry_by_path(format!("{}/{}", &hex2[..2], &hex2[2..]))
+        .unwrap()
+        .is_some());
+    assert_eq!(notes_commit.parent_ids().count(), 1);
+    assert_eq!(
+        git::read_notes(repo.store(), "refs/notes/commits").unwrap(),
+        [
+            (commit1.id().clone(), "note 1 (updated)\n".to_owned()),
+            (commit2.id().clone(), "note 2\n".to_owned()),
+        ]
+        .into()
+    );
+
+    // Removing the notes leaves an empty tree
+    git::write_notes(
+        repo.store(),
+        "commits",
+        [(commit1.id().clone(), None), (commit2.id().clone(), None)],
+        &signature,
+        "remove",
+    )
+    .unwrap();
+    assert!(git::read_notes(repo.store(), "commits").unwrap().is_empty());
+}
+
+#[test]
+fn test_rewrite_notes() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let signature = testutils::user_settings().signature();
+    let namespaces = ["review".to_owned()];
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+    assert!(!git::has_notes(repo.store(), &namespaces).unwrap());
+    git::write_notes(
+        repo.store(),
+        "review",
+        [(commit_a.id().clone(), Some("note a\n".to_owned()))],
+        &signature,
+        "add",
+    )
+    .unwrap();
+    assert!(git::has_notes(repo.store(), &namespaces).unwrap());
+    assert!(!git::has_notes(repo.store(), &["commits".to_owned()]).unwrap());
+
+    // Rewrite A twice within the same transaction, and B once
+    let mut tx = repo.start_transaction();
+    let commit_a2 = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a)
+        .set_description("a2")
+        .write()
+        .unwrap();
+    let commit_a3 = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a2)
+        .set_description("a3")
+        .write()
+        .unwrap();
+    let commit_b2 = tx
+        .repo_mut()
+        .rewrite_commit(&commit_b)
+        .set_description("b2")
+        .write()
+        .unwrap();
+    tx.repo_mut().rebase_descendants().unwrap();
+    let rewrites = tx.repo().rewritten_commits_since_base().unwrap();
+    assert_eq!(
+        git::rewrite_notes(repo.store(), &rewrites, &namespaces, &signature).unwrap(),
+        1
+    );
+    assert_eq!(
+        git::read_notes(repo.store(), "review").unwrap(),
+        [
+            (commit_a.id().clone(), "note a\n".to_owned()),
+            (commit_a3.id().clone(), "note a\n".to_owned()),
+        ]
+        .into()
+    );
+    assert!(!git::read_notes(repo.store(), "review")
+        .unwrap()
+        .contains_key(commit_b2.id()));
+
+    // Nothing to copy if no notes exist in the namespace
+    assert_eq!(
+        git::rewrite_notes(repo.store(), &rewrites, &["commits".to_owned()], &signature).unwrap(),
+        0
+    );
+
+    // Nothing to copy if no commits were rewritten. The notes aren't even read,
+    // so an invalid namespace isn't an error.
+    assert_eq!(
+        git::rewrite_notes(repo.store(), &[], &["..".to_owned()], &signature).unwrap(),
+        0
+    );
+}
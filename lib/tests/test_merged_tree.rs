@@ -23,6 +23,8 @@ use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
 use jj_lib::files;
+use jj_lib::fileset::FilePredicate;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::FilesMatcher;
 use jj_lib::matchers::Matcher;
@@ -38,6 +40,7 @@ use jj_lib::merged_tree::TreeDiffStreamImpl;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::str_util::StringPattern;
 use pollster::FutureExt as _;
 use pretty_assertions::assert_eq;
 use testutils::create_single_tree;
@@ -1638,3 +1641,129 @@ fn test_merge_simplify_file_conflict_with_absent() {
     let merged = child1_merged.merge(&parent_merged, &child2_merged).unwrap();
     assert_eq!(merged, expected_merged);
 }
+
+#[test]
+fn test_fileset_tree_matcher() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+    testutils::write_normal_file(&mut tree_builder, repo_path("normal"), "foo\nTODO: bar\n");
+    testutils::write_normal_file(&mut tree_builder, repo_path("binary"), "foo\0bar");
+    testutils::write_normal_file(&mut tree_builder, repo_path("dir/large"), &"x".repeat(2000));
+    testutils::write_executable_file(&mut tree_builder, repo_path("dir/exec"), "#!/bin/sh\n");
+    testutils::write_symlink(&mut tree_builder, repo_path("link"), "normal");
+    let tree_id = tree_builder.write_tree().unwrap();
+    let tree = MergedTree::resolved(store.get_tree(RepoPathBuf::root(), &tree_id).unwrap());
+
+    let base = create_single_tree(repo, &[(repo_path("normal"), "base")]);
+    let side1 = create_single_tree(repo, &[(repo_path("normal"), "side1")]);
+    let side2 = create_single_tree(repo, &[(repo_path("normal"), "side2")]);
+    let conflicted_tree = MergedTree::new(Merge::from_removes_adds(vec![base], vec![side1, side2]));
+
+    let matched_paths = |expr: &FilesetExpression, trees: &[&MergedTree]| -> Vec<String> {
+        let trees = trees.iter().map(|&tree| tree.clone()).collect();
+        let matcher = expr.to_tree_matcher(trees);
+        tree.entries_matching(matcher.as_ref())
+            .chain(conflicted_tree.entries_matching(matcher.as_ref()))
+            .map(|(path, _)| path.as_internal_file_string().to_owned())
+            .unique()
+            .sorted()
+            .collect()
+    };
+    let predicate = FilesetExpression::predicate;
+
+    assert_eq!(
+        matched_paths(&predicate(FilePredicate::Executable), &[&tree]),
+        ["dir/exec"]
+    );
+    assert_eq!(
+        matched_paths(&predicate(FilePredicate::Symlink), &[&tree]),
+        ["link"]
+    );
+    assert_eq!(
+        matched_paths(&predicate(FilePredicate::Binary), &[&tree]),
+        ["binary"]
+    );
+    assert_eq!(
+        matched_paths(&predicate(FilePredicate::Size(1024..u64::MAX)), &[&tree]),
+        ["dir/large"]
+    );
+    assert_eq!(
+        matched_paths(
+            &predicate(FilePredicate::Contains(StringPattern::substring("TODO"))),
+            &[&tree]
+        ),
+        ["normal"]
+    );
+    assert_eq!(
+        matched_paths(&predicate(FilePredicate::Conflicted), &[&conflicted_tree]),
+        ["normal"]
+    );
+    // Matches if any of the trees matches
+    assert_eq!(
+        matched_paths(
+            &predicate(FilePredicate::Conflicted),
+            &[&tree, &conflicted_tree]
+        ),
+        ["normal"]
+    );
+    assert!(matched_paths(&predicate(FilePredicate::Conflicted), &[&tree]).is_empty());
+
+    // Combined with path patterns
+    let expr = FilesetExpression::intersection(
+        FilesetExpression::prefix_path(repo_path_buf("dir")),
+        FilesetExpression::difference(
+            FilesetExpression::all(),
+            predicate(FilePredicate::Executable),
+        ),
+    );
+    assert_eq!(matched_paths(&expr, &[&tree]), ["dir/large"]);
+    let expr = FilesetExpression::union_all(vec![
+        FilesetExpression::file_path(repo_path_buf("link")),
+        predicate(FilePredicate::Binary),
+    ]);
+    assert_eq!(matched_paths(&expr, &[&tree]), ["binary", "link"]);
+}
+
+#[test]
+fn test_fileset_tree_matcher_ignored() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let tree = create_tree(
+        repo,
+        &[
+            (repo_path(".gitignore"), "*.log\n/build\n"),
+            (repo_path("a.log"), ""),
+            (repo_path("a.txt"), ""),
+            (repo_path("build/out"), ""),
+            (repo_path("dir/.gitignore"), "!keep.log\n*.tmp\n"),
+            (repo_path("dir/.jjignore"), "!*.tmp\n*.txt\n"),
+            (repo_path("dir/b.log"), ""),
+            (repo_path("dir/b.tmp"), ""),
+            (repo_path("dir/b.txt"), ""),
+            (repo_path("dir/keep.log"), ""),
+            (repo_path("dir/sub/build"), ""),
+            (repo_path("dir/sub/c.txt"), ""),
+        ],
+    );
+    let expr = FilesetExpression::predicate(FilePredicate::Ignored);
+    let matcher = expr.to_tree_matcher(vec![tree.clone()]);
+    let matched_paths = tree
+        .entries_matching(matcher.as_ref())
+        .map(|(path, _)| path.as_internal_file_string().to_owned())
+        .collect_vec();
+    // `.jjignore` overrides `.gitignore` in the same directory
+    assert_eq!(
+        matched_paths,
+        [
+            "a.log",
+            "build/out",
+            "dir/b.log",
+            "dir/b.txt",
+            "dir/sub/c.txt"
+        ]
+    );
+}
@@ -15,6 +15,7 @@
 use std::collections::HashMap;
 use std::iter;
 use std::path::Path;
+use std::rc::Rc;
 
 use assert_matches::assert_matches;
 use chrono::DateTime;
@@ -45,6 +46,7 @@ use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset::parse;
 use jj_lib::revset::DefaultSymbolResolver;
 use jj_lib::revset::FailingSymbolResolver;
+use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetAliasesMap;
 use jj_lib::revset::RevsetDiagnostics;
@@ -916,6 +918,28 @@ fn try_resolve_commit_ids(
     repo: &dyn Repo,
     revset_str: &str,
 ) -> Result<Vec<CommitId>, RevsetResolutionError> {
+    Ok(try_resolve_expression(repo, revset_str)?
+        .evaluate(repo)
+        .unwrap()
+        .iter()
+        .map(Result::unwrap)
+        .collect())
+}
+
+fn resolve_commit_ids_in_order(repo: &dyn Repo, revset_str: &str) -> Vec<CommitId> {
+    try_resolve_expression(repo, revset_str)
+        .unwrap()
+        .evaluate(repo)
+        .unwrap()
+        .iter_ordered()
+        .map(Result::unwrap)
+        .collect()
+}
+
+fn try_resolve_expression(
+    repo: &dyn Repo,
+    revset_str: &str,
+) -> Result<Rc<ResolvedRevsetExpression>, RevsetResolutionError> {
     let settings = testutils::user_settings();
     let context = RevsetParseContext {
         aliases_map: &RevsetAliasesMap::default(),
@@ -927,13 +951,7 @@ fn try_resolve_commit_ids(
     };
     let expression = parse(&mut RevsetDiagnostics::new(), revset_str, &context).unwrap();
     let symbol_resolver = DefaultSymbolResolver::new(repo, context.extensions.symbol_resolvers());
-    let expression = expression.resolve_user_expression(repo, &symbol_resolver)?;
-    Ok(expression
-        .evaluate(repo)
-        .unwrap()
-        .iter()
-        .map(Result::unwrap)
-        .collect())
+    expression.resolve_user_expression(repo, &symbol_resolver)
 }
 
 fn resolve_commit_ids_in_workspace(
@@ -1370,6 +1388,92 @@ fn test_evaluate_expression_ancestors() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_first_ancestors() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit = repo.store().root_commit();
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let mut graph_builder = CommitGraphBuilder::new(mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit1, &commit3]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit4]);
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_ancestors({})", commit5.id())),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit1.id().clone(),
+            root_commit.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first_ancestors({}, 2)", commit5.id())),
+        vec![commit5.id().clone(), commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("ancestors({}, first_parent=true)", commit4.id())
+        ),
+        vec![
+            commit4.id().clone(),
+            commit1.id().clone(),
+            root_commit.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("ancestors({}, depth=2, first_parent=false)", commit4.id())
+        ),
+        vec![
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("parents({}, first_parent=true)", commit4.id())
+        ),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("parents({})", commit4.id())),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    // Mainline history excluding an older part
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("first_ancestors({}) ~ ::{}", commit5.id(), commit1.id())
+        ),
+        vec![commit5.id().clone(), commit4.id().clone()]
+    );
+    // Mainline history descending from a commit merged into it
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("first_ancestors({}) & {}::", commit5.id(), commit2.id())
+        ),
+        vec![commit5.id().clone(), commit4.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("first_ancestors({}) & {}::", commit3.id(), commit2.id())
+        ),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_range() {
     let test_repo = TestRepo::init();
@@ -2458,6 +2562,144 @@ fn test_evaluate_expression_latest() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_sort_limit() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let root_commit_id = repo.store().root_commit_id().clone();
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let mut write_commit = |description: &str, author_sec: i64, committer_sec: i64| {
+        let builder = create_random_commit(mut_repo);
+        let mut author = builder.author().clone();
+        author.timestamp.timestamp = MillisSinceEpoch(author_sec * 1000);
+        let mut committer = builder.committer().clone();
+        committer.timestamp.timestamp = MillisSinceEpoch(committer_sec * 1000);
+        builder
+            .set_description(description)
+            .set_author(author)
+            .set_committer(committer)
+            .write()
+            .unwrap()
+    };
+    let commit1 = write_commit("b", 1, 3);
+    let commit2 = write_commit("c", 3, 2);
+    let commit3 = write_commit("a", 2, 2);
+
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "sort(~root(), committer_date)"),
+        vec![
+            commit1.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+        ],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "sort(~root(), by=author_date)"),
+        vec![
+            commit2.id().clone(),
+            commit3.id().clone(),
+            commit1.id().clone(),
+        ],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "sort(~root(), description)"),
+        vec![
+            commit3.id().clone(),
+            commit1.id().clone(),
+            commit2.id().clone(),
+        ],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "sort(~root(), description, reverse=true)"),
+        vec![
+            commit2.id().clone(),
+            commit1.id().clone(),
+            commit3.id().clone(),
+        ],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "sort(all(), topological, reverse=true)"),
+        vec![
+            root_commit_id.clone(),
+            commit1.id().clone(),
+            commit2.id().clone(),
+            commit3.id().clone(),
+        ],
+    );
+
+    // Order is lost in set operations
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "sort(~root(), description) & ~root()"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ],
+    );
+
+    // limit() takes commits in the order of the candidates
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "limit(all(), 2)"),
+        vec![commit3.id().clone(), commit2.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "limit(all(), 2, offset=1)"),
+        vec![commit2.id().clone(), commit1.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "limit(sort(~root(), description), 2)"),
+        vec![commit3.id().clone(), commit1.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "limit(sort(~root(), description), 2, 2)"),
+        vec![commit2.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "limit(all(), 0)"),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "limit(all(), 1, 10)"),
+        vec![]
+    );
+
+    // Nested limit() is a set
+    assert_eq!(
+        resolve_commit_ids_in_order(mut_repo, "limit(sort(~root(), description), 2) | root()"),
+        vec![
+            commit3.id().clone(),
+            commit1.id().clone(),
+            root_commit_id.clone(),
+        ],
+    );
+
+    // iter() is always in topological order
+    let expression = try_resolve_expression(mut_repo, "sort(~root(), description)").unwrap();
+    let revset = expression.evaluate(mut_repo).unwrap();
+    assert_eq!(
+        revset.iter().map(Result::unwrap).collect_vec(),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ],
+    );
+    assert_eq!(
+        revset
+            .commit_change_ids()
+            .map(|ids| ids.unwrap().0)
+            .collect_vec(),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ],
+    );
+}
+
 #[test]
 fn test_evaluate_expression_fork_point() {
     let test_repo = TestRepo::init();
@@ -2636,6 +2878,108 @@ fn test_evaluate_expression_fork_point_merge_with_ancestor() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_evolution() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit1 = write_random_commit(tx.repo_mut());
+    let commit2 = write_random_commit(tx.repo_mut());
+    let commit3 = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    // 1 -> 4 -> 5 -+-> 6
+    // 2 -----------+
+    // 3 (abandoned)
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit4 = mut_repo
+        .rewrite_commit(&commit1)
+        .set_description("4")
+        .write()
+        .unwrap();
+    let commit5 = mut_repo
+        .rewrite_commit(&commit4)
+        .set_description("5")
+        .write()
+        .unwrap();
+    let commit6 = mut_repo
+        .rewrite_commit(&commit5)
+        .set_predecessors(vec![commit5.id().clone(), commit2.id().clone()])
+        .write()
+        .unwrap();
+    mut_repo.record_abandoned_commit(&commit2);
+    mut_repo.record_abandoned_commit(&commit3);
+    mut_repo.rebase_descendants().unwrap();
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("predecessors({})", commit6.id())),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("predecessors({})", commit4.id())),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("predecessors({})", commit1.id())),
+        vec![]
+    );
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("successors({})", commit1.id())),
+        vec![
+            commit6.id().clone(),
+            commit5.id().clone(),
+            commit4.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("successors({})", commit2.id())),
+        vec![commit6.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("successors({})", commit6.id())),
+        vec![]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "successors(none())"), vec![]);
+
+    // Abandoned commits are hidden, but not obsolete
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "obsolete()"),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "hidden()"),
+        vec![
+            commit5.id().clone(),
+            commit4.id().clone(),
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone(),
+        ]
+    );
+
+    // Find where a hidden commit went
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("successors({}) ~ hidden()", commit1.id())
+        ),
+        vec![commit6.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_merges() {
     let test_repo = TestRepo::init();
@@ -2908,6 +3252,34 @@ fn test_evaluate_expression_author_date() {
         resolve_commit_ids(mut_repo, "author_date(before:'2023-03-25 12:00')"),
         vec![commit1.id().clone(), root_commit.id().clone()]
     );
+
+    // Calendar periods and ranges of them
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date('2023-03')"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date(date:'2023-01..2023-02')"),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date('..2023-03-24')"),
+        vec![root_commit.id().clone()]
+    );
+
+    // Periods are interpreted in the given time zone
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date('2023-03-25 +12:00')"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "author_date(after:'2023-03-26 +12:00')"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
 }
 
 #[test]
@@ -3089,6 +3461,39 @@ fn test_evaluate_expression_signed() {
     let unsigned_commits = resolve_commit_ids(mut_repo, "~signed()");
     assert!(!unsigned_commits.contains(commit1.id()));
     assert!(unsigned_commits.contains(commit2.id()));
+
+    let commit3 = create_random_commit(mut_repo)
+        .set_parents(vec![commit2.id().clone()])
+        .set_committer(Signature {
+            name: "name1".to_string(),
+            email: "email1".to_string(),
+            timestamp,
+        })
+        .set_sign_behavior(SignBehavior::Own)
+        .set_sign_key("trusted-key".to_owned())
+        .write()
+        .unwrap();
+    assert!(commit3.is_signed());
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(key='trusted-key')"),
+        vec![commit3.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(key=exact:'trusted-key')"),
+        vec![commit3.id().clone()]
+    );
+    // Key IDs are matched exactly by default.
+    assert_eq!(resolve_commit_ids(mut_repo, "signed(key=trusted)"), vec![]);
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(key=substring:trusted)"),
+        vec![commit3.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(key=exact:'')"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "signed(key=other)"), vec![]);
 }
 
 #[test]
@@ -3943,6 +4348,74 @@ fn test_evaluate_expression_diff_contains_conflict() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_diff_stat() {
+    let test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let file_a = repo_path("a");
+    let file_b = repo_path("b");
+    let file_c = repo_path("c");
+    let tree1 = create_tree(repo, &[(file_a, "1\n2\n3\n"), (file_b, "x\n")]);
+    let tree2 = create_tree(repo, &[(file_a, "1\n2\n3\n4\n"), (file_b, "x\n")]);
+    let tree3 = create_tree(repo, &[(file_a, "1\n2\n3\n4\n"), (file_c, "x\n")]);
+
+    let mut create_commit =
+        |parent_ids, tree_id| mut_repo.new_commit(parent_ids, tree_id).write().unwrap();
+    let commit1 = create_commit(vec![repo.store().root_commit_id().clone()], tree1.id());
+    let commit2 = create_commit(vec![commit1.id().clone()], tree2.id());
+    let commit3 = create_commit(vec![commit2.id().clone()], tree3.id());
+    let commit4 = create_commit(vec![commit3.id().clone()], tree3.id());
+
+    let query = |revset_str: &str| {
+        resolve_commit_ids_in_workspace(
+            mut_repo,
+            revset_str,
+            &test_workspace.workspace,
+            Some(test_workspace.workspace.workspace_root()),
+        )
+    };
+
+    // Added and removed lines are both counted
+    assert_eq!(
+        query("diff_lines('>=2')"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        query(&format!("diff_lines('<2') & {}::", commit1.id())),
+        vec![commit4.id().clone(), commit2.id().clone()]
+    );
+    assert_eq!(query("diff_lines(1)"), vec![commit2.id().clone()]);
+    assert_eq!(
+        query("diff_lines(1, b)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(query("diff_lines('>3', b)"), vec![]);
+
+    assert_eq!(
+        query("diff_files(2)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(query("diff_files('>2')"), vec![]);
+    assert_eq!(
+        query("diff_files(1, a)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+
+    assert_eq!(
+        query("added()"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(query("added(a)"), vec![commit1.id().clone()]);
+    assert_eq!(query("deleted()"), vec![commit3.id().clone()]);
+    assert_eq!(query("deleted(a)"), vec![]);
+    // The test backend doesn't record copies
+    assert_eq!(query("renamed()"), vec![]);
+}
+
 #[test]
 fn test_evaluate_expression_file_merged_parents() {
     let test_workspace = TestWorkspace::init();
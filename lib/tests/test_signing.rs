@@ -1,3 +1,4 @@
+use jj_lib::backend::CommitId;
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
@@ -7,7 +8,9 @@ use jj_lib::repo::Repo as _;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::SigStatus;
 use jj_lib::signing::SignBehavior;
+use jj_lib::signing::SignResult;
 use jj_lib::signing::Signer;
+use jj_lib::signing::SigningBackend;
 use jj_lib::signing::Verification;
 use jj_lib::test_signing_backend::TestSigningBackend;
 use test_case::test_case;
@@ -208,3 +211,60 @@ fn drop_behavior(backend: TestRepoBackend) {
     let rewritten_commit = repo.store().get_commit(rewritten.id()).unwrap();
     assert_eq!(rewritten_commit.verification().unwrap(), None);
 }
+
+/// Backend that reports the same verification result for any signature.
+#[derive(Debug)]
+struct FixedVerificationBackend(&'static str, Verification);
+
+impl SigningBackend for FixedVerificationBackend {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn can_read(&self, _signature: &[u8]) -> bool {
+        true
+    }
+
+    fn sign(&self, _data: &[u8], _key: Option<&str>) -> SignResult<Vec<u8>> {
+        unimplemented!()
+    }
+
+    fn verify(&self, _data: &[u8], _signature: &[u8]) -> SignResult<Verification> {
+        Ok(self.1.clone())
+    }
+}
+
+#[test]
+fn verify_keeps_unknown_details() {
+    let untrusted = Verification::new(
+        SigStatus::Unknown,
+        Some("fingerprint".to_owned()),
+        Some("certificate chain isn't trusted: CN=someone".to_owned()),
+    );
+    let good = Verification::new(SigStatus::Good, Some("key".to_owned()), None);
+    let commit_id = CommitId::from_hex("0123");
+
+    // The details of the backend which recognized the signature are kept.
+    let signer = Signer::new(
+        None,
+        vec![
+            Box::new(FixedVerificationBackend("a", Verification::unknown())),
+            Box::new(FixedVerificationBackend("b", untrusted.clone())),
+            Box::new(FixedVerificationBackend("c", Verification::unknown())),
+        ],
+    );
+    assert_eq!(
+        signer.verify(&commit_id, b"data", b"sig").unwrap(),
+        untrusted
+    );
+
+    // A backend which knows the key still takes precedence.
+    let signer = Signer::new(
+        None,
+        vec![
+            Box::new(FixedVerificationBackend("a", untrusted)),
+            Box::new(FixedVerificationBackend("b", good.clone())),
+        ],
+    );
+    assert_eq!(signer.verify(&commit_id, b"data", b"sig").unwrap(), good);
+}
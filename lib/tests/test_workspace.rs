@@ -19,9 +19,13 @@ use jj_lib::ref_name::WorkspaceNameBuf;
 use jj_lib::repo::Repo as _;
 use jj_lib::workspace::default_working_copy_factories;
 use jj_lib::workspace::default_working_copy_factory;
+use jj_lib::workspace::relink_workspace;
 use jj_lib::workspace::Workspace;
 use jj_lib::workspace::WorkspaceLoadError;
+use jj_lib::workspace::WorkspaceRelinkError;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
 use testutils::TestEnvironment;
+use testutils::TestRepoBackend;
 use testutils::TestWorkspace;
 
 #[test]
@@ -92,6 +96,66 @@ fn test_init_additional_workspace() {
     assert_eq!(same_workspace.workspace_root(), ws2.workspace_root());
 }
 
+#[test]
+fn test_relink_moved_repo() {
+    let settings = testutils::user_settings();
+    // The test backend is keyed by store path, so it can't be moved.
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_settings(TestRepoBackend::Git, &settings);
+
+    let ws2_name = WorkspaceNameBuf::from("ws2");
+    let ws2_root = test_workspace.env.root().join("ws2_root");
+    std::fs::create_dir(&ws2_root).unwrap();
+    Workspace::init_workspace_with_existing_repo(
+        &ws2_root,
+        test_workspace.repo_path(),
+        &test_workspace.repo,
+        &*default_working_copy_factory(),
+        ws2_name.clone(),
+    )
+    .unwrap();
+    let workspace_store = SimpleWorkspaceStore::load(test_workspace.repo_path());
+    assert_eq!(
+        workspace_store.get_workspace_path(&ws2_name).unwrap(),
+        Some(dunce::canonicalize(&ws2_root).unwrap())
+    );
+
+    // Move the repo away. The other workspace should report it as missing.
+    let old_root = test_workspace.workspace.workspace_root().to_owned();
+    let new_root = test_workspace.env.root().join("moved");
+    std::fs::rename(&old_root, &new_root).unwrap();
+    let load_ws2 = || {
+        Workspace::load(
+            &settings,
+            &ws2_root,
+            &test_workspace.env.default_store_factories(),
+            &default_working_copy_factories(),
+        )
+    };
+    assert_matches!(
+        load_ws2().err(),
+        Some(WorkspaceLoadError::RepoDoesNotExist(path))
+            if path == old_root.join(".jj").join("repo")
+    );
+
+    // The workspace containing the repo can't be relinked.
+    assert_matches!(
+        relink_workspace(&new_root, &ws2_root),
+        Err(WorkspaceRelinkError::WorkspaceContainsRepo(_))
+    );
+    assert_matches!(
+        relink_workspace(&ws2_root, &ws2_root),
+        Err(WorkspaceRelinkError::NoRepoHere(_))
+    );
+
+    let new_repo_path = new_root.join(".jj").join("repo");
+    let repo_path = relink_workspace(&ws2_root, &new_repo_path).unwrap();
+    assert_eq!(repo_path, dunce::canonicalize(&new_repo_path).unwrap());
+    let ws2 = load_ws2().unwrap();
+    assert_eq!(ws2.workspace_name(), &ws2_name);
+    assert_eq!(*ws2.repo_path(), repo_path);
+}
+
 /// Test cross-thread access to a workspace, which requires it to be Send
 #[test]
 fn test_sendable() {
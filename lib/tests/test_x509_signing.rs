@@ -0,0 +1,215 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use assert_matches::assert_matches;
+use jj_lib::signing::SigStatus;
+use jj_lib::signing::SignError;
+use jj_lib::signing::SigningBackend as _;
+use jj_lib::x509_signing::X509Backend;
+use testutils::ensure_running_outside_ci;
+use testutils::is_external_tool_installed;
+
+/// A locally generated CA with one signing certificate issued by it, and an
+/// unrelated CA.
+struct X509Environment {
+    dir: tempfile::TempDir,
+}
+
+fn openssl(dir: &Path, args: &[&str]) {
+    let output = Command::new("openssl")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "openssl {args:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn generate_ca(dir: &Path, name: &str, subject: &str) {
+    openssl(
+        dir,
+        &[
+            "req",
+            "-x509",
+            "-newkey",
+            "ec",
+            "-pkeyopt",
+            "ec_paramgen_curve:P-256",
+            "-nodes",
+            "-keyout",
+            &format!("{name}.key"),
+            "-out",
+            &format!("{name}.pem"),
+            "-subj",
+            subject,
+            "-days",
+            "3650",
+        ],
+    );
+}
+
+impl X509Environment {
+    fn new() -> Self {
+        let dir = tempfile::Builder::new()
+            .prefix("jj-test-x509-")
+            .tempdir()
+            .unwrap();
+        let path = dir.path();
+
+        generate_ca(path, "ca", "/CN=jj test CA");
+        generate_ca(path, "other-ca", "/CN=jj other CA");
+        openssl(
+            path,
+            &[
+                "req",
+                "-newkey",
+                "ec",
+                "-pkeyopt",
+                "ec_paramgen_curve:P-256",
+                "-nodes",
+                "-keyout",
+                "signer.key",
+                "-out",
+                "signer.csr",
+                "-subj",
+                "/CN=Someone/emailAddress=someone@example.com",
+            ],
+        );
+        openssl(
+            path,
+            &[
+                "x509",
+                "-req",
+                "-in",
+                "signer.csr",
+                "-CA",
+                "ca.pem",
+                "-CAkey",
+                "ca.key",
+                "-out",
+                "signer.crt",
+                "-days",
+                "3650",
+            ],
+        );
+        let certificate = fs::read_to_string(path.join("signer.crt")).unwrap();
+        let key = fs::read_to_string(path.join("signer.key")).unwrap();
+        fs::write(path.join("signer.pem"), certificate + &key).unwrap();
+
+        X509Environment { dir }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.path().join(name)
+    }
+
+    fn signing_key(&self) -> String {
+        self.path("signer.pem").to_str().unwrap().to_owned()
+    }
+
+    fn fingerprint(&self) -> String {
+        let output = Command::new("openssl")
+            .args(["x509", "-noout", "-fingerprint", "-sha256", "-in"])
+            .arg(self.path("signer.crt"))
+            .output()
+            .unwrap();
+        let output = String::from_utf8(output.stdout).unwrap();
+        let (_, fingerprint) = output.trim().split_once('=').unwrap();
+        fingerprint.replace(':', "")
+    }
+}
+
+macro_rules! openssl_guard {
+    () => {
+        if !is_external_tool_installed("openssl") {
+            ensure_running_outside_ci("`openssl` must be in the PATH");
+            eprintln!("Skipping test because openssl is not installed on the system");
+            return;
+        }
+    };
+}
+
+#[test]
+fn x509_signing_roundtrip() {
+    openssl_guard!();
+    let env = X509Environment::new();
+    let backend = X509Backend::new("openssl".into(), Some(env.path("ca.pem")));
+    let data = b"hello world";
+
+    let signature = backend.sign(data, Some(&env.signing_key())).unwrap();
+    assert!(backend.can_read(&signature));
+
+    let check = backend.verify(data, &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Good);
+    assert_eq!(check.key.unwrap(), env.fingerprint());
+    assert_eq!(
+        check.display.unwrap(),
+        "CN=Someone, emailAddress=someone@example.com"
+    );
+
+    let check = backend.verify(b"invalid-commit-data", &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Bad);
+}
+
+#[test]
+fn x509_signing_untrusted_certificate() {
+    openssl_guard!();
+    let env = X509Environment::new();
+    let backend = X509Backend::new("openssl".into(), Some(env.path("other-ca.pem")));
+    let data = b"hello world";
+
+    let signature = backend.sign(data, Some(&env.signing_key())).unwrap();
+
+    let check = backend.verify(data, &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Unknown);
+    assert_eq!(check.key.unwrap(), env.fingerprint());
+    assert_eq!(
+        check.display.unwrap(),
+        "CN=Someone, emailAddress=someone@example.com (untrusted certificate: unable to get \
+         local issuer certificate)"
+    );
+
+    let check = backend.verify(b"invalid-commit-data", &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Bad);
+}
+
+#[test]
+fn x509_signing_missing_key() {
+    let backend = X509Backend::new("openssl".into(), None);
+    assert_matches!(
+        backend.sign(b"hello world", None),
+        Err(SignError::Backend(_))
+    );
+}
+
+#[test]
+fn x509_verify_invalid_signature_format() {
+    openssl_guard!();
+    let backend = X509Backend::new("openssl".into(), None);
+    let signature = b"-----BEGIN CMS-----\ngarbage\n-----END CMS-----\n";
+    assert!(backend.can_read(signature));
+    assert_matches!(
+        backend.verify(b"hello world", signature),
+        Err(SignError::InvalidSignatureFormat)
+    );
+    assert!(!backend.can_read(b"-----BEGIN SIGNED MESSAGE-----"));
+}